@@ -1,5 +1,7 @@
 use clap::Parser;
 
+use crate::transcript::TranscriptFormat;
+
 #[derive(Parser, Debug, Clone)]
 #[command(name = "transcriber")]
 #[command(about = "CPU-optimized audio transcription service", long_about = None)]
@@ -41,9 +43,17 @@ pub struct Config {
 	#[arg(long, env = "VAD_SPEECH_THRESHOLD", default_value = "0.3")]
 	pub vad_speech_threshold: f32,
 
+	/// Seconds to wait for in-flight transcriptions to publish on shutdown
+	#[arg(long, env = "SHUTDOWN_GRACE_PERIOD", default_value = "10")]
+	pub shutdown_grace_secs: u64,
+
 	/// VAD mode: 0 (Quality), 1 (LowBitrate), 2 (Aggressive), 3 (VeryAggressive)
 	#[arg(long, env = "VAD_MODE", default_value = "0")]
 	pub vad_mode: u8,
+
+	/// Transcript output format: plain, json_segments, or srt
+	#[arg(long, env = "TRANSCRIPT_FORMAT", value_enum, default_value_t = TranscriptFormat::Plain)]
+	pub transcript_format: TranscriptFormat,
 }
 
 impl Config {
@@ -2,6 +2,7 @@ mod audio;
 mod config;
 mod observability;
 mod state;
+mod transcript;
 mod transcription;
 mod vad;
 mod worker;
@@ -18,7 +19,7 @@ use ws_events::events::{AudioChunkMessage, EventType, UnifiedEvent};
 
 use config::Config;
 use state::TranscriberState;
-use worker::{TranscriptionJob, TranscriptionQueue, TRANSCRIPTION_QUEUE_CAPACITY};
+use worker::{InFlightTranscriptions, TranscriptionJob, TranscriptionQueue, TRANSCRIPTION_QUEUE_CAPACITY, WHISPER_WORKER_COUNT};
 
 const NATS_MAX_RETRIES: u32 = 5;
 const NATS_INITIAL_BACKOFF_MS: u64 = 500;
@@ -41,6 +42,7 @@ async fn main() -> Result<()> {
 			whisper_model = %config.whisper_model_path,
 			vad_enabled = config.vad_enabled,
 			vad_threshold = config.vad_speech_threshold,
+			transcript_format = ?config.transcript_format,
 			"🎯 Starting transcriber service"
 	);
 
@@ -64,15 +66,20 @@ async fn main() -> Result<()> {
 	// Create cancellation token for cooperative shutdown
 	let cancellation_token = CancellationToken::new();
 
+	// Track in-flight transcriptions so shutdown can drain them
+	let in_flight = InFlightTranscriptions::new(WHISPER_WORKER_COUNT);
+
 	// Start Whisper worker thread
 	let params = transcription::create_params(config.whisper_threads);
 	worker::start_whisper_worker(
 		queue_rx,
 		Arc::new(whisper_ctx),
 		params,
+		config.transcript_format,
 		transport.clone(),
 		state.clone(),
 		metrics.clone(),
+		in_flight.clone(),
 		cancellation_token.clone(),
 	);
 
@@ -87,7 +94,7 @@ async fn main() -> Result<()> {
 	};
 
 	// Run with graceful shutdown
-	run_with_shutdown(transcriber, cancellation_token).await
+	run_with_shutdown(transcriber, in_flight, cancellation_token).await
 }
 
 struct Transcriber {
@@ -99,9 +106,10 @@ struct Transcriber {
 	cancellation_token: CancellationToken,
 }
 
-async fn run_with_shutdown(transcriber: Transcriber, cancellation_token: CancellationToken) -> Result<()> {
+async fn run_with_shutdown(transcriber: Transcriber, in_flight: InFlightTranscriptions, cancellation_token: CancellationToken) -> Result<()> {
 	// Clone state for shutdown logging
 	let state_for_shutdown = transcriber.state.clone();
+	let grace_period = std::time::Duration::from_secs(transcriber.config.shutdown_grace_secs);
 
 	tokio::select! {
 			result = transcriber.run() => {
@@ -111,12 +119,23 @@ async fn run_with_shutdown(transcriber: Transcriber, cancellation_token: Cancell
 			_ = wait_for_shutdown_signal() => {
 					info!("🛑 Shutdown signal received (SIGTERM/SIGINT)");
 
-					// Signal all async tasks to stop
+					// Signal all async tasks to stop (no new chunks are accepted after this)
 					cancellation_token.cancel();
 
 					// Give async tasks a moment to notice cancellation and exit gracefully
 					tokio::time::sleep(std::time::Duration::from_millis(SHUTDOWN_GRACE_PERIOD_MS)).await;
 
+					// Best-effort drain: wait for in-flight transcriptions to publish
+					let pending = in_flight.in_flight();
+					if pending > 0 {
+							info!(pending, grace_secs = grace_period.as_secs(), "⏳ Draining in-flight transcriptions");
+					}
+					if in_flight.drain(grace_period).await {
+							info!("✅ In-flight transcriptions drained");
+					} else {
+							warn!(pending = in_flight.in_flight(), "⚠️ Drain grace period elapsed - abandoning in-flight transcriptions");
+					}
+
 					// Log queue state at shutdown
 					let jobs_enqueued = state_for_shutdown.jobs_enqueued.load(std::sync::atomic::Ordering::Relaxed);
 					let jobs_dropped = state_for_shutdown.jobs_dropped.load(std::sync::atomic::Ordering::Relaxed);
@@ -129,8 +148,8 @@ async fn run_with_shutdown(transcriber: Transcriber, cancellation_token: Cancell
 							"📊 Shutdown statistics"
 					);
 
-					// DO NOT wait for blocking Whisper threads - they cannot be cancelled
-					// The OS will clean them up when the process exits
+					// A Whisper thread still running past the grace period cannot be cancelled
+					// The OS will clean it up when the process exits
 					info!("✅ Exiting process (OS will clean up any remaining Whisper threads)");
 
 					// Exit immediately - this is safe and correct for container environments
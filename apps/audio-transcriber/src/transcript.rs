@@ -0,0 +1,138 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Shape of the transcripts published to NATS
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TranscriptFormat {
+	/// One subtitle per Whisper segment, text only
+	#[default]
+	Plain,
+	/// One subtitle per job: a JSON array of `{ start, end, text }`
+	#[value(name = "json_segments")]
+	JsonSegments,
+	/// One subtitle per job: an SRT document with a cue per segment
+	Srt,
+}
+
+/// A Whisper segment with its timing relative to the start of the job's audio
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptSegment {
+	pub start_ms: u64,
+	pub end_ms: u64,
+	pub text: String,
+}
+
+impl TranscriptSegment {
+	/// Build from Whisper's segment timestamps, which are in centiseconds
+	pub fn from_centiseconds(t0: i64, t1: i64, text: impl Into<String>) -> Self {
+		let to_ms = |cs: i64| u64::try_from(cs).unwrap_or(0) * 10;
+		Self {
+			start_ms: to_ms(t0),
+			end_ms: to_ms(t1),
+			text: text.into(),
+		}
+	}
+}
+
+impl TranscriptFormat {
+	/// Render a job's segments into the payloads to publish
+	///
+	/// Plain keeps the existing one-subtitle-per-segment behaviour; the
+	/// structured formats bundle the whole job so timings stay together.
+	#[must_use]
+	pub fn render(self, segments: &[TranscriptSegment]) -> Vec<String> {
+		if segments.is_empty() {
+			return Vec::new();
+		}
+
+		match self {
+			Self::Plain => segments.iter().map(|segment| segment.text.clone()).collect(),
+			Self::JsonSegments => vec![render_json(segments)],
+			Self::Srt => vec![render_srt(segments)],
+		}
+	}
+}
+
+fn render_json(segments: &[TranscriptSegment]) -> String {
+	let seconds = |ms: u64| Duration::from_millis(ms).as_secs_f64();
+	let array = segments
+		.iter()
+		.map(|segment| {
+			serde_json::json!({
+				"start": seconds(segment.start_ms),
+				"end": seconds(segment.end_ms),
+				"text": segment.text,
+			})
+		})
+		.collect();
+
+	serde_json::Value::Array(array).to_string()
+}
+
+fn render_srt(segments: &[TranscriptSegment]) -> String {
+	let mut srt = String::new();
+	for (i, segment) in segments.iter().enumerate() {
+		if i > 0 {
+			srt.push('\n');
+		}
+		let _ = writeln!(srt, "{}", i + 1);
+		let _ = writeln!(srt, "{} --> {}", srt_timestamp(segment.start_ms), srt_timestamp(segment.end_ms));
+		let _ = writeln!(srt, "{}", segment.text);
+	}
+	srt
+}
+
+/// `HH:MM:SS,mmm`, as SRT requires
+fn srt_timestamp(ms: u64) -> String {
+	let mut out = String::with_capacity(12);
+	let _ = write!(out, "{:02}:{:02}:{:02},{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000);
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Segments as Whisper reports them for a short clip
+	fn whisper_segments() -> Vec<TranscriptSegment> {
+		vec![
+			TranscriptSegment::from_centiseconds(0, 248, "Hello and welcome."),
+			TranscriptSegment::from_centiseconds(248, 612, "Today we talk about \"latency\"."),
+			TranscriptSegment::from_centiseconds(360_000, 360_150, "An hour in."),
+		]
+	}
+
+	#[test]
+	fn test_plain_publishes_each_segment() {
+		assert_eq!(
+			TranscriptFormat::Plain.render(&whisper_segments()),
+			vec!["Hello and welcome.", "Today we talk about \"latency\".", "An hour in."]
+		);
+		assert!(TranscriptFormat::Srt.render(&[]).is_empty());
+	}
+
+	#[test]
+	fn test_srt_cues_have_valid_timestamps() {
+		let rendered = TranscriptFormat::Srt.render(&whisper_segments());
+		assert_eq!(rendered.len(), 1);
+		assert_eq!(
+			rendered[0],
+			"1\n00:00:00,000 --> 00:00:02,480\nHello and welcome.\n\n\
+			 2\n00:00:02,480 --> 00:00:06,120\nToday we talk about \"latency\".\n\n\
+			 3\n01:00:00,000 --> 01:00:01,500\nAn hour in.\n"
+		);
+	}
+
+	#[test]
+	fn test_json_segments_structure() {
+		let rendered = TranscriptFormat::JsonSegments.render(&whisper_segments());
+		assert_eq!(rendered.len(), 1);
+
+		let value: serde_json::Value = serde_json::from_str(&rendered[0]).unwrap();
+		let array = value.as_array().unwrap();
+		assert_eq!(array.len(), 3);
+		assert_eq!(array[0], serde_json::json!({ "start": 0.0, "end": 2.48, "text": "Hello and welcome." }));
+		assert_eq!(array[1]["text"], "Today we talk about \"latency\".");
+		assert_eq!(array[2]["start"], 3600.0);
+	}
+}
@@ -1,6 +1,8 @@
+mod drain;
 mod queue;
 mod whisper;
 
+pub use drain::InFlightTranscriptions;
 pub use queue::{TranscriptionJob, TranscriptionQueue};
 pub use whisper::start_whisper_worker;
 
@@ -14,3 +16,6 @@ pub use whisper::start_whisper_worker;
 ///
 /// DO NOT increase arbitrarily - larger queues = higher latency
 pub const TRANSCRIPTION_QUEUE_CAPACITY: usize = 4;
+
+/// Number of transcriptions that can be in flight at once (one blocking worker)
+pub const WHISPER_WORKER_COUNT: u32 = 1;
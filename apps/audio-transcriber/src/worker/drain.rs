@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Tracks transcriptions that are in flight so shutdown can wait for them
+///
+/// Each worker holds one permit from the moment it starts a job until its
+/// transcripts have been handed to NATS. When every permit is available again,
+/// nothing is in flight and it is safe to exit without losing output.
+#[derive(Clone)]
+pub struct InFlightTranscriptions {
+	semaphore: Arc<Semaphore>,
+	capacity: u32,
+}
+
+impl InFlightTranscriptions {
+	pub fn new(capacity: u32) -> Self {
+		Self {
+			semaphore: Arc::new(Semaphore::new(capacity as usize)),
+			capacity,
+		}
+	}
+
+	/// Mark a transcription as started
+	///
+	/// Returns None once draining has begun, so the caller can stop taking work.
+	/// The transcription is considered finished when the permit is dropped.
+	pub fn begin(&self) -> Option<OwnedSemaphorePermit> {
+		Arc::clone(&self.semaphore).try_acquire_owned().ok()
+	}
+
+	/// Number of transcriptions currently holding a permit
+	pub fn in_flight(&self) -> usize {
+		self.capacity as usize - self.semaphore.available_permits()
+	}
+
+	/// Wait up to `grace` for all in-flight transcriptions to finish
+	///
+	/// Best-effort: returns true if everything completed, false if the grace
+	/// period elapsed first. Either way no new transcriptions can start afterward.
+	pub async fn drain(&self, grace: Duration) -> bool {
+		let drained = match tokio::time::timeout(grace, self.semaphore.acquire_many(self.capacity)).await {
+			Ok(Ok(permits)) => {
+				permits.forget();
+				true
+			}
+			Ok(Err(_)) => true,
+			Err(_) => false,
+		};
+
+		self.semaphore.close();
+		drained
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Mutex;
+
+	#[tokio::test]
+	async fn test_drain_waits_for_in_flight_publish() {
+		let in_flight = InFlightTranscriptions::new(1);
+		let published = Arc::new(Mutex::new(Vec::new()));
+
+		let permit = in_flight.begin().expect("permit should be available");
+		assert_eq!(in_flight.in_flight(), 1);
+
+		let published_clone = Arc::clone(&published);
+		tokio::spawn(async move {
+			// Simulated Whisper run followed by the NATS publish
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			published_clone.lock().unwrap().push("hello world".to_string());
+			drop(permit);
+		});
+
+		assert!(in_flight.drain(Duration::from_secs(2)).await);
+		assert_eq!(*published.lock().unwrap(), vec!["hello world".to_string()]);
+		assert!(in_flight.begin().is_none());
+	}
+
+	#[tokio::test]
+	async fn test_drain_gives_up_after_grace_period() {
+		let in_flight = InFlightTranscriptions::new(1);
+		let _stuck = in_flight.begin().expect("permit should be available");
+
+		assert!(!in_flight.drain(Duration::from_millis(20)).await);
+		assert!(in_flight.begin().is_none());
+	}
+}
@@ -9,9 +9,11 @@ use tracing::{debug, error, info, warn};
 use whisper_rs::{FullParams, WhisperContext};
 use ws_events::events::{Event, UnifiedEvent};
 
+use super::drain::InFlightTranscriptions;
 use super::queue::TranscriptionJob;
 use crate::observability::TranscriberMetrics;
 use crate::state::TranscriberState;
+use crate::transcript::{TranscriptFormat, TranscriptSegment};
 
 /// Start the blocking Whisper worker thread
 ///
@@ -21,20 +23,24 @@ use crate::state::TranscriberState;
 /// On shutdown:
 /// - Cancellation token signals worker to stop accepting new jobs
 /// - Worker exits gracefully after current job completes
-/// - If worker is mid-job during shutdown, the thread is abandoned and OS cleans it up
+/// - Each job holds an `InFlightTranscriptions` permit until its segments are
+///   published, so shutdown can drain in-flight work before exiting
+#[allow(clippy::too_many_arguments)]
 pub fn start_whisper_worker(
 	mut rx: mpsc::Receiver<TranscriptionJob>,
 	whisper_ctx: Arc<WhisperContext>,
 	params: FullParams<'static, 'static>,
+	format: TranscriptFormat,
 	transport: NatsTransport<UnifiedEvent>,
 	state: Arc<TranscriberState>,
 	metrics: TranscriberMetrics,
+	in_flight: InFlightTranscriptions,
 	cancellation_token: CancellationToken,
 ) {
 	info!("🏭 Starting Whisper worker thread");
 
 	// Spawn ONE blocking worker - this is a CPU drainpipe
-	tokio::task::spawn_blocking(move || whisper_worker_loop(&mut rx, &whisper_ctx, params, transport, state, metrics, cancellation_token));
+	tokio::task::spawn_blocking(move || whisper_worker_loop(&mut rx, &whisper_ctx, params, format, transport, state, metrics, in_flight, cancellation_token));
 }
 
 /// Main worker loop - runs in blocking context
@@ -43,13 +49,16 @@ pub fn start_whisper_worker(
 /// - Never touches async primitives directly
 /// - Never awaits
 /// - Never spawns more workers
+#[allow(clippy::too_many_arguments)]
 fn whisper_worker_loop(
 	rx: &mut mpsc::Receiver<TranscriptionJob>,
 	whisper_ctx: &WhisperContext,
 	params: FullParams<'static, 'static>,
+	format: TranscriptFormat,
 	transport: NatsTransport<UnifiedEvent>,
 	state: Arc<TranscriberState>,
 	metrics: TranscriberMetrics,
+	in_flight: InFlightTranscriptions,
 	cancellation_token: CancellationToken,
 ) {
 	info!("🔄 Worker loop started, waiting for jobs...");
@@ -70,6 +79,12 @@ fn whisper_worker_loop(
 			}
 		};
 
+		// Hold a permit until the transcript is published; None means we're draining
+		let Some(_permit) = in_flight.begin() else {
+			info!(seq = job.seq, "🛑 Worker shutting down (draining, job not started)");
+			break;
+		};
+
 		// Log queue latency
 		let queue_latency_ms = job.queue_latency().as_millis() as f64;
 		metrics.transcription_queue_latency.record(queue_latency_ms, &[]);
@@ -93,7 +108,7 @@ fn whisper_worker_loop(
 				metrics.transcription_processing_latency.record(processing_latency_ms, &[]);
 
 				// Publish results (async boundary)
-				publish_segments_sync(segments, &transport, &state, &metrics);
+				publish_segments_sync(format.render(&segments), &transport, &state, &metrics);
 			}
 			Err(e) => {
 				error!(error = %e, "❌ Transcription job failed");
@@ -114,7 +129,7 @@ fn process_transcription_job(
 	params: &FullParams<'static, 'static>,
 	state: &TranscriberState,
 	metrics: &TranscriberMetrics,
-) -> Result<Vec<String>> {
+) -> Result<Vec<TranscriptSegment>> {
 	let audio_duration_secs = job.audio_duration_secs();
 
 	info!(
@@ -175,7 +190,7 @@ fn process_transcription_job(
 			if let Ok(text) = segment.to_str() {
 				let trimmed = text.trim();
 				if !trimmed.is_empty() {
-					segments.push(trimmed.to_string());
+					segments.push(TranscriptSegment::from_centiseconds(segment.start_timestamp(), segment.end_timestamp(), trimmed));
 				}
 			}
 		}
@@ -186,7 +201,8 @@ fn process_transcription_job(
 
 /// Publish segments from blocking context
 ///
-/// This uses a blocking runtime handle to publish async
+/// This uses a blocking runtime handle to publish async, and waits for the
+/// publishes to finish so a draining shutdown never drops a completed transcript
 fn publish_segments_sync(segments: Vec<String>, transport: &NatsTransport<UnifiedEvent>, state: &Arc<TranscriberState>, metrics: &TranscriberMetrics) {
 	if segments.is_empty() {
 		return;
@@ -196,6 +212,7 @@ fn publish_segments_sync(segments: Vec<String>, transport: &NatsTransport<Unifie
 
 	// Get or create runtime handle for async operations from blocking context
 	let handle = tokio::runtime::Handle::current();
+	let mut publishes = Vec::with_capacity(segments.len());
 
 	for (i, text) in segments.iter().enumerate() {
 		let emoji = match text.len() {
@@ -230,7 +247,7 @@ fn publish_segments_sync(segments: Vec<String>, transport: &NatsTransport<Unifie
 			let state_clone = Arc::clone(state);
 
 			// Use handle to spawn async task from blocking context
-			handle.spawn(async move {
+			publishes.push(handle.spawn(async move {
 				match transport_clone.send_to_subject(&subject, unified).await {
 					Ok(_) => {
 						state_clone.subtitles_published.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
@@ -241,9 +258,13 @@ fn publish_segments_sync(segments: Vec<String>, transport: &NatsTransport<Unifie
 						error!(error = %e, "❌ Failed to publish subtitle");
 					}
 				}
-			});
+			}));
 		}
 	}
 
+	for publish in publishes {
+		let _ = handle.block_on(publish);
+	}
+
 	info!(published = segments.len(), "✨ Publishing complete - {} subtitle(s) sent", segments.len());
 }
@@ -1,18 +1,55 @@
+pub mod replay;
+
 use cursorium::core::StreamOrchestrator;
 use dashmap::DashMap;
-use some_transport::{NatsTransport, Transport};
-use std::sync::Arc;
+use replay::StateReplay;
+use some_transport::{NatsTransport, ReceiverTrait, Transport, TransportReceiver};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
-use ws_events::events::{Event, EventType, OrchestratorCommandData, OrchestratorState, UnifiedEvent};
+use ws_events::events::{Event, EventType, OrchestratorCommandData, OrchestratorConfigData, OrchestratorMode, OrchestratorState, UnifiedEvent};
 
 type StreamId = String;
 
+/// Reason given in the `OrchestratorError` answering a state query for an unknown stream
+pub const STREAM_NOT_FOUND: &str = "stream not found";
+
 /// Internal supervisor messages for lifecycle management
 #[derive(Debug)]
 enum SupervisorMsg {
-	StreamTerminated(StreamId),
+	/// The stream reached a terminal state; `failed` if it errored rather than finished or was stopped
+	StreamTerminated { stream_id: StreamId, failed: bool },
+	/// Recreate a failed stream now that its backoff has passed
+	RestartStream {
+		stream_id: StreamId,
+		restarts: u32,
+		policy: RestartPolicy,
+		config: Option<OrchestratorConfigData>,
+	},
+}
+
+/// What the supervisor does with a stream whose orchestrator fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+	/// Remove the stream, as for a clean stop
+	#[default]
+	Never,
+	/// Recreate the stream with its last configuration, waiting `backoff`
+	/// before each attempt, until it has been restarted `max_retries` times
+	OnFailure { max_retries: u32, backoff: Duration },
+}
+
+impl RestartPolicy {
+	/// Backoff before the next restart of a stream already restarted `restarts` times, if any are left
+	#[must_use]
+	pub const fn next_backoff(self, restarts: u32) -> Option<Duration> {
+		match self {
+			Self::OnFailure { max_retries, backoff } if restarts < max_retries => Some(backoff),
+			_ => None,
+		}
+	}
 }
 
 /// Manages a single stream orchestrator
@@ -20,6 +57,11 @@ pub struct ManagedOrchestrator {
 	orchestrator: Arc<StreamOrchestrator>,
 	cancel_token: CancellationToken,
 	state_publisher_handle: tokio::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+	restart_policy: RestartPolicy,
+	/// Times this stream has been recreated after failing
+	restarts: u32,
+	/// Last configuration the orchestrator accepted, re-applied if it is restarted
+	config: Mutex<Option<OrchestratorConfigData>>,
 }
 
 impl ManagedOrchestrator {
@@ -32,19 +74,63 @@ impl ManagedOrchestrator {
 			orchestrator,
 			cancel_token,
 			state_publisher_handle: tokio::sync::Mutex::new(None),
+			restart_policy: RestartPolicy::Never,
+			restarts: 0,
+			config: Mutex::new(None),
 		})
 	}
 
+	/// Set what the supervisor does if this orchestrator fails
+	#[must_use]
+	pub const fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+		self.restart_policy = policy;
+		self
+	}
+
+	pub const fn restart_policy(&self) -> RestartPolicy {
+		self.restart_policy
+	}
+
+	/// Times this stream has been recreated after failing
+	pub const fn restarts(&self) -> u32 {
+		self.restarts
+	}
+
+	fn config(&self) -> Option<OrchestratorConfigData> {
+		self.config.lock().unwrap_or_else(PoisonError::into_inner).clone()
+	}
+
 	/// Store the state publisher handle
 	pub async fn set_publisher_handle(&self, handle: tokio::task::JoinHandle<()>) {
 		*self.state_publisher_handle.lock().await = Some(handle);
 	}
 
 	/// Send command to orchestrator
+	///
+	/// Logs the FSM mode before and after the command, and warns when an
+	/// accepted command left the orchestrator somewhere other than expected.
 	pub async fn send_command(&self, cmd: OrchestratorCommandData) -> anyhow::Result<()> {
+		let command = command_name(&cmd);
+		let from = self.current_state().mode;
+		let expected = expected_mode(&cmd, from);
+
+		let result = self.execute(cmd).await;
+
+		let to = self.current_state().mode;
+		let accepted = result.is_ok();
+		info!(command, from = ?from, to = ?to, accepted, "orchestrator command transition");
+		if accepted && expected.is_some_and(|expected| expected != to) {
+			warn!(command, from = ?from, to = ?to, expected = ?expected, "command accepted but FSM did not reach the expected mode");
+		}
+
+		result
+	}
+
+	async fn execute(&self, cmd: OrchestratorCommandData) -> anyhow::Result<()> {
 		match cmd {
 			OrchestratorCommandData::Configure(config) => {
-				self.orchestrator.configure(OrchestratorCommandData::Configure(config)).await?;
+				self.orchestrator.configure(OrchestratorCommandData::Configure(config.clone())).await?;
+				*self.config.lock().unwrap_or_else(PoisonError::into_inner) = Some(config);
 			}
 			OrchestratorCommandData::Start => {
 				self.orchestrator.start().await?;
@@ -99,40 +185,105 @@ impl ManagedOrchestrator {
 	}
 }
 
+const fn command_name(cmd: &OrchestratorCommandData) -> &'static str {
+	match cmd {
+		OrchestratorCommandData::Configure(_) => "Configure",
+		OrchestratorCommandData::Start => "Start",
+		OrchestratorCommandData::Pause => "Pause",
+		OrchestratorCommandData::Resume => "Resume",
+		OrchestratorCommandData::Stop => "Stop",
+		OrchestratorCommandData::Reset => "Reset",
+		OrchestratorCommandData::ForceScene(_) => "ForceScene",
+		OrchestratorCommandData::SkipCurrentScene => "SkipCurrentScene",
+		OrchestratorCommandData::UpdateStreamStatus { .. } => "UpdateStreamStatus",
+	}
+}
+
+/// Mode an accepted command should leave the FSM in, if it moves the FSM at all
+fn expected_mode(cmd: &OrchestratorCommandData, from: OrchestratorMode) -> Option<OrchestratorMode> {
+	match cmd {
+		OrchestratorCommandData::Configure(_) => Some(OrchestratorMode::Idle),
+		OrchestratorCommandData::Start | OrchestratorCommandData::Resume => Some(OrchestratorMode::Running),
+		OrchestratorCommandData::Pause => Some(OrchestratorMode::Paused),
+		OrchestratorCommandData::Stop => matches!(from, OrchestratorMode::Running | OrchestratorMode::Paused).then_some(OrchestratorMode::Stopped),
+		OrchestratorCommandData::Reset => (from != OrchestratorMode::Unconfigured).then_some(OrchestratorMode::Idle),
+		OrchestratorCommandData::ForceScene(_) | OrchestratorCommandData::SkipCurrentScene | OrchestratorCommandData::UpdateStreamStatus { .. } => None,
+	}
+}
+
 /// Top-level orchestrator service with supervisor pattern
+///
+/// Runs over NATS in production; any [`Transport`] with subjects and
+/// request/reply works, such as the in-memory one in tests.
 #[derive(Clone)]
-pub struct OrchestratorService {
+pub struct OrchestratorService<T = NatsTransport<UnifiedEvent>> {
 	orchestrators: Arc<DashMap<StreamId, Arc<ManagedOrchestrator>>>,
-	transport: NatsTransport<UnifiedEvent>,
+	transport: T,
+	replay: StateReplay,
 	cancel_token: CancellationToken,
 	supervisor_tx: mpsc::UnboundedSender<SupervisorMsg>,
+	/// Policy given to every orchestrator the service creates
+	restart_policy: RestartPolicy,
+	/// Streams allowed at once; commands that would create another are rejected
+	max_streams: usize,
 }
 
-impl OrchestratorService {
-	pub fn new(transport: NatsTransport<UnifiedEvent>) -> Self {
+impl<T: Transport<UnifiedEvent>> OrchestratorService<T> {
+	pub fn new(transport: T) -> Self {
+		Self::with_limits(transport, usize::MAX)
+	}
+
+	/// Create a service that runs at most `max_streams` orchestrators at once
+	///
+	/// A command for a new stream beyond the limit is rejected and reported
+	/// on `orchestrator.error` instead of creating an orchestrator.
+	pub fn with_limits(transport: T, max_streams: usize) -> Self {
 		let (supervisor_tx, _) = mpsc::unbounded_channel();
 
 		Self {
 			orchestrators: Arc::new(DashMap::new()),
 			transport,
+			replay: StateReplay::new(),
 			cancel_token: CancellationToken::new(),
 			supervisor_tx,
+			restart_policy: RestartPolicy::Never,
+			max_streams,
 		}
 	}
 
+	/// Restart streams that fail according to `policy` instead of dropping them
+	#[must_use]
+	pub const fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+		self.restart_policy = policy;
+		self
+	}
+
 	/// Main event loop: listens for commands and supervises lifecycle
-	pub async fn run(&self) -> anyhow::Result<()> {
+	///
+	/// # Errors
+	///
+	/// Never fails at present; the loop ends when the service is shut down or
+	/// a subscription closes.
+	pub async fn run<R>(&self) -> anyhow::Result<()>
+	where
+		T: Transport<UnifiedEvent, Receiver = TransportReceiver<UnifiedEvent, R>>,
+		R: ReceiverTrait<UnifiedEvent> + Send + 'static,
+	{
 		info!("🎬 Starting Orchestrator Service event loop");
 
 		let mut command_rx = self.transport.subscribe_to_subject(EventType::OrchestratorCommandData.subject()).await;
+		let mut query_rx = self.transport.subscribe_to_subject(EventType::OrchestratorStateQuery.subject()).await;
 		let (supervisor_tx, mut supervisor_rx) = mpsc::unbounded_channel::<SupervisorMsg>();
 
 		// Replace the supervisor_tx with the real one
 		let service = Self {
 			orchestrators: Arc::clone(&self.orchestrators),
 			transport: self.transport.clone(),
+			replay: self.replay.clone(),
 			cancel_token: self.cancel_token.clone(),
 			supervisor_tx,
+			restart_policy: self.restart_policy,
+			max_streams: self.max_streams,
 		};
 
 		loop {
@@ -155,6 +306,21 @@ impl OrchestratorService {
 						}
 					}
 				}
+				// A caller asked for the current state of one stream, or of every stream
+				result = query_rx.recv_request() => {
+					match result {
+						Ok((query, Some(reply))) => {
+							if let Err(e) = service.answer_state_query(query, &reply).await {
+								error!("Error answering state query: {}", e);
+							}
+						}
+						Ok((_, None)) => warn!("State query without a reply subject, ignoring"),
+						Err(e) => {
+							error!("State query receiver error: {}", e);
+							break;
+						}
+					}
+				}
 				// Handle supervisor lifecycle messages
 				Some(msg) = supervisor_rx.recv() => {
 					service.handle_supervisor_msg(msg).await;
@@ -171,18 +337,70 @@ impl OrchestratorService {
 	/// This is the ONLY place orchestrators are removed
 	async fn handle_supervisor_msg(&self, msg: SupervisorMsg) {
 		match msg {
-			SupervisorMsg::StreamTerminated(stream_id) => {
+			SupervisorMsg::StreamTerminated { stream_id, failed } => {
 				info!("🧹 Stream terminated, cleaning up: {}", stream_id);
 
 				// Enforce invariant: Terminal state => not in map
-				if let Some((_key, manager)) = self.orchestrators.remove(&stream_id) {
+				let removed = self.orchestrators.remove(&stream_id);
+				if let Some((_key, manager)) = &removed {
 					manager.shutdown().await;
 					info!("✅ Orchestrator removed and cleaned up for stream: {}", stream_id);
 				}
+				self.replay.forget(&stream_id);
+
+				if let Some((_key, manager)) = removed.filter(|_| failed) {
+					self.schedule_restart(stream_id, &manager);
+				}
+			}
+			SupervisorMsg::RestartStream {
+				stream_id,
+				restarts,
+				policy,
+				config,
+			} => {
+				// A command may have recreated the stream during the backoff
+				if self.orchestrators.contains_key(&stream_id) {
+					info!("Stream {} already recreated, skipping restart", stream_id);
+					return;
+				}
+				// or a new stream may have taken its slot
+				if let Some(reason) = self.stream_limit_reason() {
+					warn!("Not restarting stream {}: {}", stream_id, reason);
+					publish_error(&self.transport, &stream_id, reason).await;
+					return;
+				}
+				if let Err(e) = self.restart_orchestrator(stream_id.clone(), restarts, policy, config).await {
+					error!("Failed to restart stream {}: {}", stream_id, e);
+				}
 			}
 		}
 	}
 
+	/// Ask the supervisor to recreate a failed stream once its backoff passes, if its policy allows
+	fn schedule_restart(&self, stream_id: StreamId, manager: &ManagedOrchestrator) {
+		let policy = manager.restart_policy;
+		let Some(backoff) = policy.next_backoff(manager.restarts) else {
+			if policy != RestartPolicy::Never {
+				warn!("Stream {} failed after {} restarts, giving up", stream_id, manager.restarts);
+			}
+			return;
+		};
+
+		let restarts = manager.restarts + 1;
+		info!("🔁 Restarting failed stream {} in {:?} (restart {})", stream_id, backoff, restarts);
+		let msg = SupervisorMsg::RestartStream {
+			stream_id,
+			restarts,
+			policy,
+			config: manager.config(),
+		};
+		let supervisor_tx = self.supervisor_tx.clone();
+		tokio::spawn(async move {
+			tokio::time::sleep(backoff).await;
+			let _ = supervisor_tx.send(msg);
+		});
+	}
+
 	async fn handle_event(&self, unified_event: UnifiedEvent) -> anyhow::Result<()> {
 		let event: Event = Result::<Event, String>::from(unified_event).map_err(|e| anyhow::anyhow!("Failed to convert event: {}", e))?;
 
@@ -199,6 +417,10 @@ impl OrchestratorService {
 		// Get or create orchestrator
 		let managed = if let Some(mgr) = self.orchestrators.get(&stream_id) {
 			Arc::clone(&mgr)
+		} else if let Some(reason) = self.stream_limit_reason() {
+			warn!("Rejecting {} for new stream {}: {}", command_name(&cmd), stream_id, reason);
+			publish_error(&self.transport, &stream_id, reason.clone()).await;
+			anyhow::bail!("{reason}");
 		} else {
 			info!("Creating new orchestrator for stream: {}", stream_id);
 			self.create_orchestrator(stream_id.clone()).await?
@@ -213,8 +435,31 @@ impl OrchestratorService {
 		Ok(())
 	}
 
+	/// Why no further stream can be created, if the service is at its stream limit
+	fn stream_limit_reason(&self) -> Option<String> {
+		(self.orchestrators.len() >= self.max_streams).then(|| ["stream limit of ", &self.max_streams.to_string(), " reached"].concat())
+	}
+
 	async fn create_orchestrator(&self, stream_id: StreamId) -> anyhow::Result<Arc<ManagedOrchestrator>> {
-		let manager = Arc::new(ManagedOrchestrator::new(&self.cancel_token)?);
+		let manager = ManagedOrchestrator::new(&self.cancel_token)?.with_restart_policy(self.restart_policy);
+		Ok(self.insert_orchestrator(stream_id, manager).await)
+	}
+
+	/// Recreate a failed stream, re-applying its last configuration and starting it
+	async fn restart_orchestrator(&self, stream_id: StreamId, restarts: u32, policy: RestartPolicy, config: Option<OrchestratorConfigData>) -> anyhow::Result<()> {
+		let mut manager = ManagedOrchestrator::new(&self.cancel_token)?.with_restart_policy(policy);
+		manager.restarts = restarts;
+		let manager = self.insert_orchestrator(stream_id, manager).await;
+
+		if let Some(config) = config {
+			manager.send_command(OrchestratorCommandData::Configure(config)).await?;
+			manager.send_command(OrchestratorCommandData::Start).await?;
+		}
+		Ok(())
+	}
+
+	async fn insert_orchestrator(&self, stream_id: StreamId, manager: ManagedOrchestrator) -> Arc<ManagedOrchestrator> {
+		let manager = Arc::new(manager);
 
 		// Spawn state publisher with supervisor channel
 		let state_publisher_handle = self.spawn_state_publisher(stream_id.clone(), &manager, self.supervisor_tx.clone());
@@ -226,7 +471,7 @@ impl OrchestratorService {
 
 		info!("✅ Orchestrator created for stream: {}", stream_id);
 
-		Ok(manager)
+		manager
 	}
 
 	/// Spawn a task that publishes state updates and observes terminal states
@@ -238,6 +483,7 @@ impl OrchestratorService {
 	) -> tokio::task::JoinHandle<()> {
 		let mut state_rx = manager.subscribe();
 		let transport = self.transport.clone();
+		let replay = self.replay.clone();
 		let stream_id_clone = stream_id.clone();
 		let cancel_token = manager.cancel_token.clone();
 
@@ -256,26 +502,18 @@ impl OrchestratorService {
 
 						let state = state_rx.borrow().clone();
 
-						// Publish state to NATS
-						let event = Event::OrchestratorState {
-							stream_id: stream_id_clone.clone(),
-							state: state.clone(),
-						};
-
-						if let Ok(unified_event) = event.try_into() {
-							let subject = EventType::OrchestratorState.subject();
-							if let Err(e) = transport.send_to_subject(subject, unified_event).await {
-								error!("Failed to publish state for stream {}: {}", stream_id_clone, e);
-							}
-						} else {
-							warn!("Failed to convert OrchestratorState to UnifiedEvent");
-						}
+						// Keep the latest snapshot for late subscribers, then publish to NATS
+						replay.record(&stream_id_clone, state.clone());
+						publish_state(&transport, &stream_id_clone, state.clone()).await;
 
 						// Observe terminal state and notify supervisor
 						// This is pure observation, not cleanup
 						if state.is_terminal() {
 							info!("🏁 Terminal state reached for stream: {}", stream_id_clone);
-							let _ = supervisor_tx.send(SupervisorMsg::StreamTerminated(stream_id_clone.clone()));
+							let _ = supervisor_tx.send(SupervisorMsg::StreamTerminated {
+								stream_id: stream_id_clone.clone(),
+								failed: state.is_failure(),
+							});
 							break;
 						}
 					}
@@ -286,6 +524,35 @@ impl OrchestratorService {
 		})
 	}
 
+	/// Reply on `reply` with the state of the stream named in `query`, or of every stream if it names none
+	async fn answer_state_query(&self, query: UnifiedEvent, reply: &str) -> anyhow::Result<()> {
+		let event: Event = Result::<Event, String>::from(query).map_err(|e| anyhow::anyhow!("Failed to convert event: {}", e))?;
+		let Event::OrchestratorStateQuery { stream_id } = event else {
+			anyhow::bail!("Received unexpected event type in state query handler");
+		};
+
+		let answer: UnifiedEvent = self.state_query_answer(stream_id).try_into().map_err(anyhow::Error::msg)?;
+		self.transport.send_to_subject(reply, answer).await?;
+		Ok(())
+	}
+
+	/// The stream's current state, or an `OrchestratorError` with [`STREAM_NOT_FOUND`]
+	///
+	/// An empty `stream_id` gets the latest state of every stream, so a late
+	/// subscriber can catch up without the states being re-published to everyone.
+	fn state_query_answer(&self, stream_id: StreamId) -> Event {
+		if stream_id.is_empty() {
+			return Event::OrchestratorStates { states: self.replay.snapshots() };
+		}
+		match self.get_state(&stream_id) {
+			Some(state) => Event::OrchestratorState { stream_id, state },
+			None => Event::OrchestratorError {
+				stream_id,
+				reason: STREAM_NOT_FOUND.to_string(),
+			},
+		}
+	}
+
 	async fn shutdown_all(&self) {
 		info!("Shutting down all orchestrators...");
 
@@ -305,6 +572,39 @@ impl OrchestratorService {
 		self.cancel_token.cancel();
 	}
 
+	/// Pause every stream that hasn't reached a terminal state
+	///
+	/// Returns each stream's result; a stream that can't be paused (e.g. one
+	/// that isn't running) doesn't stop the rest.
+	pub async fn pause_all(&self) -> Vec<(StreamId, anyhow::Result<()>)> {
+		self.command_all(&OrchestratorCommandData::Pause).await
+	}
+
+	/// Resume every stream that hasn't reached a terminal state, like [`Self::pause_all`]
+	pub async fn resume_all(&self) -> Vec<(StreamId, anyhow::Result<()>)> {
+		self.command_all(&OrchestratorCommandData::Resume).await
+	}
+
+	async fn command_all(&self, cmd: &OrchestratorCommandData) -> Vec<(StreamId, anyhow::Result<()>)> {
+		// Collect first so no map guard is held across the awaits
+		let managers: Vec<(StreamId, Arc<ManagedOrchestrator>)> = self.orchestrators.iter().map(|e| (e.key().clone(), Arc::clone(e.value()))).collect();
+
+		let mut results = Vec::with_capacity(managers.len());
+		for (stream_id, manager) in managers {
+			if manager.current_state().is_terminal() {
+				continue;
+			}
+			let result = manager.send_command(cmd.clone()).await;
+			if let Err(e) = &result {
+				error!("Failed to execute {} for stream {}: {}", command_name(cmd), stream_id, e);
+			}
+			results.push((stream_id, result));
+		}
+
+		info!("{} sent to {} streams", command_name(cmd), results.len());
+		results
+	}
+
 	/// Get current state for a stream
 	pub fn get_state(&self, stream_id: &str) -> Option<OrchestratorState> {
 		self.orchestrators.get(stream_id).map(|mgr| mgr.current_state())
@@ -320,3 +620,295 @@ impl OrchestratorService {
 		self.orchestrators.len()
 	}
 }
+
+async fn publish_state(transport: &impl Transport<UnifiedEvent>, stream_id: &str, state: OrchestratorState) {
+	let event = Event::OrchestratorState {
+		stream_id: stream_id.to_string(),
+		state,
+	};
+
+	if let Ok(unified_event) = event.try_into() {
+		let subject = EventType::OrchestratorState.subject();
+		if let Err(e) = transport.send_to_subject(subject, unified_event).await {
+			error!("Failed to publish state for stream {}: {}", stream_id, e);
+		}
+	} else {
+		warn!("Failed to convert OrchestratorState to UnifiedEvent");
+	}
+}
+
+async fn publish_error(transport: &impl Transport<UnifiedEvent>, stream_id: &str, reason: String) {
+	let event = Event::OrchestratorError {
+		stream_id: stream_id.to_string(),
+		reason,
+	};
+
+	if let Ok(unified_event) = event.try_into() {
+		let subject = EventType::OrchestratorError.subject();
+		if let Err(e) = transport.send_to_subject(subject, unified_event).await {
+			error!("Failed to publish error for stream {}: {}", stream_id, e);
+		}
+	} else {
+		warn!("Failed to convert OrchestratorError to UnifiedEvent");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use nest::testing::CapturedLogs;
+	use some_transport::InMemTransport;
+	use ws_events::events::{OrchestratorConfigData, SceneConfigData};
+
+	fn config() -> OrchestratorConfigData {
+		OrchestratorConfigData {
+			scenes: vec![SceneConfigData {
+				scene_name: "intro".into(),
+				duration: 60_000,
+				start_time: None,
+				ui: vec![],
+			}],
+			tick_interval_ms: 10,
+			loop_scenes: false,
+		}
+	}
+
+	#[tokio::test]
+	async fn test_send_command_logs_transition() {
+		let captured = CapturedLogs::default();
+		let _guard = captured.install();
+
+		let token = CancellationToken::new();
+		let managed = ManagedOrchestrator::new(&token).unwrap();
+
+		assert!(managed.send_command(OrchestratorCommandData::Start).await.is_err());
+		managed.send_command(OrchestratorCommandData::Configure(config())).await.unwrap();
+		managed.send_command(OrchestratorCommandData::Start).await.unwrap();
+		managed.shutdown().await;
+
+		let transitions = captured.with_message("orchestrator command transition");
+		let fields: Vec<_> = transitions
+			.iter()
+			.map(|event| ["command", "from", "to", "accepted"].map(|name| event.field(name).unwrap()))
+			.collect();
+		assert_eq!(
+			fields,
+			[
+				["Start", "Unconfigured", "Unconfigured", "false"],
+				["Configure", "Unconfigured", "Idle", "true"],
+				["Start", "Idle", "Running", "true"],
+			]
+		);
+		assert!(captured.with_message("command accepted but FSM did not reach the expected mode").is_empty());
+	}
+
+	/// A client that connects in the background, so no NATS server is needed
+	async fn transport() -> NatsTransport<UnifiedEvent> {
+		let client = async_nats::ConnectOptions::new().retry_on_initial_connect().connect("nats://127.0.0.1:4222").await.unwrap();
+		NatsTransport::new(client)
+	}
+
+	async fn service() -> OrchestratorService {
+		OrchestratorService::new(transport().await)
+	}
+
+	#[tokio::test]
+	async fn test_pause_all_and_resume_all() {
+		let service = service().await;
+
+		for stream_id in ["first", "second"] {
+			service.handle_command(stream_id.to_string(), OrchestratorCommandData::Configure(config())).await.unwrap();
+			service.handle_command(stream_id.to_string(), OrchestratorCommandData::Start).await.unwrap();
+		}
+		let mode = |stream_id: &str| service.get_state(stream_id).unwrap().mode;
+
+		let paused = service.pause_all().await;
+		let mut streams: Vec<&str> = paused.iter().map(|(stream_id, _)| stream_id.as_str()).collect();
+		streams.sort_unstable();
+		assert_eq!(streams, ["first", "second"]);
+		assert!(paused.iter().all(|(_, result)| result.is_ok()));
+		assert_eq!((mode("first"), mode("second")), (OrchestratorMode::Paused, OrchestratorMode::Paused));
+
+		let resumed = service.resume_all().await;
+		assert_eq!(resumed.len(), 2);
+		assert!(resumed.iter().all(|(_, result)| result.is_ok()));
+		assert_eq!((mode("first"), mode("second")), (OrchestratorMode::Running, OrchestratorMode::Running));
+
+		service.shutdown_all().await;
+	}
+
+	#[tokio::test]
+	async fn test_run_answers_state_queries() {
+		let transport = InMemTransport::<UnifiedEvent>::new(16);
+		let service = OrchestratorService::new(transport.clone());
+		service.handle_command("live".to_string(), OrchestratorCommandData::Configure(config())).await.unwrap();
+
+		let running = service.clone();
+		let run = tokio::spawn(async move { running.run().await });
+		// The loop is listening once it has subscribed to commands and queries
+		tokio::time::timeout(Duration::from_secs(1), async {
+			while transport.total_receivers() < 2 {
+				tokio::task::yield_now().await;
+			}
+		})
+		.await
+		.unwrap();
+
+		let query = |stream_id: &str| {
+			let query = UnifiedEvent::try_from(Event::OrchestratorStateQuery { stream_id: stream_id.to_string() }).unwrap();
+			let transport = transport.clone();
+			async move {
+				let reply = tokio::time::timeout(Duration::from_secs(1), transport.request(EventType::OrchestratorStateQuery.subject(), query))
+					.await
+					.unwrap()
+					.unwrap();
+				Result::<Event, String>::from(reply).unwrap()
+			}
+		};
+
+		match query("live").await {
+			Event::OrchestratorState { stream_id, state } => {
+				assert_eq!(stream_id, "live");
+				assert_eq!(state.mode, OrchestratorMode::Idle);
+			}
+			other => panic!("expected the stream's state, got {other:?}"),
+		}
+		match query("missing").await {
+			Event::OrchestratorError { stream_id, reason } => {
+				assert_eq!(stream_id, "missing");
+				assert_eq!(reason, STREAM_NOT_FOUND);
+			}
+			other => panic!("expected a not-found error, got {other:?}"),
+		}
+
+		// A late subscriber asks for every stream; the publisher records them in the background
+		let states = tokio::time::timeout(Duration::from_secs(1), async {
+			loop {
+				match query("").await {
+					Event::OrchestratorStates { states } if !states.is_empty() => return states,
+					Event::OrchestratorStates { .. } => tokio::task::yield_now().await,
+					other => panic!("expected every stream's state, got {other:?}"),
+				}
+			}
+		})
+		.await
+		.unwrap();
+		assert_eq!(states.len(), 1);
+		assert_eq!(states[0].0, "live");
+		assert_eq!(states[0].1.mode, OrchestratorMode::Idle);
+
+		service.shutdown();
+		run.await.unwrap().unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_streams_beyond_the_limit_are_rejected() {
+		let transport = InMemTransport::<UnifiedEvent>::new(16);
+		let mut errors = transport.subscribe_to_subject(EventType::OrchestratorError.subject()).await;
+		let service = OrchestratorService::with_limits(transport.clone(), 2);
+
+		for stream_id in ["first", "second"] {
+			service.handle_command(stream_id.to_string(), OrchestratorCommandData::Configure(config())).await.unwrap();
+		}
+		let rejected = service.handle_command("third".to_string(), OrchestratorCommandData::Configure(config())).await;
+		assert!(rejected.unwrap_err().to_string().contains("stream limit of 2"));
+
+		// The rejection is also published for clients that aren't waiting on the command
+		let published = tokio::time::timeout(Duration::from_secs(1), errors.recv()).await.unwrap().unwrap();
+		match Result::<Event, String>::from(published).unwrap() {
+			Event::OrchestratorError { stream_id, reason } => {
+				assert_eq!(stream_id, "third");
+				assert_eq!(reason, "stream limit of 2 reached");
+			}
+			other => panic!("expected the rejection on orchestrator.error, got {other:?}"),
+		}
+
+		let mut streams = service.list_streams();
+		streams.sort_unstable();
+		assert_eq!(streams, ["first", "second"]);
+
+		// Existing streams still take commands at the limit
+		service.handle_command("first".to_string(), OrchestratorCommandData::Start).await.unwrap();
+		assert_eq!(service.get_state("first").unwrap().mode, OrchestratorMode::Running);
+
+		service.shutdown_all().await;
+	}
+
+	#[tokio::test]
+	async fn test_failed_stream_is_restarted_until_retries_run_out() {
+		// Hold the supervisor's receiving end, as `run` would
+		let (supervisor_tx, mut supervisor_rx) = mpsc::unbounded_channel();
+		let policy = RestartPolicy::OnFailure {
+			max_retries: 2,
+			backoff: Duration::from_millis(10),
+		};
+		let service = OrchestratorService {
+			supervisor_tx,
+			..service().await.with_restart_policy(policy)
+		};
+		let failed = |stream_id: &str| SupervisorMsg::StreamTerminated {
+			stream_id: stream_id.to_string(),
+			failed: true,
+		};
+
+		service.handle_command("flaky".to_string(), OrchestratorCommandData::Configure(config())).await.unwrap();
+		service.handle_command("flaky".to_string(), OrchestratorCommandData::Start).await.unwrap();
+
+		for restarts in 1..=2 {
+			service.handle_supervisor_msg(failed("flaky")).await;
+			assert!(service.get_state("flaky").is_none());
+
+			let restart = tokio::time::timeout(Duration::from_secs(1), supervisor_rx.recv()).await.unwrap().unwrap();
+			service.handle_supervisor_msg(restart).await;
+			let manager = Arc::clone(&service.orchestrators.get("flaky").unwrap());
+			assert_eq!(manager.restarts(), restarts);
+			assert_eq!(manager.current_state().mode, OrchestratorMode::Running, "restarted with its last config");
+		}
+
+		// Out of retries, the stream is dropped for good
+		service.handle_supervisor_msg(failed("flaky")).await;
+		assert!(service.list_streams().is_empty());
+		assert!(tokio::time::timeout(Duration::from_millis(100), supervisor_rx.recv()).await.is_err());
+
+		// A clean stop is never restarted
+		service.handle_command("stopped".to_string(), OrchestratorCommandData::Configure(config())).await.unwrap();
+		service
+			.handle_supervisor_msg(SupervisorMsg::StreamTerminated {
+				stream_id: "stopped".to_string(),
+				failed: false,
+			})
+			.await;
+		assert!(service.list_streams().is_empty());
+		assert!(tokio::time::timeout(Duration::from_millis(100), supervisor_rx.recv()).await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_restart_respects_the_stream_limit() {
+		let (supervisor_tx, mut supervisor_rx) = mpsc::unbounded_channel();
+		let policy = RestartPolicy::OnFailure {
+			max_retries: 1,
+			backoff: Duration::from_millis(10),
+		};
+		let service = OrchestratorService {
+			supervisor_tx,
+			..OrchestratorService::with_limits(transport().await, 1).with_restart_policy(policy)
+		};
+
+		service.handle_command("flaky".to_string(), OrchestratorCommandData::Configure(config())).await.unwrap();
+		service
+			.handle_supervisor_msg(SupervisorMsg::StreamTerminated {
+				stream_id: "flaky".to_string(),
+				failed: true,
+			})
+			.await;
+
+		// Another stream takes the freed slot during the backoff
+		service.handle_command("newcomer".to_string(), OrchestratorCommandData::Configure(config())).await.unwrap();
+
+		let restart = tokio::time::timeout(Duration::from_secs(1), supervisor_rx.recv()).await.unwrap().unwrap();
+		service.handle_supervisor_msg(restart).await;
+		assert_eq!(service.list_streams(), ["newcomer"]);
+
+		service.shutdown_all().await;
+	}
+}
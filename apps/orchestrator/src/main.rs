@@ -16,10 +16,13 @@ async fn main() -> Result<()> {
 	tracing::info!("📡 Connecting to NATS at {}", nats_url);
 
 	// Create NATS transport using the pooled connection
-	let transport: NatsTransport<UnifiedEvent> = NatsTransport::connect_pooled(&nats_url).await?;
+	let transport: NatsTransport<UnifiedEvent> = NatsTransport::connect_pooled(&nats_url)
+		.await?
+		.with_schema_fingerprint(UnifiedEvent::SCHEMA_FINGERPRINT.to_string());
 	tracing::info!("✅ Connected to NATS");
 	tracing::info!("   - Commands: listening on {}", ws_events::events::EventType::OrchestratorCommandData.subject());
 	tracing::info!("   - State: publishing on {}", ws_events::events::EventType::OrchestratorState.subject());
+	tracing::info!("   - State queries: answering on {}", ws_events::events::EventType::OrchestratorStateQuery.subject());
 
 	let service = OrchestratorService::new(transport);
 	tracing::info!("🎯 Service initialized");
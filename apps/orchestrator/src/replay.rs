@@ -0,0 +1,72 @@
+use dashmap::DashMap;
+use std::sync::Arc;
+use ws_events::events::OrchestratorState;
+
+/// Latest state per stream, replayed to subscribers that join late
+///
+/// Every state the publisher emits is recorded here before it goes out, so a
+/// new subscriber can ask for the current snapshot of each stream straight
+/// away instead of waiting for the next transition.
+#[derive(Clone, Default)]
+pub struct StateReplay {
+	latest: Arc<DashMap<String, OrchestratorState>>,
+}
+
+impl StateReplay {
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Remember `state` as the current one for `stream_id`
+	pub fn record(&self, stream_id: &str, state: OrchestratorState) {
+		self.latest.insert(stream_id.to_string(), state);
+	}
+
+	/// Drop the snapshot for a stream that no longer exists
+	pub fn forget(&self, stream_id: &str) {
+		self.latest.remove(stream_id);
+	}
+
+	#[must_use]
+	pub fn latest(&self, stream_id: &str) -> Option<OrchestratorState> {
+		self.latest.get(stream_id).map(|entry| entry.value().clone())
+	}
+
+	/// Current state of every stream
+	#[must_use]
+	pub fn snapshots(&self) -> Vec<(String, OrchestratorState)> {
+		self.latest.iter().map(|entry| (entry.key().clone(), entry.value().clone())).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ws_events::events::OrchestratorMode;
+
+	fn state(mode: OrchestratorMode, current_time: i64) -> OrchestratorState {
+		OrchestratorState {
+			mode,
+			current_time,
+			..OrchestratorState::new(60_000)
+		}
+	}
+
+	#[test]
+	fn test_snapshots_hold_only_the_latest_state_of_live_streams() {
+		let replay = StateReplay::new();
+		replay.record("stream-1", state(OrchestratorMode::Idle, 0));
+		replay.record("stream-1", state(OrchestratorMode::Running, 1_500));
+		replay.record("stream-2", state(OrchestratorMode::Idle, 0));
+
+		let current = replay.latest("stream-1").unwrap();
+		assert_eq!(current.mode, OrchestratorMode::Running);
+		assert_eq!(current.current_time, 1_500);
+
+		replay.forget("stream-2");
+		let snapshots = replay.snapshots();
+		assert_eq!(snapshots.len(), 1);
+		assert_eq!(snapshots[0].0, "stream-1");
+	}
+}
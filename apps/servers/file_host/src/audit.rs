@@ -0,0 +1,278 @@
+use axum::{
+	extract::{ConnectInfo, Request, State},
+	http::HeaderValue,
+	middleware::Next,
+	response::Response,
+};
+use chrono::{SecondsFormat, Utc};
+use sqlx::{Executor, SqlitePool};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+/// Most records written in one transaction
+const MAX_BATCH: usize = 64;
+
+const CREATE_AUDIT_LOG: &str = "
+CREATE TABLE IF NOT EXISTS audit_log (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    request_id  TEXT    NOT NULL,
+    method      TEXT    NOT NULL,
+    path        TEXT    NOT NULL,
+    client      TEXT    NOT NULL,
+    received_at TEXT    NOT NULL,  -- RFC 3339, UTC
+    status      INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_audit_log_received_at ON audit_log(received_at);
+CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;
+CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;
+";
+
+/// One mutating request, as stored in `audit_log`
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct AuditRecord {
+	pub request_id: String,
+	pub method: String,
+	pub path: String,
+	pub client: String,
+	pub received_at: String,
+	pub status: u16,
+}
+
+/// Append-only record of every mutating request, kept in the shared database
+///
+/// Records are handed to a background task over a bounded channel and written
+/// in batches, so a slow disk never holds up a response. When the buffer is
+/// full the record is dropped and logged instead.
+pub struct AuditLog {
+	tx: mpsc::Sender<AuditRecord>,
+}
+
+impl AuditLog {
+	/// Create the `audit_log` table if needed and start the writer task
+	///
+	/// The writer flushes what's buffered and exits once the last handle is dropped.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the table can't be created.
+	pub async fn spawn(pool: SqlitePool, buffer: usize) -> Result<Self, sqlx::Error> {
+		pool.execute(CREATE_AUDIT_LOG).await?;
+
+		let (tx, rx) = mpsc::channel(buffer.max(1));
+		tokio::spawn(write_records(pool, rx));
+		Ok(Self { tx })
+	}
+
+	fn record(&self, record: AuditRecord) {
+		if let Err(e) = self.tx.try_send(record) {
+			let record = e.into_inner();
+			tracing::error!(request_id = %record.request_id, method = %record.method, path = %record.path, "audit buffer full, dropping record");
+		}
+	}
+}
+
+async fn write_records(pool: SqlitePool, mut rx: mpsc::Receiver<AuditRecord>) {
+	let mut batch = Vec::with_capacity(MAX_BATCH);
+	while rx.recv_many(&mut batch, MAX_BATCH).await > 0 {
+		if let Err(e) = insert_batch(&pool, &batch).await {
+			tracing::error!(error = %e, count = batch.len(), "failed to write audit records");
+		}
+		batch.clear();
+	}
+}
+
+async fn insert_batch(pool: &SqlitePool, batch: &[AuditRecord]) -> Result<(), sqlx::Error> {
+	let mut tx = pool.begin().await?;
+	for record in batch {
+		sqlx::query("INSERT INTO audit_log (request_id, method, path, client, received_at, status) VALUES (?, ?, ?, ?, ?, ?)")
+			.bind(&record.request_id)
+			.bind(&record.method)
+			.bind(&record.path)
+			.bind(&record.client)
+			.bind(&record.received_at)
+			.bind(record.status)
+			.execute(tx.as_mut())
+			.await?;
+	}
+	tx.commit().await
+}
+
+/// Audits every request that isn't a safe method (GET, HEAD, OPTIONS, TRACE)
+///
+/// The request id comes from `X-Request-Id` when the client sends one and is
+/// generated otherwise; either way it's echoed back on the response. Must sit
+/// outside any `nest`, since it records the full request path.
+pub async fn audit_middleware(State(audit): State<Arc<AuditLog>>, req: Request, next: Next) -> Response {
+	if req.method().is_safe() {
+		return next.run(req).await;
+	}
+
+	let request_id = req
+		.headers()
+		.get(REQUEST_ID_HEADER)
+		.and_then(|v| v.to_str().ok())
+		.filter(|id| !id.is_empty())
+		.map_or_else(|| uuid::Uuid::new_v4().to_string(), ToString::to_string);
+	let client = req
+		.extensions()
+		.get::<ConnectInfo<SocketAddr>>()
+		.map_or_else(|| "unknown".to_string(), |ConnectInfo(addr)| addr.ip().to_string());
+	let method = req.method().to_string();
+	let path = req.uri().path().to_string();
+	let received_at = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+
+	let mut response = next.run(req).await;
+	let status = response.status().as_u16();
+	if let Ok(value) = HeaderValue::from_str(&request_id) {
+		response.headers_mut().insert(REQUEST_ID_HEADER, value);
+	}
+
+	audit.record(AuditRecord {
+		request_id,
+		method,
+		path,
+		client,
+		received_at,
+		status,
+	});
+	response
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		realtime::spawn_transport_supervisor, routes::utterance::post_utterance, AppState, CacheStore, Config, CoreContext, DedupCache, ExternalApis, IpConnectionLimit,
+		RealtimeContext, WebSocketFsm, API_V1_BASE_PATH,
+	};
+	use axum::{body::Body, http::StatusCode, middleware::from_fn_with_state, Router};
+	use clap::Parser;
+	use sdk::{GitHubClient, ReadDrive, ReadSheets, WriteToDrive};
+	use some_transport::{nats::JetStreamPublisher, NatsTransport};
+	use sqlx::sqlite::SqlitePoolOptions;
+	use std::{sync::Mutex, time::Duration};
+	use tokio_util::sync::CancellationToken;
+	use tower::ServiceExt;
+	use ws_conn_manager::ConnectionGuard;
+
+	const UNREACHABLE_NATS: &str = "nats://127.0.0.1:1";
+
+	/// What the real routes need, with nothing behind it
+	///
+	/// NATS never connects, so publishes fail fast, and the Google clients only
+	/// check that their secret file exists until they're used.
+	async fn offline_state(pool: SqlitePool, cancel: &CancellationToken) -> AppState {
+		let secret_dir = std::env::temp_dir().join(["file_host_audit_", &std::process::id().to_string()].concat());
+		std::fs::create_dir_all(&secret_dir).unwrap();
+		let secret_file = secret_dir.join("client_secret_file.json");
+		std::fs::write(&secret_file, "{}").unwrap();
+		let secret_file = secret_file.to_string_lossy().into_owned();
+
+		let config = Arc::new(
+			Config::try_parse_from([
+				"file_host",
+				"--hmac-key=test",
+				&["--client-secret-file=", &secret_file].concat(),
+				"--obs-host=127.0.0.1",
+				"--obs-password=test",
+				"--github-token=test",
+				"--database-url=sqlite::memory:",
+			])
+			.unwrap(),
+		);
+		let client = async_nats::ConnectOptions::new().retry_on_initial_connect().connect(UNREACHABLE_NATS).await.unwrap();
+
+		AppState {
+			core: CoreContext {
+				config: config.clone(),
+				cancel_token: cancel.clone(),
+				shared_db: pool,
+				connection_guard: ConnectionGuard::new(),
+				ip_connection_limit: IpConnectionLimit::new(config.ws_max_connections_per_ip),
+				otel_guard: Arc::new(Mutex::new(None)),
+			},
+			external: ExternalApis {
+				gsheet_reader: Arc::new(ReadSheets::new(String::new(), secret_file.clone()).unwrap()),
+				gdrive_reader: Arc::new(ReadDrive::new(String::new(), secret_file.clone()).unwrap()),
+				gdrive_writer: Arc::new(WriteToDrive::new(String::new(), secret_file).unwrap()),
+				github_client: Arc::new(GitHubClient::new("test".to_string()).unwrap()),
+			},
+			realtime: RealtimeContext {
+				ws: WebSocketFsm::new(),
+				dedup_cache: Arc::new(DedupCache::new(Arc::new(CacheStore::new(config.as_cache_config()).unwrap()), 16)),
+				transport: spawn_transport_supervisor(UNREACHABLE_NATS.to_string(), NatsTransport::new(client.clone()), cancel.clone()),
+				pipeline_publisher: Arc::new(JetStreamPublisher::from_client(client)),
+			},
+		}
+	}
+
+	async fn audit_rows(pool: &SqlitePool, expected: usize) -> Vec<AuditRecord> {
+		for _ in 0..100 {
+			let rows: Vec<AuditRecord> = sqlx::query_as("SELECT request_id, method, path, client, received_at, status FROM audit_log ORDER BY id")
+				.fetch_all(pool)
+				.await
+				.unwrap();
+			if rows.len() >= expected {
+				return rows;
+			}
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+		panic!("audit rows were not written");
+	}
+
+	#[tokio::test]
+	async fn test_posted_utterance_is_audited() {
+		// A single connection, since every in-memory connection is its own database
+		let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+		let audit = Arc::new(AuditLog::spawn(pool.clone(), 16).await.unwrap());
+		let cancel = CancellationToken::new();
+		// Wired like main: the real utterance route under the API prefix, audited from outside
+		let app = Router::new()
+			.nest(API_V1_BASE_PATH, post_utterance())
+			.layer(from_fn_with_state(audit, audit_middleware))
+			.with_state(offline_state(pool.clone(), &cancel).await);
+
+		let mut utterance = Request::post("/api/v1/utter")
+			.header(REQUEST_ID_HEADER, "req-42")
+			.header("content-type", "application/json")
+			.body(Body::from(
+				r#"{"text":"hello","metadata":{"url":"https://example.com","domain":"example.com","title":"Example","timestamp":"2026-10-17T00:00:00Z","element":{"tagName":"input"}}}"#,
+			))
+			.unwrap();
+		utterance.extensions_mut().insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 7], 4000))));
+		let response = app.clone().oneshot(utterance).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-42");
+
+		// Reads aren't audited; a generated id is echoed back when none is sent
+		app.clone().oneshot(Request::get("/api/v1/utter").body(Body::empty()).unwrap()).await.unwrap();
+		let anonymous = app.oneshot(Request::delete("/api/v1/utter").body(Body::empty()).unwrap()).await.unwrap();
+		let generated_id = anonymous.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+
+		let rows = audit_rows(&pool, 2).await;
+		assert_eq!(rows.len(), 2);
+		assert_eq!(
+			(
+				rows[0].request_id.as_str(),
+				rows[0].method.as_str(),
+				rows[0].path.as_str(),
+				rows[0].client.as_str(),
+				rows[0].status
+			),
+			("req-42", "POST", "/api/v1/utter", "10.0.0.7", 200)
+		);
+		assert!(chrono::DateTime::parse_from_rfc3339(&rows[0].received_at).is_ok());
+		assert_eq!(
+			(rows[1].request_id.as_str(), rows[1].method.as_str(), rows[1].client.as_str(), rows[1].status),
+			(generated_id.as_str(), "DELETE", "unknown", 405)
+		);
+
+		let tampered = sqlx::query("UPDATE audit_log SET status = 500").execute(&pool).await;
+		assert!(tampered.is_err(), "audit rows must be append-only");
+		cancel.cancel();
+	}
+}
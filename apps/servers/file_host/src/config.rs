@@ -102,6 +102,18 @@ pub struct Config {
 	#[arg(long, env = "CACHE_TTL", default_value = "600")]
 	pub cache_ttl: u64,
 
+	/// How long a POST response is replayed for a repeated Idempotency-Key, in seconds
+	#[arg(long, env = "IDEMPOTENCY_TTL", default_value = "86400")]
+	pub idempotency_ttl: u64,
+
+	/// How long clients may cache sheet responses (`Cache-Control: max-age`), in seconds
+	#[arg(long, env = "SHEETS_CACHE_MAX_AGE", default_value = "60")]
+	pub sheets_cache_max_age: u64,
+
+	/// How long clients may cache the GitHub repo list (`Cache-Control: max-age`), in seconds
+	#[arg(long, env = "REPOS_CACHE_MAX_AGE", default_value = "300")]
+	pub repos_cache_max_age: u64,
+
 	/// Enable Prometheus metrics
 	#[arg(long, env = "ENABLE_PROMETHEUS")]
 	pub enable_prometheus: bool,
@@ -122,10 +134,46 @@ pub struct Config {
 	#[arg(long, env = "MAX_REQUEST_SIZE_MB", default_value = "10")]
 	pub max_request_size: usize,
 
+	/// Total size cap for one streamed audio upload in MB, within `MAX_REQUEST_SIZE_MB`
+	#[arg(long, env = "MAX_AUDIO_UPLOAD_MB", default_value = "10")]
+	pub max_audio_upload_mb: usize,
+
 	/// Active request limit
 	#[arg(long, env = "MAX_CONCURRENT_REQUESTS", default_value = "100")]
 	pub max_concurrent_req: usize,
 
+	/// Tighter active request limits for expensive routes, as `path=limit,...`
+	#[arg(long, env = "ROUTE_CONCURRENCY_LIMITS", default_value = "")]
+	pub route_concurrency_limits: String,
+
+	/// Audit records buffered for the database writer before new ones are dropped
+	#[arg(long, env = "AUDIT_BUFFER_SIZE", default_value = "1024")]
+	pub audit_buffer_size: usize,
+
+	/// Concurrent WebSocket connections allowed from one remote IP
+	#[arg(long, env = "WS_MAX_CONNECTIONS_PER_IP", default_value = "20")]
+	pub ws_max_connections_per_ip: usize,
+
+	/// Sustained inbound messages per second allowed on one WebSocket connection
+	#[arg(long, env = "WS_MESSAGE_RATE", default_value = "20")]
+	pub ws_message_rate: u32,
+
+	/// Inbound messages a quiet WebSocket connection may send back to back
+	#[arg(long, env = "WS_MESSAGE_BURST", default_value = "40")]
+	pub ws_message_burst: u32,
+
+	/// Throttled messages after which a flooding WebSocket connection is closed
+	#[arg(long, env = "WS_MESSAGE_ABUSE_LIMIT", default_value = "200")]
+	pub ws_message_abuse_limit: u32,
+
+	/// Negotiate permessage-deflate with WebSocket clients that offer it
+	#[arg(long, env = "WS_COMPRESSION")]
+	pub ws_compression: bool,
+
+	/// Outbound WebSocket messages smaller than this many bytes are sent uncompressed
+	#[arg(long, env = "WS_COMPRESSION_THRESHOLD", default_value = "1024")]
+	pub ws_compression_threshold: usize,
+
 	/// Hard timeout for any operation
 	#[arg(long, env = "TASK_TIMEOUT_MS", default_value = "15000")]
 	pub task_timeout_ms: u64,
@@ -150,6 +198,10 @@ pub struct Config {
 	#[arg(long, env = "DATABASE_URL")]
 	pub database_url: String,
 
+	/// Bearer token for `/admin` routes; admin routes are disabled when unset
+	#[arg(long, env = "ADMIN_TOKEN")]
+	pub admin_token: Option<String>,
+
 	/// Perform health check and exit
 	#[arg(long, help = "Perform health check against running server")]
 	pub health_check: bool,
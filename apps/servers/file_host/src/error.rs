@@ -54,6 +54,9 @@ pub enum FileHostError {
 	#[error("maximum record limit exceeded")]
 	MaxRecordLimitExceeded,
 
+	#[error("payload exceeds {limit} bytes")]
+	PayloadTooLarge { limit: u64 },
+
 	// ---- transparent from-conversions ----
 	#[error("serialization error: {0}")]
 	NonSerializableData(#[from] serde_json::Error),
@@ -167,6 +170,7 @@ impl FileHostError {
 			Self::InvalidMimeType(_) | Self::MaxRecordLimitExceeded | Self::IntegerConversionError(_) | Self::OperationError(_) | Self::UnexpectedSinglePair => {
 				StatusCode::BAD_REQUEST
 			}
+			Self::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
 			Self::RequestTimeout => StatusCode::REQUEST_TIMEOUT,
 			Self::ServiceOverloaded => StatusCode::SERVICE_UNAVAILABLE,
 			Self::AudioFetchError(_) => StatusCode::BAD_REQUEST,
@@ -189,6 +193,7 @@ impl FileHostError {
 			Self::InvalidEncodedDate(_) => "invalid_encoded_date",
 			Self::UnprocessableEntity { .. } => "unprocessable_entity",
 			Self::MaxRecordLimitExceeded => "max_record_limit_exceeded",
+			Self::PayloadTooLarge { .. } => "payload_too_large",
 			Self::NonSerializableData(_) => "serialization_error",
 			Self::IntegerConversionError(_) => "integer_conversion_error",
 			Self::ResponseBuildError(_) => "response_build_error",
@@ -221,6 +226,7 @@ impl FileHostError {
 			Self::InvalidEncodedDate(_) => "invalid encoded date",
 			Self::UnprocessableEntity { .. } => "error in request body",
 			Self::MaxRecordLimitExceeded => "maximum record limit exceeded",
+			Self::PayloadTooLarge { .. } => "payload too large",
 			Self::RequestTimeout => "request timeout",
 			Self::ServiceOverloaded => "service temporarily overloaded",
 			Self::AudioFetchError(_) => "audio fetch error",
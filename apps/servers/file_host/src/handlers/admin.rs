@@ -0,0 +1,122 @@
+use crate::{AppState, Config, FileHostError, WebSocketFsm};
+use axum::{
+	extract::{Path, State},
+	http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+};
+use subtle::ConstantTimeEq;
+use tracing::instrument;
+
+/// Check `Authorization: Bearer <ADMIN_TOKEN>`; admin routes are off when no token is configured
+///
+/// The token is compared in constant time so response timing doesn't reveal
+/// how much of a guess was right.
+fn authorize(config: &Config, headers: &HeaderMap) -> Result<(), FileHostError> {
+	let Some(expected) = config.admin_token.as_deref() else {
+		return Err(FileHostError::Forbidden);
+	};
+
+	let provided = headers.get(AUTHORIZATION).and_then(|v| v.to_str().ok()).and_then(|v| v.strip_prefix("Bearer "));
+
+	match provided {
+		Some(token) if bool::from(token.as_bytes().ct_eq(expected.as_bytes())) => Ok(()),
+		_ => Err(FileHostError::Unauthorized),
+	}
+}
+
+/// `POST /admin/connections/:connection_id/close`
+///
+/// Force-closes a WebSocket connection with a policy close code (1008).
+/// Its `ConnectionGuard` slot is freed as soon as the socket tasks exit.
+///
+/// # Errors
+///
+/// Returns `Unauthorized`/`Forbidden` for a bad or unconfigured admin token,
+/// and `NotFound` if no connection has that id.
+#[instrument(name = "admin_close_connection", skip(state, headers))]
+pub async fn close_connection(State(state): State<AppState>, headers: HeaderMap, Path(connection_id): Path<String>) -> Result<StatusCode, FileHostError> {
+	force_close(&state.realtime.ws, &state.core.config, &headers, &connection_id).await
+}
+
+async fn force_close(ws: &WebSocketFsm, config: &Config, headers: &HeaderMap, connection_id: &str) -> Result<StatusCode, FileHostError> {
+	authorize(config, headers)?;
+
+	let evicted = ws.evict_connection(connection_id).await.map_err(|e| FileHostError::OperationError(e.to_string()))?;
+
+	if evicted {
+		Ok(StatusCode::NO_CONTENT)
+	} else {
+		Err(FileHostError::NotFound)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::http::HeaderValue;
+	use clap::Parser;
+	use std::net::SocketAddr;
+	use std::sync::Arc;
+	use tokio::time::{timeout, Duration};
+	use tokio_util::sync::CancellationToken;
+	use ws_conn_manager::ConnectionGuard;
+
+	fn admin_config() -> Arc<Config> {
+		let config = Config::try_parse_from([
+			"file_host",
+			"--hmac-key=test",
+			"--client-secret-file=test",
+			"--obs-host=127.0.0.1",
+			"--obs-password=test",
+			"--github-token=test",
+			"--database-url=sqlite::memory:",
+			"--admin-token=s3cret",
+		])
+		.unwrap();
+		Arc::new(config)
+	}
+
+	fn bearer(token: &str) -> HeaderMap {
+		let mut headers = HeaderMap::new();
+		headers.insert(AUTHORIZATION, HeaderValue::from_str(&["Bearer ", token].concat()).unwrap());
+		headers
+	}
+
+	#[tokio::test]
+	async fn test_force_close_removes_connection_and_frees_slot() {
+		let fsm = WebSocketFsm::new();
+		let guard = ConnectionGuard::new();
+		let cancel = CancellationToken::new();
+		let addr: SocketAddr = "127.0.0.1:9000".parse().unwrap();
+
+		let permit = guard.acquire(addr.ip().to_string()).await.unwrap();
+		let key = fsm.add_connection(&HeaderMap::new(), &addr, &cancel).await.unwrap();
+		assert_eq!(guard.active_global(), 1);
+
+		// Mock socket: exits when its connection is removed, releasing the permit like ConnectionCleanup
+		let socket_cancel = fsm.connection_token(&key).unwrap().child_token();
+		let socket = tokio::spawn(async move {
+			socket_cancel.cancelled().await;
+			permit.release();
+		});
+
+		// Wrong token is rejected and leaves the connection alone
+		let config = admin_config();
+		for wrong in ["nope", "s3cre", "s3cret!"] {
+			let denied = force_close(&fsm, &config, &bearer(wrong), &key).await;
+			assert!(matches!(denied, Err(FileHostError::Unauthorized)));
+		}
+		assert!(fsm.connection_token(&key).is_some());
+
+		let status = force_close(&fsm, &config, &bearer("s3cret"), &key).await.unwrap();
+		assert_eq!(status, StatusCode::NO_CONTENT);
+
+		timeout(Duration::from_secs(1), socket).await.expect("socket task should exit").unwrap();
+		assert!(fsm.connection_token(&key).is_none());
+		assert_eq!(guard.active_global(), 0);
+		assert_eq!(fsm.take_close_reason(&key).map(|reason| reason.close_code()), Some(1008));
+
+		// Unknown ids are a 404
+		let missing = force_close(&fsm, &config, &bearer("s3cret"), &key).await;
+		assert!(matches!(missing, Err(FileHostError::NotFound)));
+	}
+}
@@ -0,0 +1,206 @@
+use crate::{AppState, FileHostError};
+use axum::{
+	extract::{multipart::MultipartError, ConnectInfo, Multipart, Query, State},
+	Json,
+};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{future::Future, net::SocketAddr, pin::pin};
+use tokio::time::Duration;
+use tracing::{info, instrument, warn};
+use ws_events::events::Event;
+
+/// Multipart field holding the audio; other fields are skipped
+const AUDIO_FIELD: &str = "audio";
+
+/// How long an upload waits for a connection slot before it is turned away
+const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SAMPLE_BYTES: usize = std::mem::size_of::<f32>();
+
+/// Format of the uploaded PCM, which the transcriber needs with every chunk
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct AudioFormat {
+	pub sample_rate: u32,
+	pub channels: u32,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct IngestSummary {
+	pub bytes: u64,
+	pub chunks: u64,
+}
+
+/// `POST /ingest_audio?sample_rate=16000&channels=1`
+///
+/// Takes a multipart body whose `audio` part is raw little-endian `f32` PCM
+/// and forwards it to the transcriber as `AudioChunk` events while it is
+/// still arriving, so the file is never held in memory. Uploads over
+/// `MAX_AUDIO_UPLOAD_MB` fail with 413 once they cross the cap. The caller's
+/// `ConnectionGuard` slot is held for the upload and freed however it ends.
+///
+/// # Errors
+///
+/// Returns `ServiceOverloaded` when no slot frees up in time, 413 past the
+/// size cap, 422 for a malformed body or missing `audio` part, and a
+/// transport error if a chunk can't be published.
+#[instrument(name = "ingest_audio", skip(state, multipart))]
+pub async fn ingest_audio(
+	State(state): State<AppState>,
+	ConnectInfo(addr): ConnectInfo<SocketAddr>,
+	Query(format): Query<AudioFormat>,
+	mut multipart: Multipart,
+) -> Result<Json<IngestSummary>, FileHostError> {
+	// Dropped on every return path, which frees the slot
+	let _permit = state.core.connection_guard.acquire_timeout(addr.ip().to_string(), ACQUIRE_TIMEOUT).await.map_err(|e| {
+		warn!("Rejecting audio upload from {addr}: {:?}", e.kind);
+		FileHostError::ServiceOverloaded
+	})?;
+
+	let max_bytes = state.core.config.max_audio_upload_mb as u64 * 1024 * 1024;
+	let ws = state.realtime.ws;
+	let transport = state.realtime.transport.current();
+
+	while let Some(field) = multipart.next_field().await.map_err(malformed)? {
+		if field.name() != Some(AUDIO_FIELD) {
+			continue;
+		}
+		let summary = forward_pcm(field.map(|chunk| chunk.map_err(malformed)), format, max_bytes, |event| {
+			let (ws, transport) = (ws.clone(), transport.clone());
+			async move { ws.broadcast_event(transport, event).await.map_err(FileHostError::from) }
+		})
+		.await?;
+
+		info!(bytes = summary.bytes, chunks = summary.chunks, "Audio upload forwarded");
+		return Ok(Json(summary));
+	}
+
+	Err(FileHostError::unprocessable_entity([(AUDIO_FIELD, "missing audio part")]))
+}
+
+fn malformed(e: MultipartError) -> FileHostError {
+	FileHostError::unprocessable_entity([(AUDIO_FIELD, e.body_text())])
+}
+
+/// Forward each body chunk's whole samples as soon as it arrives
+///
+/// A sample split across two chunks is carried over and sent with the next
+/// one. Stops with `PayloadTooLarge` as soon as the total passes `max_bytes`,
+/// without reading the rest of the body.
+async fn forward_pcm<S, F, Fut>(body: S, format: AudioFormat, max_bytes: u64, mut forward: F) -> Result<IngestSummary, FileHostError>
+where
+	S: Stream<Item = Result<Bytes, FileHostError>>,
+	F: FnMut(Event) -> Fut,
+	Fut: Future<Output = Result<(), FileHostError>>,
+{
+	let mut body = pin!(body);
+	let mut summary = IngestSummary::default();
+	let mut partial: Vec<u8> = Vec::with_capacity(SAMPLE_BYTES);
+
+	while let Some(chunk) = body.next().await {
+		let chunk = chunk?;
+		summary.bytes += chunk.len() as u64;
+		if summary.bytes > max_bytes {
+			return Err(FileHostError::PayloadTooLarge { limit: max_bytes });
+		}
+
+		// Complete the sample left over from the previous chunk first
+		let take = (SAMPLE_BYTES - partial.len()) % SAMPLE_BYTES;
+		let (head, rest) = chunk.split_at(take.min(chunk.len()));
+		partial.extend_from_slice(head);
+
+		let mut samples = Vec::with_capacity(rest.len() / SAMPLE_BYTES + 1);
+		if partial.len() == SAMPLE_BYTES {
+			samples.push(f32::from_le_bytes([partial[0], partial[1], partial[2], partial[3]]));
+			partial.clear();
+		}
+		let whole = rest.chunks_exact(SAMPLE_BYTES);
+		partial.extend_from_slice(whole.remainder());
+		samples.extend(whole.map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])));
+
+		if samples.is_empty() {
+			continue;
+		}
+		forward(Event::AudioChunk {
+			sample_rate: format.sample_rate,
+			channels: format.channels,
+			samples,
+		})
+		.await?;
+		summary.chunks += 1;
+	}
+
+	if !partial.is_empty() {
+		return Err(FileHostError::unprocessable_entity([(AUDIO_FIELD, "audio ends partway through a sample")]));
+	}
+	Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tokio::sync::mpsc;
+	use tokio::time::timeout;
+	use tokio_stream::wrappers::UnboundedReceiverStream;
+
+	const FORMAT: AudioFormat = AudioFormat { sample_rate: 16_000, channels: 1 };
+
+	fn pcm(samples: &[f32]) -> Vec<u8> {
+		samples.iter().flat_map(|sample| sample.to_le_bytes()).collect()
+	}
+
+	fn samples_of(event: Event) -> Vec<f32> {
+		match event {
+			Event::AudioChunk { sample_rate, channels, samples } => {
+				assert_eq!((sample_rate, channels), (FORMAT.sample_rate, FORMAT.channels));
+				samples
+			}
+			other => panic!("expected an audio chunk, got {other:?}"),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_chunks_are_forwarded_as_they_arrive() {
+		let (body_tx, body_rx) = mpsc::unbounded_channel();
+		let (forwarded_tx, mut forwarded_rx) = mpsc::unbounded_channel();
+		let upload = tokio::spawn(forward_pcm(UnboundedReceiverStream::new(body_rx), FORMAT, 1024, move |event| {
+			forwarded_tx.send(event).unwrap();
+			async { Ok(()) }
+		}));
+
+		// The second sample is split across the first two parts
+		let bytes = pcm(&[0.25, -0.5, 1.0]);
+		let parts = [&bytes[..6], &bytes[6..9], &bytes[9..]];
+		let expected: [&[f32]; 3] = [&[0.25], &[-0.5], &[1.0]];
+
+		// Each part is forwarded before the next one is even sent
+		for (part, expected) in parts.into_iter().zip(expected) {
+			body_tx.send(Ok(Bytes::copy_from_slice(part))).unwrap();
+			let event = timeout(Duration::from_secs(1), forwarded_rx.recv()).await.expect("chunk was not forwarded").unwrap();
+			assert_eq!(samples_of(event), expected);
+		}
+		drop(body_tx);
+
+		let summary = upload.await.unwrap().unwrap();
+		assert_eq!(summary, IngestSummary { bytes: 12, chunks: 3 });
+	}
+
+	#[tokio::test]
+	async fn test_upload_over_the_cap_stops_reading() {
+		let (body_tx, body_rx) = mpsc::unbounded_channel();
+		let mut forwarded = 0;
+		body_tx.send(Ok(Bytes::from(pcm(&[0.0; 4])))).unwrap();
+		body_tx.send(Ok(Bytes::from(pcm(&[0.0; 4])))).unwrap();
+
+		// The body never ends, so this only returns if the upload gives up at the cap
+		let upload = forward_pcm(UnboundedReceiverStream::new(body_rx), FORMAT, 24, |_| {
+			forwarded += 1;
+			async { Ok(()) }
+		});
+		let result = timeout(Duration::from_secs(1), upload).await.expect("upload kept reading past the cap");
+		assert!(matches!(result, Err(FileHostError::PayloadTooLarge { limit: 24 })));
+		assert_eq!(forwarded, 1);
+		drop(body_tx);
+	}
+}
@@ -0,0 +1,75 @@
+use crate::AppState;
+use axum::{extract::State, response::Json};
+use serde::Serialize;
+use tracing::instrument;
+use ws_conn_manager::ConnectionGuard;
+
+/// Backoff suggested when every connection slot is taken
+const MAX_BACKOFF_MS: usize = 5_000;
+
+#[derive(Debug, Serialize)]
+pub struct CapacityResponse {
+	/// Share of the global connection limit in use, from 0.0 to 1.0
+	global_utilization: f64,
+	/// Whether a new connection would get a slot right now
+	accepting_new: bool,
+	/// How long to wait before retrying, growing with utilization; add jitter on top
+	suggested_backoff_ms: u64,
+}
+
+/// `GET /capacity`
+///
+/// Lets clients poll connection headroom before (re)connecting instead of
+/// retrying into a saturated guard.
+#[instrument(name = "capacity", skip(state))]
+pub async fn capacity(State(state): State<AppState>) -> Json<CapacityResponse> {
+	Json(capacity_of(&state.core.connection_guard))
+}
+
+fn capacity_of(guard: &ConnectionGuard) -> CapacityResponse {
+	let (active, max_global) = match guard.inner.max_global {
+		// A guard without any slots is as full as it gets
+		0 => (1, 1),
+		max_global => (guard.active_global(), max_global),
+	};
+	// Connection counts are nowhere near large enough to lose precision
+	#[allow(clippy::cast_precision_loss)]
+	let global_utilization = active as f64 / max_global as f64;
+
+	CapacityResponse {
+		global_utilization,
+		accepting_new: guard.try_acquire_permit_hint(),
+		suggested_backoff_ms: u64::try_from(active.saturating_mul(MAX_BACKOFF_MS) / max_global).unwrap_or(u64::MAX),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_capacity_tracks_saturation() {
+		let guard = ConnectionGuard::with_max_global(4);
+		let idle = capacity_of(&guard);
+		assert!(idle.accepting_new);
+		assert_eq!((idle.global_utilization, idle.suggested_backoff_ms), (0.0, 0));
+
+		let mut permits = Vec::new();
+		let mut previous = idle.suggested_backoff_ms;
+		for client in ["a", "b", "c", "d"] {
+			permits.push(guard.acquire(client.to_string()).await.unwrap());
+			let current = capacity_of(&guard);
+			assert!(current.suggested_backoff_ms > previous);
+			previous = current.suggested_backoff_ms;
+		}
+
+		let full = capacity_of(&guard);
+		assert!(!full.accepting_new);
+		assert!((full.global_utilization - 1.0).abs() < f64::EPSILON);
+		assert_eq!(full.suggested_backoff_ms, 5_000);
+
+		// Freeing a slot opens the door again
+		permits.pop().unwrap().release();
+		assert!(capacity_of(&guard).accepting_new);
+	}
+}
@@ -0,0 +1,49 @@
+use axum::{
+	http::{header::CONTENT_TYPE, StatusCode},
+	response::{IntoResponse, Response},
+};
+use prometheus::{Encoder, TextEncoder};
+use tracing::instrument;
+
+/// Everything in the default Prometheus registry, in the text exposition format
+///
+/// This includes the `some-cache` hit, miss and eviction counters alongside the
+/// WebSocket connection metrics.
+#[instrument(name = "metrics")]
+pub async fn metrics() -> Response {
+	let encoder = TextEncoder::new();
+	let mut buffer = Vec::new();
+	if let Err(e) = encoder.encode(&prometheus::gather(), &mut buffer) {
+		tracing::error!(error = %e, "failed to encode metrics");
+		return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+	}
+
+	([(CONTENT_TYPE, encoder.format_type().to_string())], buffer).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use some_cache::{CacheStore, DedupCache, FakeRedis};
+	use std::sync::Arc;
+
+	#[tokio::test]
+	async fn test_metrics_exports_cache_counters() {
+		let redis = FakeRedis::start().await.unwrap();
+		let cache = DedupCache::new(Arc::new(CacheStore::new(redis.config()).unwrap()), 16);
+
+		// A miss that fetches, then two hits served from Redis
+		for _ in 0..3 {
+			let (value, _) = cache.get_or_fetch("metrics_test:a", || async { Ok(42_u32) }).await.unwrap();
+			assert_eq!(value, 42);
+		}
+
+		let response = metrics().await;
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let body = String::from_utf8(body.to_vec()).unwrap();
+		assert!(body.contains(r#"cache_hits_total{namespace="metrics_test"} 2"#));
+		assert!(body.contains(r#"cache_misses_total{namespace="metrics_test"} 1"#));
+	}
+}
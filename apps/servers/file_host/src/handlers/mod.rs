@@ -1,9 +1,13 @@
+pub mod admin;
+pub mod audio_ingest;
 pub mod audio_files;
+pub mod capacity;
 pub mod db;
 pub mod gdrive_fs;
 pub mod gdrive_images;
 pub mod github;
 pub mod health;
+pub mod metrics;
 pub mod pipeline;
 pub mod read_sheets;
 pub mod tab_metadata;
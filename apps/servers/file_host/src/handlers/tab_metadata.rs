@@ -16,7 +16,7 @@ pub async fn now_playing(State(state): State<AppState>, Json(payload): Json<NowP
 	let key = "now_playing";
 	let redis = state.realtime.dedup_cache.store();
 	let _ = redis.set(key, &event, Some(state.core.config.cache_ttl)).await.map_err(|e| FileHostError::upstream(e))?;
-	let transport = state.realtime.transport.clone();
+	let transport = state.realtime.transport.current();
 
 	let _ = state.realtime.ws.broadcast_event(transport, event).await?;
 
@@ -11,7 +11,7 @@ use ws_events::events::{Event, UtterancePrompt};
 #[instrument(name = "utterance", skip(state))]
 pub async fn utterance(State(state): State<AppState>, Json(payload): Json<UtterancePrompt>) -> StatusCode {
 	let event = Event::from(payload);
-	let transport = state.realtime.transport.clone();
+	let transport = state.realtime.transport.current();
 
 	let _ = state.realtime.ws.broadcast_event(transport, event).await;
 
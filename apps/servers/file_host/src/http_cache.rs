@@ -0,0 +1,107 @@
+use axum::{
+	body::Body,
+	extract::{Request, State},
+	http::{
+		header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+		HeaderValue, Method, StatusCode,
+	},
+	middleware::Next,
+	response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// How long clients and proxies may reuse a route's responses
+pub struct CachePolicy {
+	cache_control: HeaderValue,
+}
+
+impl CachePolicy {
+	/// `Cache-Control: public, max-age=<max_age_secs>`
+	#[must_use]
+	pub fn max_age(max_age_secs: u64) -> Self {
+		let value = ["public, max-age=", &max_age_secs.to_string()].concat();
+		Self {
+			cache_control: HeaderValue::from_str(&value).unwrap_or_else(|_| HeaderValue::from_static("no-cache")),
+		}
+	}
+}
+
+/// Strong `ETag` from a hash of the response body
+fn etag_for(body: &[u8]) -> HeaderValue {
+	let digest = Sha256::digest(body);
+	let tag = ["\"", &hex::encode(&digest[..16]), "\""].concat();
+	HeaderValue::from_str(&tag).unwrap_or_else(|_| HeaderValue::from_static("\"\""))
+}
+
+/// Whether an `If-None-Match` header matches `etag` (weak comparison, as RFC 9110 asks for GET)
+fn matches_etag(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+	let (Ok(candidates), Ok(etag)) = (if_none_match.to_str(), etag.to_str()) else {
+		return false;
+	};
+	candidates
+		.split(',')
+		.map(str::trim)
+		.any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// Adds `Cache-Control` and `ETag` to successful GET responses, answering
+/// `If-None-Match` with `304 Not Modified` when the body hasn't changed
+///
+/// The handler still runs on every request; the saving is in bytes sent and
+/// in letting browsers and proxies reuse what they already hold.
+pub async fn cache_control_middleware(State(policy): State<Arc<CachePolicy>>, req: Request, next: Next) -> Response {
+	if req.method() != Method::GET {
+		return next.run(req).await;
+	}
+	let if_none_match = req.headers().get(IF_NONE_MATCH).cloned();
+
+	let response = next.run(req).await;
+	if response.status() != StatusCode::OK {
+		return response;
+	}
+
+	let (mut parts, body) = response.into_parts();
+	let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+		return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to read response body").into_response();
+	};
+
+	let etag = etag_for(&bytes);
+	if if_none_match.is_some_and(|value| matches_etag(&value, &etag)) {
+		return (StatusCode::NOT_MODIFIED, [(ETAG, etag), (CACHE_CONTROL, policy.cache_control.clone())]).into_response();
+	}
+
+	parts.headers.insert(ETAG, etag);
+	parts.headers.insert(CACHE_CONTROL, policy.cache_control.clone());
+	Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::{middleware::from_fn_with_state, routing::get, Router};
+	use tower::ServiceExt;
+
+	fn app() -> Router {
+		Router::new()
+			.route("/get_github_repos", get(|| async { "[\"server\"]" }))
+			.layer(from_fn_with_state(Arc::new(CachePolicy::max_age(300)), cache_control_middleware))
+	}
+
+	#[tokio::test]
+	async fn test_etag_set_and_conditional_request_returns_304() {
+		let first = app().oneshot(Request::get("/get_github_repos").body(Body::empty()).unwrap()).await.unwrap();
+		assert_eq!(first.status(), StatusCode::OK);
+		assert_eq!(first.headers()[CACHE_CONTROL], "public, max-age=300");
+		let etag = first.headers()[ETAG].clone();
+
+		let conditional = Request::get("/get_github_repos").header(IF_NONE_MATCH, etag.clone()).body(Body::empty()).unwrap();
+		let second = app().oneshot(conditional).await.unwrap();
+		assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+		assert_eq!(second.headers()[ETAG], etag);
+		assert!(axum::body::to_bytes(second.into_body(), usize::MAX).await.unwrap().is_empty());
+
+		let stale = Request::get("/get_github_repos").header(IF_NONE_MATCH, "\"outdated\"").body(Body::empty()).unwrap();
+		assert_eq!(app().oneshot(stale).await.unwrap().status(), StatusCode::OK);
+	}
+}
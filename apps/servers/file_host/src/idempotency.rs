@@ -0,0 +1,276 @@
+use crate::CacheStore;
+use axum::{
+	body::{Body, HttpBody},
+	extract::{ConnectInfo, Request, State},
+	http::{header::CONTENT_TYPE, HeaderValue, StatusCode},
+	middleware::Next,
+	response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::{future::Future, net::SocketAddr, sync::Arc};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const MAX_KEY_LEN: usize = 255;
+const MAX_CACHED_BODY: usize = 64 * 1024;
+/// How long a request holds its key while the handler runs, in case it never finishes
+const IN_FLIGHT_TTL: u64 = 60;
+
+/// A handler response as replayed for a repeated `Idempotency-Key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+	pub status: u16,
+	pub content_type: Option<String>,
+	pub body: Vec<u8>,
+}
+
+impl IntoResponse for CachedResponse {
+	fn into_response(self) -> Response {
+		let mut response = (StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK), self.body).into_response();
+		if let Some(content_type) = self.content_type.and_then(|c| HeaderValue::from_str(&c).ok()) {
+			response.headers_mut().insert(CONTENT_TYPE, content_type);
+		}
+		response
+	}
+}
+
+/// Where idempotent responses are kept between retries
+pub trait IdempotencyStore: Send + Sync + 'static {
+	fn get(&self, key: &str) -> impl Future<Output = Option<CachedResponse>> + Send;
+	fn put(&self, key: &str, response: &CachedResponse, ttl: u64) -> impl Future<Output = ()> + Send;
+	/// Mark `key` as in flight unless it already is; returns whether this caller got it
+	fn claim(&self, key: &str, ttl: u64) -> impl Future<Output = bool> + Send;
+	fn release(&self, key: &str) -> impl Future<Output = ()> + Send;
+}
+
+impl IdempotencyStore for CacheStore {
+	async fn get(&self, key: &str) -> Option<CachedResponse> {
+		Self::get(self, key).await.unwrap_or_else(|e| {
+			tracing::warn!(key, error = %e, "idempotency lookup failed, running handler");
+			None
+		})
+	}
+
+	async fn put(&self, key: &str, response: &CachedResponse, ttl: u64) {
+		if let Err(e) = self.set(key, response, Some(ttl)).await {
+			tracing::warn!(key, error = %e, "failed to store idempotent response");
+		}
+	}
+
+	async fn claim(&self, key: &str, ttl: u64) -> bool {
+		Self::claim(self, key, ttl).await.unwrap_or_else(|e| {
+			tracing::warn!(key, error = %e, "idempotency claim failed, running handler");
+			true
+		})
+	}
+
+	async fn release(&self, key: &str) {
+		if let Err(e) = self.delete(key).await {
+			tracing::warn!(key, error = %e, "failed to release idempotency key, it expires on its own");
+		}
+	}
+}
+
+pub struct Idempotency<S> {
+	store: Arc<S>,
+	ttl: u64,
+}
+
+impl<S> Idempotency<S> {
+	pub const fn new(store: Arc<S>, ttl: u64) -> Self {
+		Self { store, ttl }
+	}
+}
+
+/// Replays the stored response when a client retries a POST with the same `Idempotency-Key`
+///
+/// Keys are scoped to the caller's address and the request path, so two clients
+/// (or two endpoints) never share a key. Only successful responses are stored;
+/// a failed attempt can be retried with the same key and will run again.
+/// A retry that arrives while the first attempt is still running gets 409
+/// Conflict. Responses over 64 KiB, or of unknown length, are passed through
+/// without being stored. Requests without the header pass straight through.
+pub async fn idempotency_middleware<S: IdempotencyStore>(State(idempotency): State<Arc<Idempotency<S>>>, req: Request, next: Next) -> Response {
+	let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+		return next.run(req).await;
+	};
+	let key = match key.to_str() {
+		Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key.to_string(),
+		_ => return (StatusCode::BAD_REQUEST, "Invalid Idempotency-Key header").into_response(),
+	};
+
+	let client = req
+		.extensions()
+		.get::<ConnectInfo<SocketAddr>>()
+		.map_or_else(|| "unknown".to_string(), |ConnectInfo(addr)| addr.ip().to_string());
+	let cache_key = ["idempotency:", &client, ":", req.uri().path(), ":", &key].concat();
+
+	if let Some(cached) = idempotency.store.get(&cache_key).await {
+		tracing::debug!(key = %cache_key, "replaying idempotent response");
+		return cached.into_response();
+	}
+
+	let claim_key = [&cache_key, ":in-flight"].concat();
+	if !idempotency.store.claim(&claim_key, IN_FLIGHT_TTL).await {
+		return (StatusCode::CONFLICT, "A request with this Idempotency-Key is still in progress").into_response();
+	}
+	// The first attempt may have finished between the lookup and the claim
+	let response = match idempotency.store.get(&cache_key).await {
+		Some(cached) => cached.into_response(),
+		None => store_response(&idempotency, &cache_key, next.run(req).await).await,
+	};
+	idempotency.store.release(&claim_key).await;
+
+	response
+}
+
+/// Keep a copy of a successful, small enough `response` for retries, and pass it on
+async fn store_response<S: IdempotencyStore>(idempotency: &Idempotency<S>, cache_key: &str, response: Response) -> Response {
+	let cacheable = response.body().size_hint().exact().is_some_and(|len| len <= MAX_CACHED_BODY as u64);
+	if !response.status().is_success() || !cacheable {
+		return response;
+	}
+
+	let (parts, body) = response.into_parts();
+	let Ok(bytes) = axum::body::to_bytes(body, MAX_CACHED_BODY).await else {
+		return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to read response body").into_response();
+	};
+
+	let cached = CachedResponse {
+		status: parts.status.as_u16(),
+		content_type: parts.headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).map(ToString::to_string),
+		body: bytes.to_vec(),
+	};
+	idempotency.store.put(cache_key, &cached, idempotency.ttl).await;
+
+	Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::{middleware::from_fn_with_state, routing::post, Router};
+	use std::{
+		collections::{HashMap, HashSet},
+		sync::{
+			atomic::{AtomicUsize, Ordering},
+			Mutex,
+		},
+	};
+	use tokio::sync::Notify;
+	use tower::ServiceExt;
+
+	#[derive(Default)]
+	struct MemoryStore {
+		responses: Mutex<HashMap<String, CachedResponse>>,
+		in_flight: Mutex<HashSet<String>>,
+	}
+
+	impl IdempotencyStore for MemoryStore {
+		async fn get(&self, key: &str) -> Option<CachedResponse> {
+			self.responses.lock().unwrap().get(key).cloned()
+		}
+
+		async fn put(&self, key: &str, response: &CachedResponse, _ttl: u64) {
+			self.responses.lock().unwrap().insert(key.to_string(), response.clone());
+		}
+
+		async fn claim(&self, key: &str, _ttl: u64) -> bool {
+			self.in_flight.lock().unwrap().insert(key.to_string())
+		}
+
+		async fn release(&self, key: &str) {
+			self.in_flight.lock().unwrap().remove(key);
+		}
+	}
+
+	fn post_with(key: &str, ip: [u8; 4]) -> Request {
+		let mut req = Request::post("/utter").header(IDEMPOTENCY_KEY_HEADER, key).body(Body::empty()).unwrap();
+		req.extensions_mut().insert(ConnectInfo(SocketAddr::from((ip, 4000))));
+		req
+	}
+
+	#[tokio::test]
+	async fn test_repeated_key_replays_cached_response() {
+		let calls = Arc::new(AtomicUsize::new(0));
+		let counter = Arc::clone(&calls);
+		let app = Router::new()
+			.route(
+				"/utter",
+				post(move || async move {
+					let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+					(StatusCode::CREATED, n.to_string())
+				}),
+			)
+			.layer(from_fn_with_state(
+				Arc::new(Idempotency::new(Arc::new(MemoryStore::default()), 60)),
+				idempotency_middleware::<MemoryStore>,
+			));
+
+		let first = app.clone().oneshot(post_with("abc", [10, 0, 0, 1])).await.unwrap();
+		let retry = app.clone().oneshot(post_with("abc", [10, 0, 0, 1])).await.unwrap();
+
+		assert_eq!(first.status(), StatusCode::CREATED);
+		assert_eq!(retry.status(), StatusCode::CREATED);
+		assert_eq!(axum::body::to_bytes(retry.into_body(), usize::MAX).await.unwrap(), "1");
+		assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+		// The same key from another client is a different request
+		let other = app.oneshot(post_with("abc", [10, 0, 0, 2])).await.unwrap();
+		assert_eq!(axum::body::to_bytes(other.into_body(), usize::MAX).await.unwrap(), "2");
+		assert_eq!(calls.load(Ordering::SeqCst), 2);
+	}
+
+	#[tokio::test]
+	async fn test_retry_during_first_attempt_conflicts() {
+		let release = Arc::new(Notify::new());
+		let gate = Arc::clone(&release);
+		let app = Router::new()
+			.route(
+				"/utter",
+				post(move || async move {
+					gate.notified().await;
+					StatusCode::CREATED
+				}),
+			)
+			.layer(from_fn_with_state(
+				Arc::new(Idempotency::new(Arc::new(MemoryStore::default()), 60)),
+				idempotency_middleware::<MemoryStore>,
+			));
+
+		let first = tokio::spawn(app.clone().oneshot(post_with("abc", [10, 0, 0, 1])));
+		tokio::task::yield_now().await;
+		let retry = app.clone().oneshot(post_with("abc", [10, 0, 0, 1])).await.unwrap();
+		assert_eq!(retry.status(), StatusCode::CONFLICT);
+
+		release.notify_one();
+		assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::CREATED);
+		// Once the first attempt is done its response is replayed
+		let replay = app.oneshot(post_with("abc", [10, 0, 0, 1])).await.unwrap();
+		assert_eq!(replay.status(), StatusCode::CREATED);
+	}
+
+	#[tokio::test]
+	async fn test_oversized_response_passes_through_uncached() {
+		let calls = Arc::new(AtomicUsize::new(0));
+		let counter = Arc::clone(&calls);
+		let app = Router::new()
+			.route(
+				"/utter",
+				post(move || async move {
+					counter.fetch_add(1, Ordering::SeqCst);
+					vec![b'x'; MAX_CACHED_BODY + 1]
+				}),
+			)
+			.layer(from_fn_with_state(
+				Arc::new(Idempotency::new(Arc::new(MemoryStore::default()), 60)),
+				idempotency_middleware::<MemoryStore>,
+			));
+
+		for _ in 0..2 {
+			let response = app.clone().oneshot(post_with("abc", [10, 0, 0, 1])).await.unwrap();
+			assert_eq!(response.status(), StatusCode::OK);
+			assert_eq!(axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().len(), MAX_CACHED_BODY + 1);
+		}
+		assert_eq!(calls.load(Ordering::SeqCst), 2);
+	}
+}
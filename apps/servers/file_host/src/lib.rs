@@ -1,21 +1,27 @@
 use crate::error::{FileHostError, GSheetDeriveError};
+use crate::realtime::SupervisedTransport;
 use axum::extract::FromRef;
 use sdk::{GitHubClient, ReadDrive, ReadSheets, WriteToDrive};
-use some_transport::{nats::JetStreamPublisher, NatsTransport};
+use some_transport::nats::JetStreamPublisher;
 use sqlx::SqlitePool;
 use std::sync::{Arc, Mutex};
 use tokio_util::sync::CancellationToken;
 use ws_conn_manager::{AcquireErrorKind, ConnectionGuard, ConnectionPermit};
-use ws_events::{tabsched::JobEnvelope, UnifiedEvent};
+use ws_events::tabsched::JobEnvelope;
 
+pub mod audit;
 pub mod cache;
 pub mod config;
 pub mod error;
 pub mod handlers;
 pub mod health;
+pub mod http_cache;
+pub mod idempotency;
 pub mod metrics;
 pub mod models;
 pub mod rate_limiter;
+pub mod realtime;
+pub mod route_limits;
 pub mod routes;
 pub mod utils;
 pub mod websocket;
@@ -25,7 +31,7 @@ pub mod websocket;
 /// balancers and orchestrators don't need to track API version bumps.
 pub const API_V1_BASE_PATH: &str = "/api/v1";
 
-pub use crate::websocket::WebSocketFsm;
+pub use crate::websocket::{IpConnectionLimit, WebSocketFsm};
 pub use cache::{CacheConfig, CacheStore, DedupCache};
 pub use config::*;
 pub use handlers::audio_files::error::AudioServiceError;
@@ -40,6 +46,7 @@ pub struct CoreContext {
 	pub cancel_token: CancellationToken,
 	pub shared_db: SqlitePool,
 	pub connection_guard: ConnectionGuard,
+	pub ip_connection_limit: IpConnectionLimit,
 	// Wrap in Mutex<Option<>> so we can take ownership during shutdown
 	pub otel_guard: Arc<Mutex<Option<OtelGuard>>>,
 }
@@ -58,7 +65,7 @@ pub struct ExternalApis {
 pub struct RealtimeContext {
 	pub ws: WebSocketFsm,
 	pub dedup_cache: Arc<DedupCache>,
-	pub transport: NatsTransport<UnifiedEvent>,
+	pub transport: SupervisedTransport,
 	pub pipeline_publisher: Arc<JetStreamPublisher<JobEnvelope>>,
 }
 
@@ -78,6 +85,7 @@ impl AppState {
 			cancel_token: cancel_token.clone(),
 			shared_db: pool,
 			connection_guard: ConnectionGuard::new(),
+			ip_connection_limit: IpConnectionLimit::new(config.ws_max_connections_per_ip),
 			otel_guard,
 		};
 
@@ -96,12 +104,15 @@ impl AppState {
 
 		// Initialize NATS transports
 		let nats_url = config.nats_url.as_deref().unwrap_or("nats://localhost:4222");
-		let transport = NatsTransport::connect_pooled(nats_url).await?;
+		let transport = realtime::connect_transport(nats_url).await?;
 
 		// Reuse the Arc<Client> from the transport for JetStream
 		let client = transport.client().clone();
 		let pipeline_publisher = Arc::new(JetStreamPublisher::from_client(client));
 
+		// Re-establish the connection and resubscribe the WS bridge if it drops
+		let transport = realtime::spawn_transport_supervisor(nats_url.to_owned(), transport, cancel_token.child_token());
+
 		let ws = WebSocketFsm::new();
 
 		let realtime = RealtimeContext {
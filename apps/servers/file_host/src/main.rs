@@ -4,11 +4,14 @@ mod models;
 mod routes;
 
 use crate::routes::{
-	audio_files::get_audio,
+	admin::admin_connections,
+	audio_files::{get_audio, ingest_audio},
+	capacity::get_capacity,
 	db::{mood_events, tabs},
 	gdrive::{get_gdrive_image, write_gdrive_fs},
 	github::get_repos,
 	health::get_health,
+	metrics::get_metrics,
 	sheets::get_sheets,
 	tab_metadata::post_now_playing,
 	utterance::post_utterance,
@@ -16,10 +19,14 @@ use crate::routes::{
 use anyhow::Result;
 use axum::{error_handling::HandleErrorLayer, middleware::from_fn_with_state, Router};
 use clap::Parser;
+use file_host::audit::{audit_middleware, AuditLog};
+use file_host::http_cache::{cache_control_middleware, CachePolicy};
+use file_host::idempotency::{idempotency_middleware, Idempotency};
 use file_host::rate_limiter::token_bucket::rate_limit_middleware;
+use file_host::route_limits::{route_concurrency_middleware, RouteConcurrencyLimits};
 use file_host::{
 	error::{FileHostError, GSheetDeriveError},
-	perform_health_check, AppState, AudioServiceError, Config, DedupCache, API_V1_BASE_PATH,
+	perform_health_check, AppState, AudioServiceError, Config, DedupCache, WebSocketFsm, API_V1_BASE_PATH,
 };
 use sdk::ReadDrive;
 use some_services::rate_limiter::TokenBucketRateLimiter;
@@ -66,28 +73,44 @@ async fn main() -> Result<()> {
 		.await?;
 	let shutdown_token = CancellationToken::new();
 
+	let audit_log = Arc::new(AuditLog::spawn(pool.clone(), config.audit_buffer_size).await?);
 	let app_state = AppState::build(config.clone(), pool, shutdown_token.clone()).await?;
 
+	let idempotency = Arc::new(Idempotency::new(app_state.realtime.dedup_cache.store(), config.idempotency_ttl));
+
 	let mut versioned_routes = Router::new()
-		.merge(get_sheets(&config))
+		.merge(get_sheets(&config).layer(from_fn_with_state(Arc::new(CachePolicy::max_age(config.sheets_cache_max_age)), cache_control_middleware)))
 		.merge(get_gdrive_image())
 		.merge(write_gdrive_fs(&config))
-		.merge(get_repos())
+		.merge(get_repos().layer(from_fn_with_state(Arc::new(CachePolicy::max_age(config.repos_cache_max_age)), cache_control_middleware)))
 		.merge(mood_events())
 		.merge(tabs())
 		.merge(get_audio(&config))
-		.merge(post_now_playing())
-		.merge(post_utterance());
+		.merge(ingest_audio(&config))
+		.merge(post_now_playing().layer(from_fn_with_state(idempotency.clone(), idempotency_middleware)))
+		.merge(post_utterance().layer(from_fn_with_state(idempotency, idempotency_middleware)));
 
 	let max_requests = config.clone().max_request_size.try_into()?;
 	// TODO: Is this even working! boyo needs to know!
 	versioned_routes = versioned_routes.layer(from_fn_with_state(Arc::new(TokenBucketRateLimiter::new(max_requests)), rate_limit_middleware));
 
-	let app = Router::new()
+	let mut app = Router::new()
 		.nest(API_V1_BASE_PATH, versioned_routes)
 		.merge(get_health())
-		.merge(app_state.realtime.ws.clone().router())
-		.with_state(app_state.clone());
+		.merge(get_capacity())
+		.merge(admin_connections())
+		.merge(app_state.realtime.ws.clone().router());
+
+	if config.enable_prometheus {
+		app = app.merge(get_metrics());
+	}
+
+	let route_limits: RouteConcurrencyLimits = config.route_concurrency_limits.parse().map_err(anyhow::Error::msg)?;
+	if !route_limits.is_empty() {
+		app = app.layer(from_fn_with_state(Arc::new(route_limits), route_concurrency_middleware));
+	}
+	let app = app.layer(from_fn_with_state(audit_log, audit_middleware));
+	let app = app.with_state(app_state.clone());
 
 	let app = app.layer(
 		ServiceBuilder::new()
@@ -131,7 +154,7 @@ async fn main() -> Result<()> {
 
 		app_state.core.shared_db.close().await;
 		tracing::info!("Database closed");
-		app_state.realtime.transport.client().flush().await.ok();
+		app_state.realtime.transport.current().client().flush().await.ok();
 		tracing::info!("Nats channel closed");
 		app_state.realtime.ws.shutdown().await;
 		tracing::info!("All WebSocket connections cleanup up");
@@ -10,6 +10,8 @@ pub struct Metrics {
 	pub errors: Counter<u64>,
 	pub file_downloads: Counter<u64>,
 	pub file_size_bytes: Histogram<f64>,
+	pub ws_messages_rejected: Counter<u64>,
+	pub ws_messages_throttled: Counter<u64>,
 }
 
 impl Metrics {
@@ -32,6 +34,14 @@ impl Metrics {
 					.with_description("Downloaded file size in bytes")
 					.with_unit("By")
 					.build(),
+				ws_messages_rejected: meter
+					.u64_counter("websocket.messages.rejected")
+					.with_description("Inbound WebSocket messages that failed validation")
+					.build(),
+				ws_messages_throttled: meter
+					.u64_counter("websocket.messages.throttled")
+					.with_description("Inbound WebSocket messages dropped by the per-connection rate limit")
+					.build(),
 			}
 		})
 	}
@@ -112,3 +122,13 @@ pub fn record_cache_invalidation(operation: &str, keys_invalidated: usize) {
 		],
 	);
 }
+
+/// Record an inbound WebSocket message rejected by schema validation
+pub fn record_ws_message_rejected(reason: &str) {
+	Metrics::get().ws_messages_rejected.add(1, &[KeyValue::new("reason", reason.to_string())]);
+}
+
+/// Record an inbound WebSocket message dropped by the per-connection rate limit
+pub fn record_ws_message_throttled() {
+	Metrics::get().ws_messages_throttled.add(1, &[]);
+}
@@ -53,13 +53,11 @@ pub async fn rate_limit_middleware(State(limiter): State<Arc<SlidingWindowRateLi
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use clap::Parser;
 	use tokio::time::sleep;
 
 	#[tokio::test]
 	async fn test_allow_request_within_limit() {
-		dotenv::dotenv().ok();
-		let limiter = SlidingWindowRateLimiter::new(Arc::new(Config::parse()));
+		let limiter = SlidingWindowRateLimiter::new(3);
 
 		assert!(limiter.allow_request().await);
 		assert!(limiter.allow_request().await);
@@ -68,7 +66,7 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_deny_request_exceeding_limit() {
-		let limiter = SlidingWindowRateLimiter::new(Arc::new(Config::parse()));
+		let limiter = SlidingWindowRateLimiter::new(2);
 
 		assert!(limiter.allow_request().await);
 		assert!(limiter.allow_request().await);
@@ -77,7 +75,11 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_request_allowed_after_window_expires() {
-		let limiter = SlidingWindowRateLimiter::new(Arc::new(Config::parse()));
+		let limiter = SlidingWindowRateLimiter {
+			max_requests: 2,
+			window_size: Duration::from_secs(1),
+			request_timestamps: Arc::new(Mutex::new(VecDeque::new())),
+		};
 
 		assert!(limiter.allow_request().await);
 		assert!(limiter.allow_request().await);
@@ -0,0 +1,303 @@
+use some_transport::{ConnectionState, NatsConnectionPool, NatsTransport, TransportError};
+use std::future::Future;
+use tokio::sync::watch;
+use tokio::time::{sleep, timeout, Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+use ws_events::UnifiedEvent;
+
+/// How often the connection state is sampled
+const STATE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long the client gets to reconnect on its own before the connection is replaced
+const RECONNECT_GRACE: Duration = Duration::from_secs(10);
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Realtime transport handle kept current by the reconnect supervisor
+///
+/// Clones share the same watch, so every holder sees a replacement. Long-lived
+/// subscribers should resubscribe when [`replaced`](Self::replaced) resolves;
+/// one-shot publishers can just call [`current`](Self::current).
+#[derive(Clone)]
+pub struct SupervisedTransport<T = NatsTransport<UnifiedEvent>> {
+	current: watch::Receiver<T>,
+}
+
+impl<T: Clone> SupervisedTransport<T> {
+	/// The transport to use right now
+	#[must_use]
+	pub fn current(&self) -> T {
+		self.current.borrow().clone()
+	}
+
+	/// The transport to subscribe on, marking it seen so `replaced` waits for the next one
+	pub fn subscribe_current(&mut self) -> T {
+		self.current.borrow_and_update().clone()
+	}
+
+	/// Wait until the connection comes back after a drop
+	///
+	/// Returns `false` once the supervisor has stopped and no more replacements will come.
+	pub async fn replaced(&mut self) -> bool {
+		self.current.changed().await.is_ok()
+	}
+}
+
+/// Connect the pooled realtime transport, tagged with the `UnifiedEvent` schema fingerprint
+///
+/// Receivers then reject events published by a build with a different schema
+/// instead of failing to decode them.
+///
+/// # Errors
+///
+/// Returns an error if the connection to `url` can't be established.
+pub async fn connect_transport(url: &str) -> Result<NatsTransport<UnifiedEvent>, TransportError> {
+	Ok(
+		NatsTransport::connect_pooled(url)
+			.await?
+			.with_schema_fingerprint(UnifiedEvent::SCHEMA_FINGERPRINT.to_string()),
+	)
+}
+
+/// Supervise the pooled connection behind `transport`, reconnecting to `url` if it drops for good
+///
+/// async-nats reconnects on its own, so a drop first gets `RECONNECT_GRACE` to
+/// recover. Either way, subscribers are told to resubscribe once the connection
+/// is back. Stops when `cancel_token` is cancelled.
+#[must_use]
+pub fn spawn_transport_supervisor(url: String, transport: NatsTransport<UnifiedEvent>, cancel_token: CancellationToken) -> SupervisedTransport {
+	supervise(
+		transport,
+		|transport| transport.connection_state_watch(STATE_POLL_INTERVAL),
+		move || {
+			let url = url.clone();
+			async move {
+				// Evict the dead client so the pool dials a fresh connection
+				NatsConnectionPool::global().remove(&url);
+				connect_transport(&url).await
+			}
+		},
+		RECONNECT_GRACE,
+		cancel_token,
+	)
+}
+
+/// Reconnect loop behind [`spawn_transport_supervisor`], generic so it can run without a server
+fn supervise<T, W, C, F>(initial: T, watch_state: W, connect: C, grace: Duration, cancel_token: CancellationToken) -> SupervisedTransport<T>
+where
+	T: Clone + Send + Sync + 'static,
+	W: Fn(&T) -> watch::Receiver<ConnectionState> + Send + 'static,
+	C: Fn() -> F + Send + 'static,
+	F: Future<Output = Result<T, TransportError>> + Send,
+{
+	let (tx, rx) = watch::channel(initial);
+
+	tokio::spawn(async move {
+		loop {
+			let mut state = watch_state(&tx.borrow());
+
+			tokio::select! {
+				() = cancel_token.cancelled() => return,
+				_ = wait_until(&mut state, |s| *s != ConnectionState::Connected) => {}
+			}
+			warn!("NATS connection lost");
+
+			let recovered = tokio::select! {
+				() = cancel_token.cancelled() => return,
+				result = timeout(grace, wait_until(&mut state, |s| *s == ConnectionState::Connected)) => result.unwrap_or(false),
+			};
+			if recovered {
+				info!("NATS connection recovered, resubscribing");
+				tx.send_modify(|_| {});
+				continue;
+			}
+
+			let mut backoff = Duration::from_secs(1);
+			loop {
+				let attempt = tokio::select! {
+					() = cancel_token.cancelled() => return,
+					attempt = connect() => attempt,
+				};
+				match attempt {
+					Ok(transport) => {
+						info!("NATS connection re-established, resubscribing");
+						tx.send_replace(transport);
+						break;
+					}
+					Err(e) => {
+						warn!(error = %e, retry_in = ?backoff, "NATS reconnect failed");
+						tokio::select! {
+							() = cancel_token.cancelled() => return,
+							() = sleep(backoff) => {}
+						}
+						backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
+					}
+				}
+			}
+		}
+	});
+
+	SupervisedTransport { current: rx }
+}
+
+/// Wait until `state` satisfies `f`, returning `false` if the watch closed first
+///
+/// Keeps the watch's `!Send` borrow out of the supervisor's futures.
+async fn wait_until(state: &mut watch::Receiver<ConnectionState>, f: impl FnMut(&ConnectionState) -> bool) -> bool {
+	state.wait_for(f).await.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::websocket::broadcast::spawn_nats_task;
+	use some_transport::{InMemTransport, Transport};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::sync::Arc;
+	use tokio::sync::mpsc;
+	use ws_events::events::{Event, EventType, OrchestratorState};
+
+	/// Wait until `transport` has exactly `count` live subscribers
+	async fn wait_for_subscribers(transport: &InMemTransport<UnifiedEvent>, count: usize) {
+		timeout(Duration::from_secs(2), async {
+			while transport.total_receivers() != count {
+				sleep(Duration::from_millis(5)).await;
+			}
+		})
+		.await
+		.unwrap_or_else(|_| panic!("expected {count} subscribers, found {}", transport.total_receivers()));
+	}
+
+	#[tokio::test]
+	async fn test_bridge_resubscribes_after_reconnect() {
+		let (state_tx, state_rx) = watch::channel(ConnectionState::Connected);
+		let state_tx = Arc::new(state_tx);
+		let connects = Arc::new(AtomicUsize::new(0));
+		let cancel = CancellationToken::new();
+
+		// Each reconnect dials a fresh transport and comes up connected
+		let (reconnected_tx, mut reconnected_rx) = mpsc::unbounded_channel();
+		let connect = {
+			let (state_tx, connects) = (Arc::clone(&state_tx), Arc::clone(&connects));
+			move || {
+				connects.fetch_add(1, Ordering::SeqCst);
+				let transport = InMemTransport::<UnifiedEvent>::new(8);
+				reconnected_tx.send(transport.clone()).unwrap();
+				state_tx.send_replace(ConnectionState::Connected);
+				async move { Ok(transport) }
+			}
+		};
+
+		let original = InMemTransport::<UnifiedEvent>::new(8);
+		let supervised = supervise(original.clone(), move |_| state_rx.clone(), connect, Duration::from_millis(20), cancel.clone());
+
+		let (events_tx, _events_rx) = mpsc::channel(8);
+		spawn_nats_task(
+			EventType::TabMetaData,
+			supervised.clone(),
+			events_tx,
+			"conn".to_string(),
+			cancel.clone(),
+			CancellationToken::new(),
+			false,
+		);
+		wait_for_subscribers(&original, 1).await;
+
+		// The connection drops and doesn't come back within the grace period
+		state_tx.send_replace(ConnectionState::Disconnected);
+
+		let replacement = timeout(Duration::from_secs(2), reconnected_rx.recv()).await.unwrap().unwrap();
+		wait_for_subscribers(&replacement, 1).await;
+		wait_for_subscribers(&original, 0).await;
+		assert_eq!(connects.load(Ordering::SeqCst), 1);
+		assert_eq!(supervised.current().total_receivers(), 1);
+
+		cancel.cancel();
+	}
+
+	#[tokio::test]
+	async fn test_late_state_subscriber_alone_gets_the_current_states() {
+		let transport = InMemTransport::<UnifiedEvent>::new(8);
+		let (_state_tx, state_rx) = watch::channel(ConnectionState::Connected);
+		let cancel = CancellationToken::new();
+		let supervised = supervise(
+			transport.clone(),
+			move |_| state_rx.clone(),
+			|| async { unreachable!("never disconnects") },
+			Duration::from_secs(1),
+			cancel.clone(),
+		);
+
+		// Stands in for the orchestrator answering the catch-up query
+		let mut queries = transport.subscribe_to_subject(EventType::OrchestratorStateQuery.subject()).await;
+		let responder = transport.clone();
+		tokio::spawn(async move {
+			while let Ok((_, Some(reply))) = queries.recv_request().await {
+				let states = Event::OrchestratorStates {
+					states: vec![("live".to_string(), OrchestratorState::new(60_000))],
+				};
+				responder.send_to_subject(&reply, states.try_into().unwrap()).await.unwrap();
+			}
+		});
+		let mut other_dashboard = transport.subscribe_to_subject(EventType::OrchestratorState.subject()).await;
+
+		let (events_tx, mut events_rx) = mpsc::channel(8);
+		spawn_nats_task(
+			EventType::OrchestratorState,
+			supervised,
+			events_tx,
+			"conn".to_string(),
+			cancel.clone(),
+			CancellationToken::new(),
+			false,
+		);
+
+		match timeout(Duration::from_secs(2), events_rx.recv()).await.unwrap().unwrap() {
+			Event::OrchestratorState { stream_id, .. } => assert_eq!(stream_id, "live"),
+			other => panic!("expected the current state, got {other:?}"),
+		}
+		// The catch-up went to this connection only, not out on orchestrator.state
+		assert!(other_dashboard.try_recv().is_err());
+
+		cancel.cancel();
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn test_full_channel_flags_a_slow_consumer() {
+		let transport = InMemTransport::<UnifiedEvent>::new(8);
+		let (_state_tx, state_rx) = watch::channel(ConnectionState::Connected);
+		let cancel = CancellationToken::new();
+		let supervised = supervise(
+			transport.clone(),
+			move |_| state_rx.clone(),
+			|| async { unreachable!("never disconnects") },
+			Duration::from_secs(1),
+			cancel.clone(),
+		);
+
+		// Nobody drains this channel, so the second event has nowhere to go
+		let (events_tx, _events_rx) = mpsc::channel(1);
+		let slow_consumer = CancellationToken::new();
+		spawn_nats_task(
+			EventType::OrchestratorState,
+			supervised,
+			events_tx,
+			"conn".to_string(),
+			cancel.clone(),
+			slow_consumer.clone(),
+			false,
+		);
+		wait_for_subscribers(&transport, 1).await;
+
+		for stream_id in ["one", "two"] {
+			let state = Event::OrchestratorState {
+				stream_id: stream_id.to_string(),
+				state: OrchestratorState::new(60_000),
+			};
+			transport.send_to_subject(EventType::OrchestratorState.subject(), state.try_into().unwrap()).await.unwrap();
+		}
+
+		timeout(Duration::from_secs(10), slow_consumer.cancelled()).await.unwrap();
+		wait_for_subscribers(&transport, 0).await;
+		assert!(!cancel.is_cancelled());
+	}
+}
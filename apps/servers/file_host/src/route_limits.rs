@@ -0,0 +1,131 @@
+use crate::error::FileHostError;
+use axum::{
+	extract::{Request, State},
+	middleware::Next,
+	response::{IntoResponse, Response},
+};
+use std::{str::FromStr, sync::Arc};
+use tokio::sync::Semaphore;
+
+/// In-flight request caps for individual routes, on top of the global limit
+///
+/// Each entry covers a path and everything below it, so `/api/v1/audio`
+/// also limits `/api/v1/audio/{id}`. When several entries match, the longest
+/// path wins. Requests to paths without an entry are not limited here.
+#[derive(Debug, Default)]
+pub struct RouteConcurrencyLimits {
+	/// Longest path first, so the first match is the most specific
+	routes: Vec<(String, Arc<Semaphore>)>,
+}
+
+impl RouteConcurrencyLimits {
+	/// Cap `path` at `max_in_flight` concurrent requests
+	#[must_use]
+	pub fn with_limit(mut self, path: &str, max_in_flight: usize) -> Self {
+		let path = path.trim_end_matches('/').to_string();
+		self.routes.retain(|(existing, _)| *existing != path);
+		self.routes.push((path, Arc::new(Semaphore::new(max_in_flight))));
+		self.routes.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+		self
+	}
+
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.routes.is_empty()
+	}
+
+	fn semaphore_for(&self, path: &str) -> Option<&Arc<Semaphore>> {
+		self
+			.routes
+			.iter()
+			.find(|(prefix, _)| path.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.is_empty() || rest.starts_with('/')))
+			.map(|(_, semaphore)| semaphore)
+	}
+}
+
+/// Parses `ROUTE_CONCURRENCY_LIMITS`: comma-separated `path=limit` pairs,
+/// e.g. `/api/v1/audio=4,/api/v1/sheets=20`
+impl FromStr for RouteConcurrencyLimits {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		s.split(',').map(str::trim).filter(|entry| !entry.is_empty()).try_fold(Self::default(), |limits, entry| {
+			let (path, limit) = entry.split_once('=').ok_or_else(|| ["expected path=limit, got '", entry, "'"].concat())?;
+			let limit = limit.trim().parse().map_err(|_| ["invalid concurrency limit for ", path.trim()].concat())?;
+			Ok(limits.with_limit(path.trim(), limit))
+		})
+	}
+}
+
+/// Sheds requests with `503 Service Unavailable` once their route's limit is reached
+///
+/// Must sit outside any `nest`, since it matches on the full request path.
+pub async fn route_concurrency_middleware(State(limits): State<Arc<RouteConcurrencyLimits>>, req: Request, next: Next) -> Response {
+	let Some(semaphore) = limits.semaphore_for(req.uri().path()) else {
+		return next.run(req).await;
+	};
+	let Ok(_permit) = Arc::clone(semaphore).try_acquire_owned() else {
+		tracing::warn!(path = req.uri().path(), "route concurrency limit reached");
+		return FileHostError::ServiceOverloaded.into_response();
+	};
+	next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::{body::Body, http::StatusCode, middleware::from_fn_with_state, routing::get, Router};
+	use tokio::sync::Notify;
+	use tower::ServiceExt;
+
+	fn get_request(path: &str) -> Request {
+		Request::get(path).body(Body::empty()).unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_saturated_route_does_not_block_others() {
+		let (entered, release) = (Arc::new(Notify::new()), Arc::new(Notify::new()));
+		let (on_enter, on_release) = (Arc::clone(&entered), Arc::clone(&release));
+		let limits: RouteConcurrencyLimits = "/audio=1".parse().unwrap();
+
+		let app = Router::new()
+			.route(
+				"/audio/:id",
+				get(move || async move {
+					on_enter.notify_one();
+					on_release.notified().await;
+					"transcoded"
+				}),
+			)
+			.route("/sheets", get(|| async { "rows" }))
+			.layer(from_fn_with_state(Arc::new(limits), route_concurrency_middleware));
+
+		// Hold the only audio slot
+		let in_flight = tokio::spawn(app.clone().oneshot(get_request("/audio/1")));
+		entered.notified().await;
+
+		assert_eq!(app.clone().oneshot(get_request("/audio/2")).await.unwrap().status(), StatusCode::SERVICE_UNAVAILABLE);
+		assert_eq!(app.clone().oneshot(get_request("/sheets")).await.unwrap().status(), StatusCode::OK);
+
+		release.notify_one();
+		assert_eq!(in_flight.await.unwrap().unwrap().status(), StatusCode::OK);
+
+		// The slot is free again once the first request finishes
+		let retry = tokio::spawn(app.clone().oneshot(get_request("/audio/2")));
+		entered.notified().await;
+		release.notify_one();
+		assert_eq!(retry.await.unwrap().unwrap().status(), StatusCode::OK);
+	}
+
+	#[test]
+	fn test_longest_matching_path_wins() {
+		let limits: RouteConcurrencyLimits = " /api/v1=10, /api/v1/audio/=2 ".parse().unwrap();
+
+		assert_eq!(limits.semaphore_for("/api/v1/audio/7").map(|s| s.available_permits()), Some(2));
+		assert_eq!(limits.semaphore_for("/api/v1/sheets").map(|s| s.available_permits()), Some(10));
+		assert!(limits.semaphore_for("/api/v1audio").is_none());
+		assert!(limits.semaphore_for("/health").is_none());
+		assert!("/api/v1/audio".parse::<RouteConcurrencyLimits>().is_err());
+		assert!("".parse::<RouteConcurrencyLimits>().unwrap().is_empty());
+	}
+}
@@ -0,0 +1,12 @@
+use crate::handlers::admin as routes;
+use crate::AppState;
+use axum::{extract::FromRef, routing::post, Router};
+
+pub fn admin_connections<S>() -> Router<S>
+where
+	S: Clone + Send + Sync + 'static,
+	AppState: FromRef<S>,
+{
+	// Unversioned and without CORS: operator tooling only, never called from browsers
+	Router::new().route("/admin/connections/:connection_id/close", post(routes::close_connection))
+}
@@ -1,9 +1,9 @@
-use crate::handlers::audio_files as routes;
+use crate::handlers::{audio_files as routes, audio_ingest};
 use crate::routes::cors::allowlisted_cors;
 use crate::{AppState, Config};
 use axum::routing::{get, post};
 use axum::{
-	extract::FromRef,
+	extract::{DefaultBodyLimit, FromRef},
 	http::{
 		header::{AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, ETAG, LAST_MODIFIED},
 		Method,
@@ -27,3 +27,17 @@ where
 		.route("/search_audio", post(routes::search_audio_post))
 		.layer(cors)
 }
+
+pub fn ingest_audio<S>(config: &Config) -> Router<S>
+where
+	S: Clone + Send + Sync + 'static,
+	AppState: FromRef<S>,
+{
+	let cors = allowlisted_cors(config, vec![Method::POST], vec![CONTENT_TYPE, AUTHORIZATION]);
+
+	Router::new()
+		// Stream raw PCM to the transcriber; the handler enforces its own size cap
+		.route("/ingest_audio", post(audio_ingest::ingest_audio))
+		.layer(DefaultBodyLimit::disable())
+		.layer(cors)
+}
@@ -0,0 +1,16 @@
+use crate::handlers::capacity as routes;
+use crate::AppState;
+use axum::routing::get;
+use axum::{extract::FromRef, http::Method, Router};
+use tower_http::cors::{Any, CorsLayer};
+
+/// Connection headroom for adaptive clients, unversioned like `/health`
+pub fn get_capacity<S>() -> Router<S>
+where
+	S: Clone + Send + Sync + 'static,
+	AppState: FromRef<S>,
+{
+	let cors = CorsLayer::new().allow_origin(Any).allow_methods([Method::GET]).allow_headers(Any);
+
+	Router::new().route("/capacity", get(routes::capacity)).layer(cors)
+}
@@ -0,0 +1,11 @@
+use crate::handlers::metrics as routes;
+use axum::routing::get;
+use axum::Router;
+
+/// Prometheus scrape endpoint, unversioned like `/health`
+pub fn get_metrics<S>() -> Router<S>
+where
+	S: Clone + Send + Sync + 'static,
+{
+	Router::new().route("/metrics", get(routes::metrics))
+}
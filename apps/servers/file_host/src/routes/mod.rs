@@ -1,9 +1,12 @@
+pub mod admin;
 pub mod audio_files;
+pub mod capacity;
 pub mod cors;
 pub mod db;
 pub mod gdrive;
 pub mod github;
 pub mod health;
+pub mod metrics;
 pub mod sheets;
 pub mod tab_metadata;
 pub mod utterance;
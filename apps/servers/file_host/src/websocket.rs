@@ -1,45 +1,57 @@
 use crate::*;
 use axum::{
-	extract::{
-		ws::{WebSocket, WebSocketUpgrade},
-		ConnectInfo, FromRef, State,
-	},
+	extract::{ConnectInfo, FromRef, Query, State},
 	http::{HeaderMap, StatusCode},
-	response::IntoResponse,
+	response::{IntoResponse, Response},
 	routing::get,
 	Router,
 };
+use chrono::Utc;
+use dashmap::DashMap;
 use futures::stream::StreamExt;
 use std::{net::SocketAddr, sync::Arc};
 use tokio::task::JoinHandle;
-use tokio::time::{timeout, Duration};
+use tokio::time::Duration;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
-use ws_connection::ConnectionStore;
+use ws_connection::{ClientId, ConnectionStore};
 use ws_events::events::EventType;
 
+pub mod auth;
 pub mod broadcast;
+pub mod close;
 pub mod connection;
 pub mod heartbeat;
+pub mod ip_limit;
 pub mod message;
 pub mod shutdown;
+pub mod upgrade;
 
+use auth::{authenticate, WsAuthQuery};
 use broadcast::spawn_event_forwarder;
+pub use close::CloseReason;
 use connection::{clear_connection, establish_connection, send_initial_handshake};
-use message::spawn_process_incoming_messages;
+pub use ip_limit::{IpConnectionLimit, IpConnectionPermit};
+use message::{spawn_process_incoming_messages, MessageRateLimit};
+use upgrade::{Compression, WebSocket, WebSocketUpgrade};
 
 // Enhanced WebSocket FSM with comprehensive observability
 #[derive(Clone)]
 pub struct WebSocketFsm {
 	/// Domain layer: Connection actor handles
 	store: Arc<ConnectionStore<EventType>>,
+	/// Close reasons set by server-initiated closes, consumed by the event forwarder
+	close_reasons: Arc<DashMap<String, CloseReason>>,
 }
 
 impl WebSocketFsm {
 	/// Creates a new WebSocketFsm instance - only responsible for initialization
 	pub fn new() -> Self {
 		let store = Arc::new(ConnectionStore::<EventType>::new());
-		Self { store }
+		Self {
+			store,
+			close_reasons: Arc::new(DashMap::new()),
+		}
 	}
 
 	pub fn router<S>(self) -> Router<S>
@@ -80,37 +92,76 @@ impl Drop for ConnectionCleanup {
 	}
 }
 
-async fn websocket_handler(ws: WebSocketUpgrade, State(state): State<AppState>, ConnectInfo(addr): ConnectInfo<SocketAddr>, headers: HeaderMap) -> impl IntoResponse {
-	let client_id = addr.ip().to_string();
+async fn websocket_handler(
+	ws: WebSocketUpgrade,
+	State(state): State<AppState>,
+	ConnectInfo(addr): ConnectInfo<SocketAddr>,
+	Query(auth): Query<WsAuthQuery>,
+	headers: HeaderMap,
+) -> impl IntoResponse {
 	let cancel_token = state.core.cancel_token.clone();
-	info!("Incoming WS request from {client_id}");
+	info!("Incoming WS request from {addr}");
+
+	// Checked first, so one address can't tie up auth or the guard with many client ids
+	let ip_permit = match state.core.ip_connection_limit.admit(addr.ip()) {
+		Ok(ip_permit) => ip_permit,
+		Err(response) => return response,
+	};
+
+	match admit(&state.core.connection_guard, &state.core.config, &headers, &auth).await {
+		Ok((client_id, permit)) => ws.on_upgrade(Compression::from(state.core.config.as_ref()), move |socket| async move {
+			handle_socket(socket, state, client_id, headers, addr, permit, cancel_token).await;
+			// The address keeps its slot until the socket is done
+			drop(ip_permit);
+		}),
+		Err(response) => response,
+	}
+}
 
-	if !state.core.connection_guard.try_acquire_permit_hint() {
+/// Authenticate the upgrade request, then acquire a connection slot for the validated client
+///
+/// Unauthenticated requests are rejected with 401 before the guard is touched,
+/// so they can't consume capacity.
+async fn admit(guard: &ConnectionGuard, config: &Config, headers: &HeaderMap, auth: &WsAuthQuery) -> Result<(ClientId, ConnectionPermit), Response> {
+	let client_id = match authenticate(&config.hmac_key, headers, auth, Utc::now().timestamp()) {
+		Ok(client_id) => client_id,
+		Err(err) => {
+			warn!("Rejecting unauthenticated WS request");
+			return Err(err.into_response());
+		}
+	};
+
+	if !guard.try_acquire_permit_hint() {
 		warn!("Global limit exceeded — rejecting early");
-		return (StatusCode::SERVICE_UNAVAILABLE, "Too many connections").into_response();
+		return Err((StatusCode::SERVICE_UNAVAILABLE, "Too many connections").into_response());
 	}
 
-	// Wrap acquire in a timeout (e.g., 5 seconds)
-	match timeout(Duration::from_secs(5), state.core.connection_guard.acquire(client_id.clone())).await {
-		Ok(Ok(permit)) => ws.on_upgrade(move |socket| handle_socket(socket, state, headers, addr, permit, cancel_token)),
-		Ok(Err(err)) => {
+	// Give up after 5 seconds; a timed-out request leaves the client's queue
+	match guard.acquire_timeout(client_id.to_string(), Duration::from_secs(5)).await {
+		Ok(permit) => Ok((client_id, permit)),
+		Err(err) => {
 			use AcquireErrorKind::*;
-			let reason = match err.kind {
-				QueueFull => "Too many pending connections for this client",
-				GlobalLimit => "Server is at capacity",
+			let (status, reason) = match err.kind {
+				QueueFull => (StatusCode::SERVICE_UNAVAILABLE, "Too many pending connections for this client"),
+				QueueTimeout | Timeout => (StatusCode::REQUEST_TIMEOUT, "Connection acquisition timed out"),
+				GlobalLimit => (StatusCode::SERVICE_UNAVAILABLE, "Server is at capacity"),
 			};
 			error!("Rejecting WS for {client_id}: {reason}");
-			(StatusCode::SERVICE_UNAVAILABLE, reason).into_response()
-		}
-		Err(_timeout_elapsed) => {
-			error!("Timeout waiting for permit for {client_id}");
-			(StatusCode::REQUEST_TIMEOUT, "Connection acquisition timed out").into_response()
+			Err((status, reason).into_response())
 		}
 	}
 }
 
 /// Orchestrates the WebSocket connection lifecycle
-async fn handle_socket(socket: WebSocket, state: AppState, headers: HeaderMap, addr: SocketAddr, permit: ConnectionPermit, cancel_token: CancellationToken) {
+async fn handle_socket(
+	socket: WebSocket,
+	state: AppState,
+	client_id: ClientId,
+	headers: HeaderMap,
+	addr: SocketAddr,
+	permit: ConnectionPermit,
+	cancel_token: CancellationToken,
+) {
 	let (mut sender, receiver) = socket.split();
 
 	// Direct WS client channel (mpsc)
@@ -120,7 +171,7 @@ async fn handle_socket(socket: WebSocket, state: AppState, headers: HeaderMap, a
 	let ws_fsm = state.realtime.ws;
 
 	// Establish connection through FSM
-	let conn_key = match establish_connection(&ws_fsm, &headers, &addr, &cancel_token).await {
+	let conn_key = match establish_connection(&ws_fsm, client_id, &headers, &addr, &cancel_token).await {
 		Ok(connection) => connection,
 		Err(_) => {
 			return;
@@ -132,13 +183,23 @@ async fn handle_socket(socket: WebSocket, state: AppState, headers: HeaderMap, a
 		return;
 	}
 
-	// Pass cancel token to both tasks
-	let forward_cancel = cancel_token.child_token().clone();
-	let process_cancel = cancel_token.child_token().clone();
+	// Tie both tasks to the connection's actor so removing it from the store closes the socket
+	let conn_cancel = ws_fsm.connection_token(&conn_key).unwrap_or_else(|| cancel_token.child_token());
+	let forward_cancel = conn_cancel.child_token();
+	let process_cancel = conn_cancel.child_token();
 
 	let forward_task = spawn_event_forwarder(sender, ws_rx, ws_fsm.clone(), transport.clone(), conn_key.clone(), forward_cancel.clone());
 
-	let message_task = spawn_process_incoming_messages(receiver, ws_fsm.clone(), transport.clone(), ws_tx.clone(), conn_key.clone(), process_cancel.clone());
+	let rate_limit = MessageRateLimit::from(state.core.config.as_ref());
+	let message_task = spawn_process_incoming_messages(
+		receiver,
+		ws_fsm.clone(),
+		transport.clone(),
+		ws_tx.clone(),
+		conn_key.clone(),
+		rate_limit,
+		process_cancel.clone(),
+	);
 
 	let mut cleanup = ConnectionCleanup {
 		permit: Some(permit),
@@ -157,3 +218,63 @@ async fn handle_socket(socket: WebSocket, state: AppState, headers: HeaderMap, a
 
 // Re-export for compatibility
 pub use WebSocketFsm as WebSocketState;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::http::{header::AUTHORIZATION, HeaderValue};
+	use clap::Parser;
+
+	fn ws_config() -> Config {
+		Config::try_parse_from([
+			"file_host",
+			"--hmac-key=test-key",
+			"--client-secret-file=test",
+			"--obs-host=127.0.0.1",
+			"--obs-password=test",
+			"--github-token=test",
+			"--database-url=sqlite::memory:",
+		])
+		.unwrap()
+	}
+
+	fn bearer(token: &str) -> HeaderMap {
+		let mut headers = HeaderMap::new();
+		headers.insert(AUTHORIZATION, HeaderValue::from_str(&["Bearer ", token].concat()).unwrap());
+		headers
+	}
+
+	#[tokio::test]
+	async fn test_admit_authenticates_before_acquiring_a_permit() {
+		let config = ws_config();
+		let guard = ConnectionGuard::new();
+		let expires_at = Utc::now().timestamp() + 60;
+		let no_query = WsAuthQuery::default();
+
+		// Missing, forged, and expired tokens are all rejected without touching the guard
+		let forged = auth::issue_token("other-key", "alice", expires_at);
+		let expired = auth::issue_token(&config.hmac_key, "alice", Utc::now().timestamp() - 1);
+		for headers in [HeaderMap::new(), bearer(&forged), bearer(&expired), bearer("alice.garbage")] {
+			let rejected = admit(&guard, &config, &headers, &no_query).await.err().expect("token should be rejected");
+			assert_eq!(rejected.status(), StatusCode::UNAUTHORIZED);
+		}
+		assert_eq!(guard.active_global(), 0);
+
+		// A valid token proceeds with the client id taken from the token, not the request
+		let token = auth::issue_token(&config.hmac_key, "alice", expires_at);
+		let mut headers = bearer(&token);
+		headers.insert("x-client-id", HeaderValue::from_static("mallory"));
+		let (client_id, permit) = admit(&guard, &config, &headers, &no_query).await.unwrap();
+		assert_eq!(client_id.as_str(), "auth:alice");
+		assert_eq!(guard.active_global(), 1);
+		permit.release();
+
+		// Browsers pass the token as a query parameter instead
+		let query = WsAuthQuery {
+			token: Some(auth::issue_token(&config.hmac_key, "bob", expires_at)),
+		};
+		let (client_id, permit) = admit(&guard, &config, &HeaderMap::new(), &query).await.unwrap();
+		assert_eq!(client_id.as_str(), "auth:bob");
+		permit.release();
+	}
+}
@@ -0,0 +1,74 @@
+use crate::FileHostError;
+use axum::http::{header::AUTHORIZATION, HeaderMap};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use ws_connection::ClientId;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Longest client name a token may carry
+const MAX_CLIENT_LEN: usize = 64;
+
+/// Query parameters accepted on the upgrade request
+///
+/// Browsers can't set headers on a WebSocket upgrade, so the token may be
+/// passed as `?token=` instead of `Authorization: Bearer`.
+#[derive(Debug, Default, Deserialize)]
+pub struct WsAuthQuery {
+	pub token: Option<String>,
+}
+
+/// Sign a token for `client` valid until `expires_at` (unix seconds)
+///
+/// Tokens have the form `<client>.<expires_at>.<hex hmac-sha256>`, keyed by
+/// `HMAC_KEY`, so `client` must not contain a `.`.
+#[must_use]
+pub fn issue_token(hmac_key: &str, client: &str, expires_at: i64) -> String {
+	let payload = [client, ".", &expires_at.to_string()].concat();
+	let signature = hex::encode(sign(hmac_key, &payload).finalize().into_bytes());
+	[payload.as_str(), ".", &signature].concat()
+}
+
+/// Validate the upgrade request's token and derive the connection's `ClientId`
+///
+/// The `Authorization: Bearer` header takes priority over the `token` query parameter.
+///
+/// # Errors
+///
+/// Returns `Unauthorized` if no token is present, or it is malformed, expired,
+/// or not signed with `hmac_key`.
+pub fn authenticate(hmac_key: &str, headers: &HeaderMap, query: &WsAuthQuery, now: i64) -> Result<ClientId, FileHostError> {
+	let token = headers
+		.get(AUTHORIZATION)
+		.and_then(|v| v.to_str().ok())
+		.and_then(|v| v.strip_prefix("Bearer "))
+		.or(query.token.as_deref())
+		.ok_or(FileHostError::Unauthorized)?;
+
+	let mut parts = token.splitn(3, '.');
+	let (Some(client), Some(expires_at), Some(signature)) = (parts.next(), parts.next(), parts.next()) else {
+		return Err(FileHostError::Unauthorized);
+	};
+	if client.is_empty() || client.len() > MAX_CLIENT_LEN {
+		return Err(FileHostError::Unauthorized);
+	}
+
+	let signature = hex::decode(signature).map_err(|_| FileHostError::Unauthorized)?;
+	sign(hmac_key, &[client, ".", expires_at].concat())
+		.verify_slice(&signature)
+		.map_err(|_| FileHostError::Unauthorized)?;
+
+	let expires_at: i64 = expires_at.parse().map_err(|_| FileHostError::Unauthorized)?;
+	if expires_at <= now {
+		return Err(FileHostError::Unauthorized);
+	}
+
+	Ok(ClientId::new(["auth:", client].concat()))
+}
+
+fn sign(hmac_key: &str, payload: &str) -> HmacSha256 {
+	let mut mac = HmacSha256::new_from_slice(hmac_key.as_bytes()).expect("HMAC accepts keys of any length");
+	mac.update(payload.as_bytes());
+	mac
+}
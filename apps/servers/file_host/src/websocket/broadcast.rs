@@ -7,6 +7,8 @@ mod handlers;
 
 pub(crate) use errors::BroadcastError;
 pub(crate) use handlers::spawn_event_forwarder;
+#[cfg(test)]
+pub(crate) use handlers::spawn_nats_task;
 
 impl WebSocketFsm {
 	/// Broadcast an event to all subscribers of its type
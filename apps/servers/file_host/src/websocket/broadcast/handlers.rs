@@ -1,7 +1,9 @@
+use crate::realtime::SupervisedTransport;
+use crate::websocket::upgrade::{Message, WebSocket};
+use crate::websocket::CloseReason;
 use crate::WebSocketFsm;
-use axum::extract::ws::{Message, WebSocket};
 use futures::{sink::SinkExt, stream::SplitSink};
-use some_transport::{NatsTransport, RecvResult, SendResult, SenderExt, Transport, UnboundedReceiverExt};
+use some_transport::{ReceiverTrait, RecvResult, SendResult, SenderExt, Transport, TransportReceiver, UnboundedReceiverExt};
 use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::{
 	sync::mpsc::{self, UnboundedReceiver},
@@ -16,12 +18,15 @@ use ws_events::{
 
 static WS_FORWARD_ERR_COUNT: AtomicU64 = AtomicU64::new(0);
 
+/// How long an event may wait for room in a full per-event channel before the client counts as too slow
+const SLOW_CONSUMER_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Spawn the NATS -> WS pipeline for a connection
 pub(crate) fn spawn_event_forwarder(
 	mut ws_sender: SplitSink<WebSocket, Message>,
 	mut ws_direct: UnboundedReceiver<Event>,
 	state: WebSocketFsm,
-	transport: NatsTransport<UnifiedEvent>,
+	transport: SupervisedTransport,
 	conn_key: String,
 	cancel_token: CancellationToken,
 ) -> tokio::task::JoinHandle<()> {
@@ -33,20 +38,64 @@ pub(crate) fn spawn_event_forwarder(
 		let (tab_tx, mut tab_rx) = mpsc::channel::<Event>(100);
 		let (utt_tx, mut utt_rx) = mpsc::channel::<Event>(100);
 		let (orch_tx, mut orch_rx) = mpsc::channel::<Event>(100);
+		// Cancelled by a receiver task whose channel stayed full
+		let slow_consumer = CancellationToken::new();
 
 		// Spawn receiver tasks
-		spawn_nats_task(EventType::ObsStatus, transport.clone(), obs_tx, conn_key.clone(), cancel_token.clone(), true);
-		spawn_nats_task(EventType::TabMetaData, transport.clone(), tab_tx, conn_key.clone(), cancel_token.clone(), false);
-		spawn_nats_task(EventType::Utterance, transport.clone(), utt_tx, conn_key.clone(), cancel_token.clone(), false);
-		spawn_nats_task(EventType::OrchestratorState, transport.clone(), orch_tx, conn_key.clone(), cancel_token.clone(), false);
+		spawn_nats_task(
+			EventType::ObsStatus,
+			transport.clone(),
+			obs_tx,
+			conn_key.clone(),
+			cancel_token.clone(),
+			slow_consumer.clone(),
+			true,
+		);
+		spawn_nats_task(
+			EventType::TabMetaData,
+			transport.clone(),
+			tab_tx,
+			conn_key.clone(),
+			cancel_token.clone(),
+			slow_consumer.clone(),
+			false,
+		);
+		spawn_nats_task(
+			EventType::Utterance,
+			transport.clone(),
+			utt_tx,
+			conn_key.clone(),
+			cancel_token.clone(),
+			slow_consumer.clone(),
+			false,
+		);
+		spawn_nats_task(
+			EventType::OrchestratorState,
+			transport.clone(),
+			orch_tx,
+			conn_key.clone(),
+			cancel_token.clone(),
+			slow_consumer.clone(),
+			false,
+		);
 
 		let mut total_forwarded = 0u64;
+		// Why the loop ended, unless the connection was already removed elsewhere
+		let reason;
 
 		loop {
 			tokio::select! {
 				_ = cancel_token.cancelled() => {
-					info!(connection_id=%conn_key, "Event forwarder cancelled");
-					let _ = ws_sender.send(Message::Close(None)).await;
+					reason = state.take_close_reason(&conn_key).unwrap_or(CloseReason::ServerShutdown);
+					info!(connection_id=%conn_key, %reason, "Event forwarder cancelled");
+					let _ = ws_sender.send(Message::Close(Some(reason.close_frame()))).await;
+					break;
+				}
+
+				() = slow_consumer.cancelled() => {
+					reason = CloseReason::SlowConsumer;
+					warn!(connection_id=%conn_key, "Client fell behind on outbound events - closing");
+					let _ = ws_sender.send(Message::Close(Some(reason.close_frame()))).await;
 					break;
 				}
 
@@ -102,73 +151,130 @@ pub(crate) fn spawn_event_forwarder(
 					};
 					if let Err(e) = ws_sender.send(Message::Text(msg)).await {
 						warn!("Failed to send ping to {conn_key}: {e} - client disconnected");
+						reason = CloseReason::ClientInitiated;
 						break;
 					}
 					debug!("Sent ping to {conn_key}");
 				}
 
-				else => break, // all channels closed
+				else => {
+					// all channels closed
+					reason = CloseReason::ClientInitiated;
+					break;
+				}
 			}
 		}
 
 		// Cleanup connection from store
-		let _ = state.remove_connection(&conn_key, "Event forwarder ended".to_string()).await;
+		let _ = state.remove_connection(&conn_key, reason).await;
 
 		info!(connection_id=%conn_key, total_forwarded, "Forwarding ended");
 	})
 }
 
 /// Spawn a single NATS receiver task
-fn spawn_nats_task(
+///
+/// Resubscribes whenever the supervisor brings the connection back, so a
+/// dropped transport pauses the subscription instead of ending it. With
+/// `drop_if_full` events that don't fit in `sender` are dropped; otherwise an
+/// event that can't be queued within `SLOW_CONSUMER_TIMEOUT` cancels
+/// `slow_consumer` and ends the task.
+pub(crate) fn spawn_nats_task<T, R>(
 	event_type: EventType,
-	transport: NatsTransport<UnifiedEvent>,
+	mut transport: SupervisedTransport<T>,
 	sender: mpsc::Sender<Event>,
 	conn_key: String,
 	cancel_token: CancellationToken,
+	slow_consumer: CancellationToken,
 	drop_if_full: bool,
-) {
+) where
+	T: Transport<UnifiedEvent, Receiver = TransportReceiver<UnifiedEvent, R>>,
+	R: ReceiverTrait<UnifiedEvent> + Send + 'static,
+{
 	tokio::spawn(async move {
-		let mut rx = transport.subscribe_to_subject(&event_type.subject()).await;
+		let mut supervised = true;
 
-		loop {
-			tokio::select! {
-				_ = cancel_token.cancelled() => break,
-
-				result = rx.recv() => match result {
-					Ok(unified) => {
-						let event_result: Result<Event, String> = unified.into();
-						let event = match event_result {
-							Ok(e) => e,
-							Err(e) => {
-								error!(
-									connection_id = %conn_key,
-									?event_type,
-									error = %e,
-									"Fatal schema mismatch — stopping subscriber"
-								);
-								continue;
-							}
-						};
-
-						// Send using MPSC utils
-						let send_result = if drop_if_full {
-							sender.try_send_graceful(event.clone(), &format!("NATS {}", event_type.subject()))
-						} else {
-							sender.send_with_backpressure_warn(event.clone(), &format!("NATS {}", event_type.subject())).await
-						};
-
-						if let SendResult::ReceiverDropped(_) = send_result {
-							debug!(
-								connection_id=%conn_key,
-								?event_type,
-								"Receiver dropped, message lost"
-							);
+		'subscribe: loop {
+			let current = transport.subscribe_current();
+			let mut rx = current.subscribe_to_subject(&event_type.subject()).await;
+
+			// Orchestrator state is only published on transitions; now that we are
+			// listening, ask for the current state so the client doesn't start blank
+			if event_type == EventType::OrchestratorState {
+				tokio::select! {
+					() = cancel_token.cancelled() => break 'subscribe,
+					() = replay_orchestrator_states(&current, &sender, &conn_key) => {}
+				}
+			}
 
+			loop {
+				tokio::select! {
+					_ = cancel_token.cancelled() => break 'subscribe,
+
+					replaced = transport.replaced(), if supervised => {
+						if replaced {
+							debug!(connection_id = %conn_key, ?event_type, "Transport reconnected - resubscribing");
+							continue 'subscribe;
 						}
+						supervised = false;
 					}
-					Err(e) => {
-						error!(connection_id=%conn_key, ?event_type, "NATS receive error: {}", e);
-						break;
+
+					result = rx.recv() => match result {
+						Ok(unified) => {
+							let event_result: Result<Event, String> = unified.into();
+							let event = match event_result {
+								Ok(e) => e,
+								Err(e) => {
+									error!(
+										connection_id = %conn_key,
+										?event_type,
+										error = %e,
+										"Fatal schema mismatch — stopping subscriber"
+									);
+									continue;
+								}
+							};
+
+							// Send using MPSC utils
+							let context = ["NATS ", event_type.subject()].concat();
+							let send_result = if drop_if_full {
+								sender.try_send_graceful(event.clone(), &context)
+							} else {
+								sender.send_graceful_timeout(event.clone(), SLOW_CONSUMER_TIMEOUT, &context).await
+							};
+
+							match send_result {
+								SendResult::ReceiverDropped(_) => {
+									debug!(
+										connection_id=%conn_key,
+										?event_type,
+										"Receiver dropped, message lost"
+									);
+								}
+								SendResult::ChannelFull(_) if !drop_if_full => {
+									slow_consumer.cancel();
+									break 'subscribe;
+								}
+								_ => {}
+							}
+						}
+						Err(e) => {
+							error!(connection_id=%conn_key, ?event_type, "NATS receive error: {}", e);
+							if !supervised {
+								break 'subscribe;
+							}
+
+							// Wait for the supervisor to bring the connection back
+							tokio::select! {
+								_ = cancel_token.cancelled() => break 'subscribe,
+								replaced = transport.replaced() => {
+									if replaced {
+										continue 'subscribe;
+									}
+									break 'subscribe;
+								}
+							}
+						}
 					}
 				}
 			}
@@ -176,6 +282,29 @@ fn spawn_nats_task(
 	});
 }
 
+/// Ask the orchestrator for the current state of every stream and queue it for this connection only
+async fn replay_orchestrator_states<T: Transport<UnifiedEvent>>(transport: &T, sender: &mpsc::Sender<Event>, conn_key: &str) {
+	// An empty stream id asks for every stream
+	let query = Event::OrchestratorStateQuery { stream_id: String::new() };
+	let reply = match UnifiedEvent::try_from(query) {
+		Ok(query) => transport.request(EventType::OrchestratorStateQuery.subject(), query).await.map_err(|e| e.to_string()),
+		Err(e) => Err(e),
+	};
+
+	match reply.and_then(Result::<Event, String>::from) {
+		Ok(Event::OrchestratorStates { states }) => {
+			for (stream_id, state) in states {
+				let event = Event::OrchestratorState { stream_id, state };
+				if let SendResult::ReceiverDropped(_) = sender.send_with_backpressure_warn(event, "orchestrator state replay").await {
+					return;
+				}
+			}
+		}
+		Ok(other) => warn!(connection_id = %conn_key, event_type = ?other.get_type(), "Unexpected reply to orchestrator state query"),
+		Err(e) => warn!(connection_id = %conn_key, error = %e, "Failed to fetch current orchestrator states"),
+	}
+}
+
 /// Forward a single event to the WebSocket client
 async fn forward_event(sender: &mut SplitSink<WebSocket, Message>, event: &Event, conn_key: &str) -> Result<(), ()> {
 	let json = serde_json::to_string(event).map_err(|e| {
@@ -0,0 +1,117 @@
+use crate::websocket::connection::instrument::WS_CLOSED_TOTAL;
+use crate::websocket::upgrade::CloseFrame;
+use std::fmt;
+
+/// Why a WebSocket connection was closed, used to label `ws_closed_total`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+	/// No inbound activity within the stale timeout
+	IdleTimeout,
+	/// Client could not keep up with outbound traffic
+	SlowConsumer,
+	/// Server-enforced policy (abuse, moderation, eviction)
+	PolicyViolation,
+	/// Client sent a close frame, its stream ended, or its socket went away
+	ClientInitiated,
+	/// Server is shutting down
+	ServerShutdown,
+	/// Setup, transport, or protocol failure
+	Error,
+}
+
+impl CloseReason {
+	/// Metric label value
+	#[must_use]
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			Self::IdleTimeout => "idle_timeout",
+			Self::SlowConsumer => "slow_consumer",
+			Self::PolicyViolation => "policy_violation",
+			Self::ClientInitiated => "client_initiated",
+			Self::ServerShutdown => "server_shutdown",
+			Self::Error => "error",
+		}
+	}
+
+	/// WebSocket close code (RFC 6455 §7.4.1) matching this reason
+	#[must_use]
+	pub const fn close_code(&self) -> u16 {
+		match self {
+			Self::IdleTimeout | Self::ClientInitiated => 1000,
+			Self::ServerShutdown => 1001,
+			Self::PolicyViolation => 1008,
+			Self::SlowConsumer | Self::Error => 1011,
+		}
+	}
+
+	/// Close frame to send to the client for this reason
+	#[must_use]
+	pub fn close_frame(self) -> CloseFrame<'static> {
+		CloseFrame {
+			code: self.close_code().into(),
+			reason: self.as_str().into(),
+		}
+	}
+
+	/// Increment `ws_closed_total{reason}`
+	pub(crate) fn record(self) {
+		WS_CLOSED_TOTAL.with_label_values(&[self.as_str()]).inc();
+	}
+}
+
+impl fmt::Display for CloseReason {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::WebSocketFsm;
+	use axum::http::HeaderMap;
+	use std::net::SocketAddr;
+	use tokio_util::sync::CancellationToken;
+
+	fn closed_count(reason: CloseReason) -> u64 {
+		WS_CLOSED_TOTAL.with_label_values(&[reason.as_str()]).get()
+	}
+
+	#[tokio::test]
+	async fn test_close_paths_increment_labeled_counter() {
+		let fsm = WebSocketFsm::new();
+		let cancel = CancellationToken::new();
+		let addr: SocketAddr = "127.0.0.1:9000".parse().unwrap();
+
+		let client_before = closed_count(CloseReason::ClientInitiated);
+		let shutdown_before = closed_count(CloseReason::ServerShutdown);
+
+		let first = fsm.add_connection(&HeaderMap::new(), &addr, &cancel).await.unwrap();
+		let _second = fsm.add_connection(&HeaderMap::new(), &addr, &cancel).await.unwrap();
+
+		// Client-initiated close for one connection
+		fsm.remove_connection(&first, CloseReason::ClientInitiated).await.unwrap();
+		// Removing an already-closed connection must not double count
+		fsm.remove_connection(&first, CloseReason::Error).await.unwrap();
+
+		// Shutdown closes the remaining one
+		fsm.shutdown().await;
+
+		assert_eq!(closed_count(CloseReason::ClientInitiated) - client_before, 1);
+		assert_eq!(closed_count(CloseReason::ServerShutdown) - shutdown_before, 1);
+	}
+
+	#[tokio::test]
+	async fn test_untaken_close_reason_is_dropped_on_final_cleanup() {
+		let fsm = WebSocketFsm::new();
+		let cancel = CancellationToken::new();
+		let addr: SocketAddr = "127.0.0.1:9000".parse().unwrap();
+
+		let key = fsm.add_connection(&HeaderMap::new(), &addr, &cancel).await.unwrap();
+		assert!(fsm.evict_connection(&key).await.unwrap());
+
+		// The forwarder exits without reading the reason and cleans up after itself
+		fsm.remove_connection(&key, CloseReason::ClientInitiated).await.unwrap();
+		assert_eq!(fsm.take_close_reason(&key), None);
+	}
+}
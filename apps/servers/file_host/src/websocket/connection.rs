@@ -1,3 +1,4 @@
+use crate::websocket::CloseReason;
 use crate::WebSocketFsm;
 use axum::http::HeaderMap;
 use std::net::SocketAddr;
@@ -48,10 +49,16 @@ impl WebSocketFsm {
 
 	/// Adds a connection to the store with comprehensive observability
 	pub async fn add_connection(&self, headers: &HeaderMap, addr: &SocketAddr, cancel_token: &CancellationToken) -> Result<String, ConnectionError> {
-		let start = Instant::now();
 		let client_id = self.client_id_from_request(headers, addr);
+		self.add_client_connection(client_id, headers, addr, cancel_token).await
+	}
 
-		let domain_conn = Connection::new(client_id.clone(), *addr);
+	/// Adds a connection for an already identified client, e.g. one that authenticated on upgrade
+	pub async fn add_client_connection(&self, client_id: ClientId, headers: &HeaderMap, addr: &SocketAddr, cancel_token: &CancellationToken) -> Result<String, ConnectionError> {
+		let start = Instant::now();
+
+		let user_agent = headers.get("user-agent").and_then(|v| v.to_str().ok()).unwrap_or("unknown");
+		let domain_conn = Connection::new(client_id.clone(), *addr).with_metadata("user_agent", user_agent);
 
 		let connection_id = domain_conn.id.clone();
 		let client_key = connection_id.as_string();
@@ -108,7 +115,12 @@ impl WebSocketFsm {
 	}
 
 	/// Remove a connection with comprehensive cleanup and observability
-	pub async fn remove_connection(&self, client_key: &str, reason: String) -> Result<(), ConnectionError> {
+	///
+	/// `ws_closed_total{reason}` is only incremented when the connection was
+	/// still present, so racing close paths don't double count. Removing a
+	/// connection that is already gone also drops any close reason nobody took,
+	/// which the forwarder's own cleanup always does.
+	pub async fn remove_connection(&self, client_key: &str, reason: CloseReason) -> Result<(), ConnectionError> {
 		let start = Instant::now();
 
 		match self.store.remove(client_key).await {
@@ -128,6 +140,7 @@ impl WebSocketFsm {
 					);
 				}
 
+				reason.record();
 				let elapsed = start.elapsed();
 
 				info!(
@@ -143,6 +156,7 @@ impl WebSocketFsm {
 				Ok(())
 			}
 			None => {
+				self.close_reasons.remove(client_key);
 				warn!(
 					connection_key = client_key,
 					reason = %reason,
@@ -153,6 +167,39 @@ impl WebSocketFsm {
 		}
 	}
 
+	/// Token cancelled when the connection is removed from the store
+	#[must_use]
+	pub fn connection_token(&self, client_key: &str) -> Option<CancellationToken> {
+		self.store.get(client_key).map(|handle| handle.cancel_token())
+	}
+
+	/// Force-close a connection with a policy close code
+	///
+	/// Removing it cancels the connection's tasks, so the socket is closed with
+	/// 1008 and its `ConnectionGuard` permit is released once they exit.
+	/// Returns `false` if no connection with that key exists.
+	///
+	/// # Errors
+	///
+	/// Returns an error if removing the connection fails.
+	pub async fn evict_connection(&self, client_key: &str) -> Result<bool, ConnectionError> {
+		if self.store.get(client_key).is_none() {
+			return Ok(false);
+		}
+
+		self.close_reasons.insert(client_key.to_string(), CloseReason::PolicyViolation);
+		self.remove_connection(client_key, CloseReason::PolicyViolation).await?;
+
+		warn!(connection_id = %client_key, "Connection force-closed by admin");
+		Ok(true)
+	}
+
+	/// Take the reason recorded for a server-initiated close, if any
+	#[must_use]
+	pub fn take_close_reason(&self, client_key: &str) -> Option<CloseReason> {
+		self.close_reasons.remove(client_key).map(|(_, reason)| reason)
+	}
+
 	/// Handle subscription changes for a connection
 	pub async fn handle_subscription_update(&self, connection_id: &str, add_types: Vec<EventType>, remove_types: Vec<EventType>) -> Result<(), ConnectionError> {
 		// Update actor subscription state
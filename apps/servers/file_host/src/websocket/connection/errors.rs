@@ -27,8 +27,8 @@ pub enum ConnectionError {
 	WebSocketSendError(String),
 }
 
-impl From<axum::Error> for ConnectionError {
-	fn from(err: axum::Error) -> Self {
+impl From<crate::websocket::upgrade::Error> for ConnectionError {
+	fn from(err: crate::websocket::upgrade::Error) -> Self {
 		ConnectionError::WebSocketSendError(err.to_string())
 	}
 }
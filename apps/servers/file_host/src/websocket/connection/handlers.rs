@@ -1,16 +1,24 @@
 use super::errors::ConnectionError;
+use crate::websocket::upgrade::{Message, WebSocket};
+use crate::websocket::CloseReason;
 use crate::WebSocketFsm;
-use axum::extract::ws::{Message, WebSocket};
 use axum::http::HeaderMap;
 use futures::sink::SinkExt;
 use futures::stream::SplitSink;
 use std::net::SocketAddr;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
+use ws_connection::ClientId;
 use ws_events::events::Event;
 
-pub(crate) async fn establish_connection(state: &WebSocketFsm, headers: &HeaderMap, addr: &SocketAddr, cancel_token: &CancellationToken) -> Result<String, ConnectionError> {
-	let key = state.add_connection(headers, addr, cancel_token).await?;
+pub(crate) async fn establish_connection(
+	state: &WebSocketFsm,
+	client_id: ClientId,
+	headers: &HeaderMap,
+	addr: &SocketAddr,
+	cancel_token: &CancellationToken,
+) -> Result<String, ConnectionError> {
+	let key = state.add_client_connection(client_id, headers, addr, cancel_token).await?;
 	info!(connection_id = %key, "WebSocket connection established");
 	Ok(key)
 }
@@ -25,7 +33,7 @@ pub(crate) async fn send_initial_handshake(sender: &mut SplitSink<WebSocket, Mes
 }
 
 pub(crate) async fn clear_connection(state: &WebSocketFsm, conn_key: &str) {
-	let result = state.remove_connection(conn_key, "Connection failed during setup".to_string()).await;
+	let result = state.remove_connection(conn_key, CloseReason::Error).await;
 
 	if let Err(e) = result {
 		error!(
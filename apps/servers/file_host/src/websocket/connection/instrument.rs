@@ -46,6 +46,12 @@ lazy_static! {
 				&["event_type"]
 		).expect("Failed to register CONNECTION_SUBSCRIPTIONS");
 
+		pub static ref WS_CLOSED_TOTAL: IntCounterVec = register_int_counter_vec!(
+				"ws_closed_total",
+				"Closed connections by close reason",
+				&["reason"] // see CloseReason::as_str
+		).expect("Failed to register WS_CLOSED_TOTAL");
+
 		pub static ref CONNECTION_ERRORS: IntCounterVec = register_int_counter_vec!(
 				"ws_connection_errors_total",
 				"Connection-related errors",
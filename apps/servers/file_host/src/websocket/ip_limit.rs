@@ -0,0 +1,111 @@
+use axum::{
+	http::StatusCode,
+	response::{IntoResponse, Response},
+};
+use dashmap::DashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Caps concurrent WebSocket connections per remote IP
+///
+/// Checked before authentication and the `ConnectionGuard`, which limits per
+/// client id; one address could otherwise register any number of client ids.
+/// Clones share the same counts.
+#[derive(Clone)]
+pub struct IpConnectionLimit {
+	max_per_ip: usize,
+	counts: Arc<DashMap<IpAddr, AtomicUsize>>,
+}
+
+/// One connection counted against its IP; the count drops when this does
+pub struct IpConnectionPermit {
+	ip: IpAddr,
+	counts: Arc<DashMap<IpAddr, AtomicUsize>>,
+}
+
+impl IpConnectionLimit {
+	#[must_use]
+	pub fn new(max_per_ip: usize) -> Self {
+		Self {
+			max_per_ip,
+			counts: Arc::new(DashMap::new()),
+		}
+	}
+
+	/// Count a new connection from `ip`, or `None` if it is already at the cap
+	#[must_use]
+	pub fn try_acquire(&self, ip: IpAddr) -> Option<IpConnectionPermit> {
+		let count = self.counts.entry(ip).or_default();
+		count.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < self.max_per_ip).then_some(n + 1)).ok()?;
+		drop(count);
+		Some(IpConnectionPermit {
+			ip,
+			counts: Arc::clone(&self.counts),
+		})
+	}
+
+	/// Admit a connection from `ip`, rejecting it with 429 once the address is at the cap
+	///
+	/// # Errors
+	///
+	/// Returns a `429 Too Many Requests` response if `ip` already has `max_per_ip` connections.
+	pub fn admit(&self, ip: IpAddr) -> Result<IpConnectionPermit, Response> {
+		self.try_acquire(ip).ok_or_else(|| {
+			warn!("Rejecting WS from {ip}: {} connections already open", self.max_per_ip);
+			(StatusCode::TOO_MANY_REQUESTS, "Too many connections from this address").into_response()
+		})
+	}
+
+	/// Open connections counted against `ip`
+	#[must_use]
+	pub fn connections_from(&self, ip: IpAddr) -> usize {
+		self.counts.get(&ip).map_or(0, |count| count.load(Ordering::SeqCst))
+	}
+}
+
+impl Drop for IpConnectionPermit {
+	fn drop(&mut self) {
+		let Some(count) = self.counts.get(&self.ip) else {
+			return;
+		};
+		let remaining = count.fetch_sub(1, Ordering::SeqCst) - 1;
+		drop(count);
+
+		// Forget addresses with no connections so the map doesn't grow with every client seen
+		if remaining == 0 {
+			self.counts.remove_if(&self.ip, |_, count| count.load(Ordering::SeqCst) == 0);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_connections_past_the_per_ip_cap_are_rejected() {
+		let limit = IpConnectionLimit::new(2);
+		let ip: IpAddr = "203.0.113.7".parse().unwrap();
+		let other: IpAddr = "203.0.113.8".parse().unwrap();
+
+		let first = limit.admit(ip).unwrap();
+		let second = limit.admit(ip).unwrap();
+		let rejected = limit.admit(ip).err().expect("third connection should be rejected");
+		assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+		assert_eq!(limit.connections_from(ip), 2);
+
+		// Other addresses are unaffected
+		let elsewhere = limit.admit(other).unwrap();
+
+		// Closing a connection frees its slot
+		drop(first);
+		assert_eq!(limit.connections_from(ip), 1);
+		let third = limit.admit(ip).unwrap();
+
+		// Addresses with no open connections are cleaned up
+		drop((second, third, elsewhere));
+		assert!(limit.counts.is_empty());
+	}
+}
@@ -1,3 +1,4 @@
+use crate::metrics::otel::record_ws_message_rejected;
 use crate::WebSocketFsm;
 use some_transport::{NatsTransport, UnboundedSenderExt};
 use tokio::sync::mpsc::UnboundedSender;
@@ -5,26 +6,19 @@ use tracing::{error, warn};
 use ws_events::events::{Event, EventType, SystemEvent, UnifiedEvent};
 
 pub(crate) mod handlers;
+mod throttle;
+mod validation;
 
 pub(crate) use handlers::spawn_process_incoming_messages;
+pub use throttle::MessageRateLimit;
+pub use validation::{parse_client_message, InboundMessageError};
 
 impl WebSocketFsm {
 	/// Process a text message from a client
 	pub async fn process_message(&self, transport: NatsTransport<UnifiedEvent>, ws_tx: UnboundedSender<Event>, conn_key: &str, raw_message: String) {
 		// Parse the message
-		let client_message = match serde_json::from_str::<Event>(&raw_message) {
-			Ok(msg) => msg,
-			Err(e) => {
-				warn!(
-					connection_id = %conn_key,
-					error = %e,
-					raw_message = %raw_message,
-					"Failed to deserialize websocket JSON message"
-				);
-
-				self.send_error_to_client(ws_tx, &format!("Invalid JSON: {}", e));
-				return;
-			}
+		let Some(client_message) = self.validate_message(&ws_tx, conn_key, &raw_message) else {
+			return;
 		};
 
 		// Handle the message based on its type
@@ -49,6 +43,28 @@ impl WebSocketFsm {
 		};
 	}
 
+	/// Validate a client frame, replying with a structured error if it is rejected
+	///
+	/// Rejected messages never close the connection; the client can correct and resend.
+	fn validate_message(&self, ws_tx: &UnboundedSender<Event>, conn_key: &str, raw_message: &str) -> Option<Event> {
+		match parse_client_message(raw_message) {
+			Ok(msg) => Some(msg),
+			Err(e) => {
+				warn!(
+					connection_id = %conn_key,
+					reason = e.code(),
+					error = %e,
+					raw_message = %raw_message,
+					"Rejected invalid websocket message"
+				);
+
+				record_ws_message_rejected(e.code());
+				self.send_error_to_client(ws_tx.clone(), &e.to_string());
+				None
+			}
+		}
+	}
+
 	/// Handle subscribe request - add new event type subscriptions
 	async fn handle_subscribe(&self, ws_tx: UnboundedSender<Event>, conn_key: &str, event_types: Vec<EventType>) {
 		// Update actor state
@@ -121,3 +137,38 @@ impl WebSocketFsm {
 		ws_tx.send_graceful(error_event, context);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::http::HeaderMap;
+	use std::net::SocketAddr;
+	use tokio::sync::mpsc::unbounded_channel;
+	use tokio_util::sync::CancellationToken;
+
+	#[tokio::test]
+	async fn test_garbage_message_gets_error_reply_and_keeps_connection() {
+		let fsm = WebSocketFsm::new();
+		let cancel = CancellationToken::new();
+		let addr: SocketAddr = "127.0.0.1:9000".parse().unwrap();
+		let key = fsm.add_connection(&HeaderMap::new(), &addr, &cancel).await.unwrap();
+		let (ws_tx, mut ws_rx) = unbounded_channel();
+
+		assert!(fsm.validate_message(&ws_tx, &key, "\u{0}garbage{{").is_none());
+		match ws_rx.try_recv() {
+			Ok(Event::Error { message }) => assert!(message.starts_with("malformed_message"), "unexpected error: {message}"),
+			other => panic!("expected an error reply, got {other:?}"),
+		}
+
+		assert!(fsm.validate_message(&ws_tx, &key, r#"{"type": "selfDestruct"}"#).is_none());
+		match ws_rx.try_recv() {
+			Ok(Event::Error { message }) => assert!(message.starts_with("unknown_message_type"), "unexpected error: {message}"),
+			other => panic!("expected an error reply, got {other:?}"),
+		}
+
+		// The connection is untouched and still accepts valid messages
+		assert!(fsm.connection_token(&key).is_some_and(|token| !token.is_cancelled()));
+		assert!(matches!(fsm.validate_message(&ws_tx, &key, r#"{"type": "ping"}"#), Some(Event::Ping)));
+		assert!(ws_rx.try_recv().is_err());
+	}
+}
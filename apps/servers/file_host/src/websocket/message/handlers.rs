@@ -1,5 +1,9 @@
+use super::throttle::{InboundThrottle, MessageRateLimit, Verdict};
+use crate::metrics::otel::record_ws_message_throttled;
+use crate::realtime::SupervisedTransport;
+use crate::websocket::upgrade::{Message, WebSocket};
+use crate::websocket::CloseReason;
 use crate::WebSocketFsm;
-use axum::extract::ws::{Message, WebSocket};
 use futures::stream::{SplitStream, StreamExt};
 use some_transport::NatsTransport;
 use tokio::{
@@ -14,24 +18,27 @@ use ws_events::events::{Event, UnifiedEvent};
 pub(crate) fn spawn_process_incoming_messages(
 	receiver: SplitStream<WebSocket>,
 	state: WebSocketFsm,
-	transport: NatsTransport<UnifiedEvent>,
+	transport: SupervisedTransport,
 	ws_tx: UnboundedSender<Event>,
 	conn_key: String,
+	rate_limit: MessageRateLimit,
 	cancel_token: CancellationToken,
 ) -> JoinHandle<u64> {
-	tokio::spawn(async move { process_incoming_messages(receiver, state, transport, ws_tx, conn_key, cancel_token).await })
+	tokio::spawn(async move { process_incoming_messages(receiver, state, transport, ws_tx, conn_key, rate_limit, cancel_token).await })
 }
 
 /// Process all incoming messages from the WebSocket
 async fn process_incoming_messages(
 	mut receiver: SplitStream<WebSocket>,
 	state: WebSocketFsm,
-	transport: NatsTransport<UnifiedEvent>,
+	transport: SupervisedTransport,
 	ws_tx: UnboundedSender<Event>,
 	conn_key: String,
+	rate_limit: MessageRateLimit,
 	cancel_token: CancellationToken,
 ) -> u64 {
 	let mut message_count = 0u64;
+	let mut throttle = InboundThrottle::new(rate_limit, Instant::now());
 
 	let mut stale_check_interval = interval(Duration::from_secs(30));
 	let stale_timeout = Duration::from_secs(120);
@@ -71,10 +78,7 @@ async fn process_incoming_messages(
 							);
 
 							let _ = state
-								.remove_connection(
-									&conn_key,
-									"Stale connection - no inbound activity".to_string(),
-								)
+								.remove_connection(&conn_key, CloseReason::IdleTimeout)
 								.await;
 							break;
 						}
@@ -95,6 +99,24 @@ async fn process_incoming_messages(
 					Some(Ok(msg)) => {
 						message_count += 1;
 
+						match throttle.check(&msg, Instant::now()) {
+							Verdict::Allow => {}
+							Verdict::Drop => {
+								record_ws_message_throttled();
+								continue;
+							}
+							Verdict::Close => {
+								record_ws_message_throttled();
+								warn!(
+									connection_id = %conn_key,
+									messages_per_second = rate_limit.per_second,
+									"Client kept flooding past the message rate - closing"
+								);
+								let _ = state.remove_connection(&conn_key, CloseReason::PolicyViolation).await;
+								break;
+							}
+						}
+
 						let Some(handle) = store.get(&conn_key) else {
 							debug!(
 								connection_id = %conn_key,
@@ -116,7 +138,7 @@ async fn process_incoming_messages(
 						if handle_websocket_message(
 							msg,
 							&state,
-							transport.clone(),
+							transport.current(),
 							ws_tx.clone(),
 							&conn_key
 						)
@@ -135,6 +157,7 @@ async fn process_incoming_messages(
 							error = %e,
 							"WebSocket error"
 						);
+						let _ = state.remove_connection(&conn_key, CloseReason::Error).await;
 						break;
 					}
 
@@ -143,6 +166,7 @@ async fn process_incoming_messages(
 							connection_id = %conn_key,
 							"WebSocket stream ended"
 						);
+						let _ = state.remove_connection(&conn_key, CloseReason::ClientInitiated).await;
 						break;
 					}
 				}
@@ -185,10 +209,10 @@ async fn handle_websocket_message(
 			);
 
 			// Remove the connection (cleanup handled in remove_connection)
-			let _ = state.remove_connection(conn_key, "WebSocket closed".to_string()).await;
+			let _ = state.remove_connection(conn_key, CloseReason::ClientInitiated).await;
 			Err(())
 		}
 
-		Message::Binary(_) => Ok(()),
+		Message::Binary(_) | Message::Frame(_) => Ok(()),
 	}
 }
@@ -0,0 +1,127 @@
+use crate::websocket::upgrade::Message;
+use crate::Config;
+use tokio::time::Instant;
+
+/// Per-connection limit on inbound WebSocket messages
+#[derive(Clone, Copy, Debug)]
+pub struct MessageRateLimit {
+	/// Sustained messages per second
+	pub per_second: u32,
+	/// Messages a quiet connection may send back to back before throttling starts
+	pub burst: u32,
+	/// Dropped messages after which the connection is closed as abusive
+	pub close_after: u32,
+}
+
+impl From<&Config> for MessageRateLimit {
+	fn from(config: &Config) -> Self {
+		Self {
+			per_second: config.ws_message_rate,
+			burst: config.ws_message_burst,
+			close_after: config.ws_message_abuse_limit,
+		}
+	}
+}
+
+/// What to do with an inbound message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Verdict {
+	Allow,
+	Drop,
+	/// The client kept flooding past the limit
+	Close,
+}
+
+/// Token bucket over one connection's inbound data messages
+///
+/// Control frames are never throttled, so a flooding client can still be
+/// closed cleanly and a well-behaved one can always keep its heartbeat.
+pub(crate) struct InboundThrottle {
+	limit: MessageRateLimit,
+	tokens: f64,
+	last_refill: Instant,
+	/// Messages dropped since the bucket was last full
+	dropped: u32,
+}
+
+impl InboundThrottle {
+	pub(crate) fn new(limit: MessageRateLimit, now: Instant) -> Self {
+		Self {
+			limit,
+			tokens: f64::from(limit.burst),
+			last_refill: now,
+			dropped: 0,
+		}
+	}
+
+	pub(crate) fn check(&mut self, msg: &Message, now: Instant) -> Verdict {
+		if matches!(msg, Message::Ping(_) | Message::Pong(_) | Message::Close(_)) {
+			return Verdict::Allow;
+		}
+
+		let burst = f64::from(self.limit.burst);
+		let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+		self.tokens = elapsed.mul_add(f64::from(self.limit.per_second), self.tokens).min(burst);
+		self.last_refill = now;
+		// Only a client that backs off long enough to refill the bucket is forgiven
+		if self.tokens >= burst {
+			self.dropped = 0;
+		}
+
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			Verdict::Allow
+		} else {
+			self.dropped = self.dropped.saturating_add(1);
+			if self.dropped >= self.limit.close_after {
+				Verdict::Close
+			} else {
+				Verdict::Drop
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::stream::{self, StreamExt};
+	use tokio::time::Duration;
+
+	const LIMIT: MessageRateLimit = MessageRateLimit {
+		per_second: 10,
+		burst: 5,
+		close_after: 40,
+	};
+
+	/// Feed a mock socket's frames through the throttle, returning how many got through
+	async fn allowed(throttle: &mut InboundThrottle, frames: Vec<Message>, now: Instant) -> (usize, bool) {
+		let verdicts: Vec<_> = stream::iter(frames).map(|msg| throttle.check(&msg, now)).collect().await;
+		(verdicts.iter().filter(|v| **v == Verdict::Allow).count(), verdicts.contains(&Verdict::Close))
+	}
+
+	fn burst(n: usize) -> Vec<Message> {
+		(0..n).map(|i| Message::Text(format!(r#"{{"type": "ping", "n": {i}}}"#))).collect()
+	}
+
+	#[tokio::test]
+	async fn test_burst_past_the_rate_is_dropped() {
+		let start = Instant::now();
+		let mut throttle = InboundThrottle::new(LIMIT, start);
+
+		// Only the burst allowance gets through; control frames always do
+		assert_eq!(allowed(&mut throttle, burst(20), start).await, (5, false));
+		assert_eq!(allowed(&mut throttle, vec![Message::Ping(Vec::new())], start).await, (1, false));
+
+		// The allowed rate of 10/s keeps passing
+		assert_eq!(allowed(&mut throttle, burst(20), start + Duration::from_millis(400)).await, (4, false));
+
+		// Still flooding: the drops since the bucket was last full reach the abuse limit
+		assert_eq!(allowed(&mut throttle, burst(10), start + Duration::from_millis(500)).await, (1, true));
+
+		// A client that backs off is forgiven
+		let mut throttle = InboundThrottle::new(LIMIT, start);
+		assert_eq!(allowed(&mut throttle, burst(25), start).await, (5, false));
+		assert_eq!(allowed(&mut throttle, burst(25), start + Duration::from_secs(1)).await, (5, false));
+	}
+}
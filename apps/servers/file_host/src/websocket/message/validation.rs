@@ -0,0 +1,81 @@
+use serde_json::Value;
+use ws_events::events::Event;
+
+/// Message types a client is allowed to send over the socket
+const INBOUND_TYPES: [&str; 6] = ["subscribe", "unsubscribe", "obsCmd", "orchestratorCommandData", "ping", "pong"];
+
+/// Why an inbound text frame was rejected at the WebSocket boundary
+#[derive(Debug, thiserror::Error)]
+pub enum InboundMessageError {
+	#[error("malformed_message: {0}")]
+	Malformed(String),
+
+	#[error("unknown_message_type: '{0}' is not accepted from clients")]
+	UnknownType(String),
+
+	#[error("invalid_fields: {message_type}: {reason}")]
+	InvalidFields { message_type: String, reason: String },
+}
+
+impl InboundMessageError {
+	/// Stable label for metrics and logs
+	#[must_use]
+	pub const fn code(&self) -> &'static str {
+		match self {
+			Self::Malformed(_) => "malformed_message",
+			Self::UnknownType(_) => "unknown_message_type",
+			Self::InvalidFields { .. } => "invalid_fields",
+		}
+	}
+}
+
+/// Parse a client frame into an [`Event`], checking the envelope before the payload
+///
+/// The `type` tag is resolved first so an unrecognized type is reported as such,
+/// rather than as whatever field serde happened to trip over.
+///
+/// # Errors
+///
+/// Returns an [`InboundMessageError`] describing why the frame was rejected.
+pub fn parse_client_message(raw: &str) -> Result<Event, InboundMessageError> {
+	let value: Value = serde_json::from_str(raw).map_err(|e| InboundMessageError::Malformed(e.to_string()))?;
+
+	let message_type = match value.get("type") {
+		Some(Value::String(t)) => t.clone(),
+		Some(_) => return Err(InboundMessageError::Malformed("'type' must be a string".to_string())),
+		None if value.is_object() => return Err(InboundMessageError::Malformed("missing 'type' field".to_string())),
+		None => return Err(InboundMessageError::Malformed("expected a JSON object".to_string())),
+	};
+
+	if !INBOUND_TYPES.contains(&message_type.as_str()) {
+		return Err(InboundMessageError::UnknownType(message_type));
+	}
+
+	serde_json::from_value(value).map_err(|e| InboundMessageError::InvalidFields {
+		message_type,
+		reason: e.to_string(),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_distinguishes_error_kinds() {
+		assert!(matches!(parse_client_message("not json"), Err(InboundMessageError::Malformed(_))));
+		assert!(matches!(parse_client_message("[1, 2]"), Err(InboundMessageError::Malformed(_))));
+		assert!(matches!(parse_client_message(r#"{"event_types": []}"#), Err(InboundMessageError::Malformed(_))));
+		assert!(matches!(parse_client_message(r#"{"type": "launchMissiles"}"#), Err(InboundMessageError::UnknownType(t)) if t == "launchMissiles"));
+		// Server-originated types are not accepted from clients
+		assert!(matches!(
+			parse_client_message(r#"{"type": "clientCount", "count": 3}"#),
+			Err(InboundMessageError::UnknownType(_))
+		));
+		assert!(matches!(
+			parse_client_message(r#"{"type": "subscribe", "event_types": 42}"#),
+			Err(InboundMessageError::InvalidFields { message_type, .. }) if message_type == "subscribe"
+		));
+		assert!(matches!(parse_client_message(r#"{"type": "ping"}"#), Ok(Event::Ping)));
+	}
+}
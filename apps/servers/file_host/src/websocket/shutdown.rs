@@ -22,7 +22,7 @@ impl WebSocketFsm {
 		let mut disconnected_count = 0;
 
 		for key in connection_keys {
-			match self.remove_connection(&key, "Server shutdown".to_string()).await {
+			match self.remove_connection(&key, CloseReason::ServerShutdown).await {
 				Ok(()) => {
 					disconnected_count += 1;
 				}
@@ -0,0 +1,290 @@
+//! `/ws` upgrade with permessage-deflate negotiation
+//!
+//! axum's `WebSocketUpgrade` can't offer extensions, so this mirrors its
+//! handshake checks and answers the upgrade itself. The socket is a
+//! tungstenite stream over a [`DeflateStream`], which only compresses when the
+//! extension was agreed on.
+
+use crate::Config;
+use axum::{
+	async_trait,
+	body::Body,
+	extract::FromRequestParts,
+	http::{header, request::Parts, HeaderMap, HeaderValue, Method, StatusCode},
+	response::{IntoResponse, Response},
+};
+use hyper::upgrade::{OnUpgrade, Upgraded};
+use hyper_util::rt::TokioIo;
+use std::future::Future;
+use tokio_tungstenite::tungstenite::{handshake::derive_accept_key, protocol::Role};
+use tokio_tungstenite::WebSocketStream;
+use tracing::warn;
+
+mod deflate;
+
+pub use deflate::DeflateStream;
+pub use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+pub use tokio_tungstenite::tungstenite::{Error, Message};
+
+/// Server side of an upgraded `/ws` connection
+pub type WebSocket = WebSocketStream<DeflateStream<TokioIo<Upgraded>>>;
+
+/// Extension response: both sides start every message from a fresh DEFLATE context
+const NEGOTIATED: &str = "permessage-deflate; server_no_context_takeover; client_no_context_takeover";
+
+/// permessage-deflate settings, taken from `WS_COMPRESSION` and `WS_COMPRESSION_THRESHOLD`
+#[derive(Clone, Copy, Debug)]
+pub struct Compression {
+	pub enabled: bool,
+	/// Outbound messages smaller than this many bytes are sent uncompressed
+	pub threshold: usize,
+}
+
+impl From<&Config> for Compression {
+	fn from(config: &Config) -> Self {
+		Self {
+			enabled: config.ws_compression,
+			threshold: config.ws_compression_threshold,
+		}
+	}
+}
+
+/// Validated WebSocket upgrade request
+pub struct WebSocketUpgrade {
+	key: HeaderValue,
+	/// The client offered a permessage-deflate configuration we can honour
+	deflate_offered: bool,
+	on_upgrade: OnUpgrade,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for WebSocketUpgrade {
+	type Rejection = Response;
+
+	async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+		let rejection = |status: StatusCode, reason: &'static str| (status, reason).into_response();
+		if parts.method != Method::GET {
+			return Err(rejection(StatusCode::METHOD_NOT_ALLOWED, "Request method must be `GET`"));
+		}
+		if !header_contains(&parts.headers, header::CONNECTION, "upgrade") {
+			return Err(rejection(StatusCode::BAD_REQUEST, "Connection header did not include 'upgrade'"));
+		}
+		if !header_eq(&parts.headers, header::UPGRADE, "websocket") {
+			return Err(rejection(StatusCode::BAD_REQUEST, "`Upgrade` header did not include 'websocket'"));
+		}
+		if !header_eq(&parts.headers, header::SEC_WEBSOCKET_VERSION, "13") {
+			return Err(rejection(StatusCode::BAD_REQUEST, "`Sec-WebSocket-Version` header did not include '13'"));
+		}
+		let key = parts
+			.headers
+			.get(header::SEC_WEBSOCKET_KEY)
+			.cloned()
+			.ok_or_else(|| rejection(StatusCode::BAD_REQUEST, "`Sec-WebSocket-Key` header missing"))?;
+		let on_upgrade = parts
+			.extensions
+			.remove::<OnUpgrade>()
+			.ok_or_else(|| rejection(StatusCode::UPGRADE_REQUIRED, "WebSocket request couldn't be upgraded since no upgrade state was present"))?;
+
+		Ok(Self {
+			key,
+			deflate_offered: offers_deflate(&parts.headers),
+			on_upgrade,
+		})
+	}
+}
+
+impl WebSocketUpgrade {
+	/// Switch protocols and hand the socket to `callback` once the upgrade completes
+	///
+	/// Messages are compressed when `compression` is enabled and the client
+	/// offered permessage-deflate.
+	pub fn on_upgrade<C, Fut>(self, compression: Compression, callback: C) -> Response
+	where
+		C: FnOnce(WebSocket) -> Fut + Send + 'static,
+		Fut: Future<Output = ()> + Send + 'static,
+	{
+		let deflate = compression.enabled && self.deflate_offered;
+		let on_upgrade = self.on_upgrade;
+		tokio::spawn(async move {
+			let upgraded = match on_upgrade.await {
+				Ok(upgraded) => upgraded,
+				Err(e) => {
+					warn!(error = %e, "WebSocket upgrade failed");
+					return;
+				}
+			};
+			let stream = DeflateStream::new(TokioIo::new(upgraded), deflate.then_some(compression.threshold));
+			callback(WebSocketStream::from_raw_socket(stream, Role::Server, None).await).await;
+		});
+
+		let mut response = Response::builder()
+			.status(StatusCode::SWITCHING_PROTOCOLS)
+			.header(header::CONNECTION, "upgrade")
+			.header(header::UPGRADE, "websocket")
+			.header(header::SEC_WEBSOCKET_ACCEPT, derive_accept_key(self.key.as_bytes()));
+		if deflate {
+			response = response.header(header::SEC_WEBSOCKET_EXTENSIONS, NEGOTIATED);
+		}
+		response.body(Body::empty()).unwrap_or_default()
+	}
+}
+
+/// Whether any `Sec-WebSocket-Extensions` offer is a permessage-deflate we can accept
+///
+/// Offers pinning `server_max_window_bits` below 15 or carrying unknown
+/// parameters are declined, as RFC 7692 §5 requires for parameters a server
+/// can't honour.
+fn offers_deflate(headers: &HeaderMap) -> bool {
+	headers
+		.get_all(header::SEC_WEBSOCKET_EXTENSIONS)
+		.iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.any(|offer| {
+			let mut params = offer.split(';').map(str::trim);
+			params.next().is_some_and(|name| name.eq_ignore_ascii_case("permessage-deflate"))
+				&& params.all(|param| {
+					let (name, value) = param
+						.split_once('=')
+						.map_or((param, None), |(name, value)| (name.trim(), Some(value.trim().trim_matches('"'))));
+					match name {
+						"server_no_context_takeover" | "client_no_context_takeover" => value.is_none(),
+						"client_max_window_bits" => true,
+						"server_max_window_bits" => value == Some("15"),
+						_ => false,
+					}
+				})
+		})
+}
+
+fn header_eq(headers: &HeaderMap, key: header::HeaderName, value: &str) -> bool {
+	headers.get(key).is_some_and(|header| header.as_bytes().eq_ignore_ascii_case(value.as_bytes()))
+}
+
+fn header_contains(headers: &HeaderMap, key: header::HeaderName, value: &str) -> bool {
+	headers
+		.get(key)
+		.and_then(|header| header.to_str().ok())
+		.is_some_and(|header| header.split(',').any(|token| token.trim().eq_ignore_ascii_case(value)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::{routing::get, Router};
+	use futures::{SinkExt, StreamExt};
+	use std::io::Cursor;
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use tokio::net::{TcpListener, TcpStream};
+	use tokio_tungstenite::tungstenite::protocol::frame::{
+		coding::{Data, OpCode},
+		Frame, FrameHeader,
+	};
+
+	fn extensions(offer: &str) -> HeaderMap {
+		let mut headers = HeaderMap::new();
+		headers.insert(header::SEC_WEBSOCKET_EXTENSIONS, HeaderValue::from_str(offer).unwrap());
+		headers
+	}
+
+	#[test]
+	fn test_deflate_offers_we_can_honour_are_accepted() {
+		assert!(offers_deflate(&extensions("permessage-deflate; client_max_window_bits")));
+		assert!(offers_deflate(&extensions("x-webkit-deflate-frame, permessage-deflate")));
+		assert!(offers_deflate(&extensions("permessage-deflate; server_max_window_bits=10, permessage-deflate")));
+
+		assert!(!offers_deflate(&HeaderMap::new()));
+		assert!(!offers_deflate(&extensions("permessage-deflate; server_max_window_bits=10")));
+		assert!(!offers_deflate(&extensions("permessage-deflate; x-unknown")));
+	}
+
+	/// Echoes every text message back
+	async fn serve(compression: Compression) -> String {
+		let app = Router::new().route(
+			"/ws",
+			get(move |ws: WebSocketUpgrade| async move {
+				ws.on_upgrade(compression, |mut socket| async move {
+					while let Some(Ok(Message::Text(text))) = socket.next().await {
+						if socket.send(Message::Text(text)).await.is_err() {
+							break;
+						}
+					}
+				})
+			}),
+		);
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap().to_string();
+		tokio::spawn(async move { axum::serve(listener, app).await });
+		addr
+	}
+
+	/// Raw client that offers permessage-deflate, returning the socket and the response head
+	async fn connect(addr: &str) -> (TcpStream, String) {
+		let mut stream = TcpStream::connect(addr).await.unwrap();
+		let request = [
+			"GET /ws HTTP/1.1\r\nHost: ",
+			addr,
+			"\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Version: 13\r\n",
+			"Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n",
+			"Sec-WebSocket-Extensions: permessage-deflate; client_max_window_bits\r\n\r\n",
+		]
+		.concat();
+		stream.write_all(request.as_bytes()).await.unwrap();
+
+		let mut head = Vec::new();
+		while !head.ends_with(b"\r\n\r\n") {
+			head.push(stream.read_u8().await.unwrap());
+		}
+		(stream, String::from_utf8(head).unwrap())
+	}
+
+	async fn read_frame(stream: &mut TcpStream) -> (FrameHeader, Vec<u8>) {
+		let mut bytes = Vec::new();
+		loop {
+			let mut cursor = Cursor::new(&bytes);
+			if let Some((header, len)) = FrameHeader::parse(&mut cursor).unwrap() {
+				let start = usize::try_from(cursor.position()).unwrap();
+				let end = start + usize::try_from(len).unwrap();
+				if bytes.len() >= end {
+					return (header, bytes[start..end].to_vec());
+				}
+			}
+			bytes.push(stream.read_u8().await.unwrap());
+		}
+	}
+
+	#[tokio::test]
+	async fn test_negotiated_connection_round_trips_a_compressed_frame() {
+		let addr = serve(Compression { enabled: true, threshold: 1024 }).await;
+		let (mut stream, head) = connect(&addr).await;
+		assert!(head.starts_with("HTTP/1.1 101"));
+		assert!(head.to_ascii_lowercase().contains(&["sec-websocket-extensions: ", NEGOTIATED].concat()));
+
+		// A large compressed message from the client is inflated for the handler, then echoed back compressed
+		let text: String = (0..200).map(|i| [r#"{"type":"ObsStatus","scene":"scene-"#, &i.to_string(), r#""}"#].concat()).collect();
+		let header = FrameHeader {
+			is_final: true,
+			rsv1: true,
+			rsv2: false,
+			rsv3: false,
+			opcode: OpCode::Data(Data::Text),
+			mask: Some([7, 11, 13, 17]),
+		};
+		let mut wire = Vec::new();
+		Frame::from_payload(header, deflate::deflate(text.as_bytes()).unwrap()).format(&mut wire).unwrap();
+		stream.write_all(&wire).await.unwrap();
+
+		let (header, payload) = read_frame(&mut stream).await;
+		assert!(header.rsv1);
+		assert!(payload.len() < text.len() / 4);
+		assert_eq!(deflate::inflate(&payload).unwrap(), text.as_bytes());
+	}
+
+	#[tokio::test]
+	async fn test_compression_stays_off_unless_enabled() {
+		let addr = serve(Compression { enabled: false, threshold: 1024 }).await;
+		let (_stream, head) = connect(&addr).await;
+		assert!(head.starts_with("HTTP/1.1 101"));
+		assert!(!head.to_ascii_lowercase().contains("sec-websocket-extensions"));
+	}
+}
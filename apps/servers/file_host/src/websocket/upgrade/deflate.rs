@@ -0,0 +1,378 @@
+//! permessage-deflate (RFC 7692) as a transcoding layer under tungstenite
+//!
+//! tungstenite neither negotiates extensions nor accepts frames with RSV1 set,
+//! so compression happens on the raw byte stream instead. Compressed messages
+//! from the client are inflated into plain frames before tungstenite reads
+//! them, and whole messages it writes are deflated on the way out.
+//!
+//! Both directions run without context takeover: every message is compressed
+//! and inflated with a fresh DEFLATE state, so a connection keeps no window
+//! between messages and one bad message can't corrupt the next.
+
+use bytes::{Buf, BufMut, BytesMut};
+use flate2::{Compress, Decompress, FlushCompress, FlushDecompress, Status};
+use std::io::{self, Cursor};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::{Data, OpCode};
+use tokio_tungstenite::tungstenite::protocol::frame::{Frame, FrameHeader};
+
+/// Empty stored block ending every sync flush, left off the wire by RFC 7692 §7.2.1
+const TAIL: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+/// Largest message inflated from a client, matching tungstenite's default `max_message_size`
+const MAX_MESSAGE: usize = 64 << 20;
+
+/// Compressed bytes held for the socket before writes push back
+const WRITE_BACKLOG: usize = 256 << 10;
+
+/// Byte stream that compresses outbound and inflates inbound WebSocket messages
+///
+/// Built without a threshold it passes every byte through untouched, for
+/// clients that didn't negotiate the extension.
+pub struct DeflateStream<S> {
+	inner: S,
+	/// Outbound messages at least this large are compressed
+	threshold: Option<usize>,
+	/// Bytes from the client that don't make up a whole frame yet
+	read_raw: BytesMut,
+	/// Plain frames waiting for tungstenite to read them
+	read_ready: BytesMut,
+	/// Opcode and payload so far of a compressed message arriving in fragments
+	fragments: Option<(OpCode, Vec<u8>)>,
+	/// Bytes from tungstenite that don't make up a whole frame yet
+	write_raw: BytesMut,
+	/// Frames waiting to be written to the socket
+	write_ready: BytesMut,
+}
+
+impl<S> DeflateStream<S> {
+	#[must_use]
+	pub fn new(inner: S, threshold: Option<usize>) -> Self {
+		Self {
+			inner,
+			threshold,
+			read_raw: BytesMut::new(),
+			read_ready: BytesMut::new(),
+			fragments: None,
+			write_raw: BytesMut::new(),
+			write_ready: BytesMut::new(),
+		}
+	}
+
+	/// Turn one whole frame from the client into what tungstenite should read
+	fn decode(&mut self, frame: &[u8]) -> io::Result<()> {
+		let (header, payload) = split_frame(frame)?;
+		let continues_compressed = header.opcode == OpCode::Data(Data::Continue) && self.fragments.is_some();
+		if !header.rsv1 && !continues_compressed {
+			self.read_ready.extend_from_slice(frame);
+			return Ok(());
+		}
+
+		let mut payload = payload.to_vec();
+		if let Some(mask) = header.mask {
+			unmask(&mut payload, mask);
+		}
+		let (opcode, message) = match (header.opcode, self.fragments.take()) {
+			(OpCode::Data(Data::Continue), Some((opcode, mut message))) if !header.rsv1 => {
+				message.extend_from_slice(&payload);
+				(opcode, message)
+			}
+			(opcode @ OpCode::Data(Data::Text | Data::Binary), None) => (opcode, payload),
+			_ => return Err(invalid("RSV1 set outside the first frame of a data message")),
+		};
+		if message.len() > MAX_MESSAGE {
+			return Err(invalid("compressed message too large"));
+		}
+		if !header.is_final {
+			self.fragments = Some((opcode, message));
+			return Ok(());
+		}
+
+		// tungstenite insists client frames are masked; a zero key leaves the payload as is
+		let plain = FrameHeader {
+			is_final: true,
+			rsv1: false,
+			rsv2: header.rsv2,
+			rsv3: header.rsv3,
+			opcode,
+			mask: Some([0; 4]),
+		};
+		Frame::from_payload(plain, inflate(&message)?).format(&mut (&mut self.read_ready).writer()).map_err(invalid)
+	}
+
+	/// Turn one whole frame from tungstenite into what goes on the wire
+	fn encode(&mut self, frame: &[u8], threshold: usize) -> io::Result<()> {
+		let (header, payload) = split_frame(frame)?;
+		// Fragmented and control frames go out as they are, so RSV1 only ever marks whole messages
+		let whole_message = header.is_final && matches!(header.opcode, OpCode::Data(Data::Text | Data::Binary));
+		if !whole_message || header.mask.is_some() || payload.len() < threshold {
+			self.write_ready.extend_from_slice(frame);
+			return Ok(());
+		}
+
+		let compressed = deflate(payload)?;
+		if compressed.len() >= payload.len() {
+			self.write_ready.extend_from_slice(frame);
+			return Ok(());
+		}
+		Frame::from_payload(FrameHeader { rsv1: true, ..header }, compressed)
+			.format(&mut (&mut self.write_ready).writer())
+			.map_err(invalid)
+	}
+}
+
+impl<S: AsyncWrite + Unpin> DeflateStream<S> {
+	/// Write out every queued frame
+	fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		while !self.write_ready.is_empty() {
+			let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.write_ready))?;
+			if written == 0 {
+				return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+			}
+			self.write_ready.advance(written);
+		}
+		Poll::Ready(Ok(()))
+	}
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for DeflateStream<S> {
+	fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		if this.threshold.is_none() {
+			return Pin::new(&mut this.inner).poll_read(cx, buf);
+		}
+
+		while this.read_ready.is_empty() {
+			if let Some(len) = frame_len(&this.read_raw, MAX_MESSAGE)? {
+				let frame = this.read_raw.split_to(len);
+				this.decode(&frame)?;
+				continue;
+			}
+
+			let mut chunk = [0; 8192];
+			let mut chunk = ReadBuf::new(&mut chunk);
+			ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk))?;
+			if chunk.filled().is_empty() {
+				// End of stream: hand over any partial frame and let tungstenite report it
+				let rest = this.read_raw.split();
+				this.read_ready.unsplit(rest);
+				break;
+			}
+			this.read_raw.extend_from_slice(chunk.filled());
+		}
+
+		let len = buf.remaining().min(this.read_ready.len());
+		buf.put_slice(&this.read_ready.split_to(len));
+		Poll::Ready(Ok(()))
+	}
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for DeflateStream<S> {
+	fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+		let this = self.get_mut();
+		let Some(threshold) = this.threshold else {
+			return Pin::new(&mut this.inner).poll_write(cx, buf);
+		};
+
+		if this.write_ready.len() >= WRITE_BACKLOG {
+			ready!(this.poll_drain(cx))?;
+		}
+		this.write_raw.extend_from_slice(buf);
+		while let Some(len) = frame_len(&this.write_raw, usize::MAX)? {
+			let frame = this.write_raw.split_to(len);
+			this.encode(&frame, threshold)?;
+		}
+		Poll::Ready(Ok(buf.len()))
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		ready!(this.poll_drain(cx))?;
+		Pin::new(&mut this.inner).poll_flush(cx)
+	}
+
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		ready!(this.poll_drain(cx))?;
+		Pin::new(&mut this.inner).poll_shutdown(cx)
+	}
+}
+
+/// Length of the whole frame at the start of `bytes`, once all of it has arrived
+fn frame_len(bytes: &[u8], max_payload: usize) -> io::Result<Option<usize>> {
+	let mut cursor = Cursor::new(bytes);
+	let Some((_, payload_len)) = FrameHeader::parse(&mut cursor).map_err(invalid)? else {
+		return Ok(None);
+	};
+	let payload_len = usize::try_from(payload_len)
+		.ok()
+		.filter(|len| *len <= max_payload)
+		.ok_or_else(|| invalid("frame too large"))?;
+	let header_len = usize::try_from(cursor.position()).map_err(invalid)?;
+	let len = header_len + payload_len;
+	Ok((bytes.len() >= len).then_some(len))
+}
+
+fn split_frame(frame: &[u8]) -> io::Result<(FrameHeader, &[u8])> {
+	let mut cursor = Cursor::new(frame);
+	let (header, _) = FrameHeader::parse(&mut cursor).map_err(invalid)?.ok_or_else(|| invalid("truncated frame"))?;
+	let header_len = usize::try_from(cursor.position()).map_err(invalid)?;
+	Ok((header, &frame[header_len..]))
+}
+
+fn unmask(payload: &mut [u8], mask: [u8; 4]) {
+	for (i, byte) in payload.iter_mut().enumerate() {
+		*byte ^= mask[i % 4];
+	}
+}
+
+/// Compress one message with a fresh context, without the trailing [`TAIL`]
+pub(crate) fn deflate(payload: &[u8]) -> io::Result<Vec<u8>> {
+	let mut compress = Compress::new(flate2::Compression::fast(), false);
+	let mut out = Vec::with_capacity(payload.len() / 2 + 64);
+	loop {
+		let consumed = usize::try_from(compress.total_in()).map_err(invalid)?;
+		compress.compress_vec(&payload[consumed..], &mut out, FlushCompress::Sync).map_err(invalid)?;
+		// A flush that still had room to spare has written everything
+		if usize::try_from(compress.total_in()).map_err(invalid)? == payload.len() && out.len() < out.capacity() {
+			break;
+		}
+		if out.len() == out.capacity() {
+			out.reserve(out.capacity());
+		}
+	}
+
+	if out.ends_with(&TAIL) {
+		out.truncate(out.len() - TAIL.len());
+	}
+	Ok(out)
+}
+
+/// Inflate one message with a fresh context, refusing output past [`MAX_MESSAGE`]
+pub(crate) fn inflate(payload: &[u8]) -> io::Result<Vec<u8>> {
+	let input = [payload, &TAIL].concat();
+	let mut decompress = Decompress::new(false);
+	let mut out = Vec::with_capacity(payload.len() * 2 + 64);
+	loop {
+		let consumed = decompress.total_in();
+		let produced = out.len();
+		let status = decompress
+			.decompress_vec(&input[usize::try_from(consumed).map_err(invalid)?..], &mut out, FlushDecompress::Sync)
+			.map_err(invalid)?;
+		if out.len() > MAX_MESSAGE {
+			return Err(invalid("inflated message too large"));
+		}
+
+		let all_in = usize::try_from(decompress.total_in()).map_err(invalid)? == input.len();
+		if status == Status::StreamEnd || (all_in && out.len() < out.capacity()) {
+			break;
+		}
+		if out.len() == out.capacity() {
+			out.reserve(out.capacity());
+		} else if decompress.total_in() == consumed && out.len() == produced {
+			return Err(invalid("truncated compressed message"));
+		}
+	}
+	Ok(out)
+}
+
+fn invalid(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::{SinkExt, StreamExt};
+	use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt, DuplexStream};
+	use tokio_tungstenite::tungstenite::protocol::Role;
+	use tokio_tungstenite::tungstenite::Message;
+	use tokio_tungstenite::WebSocketStream;
+
+	async fn server(threshold: Option<usize>) -> (WebSocketStream<DeflateStream<DuplexStream>>, DuplexStream) {
+		let (server, client) = duplex(1 << 20);
+		(WebSocketStream::from_raw_socket(DeflateStream::new(server, threshold), Role::Server, None).await, client)
+	}
+
+	async fn read_frame(client: &mut DuplexStream) -> (FrameHeader, Vec<u8>) {
+		let mut bytes = Vec::new();
+		loop {
+			if let Some(len) = frame_len(&bytes, usize::MAX).unwrap() {
+				let (header, payload) = split_frame(&bytes[..len]).unwrap();
+				return (header, payload.to_vec());
+			}
+			let mut chunk = [0; 4096];
+			let read = client.read(&mut chunk).await.unwrap();
+			assert_ne!(read, 0, "stream ended mid-frame");
+			bytes.extend_from_slice(&chunk[..read]);
+		}
+	}
+
+	fn large_text() -> String {
+		(0..200).map(|i| [r#"{"type":"ObsStatus","scene":"scene-"#, &i.to_string(), r#""}"#].concat()).collect()
+	}
+
+	#[tokio::test]
+	async fn test_large_message_goes_out_compressed_and_inflates_to_the_original() {
+		let (mut socket, mut client) = server(Some(1024)).await;
+		let text = large_text();
+
+		socket.send(Message::Text(text.clone())).await.unwrap();
+		let (header, payload) = read_frame(&mut client).await;
+		assert!(header.rsv1);
+		assert!(payload.len() < text.len() / 4);
+		assert_eq!(inflate(&payload).unwrap(), text.as_bytes());
+
+		// Small messages aren't worth compressing
+		socket.send(Message::Text("{}".into())).await.unwrap();
+		let (header, payload) = read_frame(&mut client).await;
+		assert!(!header.rsv1);
+		assert_eq!(payload, b"{}");
+	}
+
+	#[tokio::test]
+	async fn test_compressed_client_messages_reach_tungstenite_inflated() {
+		let (mut socket, mut client) = server(Some(1024)).await;
+		let text = large_text();
+		let compressed = deflate(text.as_bytes()).unwrap();
+		let mask = [0x1f, 0x2e, 0x3d, 0x4c];
+
+		// One message in two fragments, then the same message again: each inflates on its own
+		let (first, second) = compressed.split_at(compressed.len() / 2);
+		let frames = [
+			(false, true, OpCode::Data(Data::Text), first),
+			(true, false, OpCode::Data(Data::Continue), second),
+			(true, true, OpCode::Data(Data::Text), &compressed[..]),
+		];
+		for (is_final, rsv1, opcode, payload) in frames {
+			let header = FrameHeader {
+				is_final,
+				rsv1,
+				rsv2: false,
+				rsv3: false,
+				opcode,
+				mask: Some(mask),
+			};
+			let mut wire = Vec::new();
+			Frame::from_payload(header, payload.to_vec()).format(&mut wire).unwrap();
+			client.write_all(&wire).await.unwrap();
+		}
+
+		for _ in 0..2 {
+			assert_eq!(socket.next().await.unwrap().unwrap(), Message::Text(text.clone()));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_without_negotiation_frames_pass_through() {
+		let (mut socket, mut client) = server(None).await;
+		let text = large_text();
+
+		socket.send(Message::Text(text.clone())).await.unwrap();
+		let (header, payload) = read_frame(&mut client).await;
+		assert!(!header.rsv1);
+		assert_eq!(payload, text.as_bytes());
+	}
+}
@@ -1,4 +1,5 @@
 use crate::core::model::{CreateMoodEvent, MoodEvent};
+use futures::{Stream, StreamExt};
 use sqlx::{Error, Sqlite, SqlitePool, Transaction};
 
 pub async fn get_next_index(tx: &mut Transaction<'_, Sqlite>) -> Result<i64, Error> {
@@ -99,6 +100,35 @@ pub async fn fetch_all_mood_events(pool: &SqlitePool) -> Result<Vec<MoodEvent>,
 	Ok(events)
 }
 
+/// Rows in index order, decoded one at a time as the cursor advances
+///
+/// The stream checks a connection out of `pool` on first poll and keeps it until
+/// the stream is dropped or exhausted, so nothing is buffered beyond the current row.
+pub fn stream_all_mood_events(pool: &SqlitePool) -> impl Stream<Item = Result<MoodEvent, Error>> + Send + '_ {
+	sqlx::query!(
+		r#"
+        SELECT id, index_pos as "index_pos: i64", week, label, description, team, category, delta, mood
+        FROM mood_events 
+        ORDER BY index_pos ASC
+        "#
+	)
+	.fetch(pool)
+	.map(|row| {
+		let r = row?;
+		Ok(MoodEvent {
+			id: r.id.ok_or(Error::RowNotFound)?,
+			index: r.index_pos,
+			week: r.week,
+			label: r.label,
+			description: r.description,
+			team: r.team,
+			category: r.category,
+			delta: r.delta,
+			mood: r.mood,
+		})
+	})
+}
+
 pub async fn fetch_by_week(pool: &SqlitePool, week: i64) -> Result<Vec<MoodEvent>, Error> {
 	let rows = sqlx::query!(
 		r#"
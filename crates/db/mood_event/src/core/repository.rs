@@ -1,5 +1,6 @@
 use super::model::{CreateMoodEvent, MoodEvent, UpdateMoodEvent};
 use super::queries;
+use futures::Stream;
 use sqlx::{Error, Sqlite, SqlitePool, Transaction};
 use std::collections::HashMap;
 
@@ -65,6 +66,11 @@ impl MoodEventRepository {
 		queries::fetch_all_mood_events(&self.pool).await
 	}
 
+	/// Like [`get_all`](Self::get_all), but yields rows as they are read instead of collecting them
+	pub fn stream_all(&self) -> impl Stream<Item = Result<MoodEvent, Error>> + Send + '_ {
+		queries::stream_all_mood_events(&self.pool)
+	}
+
 	pub async fn get_by_week(&self, week: i64) -> Result<Vec<MoodEvent>, Error> {
 		queries::fetch_by_week(&self.pool, week).await
 	}
@@ -266,3 +272,43 @@ impl MoodEventRepository {
 		Ok(rows_affected > 0)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::StreamExt;
+	use sqlx::sqlite::SqlitePoolOptions;
+
+	async fn repository() -> MoodEventRepository {
+		// A single connection so every query sees the same in-memory database
+		let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+		sqlx::migrate!().run(&pool).await.unwrap();
+		MoodEventRepository::new(pool)
+	}
+
+	#[tokio::test]
+	async fn test_stream_all_yields_rows_in_order() {
+		let repo = repository().await;
+		let events = (0..1000)
+			.map(|i| CreateMoodEvent {
+				week: i / 100,
+				label: ["event ", &i.to_string()].concat(),
+				description: String::new(),
+				team: "DET".to_string(),
+				category: "play".to_string(),
+				delta: 1,
+			})
+			.collect();
+		repo.batch_create(events).await.unwrap();
+
+		let mut stream = std::pin::pin!(repo.stream_all());
+		let mut expected = 0;
+		while let Some(event) = stream.next().await {
+			let event = event.unwrap();
+			assert_eq!(event.index, expected);
+			assert_eq!(event.mood, 101 + expected);
+			expected += 1;
+		}
+		assert_eq!(expected, 1000);
+	}
+}
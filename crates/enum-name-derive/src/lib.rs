@@ -1,7 +1,26 @@
+mod schema;
+mod subject;
+
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, DeriveInput, Lit, Meta};
 
+/// Generates `subject()`, its inverse `from_subject()`, and the `VARIANTS`
+/// and `SUBJECTS` lists from a `#[subject = "..."]` on every variant
+#[proc_macro_derive(Subject, attributes(subject))]
+pub fn derive_subject(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	subject::expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Generates a `SCHEMA_FINGERPRINT` const from the `#[prost(...)]` attributes
+/// of a message, oneof or enumeration and of the types nested in it
+#[proc_macro_derive(SchemaFingerprint, attributes(prost))]
+pub fn derive_schema_fingerprint(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	schema::expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
 #[proc_macro_derive(EnumFilenameAndFromString, attributes(filename))]
 pub fn derive_enum_filename_and_from_string(input: TokenStream) -> TokenStream {
 	let input = parse_macro_input!(input as DeriveInput);
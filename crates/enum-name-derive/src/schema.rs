@@ -0,0 +1,140 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Fields, GenericArgument, Lit, Meta, NestedMeta, Path, PathArguments, Type};
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Expand `#[derive(SchemaFingerprint)]`
+///
+/// The fingerprint covers what reaches the wire: every field's or oneof
+/// variant's `#[prost(...)]` attribute (type and tag), and the discriminants of
+/// a `prost::Enumeration`. Names are left out, since renaming a field doesn't
+/// change the encoding. Nested messages, oneofs and enumerations contribute
+/// their own `SCHEMA_FINGERPRINT`, so they must derive it too.
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+	let mut description = String::new();
+	let mut nested = Vec::new();
+
+	match &input.data {
+		Data::Struct(data) => {
+			for field in &data.fields {
+				let Some(attr) = prost_attribute(&field.attrs) else { continue };
+				describe(&mut description, attr)?;
+				nested.extend(nested_type(attr, &field.ty)?);
+			}
+		}
+		Data::Enum(data) if data.variants.iter().all(|variant| matches!(variant.fields, Fields::Unit)) => {
+			for variant in &data.variants {
+				let Some((_, discriminant)) = &variant.discriminant else {
+					return Err(syn::Error::new_spanned(
+						variant,
+						"SchemaFingerprint needs an explicit discriminant on every enumeration variant",
+					));
+				};
+				description.push_str(&quote!(#discriminant).to_string());
+				description.push(';');
+			}
+		}
+		Data::Enum(data) => {
+			for variant in &data.variants {
+				let attr = prost_attribute(&variant.attrs).ok_or_else(|| syn::Error::new_spanned(variant, "missing #[prost(...)] on oneof variant"))?;
+				let Fields::Unnamed(fields) = &variant.fields else {
+					return Err(syn::Error::new_spanned(variant, "oneof variants must hold exactly one field"));
+				};
+				let [field] = fields.unnamed.iter().collect::<Vec<_>>()[..] else {
+					return Err(syn::Error::new_spanned(variant, "oneof variants must hold exactly one field"));
+				};
+				describe(&mut description, attr)?;
+				nested.extend(nested_type(attr, &field.ty)?);
+			}
+		}
+		Data::Union(_) => return Err(syn::Error::new_spanned(&input.ident, "SchemaFingerprint can't be derived for unions")),
+	}
+
+	let own = description.bytes().fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+	let prime = PRIME;
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics #name #ty_generics #where_clause {
+			/// Fingerprint of this type's wire schema, nested messages included
+			pub const SCHEMA_FINGERPRINT: u64 = {
+				let hash: u64 = #own;
+				#(let hash = (hash ^ <#nested>::SCHEMA_FINGERPRINT).wrapping_mul(#prime);)*
+				hash
+			};
+		}
+	})
+}
+
+fn prost_attribute(attrs: &[Attribute]) -> Option<&Attribute> {
+	attrs.iter().find(|attr| attr.path.is_ident("prost"))
+}
+
+/// Append a field's `#[prost(...)]` settings, leaving out the type paths
+///
+/// `oneof = "..."` and `enumeration = "..."` only name a type, whose own
+/// fingerprint is mixed in separately, so moving or re-exporting it doesn't
+/// change this one.
+fn describe(description: &mut String, attr: &Attribute) -> syn::Result<()> {
+	let Meta::List(list) = attr.parse_meta()? else {
+		return Err(syn::Error::new_spanned(attr, "expected #[prost(...)]"));
+	};
+	for item in &list.nested {
+		match item {
+			NestedMeta::Meta(Meta::NameValue(meta)) if is_type_path(&meta.path) => {
+				let path = &meta.path;
+				description.push_str(&quote!(#path).to_string());
+			}
+			item => description.push_str(&quote!(#item).to_string()),
+		}
+		description.push(',');
+	}
+	description.push(';');
+	Ok(())
+}
+
+fn is_type_path(path: &Path) -> bool {
+	path.is_ident("oneof") || path.is_ident("enumeration")
+}
+
+/// Type whose fingerprint a field depends on: its message, oneof or enumeration
+fn nested_type(attr: &Attribute, ty: &Type) -> syn::Result<Option<Type>> {
+	let Meta::List(list) = attr.parse_meta()? else {
+		return Ok(None);
+	};
+
+	for item in &list.nested {
+		match item {
+			NestedMeta::Meta(Meta::Path(path)) if path.is_ident("message") => return Ok(Some(innermost(ty).clone())),
+			NestedMeta::Meta(Meta::NameValue(meta)) if is_type_path(&meta.path) => {
+				let Lit::Str(path) = &meta.lit else {
+					return Err(syn::Error::new_spanned(&meta.lit, "expected a type path string"));
+				};
+				return Ok(Some(Type::Path(syn::TypePath {
+					qself: None,
+					path: path.parse::<Path>()?,
+				})));
+			}
+			_ => {}
+		}
+	}
+	Ok(None)
+}
+
+/// `T` out of `Option<T>`, `Vec<T>` or `Box<T>`
+fn innermost(ty: &Type) -> &Type {
+	let Type::Path(path) = ty else { return ty };
+	let Some(segment) = path.path.segments.last() else { return ty };
+	if !["Option", "Vec", "Box"].iter().any(|wrapper| segment.ident == wrapper) {
+		return ty;
+	}
+	match &segment.arguments {
+		PathArguments::AngleBracketed(args) => match args.args.first() {
+			Some(GenericArgument::Type(inner)) => innermost(inner),
+			_ => ty,
+		},
+		_ => ty,
+	}
+}
@@ -0,0 +1,75 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::HashMap;
+use syn::{Data, DeriveInput, Fields, Lit, LitStr, Meta, Variant};
+
+/// Expand `#[derive(Subject)]`
+///
+/// Every variant must be a unit variant carrying `#[subject = "..."]`, and no
+/// two variants may share a subject, so `subject` and `from_subject` are exact
+/// inverses.
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+	let Data::Enum(data) = &input.data else {
+		return Err(syn::Error::new_spanned(&input.ident, "Subject can only be derived for enums"));
+	};
+
+	let mut seen: HashMap<String, &Variant> = HashMap::new();
+	let mut variants = Vec::with_capacity(data.variants.len());
+	let mut subjects = Vec::with_capacity(data.variants.len());
+	for variant in &data.variants {
+		if !matches!(variant.fields, Fields::Unit) {
+			return Err(syn::Error::new_spanned(variant, "Subject only supports unit variants"));
+		}
+		let subject = subject_attribute(variant)?;
+		if let Some(first) = seen.insert(subject.value(), variant) {
+			let message = ["subject \"", &subject.value(), "\" is already used by ", &first.ident.to_string()].concat();
+			return Err(syn::Error::new_spanned(subject, message));
+		}
+		variants.push(&variant.ident);
+		subjects.push(subject);
+	}
+
+	let name = &input.ident;
+	Ok(quote! {
+		impl #name {
+			/// Every variant, in declaration order
+			pub const VARIANTS: &'static [Self] = &[#(Self::#variants),*];
+
+			/// Every subject, in declaration order
+			pub const SUBJECTS: &'static [&'static str] = &[#(#subjects),*];
+
+			/// Transport subject for this variant
+			#[must_use]
+			pub const fn subject(&self) -> &'static str {
+				match self {
+					#(Self::#variants => #subjects,)*
+				}
+			}
+
+			/// Variant whose subject is exactly `subject`
+			#[must_use]
+			pub fn from_subject(subject: &str) -> Option<Self> {
+				match subject {
+					#(#subjects => Some(Self::#variants),)*
+					_ => None,
+				}
+			}
+		}
+	})
+}
+
+fn subject_attribute(variant: &Variant) -> syn::Result<LitStr> {
+	let attr = variant
+		.attrs
+		.iter()
+		.find(|attr| attr.path.is_ident("subject"))
+		.ok_or_else(|| syn::Error::new_spanned(variant, "missing #[subject = \"...\"]"))?;
+
+	match attr.parse_meta()? {
+		Meta::NameValue(meta) => match meta.lit {
+			Lit::Str(subject) if !subject.value().is_empty() => Ok(subject),
+			lit => Err(syn::Error::new_spanned(lit, "subject must be a non-empty string")),
+		},
+		meta => Err(syn::Error::new_spanned(meta, "expected #[subject = \"...\"]")),
+	}
+}
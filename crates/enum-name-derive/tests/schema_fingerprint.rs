@@ -0,0 +1,130 @@
+#![allow(dead_code)]
+
+use enum_name_derive::SchemaFingerprint;
+
+mod v1 {
+	use super::SchemaFingerprint;
+
+	#[derive(SchemaFingerprint)]
+	pub struct Inner {
+		#[prost(string, tag = "1")]
+		pub name: String,
+	}
+
+	#[derive(SchemaFingerprint)]
+	pub struct Outer {
+		#[prost(message, optional, tag = "1")]
+		pub inner: Option<Inner>,
+		#[prost(enumeration = "Mode", tag = "2")]
+		pub mode: i32,
+	}
+
+	#[derive(SchemaFingerprint)]
+	#[repr(i32)]
+	pub enum Mode {
+		Idle = 0,
+		Running = 1,
+	}
+}
+
+/// `v1` with every name changed but the same fields and tags
+mod renamed {
+	use super::SchemaFingerprint;
+
+	#[derive(SchemaFingerprint)]
+	pub struct Inner {
+		#[prost(string, tag = "1")]
+		pub label: String,
+	}
+
+	#[derive(SchemaFingerprint)]
+	pub struct Outer {
+		#[prost(message, optional, tag = "1")]
+		pub child: Option<Inner>,
+		#[prost(enumeration = "Mode", tag = "2")]
+		pub state: i32,
+	}
+
+	#[derive(SchemaFingerprint)]
+	#[repr(i32)]
+	pub enum Mode {
+		Waiting = 0,
+		Busy = 1,
+	}
+}
+
+/// `v1` with a field added to the nested message only, every path kept the same
+mod nested_change {
+	use super::v1::Mode;
+	use super::SchemaFingerprint;
+
+	#[derive(SchemaFingerprint)]
+	pub struct Inner {
+		#[prost(string, tag = "1")]
+		pub name: String,
+		#[prost(uint64, tag = "2")]
+		pub count: u64,
+	}
+
+	#[derive(SchemaFingerprint)]
+	pub struct Outer {
+		#[prost(message, optional, tag = "1")]
+		pub inner: Option<Inner>,
+		#[prost(enumeration = "Mode", tag = "2")]
+		pub mode: i32,
+	}
+}
+
+/// `v1` reaching the same types through different paths
+mod moved {
+	use super::SchemaFingerprint;
+
+	#[derive(SchemaFingerprint)]
+	pub struct Outer {
+		#[prost(message, optional, tag = "1")]
+		pub inner: Option<super::v1::Inner>,
+		#[prost(enumeration = "super::v1::Mode", tag = "2")]
+		pub mode: i32,
+	}
+}
+
+mod oneof {
+	use super::SchemaFingerprint;
+
+	#[derive(SchemaFingerprint)]
+	pub struct Envelope {
+		#[prost(oneof = "Body", tags = "1, 2")]
+		pub body: Option<Body>,
+	}
+
+	#[derive(SchemaFingerprint)]
+	pub enum Body {
+		#[prost(message, tag = "1")]
+		First(super::v1::Inner),
+		#[prost(message, tag = "2")]
+		Second(super::nested_change::Inner),
+	}
+}
+
+#[test]
+fn test_renames_keep_the_fingerprint() {
+	assert_eq!(v1::Outer::SCHEMA_FINGERPRINT, renamed::Outer::SCHEMA_FINGERPRINT);
+	assert_eq!(v1::Mode::SCHEMA_FINGERPRINT, renamed::Mode::SCHEMA_FINGERPRINT);
+}
+
+#[test]
+fn test_type_paths_leave_the_fingerprint_alone() {
+	assert_eq!(v1::Outer::SCHEMA_FINGERPRINT, moved::Outer::SCHEMA_FINGERPRINT);
+}
+
+#[test]
+fn test_nested_changes_reach_the_outer_fingerprint() {
+	assert_ne!(v1::Inner::SCHEMA_FINGERPRINT, nested_change::Inner::SCHEMA_FINGERPRINT);
+	assert_ne!(v1::Outer::SCHEMA_FINGERPRINT, nested_change::Outer::SCHEMA_FINGERPRINT);
+}
+
+#[test]
+fn test_oneof_variants_contribute_their_messages() {
+	assert_ne!(oneof::Body::SCHEMA_FINGERPRINT, v1::Inner::SCHEMA_FINGERPRINT);
+	assert_ne!(oneof::Envelope::SCHEMA_FINGERPRINT, oneof::Body::SCHEMA_FINGERPRINT);
+}
@@ -0,0 +1,184 @@
+use super::sniff::{ChunkStrategy, SNIFF_LEN};
+
+/// 1-based line and column of a byte in the stream
+///
+/// Columns count characters, not bytes, so they line up with what an editor
+/// shows for UTF-8 text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+	pub line: usize,
+	pub column: usize,
+}
+
+impl Position {
+	const START: Self = Self { line: 1, column: 1 };
+
+	/// Position just past `bytes`, starting from `self`
+	fn advance(mut self, bytes: &[u8]) -> Self {
+		for &byte in bytes {
+			if byte == b'\n' {
+				self.line += 1;
+				self.column = 1;
+			} else if byte & 0xC0 != 0x80 {
+				// UTF-8 continuation bytes belong to the previous character
+				self.column += 1;
+			}
+		}
+		self
+	}
+}
+
+/// A slice of the input, with where it starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunk<'a> {
+	pub bytes: &'a [u8],
+	/// Byte offset of the first byte in the input
+	pub offset: usize,
+	/// Line and column of the first byte, when position tracking is on
+	pub position: Option<Position>,
+}
+
+/// Splits input into chunks of at most `chunk_size` bytes along a [`ChunkStrategy`]
+///
+/// A chunk is only cut mid-line (or mid-tag) when the line itself is longer
+/// than `chunk_size`.
+#[derive(Debug, Clone)]
+pub struct Chunks<'a> {
+	data: &'a [u8],
+	strategy: ChunkStrategy,
+	chunk_size: usize,
+	offset: usize,
+	position: Option<Position>,
+}
+
+impl<'a> Chunks<'a> {
+	#[must_use]
+	pub fn new(data: &'a [u8], strategy: ChunkStrategy, chunk_size: usize) -> Self {
+		Self {
+			data,
+			strategy,
+			chunk_size: chunk_size.max(1),
+			offset: 0,
+			position: None,
+		}
+	}
+
+	/// Split `data` along the strategy sniffed from its first [`SNIFF_LEN`] bytes
+	#[must_use]
+	pub fn auto(data: &'a [u8], chunk_size: usize) -> Self {
+		let strategy = ChunkStrategy::sniff(&data[..data.len().min(SNIFF_LEN)]);
+		Self::new(data, strategy, chunk_size)
+	}
+
+	/// The strategy chunks are split along
+	#[must_use]
+	pub const fn strategy(&self) -> ChunkStrategy {
+		self.strategy
+	}
+
+	/// Report the line and column each chunk starts at
+	///
+	/// Costs one pass over every byte to count newlines, so it is opt-in.
+	#[must_use]
+	pub const fn with_positions(mut self) -> Self {
+		self.position = Some(Position::START);
+		self
+	}
+
+	/// Length of the next chunk starting at `self.offset`
+	fn next_len(&self) -> usize {
+		let rest = &self.data[self.offset..];
+		if rest.len() <= self.chunk_size {
+			return rest.len();
+		}
+
+		let window = &rest[..self.chunk_size];
+		let boundary = match self.strategy {
+			ChunkStrategy::LineBoundary => window.iter().rposition(|&b| b == b'\n'),
+			ChunkStrategy::TagBoundary => window.iter().rposition(|&b| b == b'>'),
+			ChunkStrategy::FixedBytes => None,
+		};
+		boundary.map_or(self.chunk_size, |idx| idx + 1)
+	}
+}
+
+impl<'a> Iterator for Chunks<'a> {
+	type Item = Chunk<'a>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.offset >= self.data.len() {
+			return None;
+		}
+
+		let len = self.next_len();
+		let bytes = &self.data[self.offset..self.offset + len];
+		let chunk = Chunk {
+			bytes,
+			offset: self.offset,
+			position: self.position,
+		};
+
+		self.offset += len;
+		self.position = self.position.map(|position| position.advance(bytes));
+		Some(chunk)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Write;
+	use tempfile::NamedTempFile;
+
+	const fn at(line: usize, column: usize) -> Position {
+		Position { line, column }
+	}
+
+	#[test]
+	fn test_chunk_positions_over_multiline_file() {
+		let mut file = NamedTempFile::new().unwrap();
+		file.write_all("1st & 10\n2nd & 4\n3rd & 1\n4th & goal\n".as_bytes()).unwrap();
+		let content = std::fs::read(file.path()).unwrap();
+
+		// Two 8-9 byte lines fit in 20 bytes, so each chunk starts two lines further on
+		let lines: Vec<_> = Chunks::new(&content, ChunkStrategy::LineBoundary, 20)
+			.with_positions()
+			.map(|c| (c.offset, c.position.unwrap()))
+			.collect();
+		assert_eq!(lines, vec![(0, at(1, 1)), (17, at(3, 1))]);
+
+		// Fixed-size chunks land mid-line
+		let fixed: Vec<_> = Chunks::new(&content, ChunkStrategy::FixedBytes, 6).with_positions().map(|c| c.position.unwrap()).collect();
+		assert_eq!(fixed[..4], [at(1, 1), at(1, 7), at(2, 4), at(3, 2)]);
+		assert_eq!(fixed.len(), content.len().div_ceil(6));
+	}
+
+	#[test]
+	fn test_columns_count_characters() {
+		let content = "é→x\nline".as_bytes();
+		let chunks: Vec<_> = Chunks::new(content, ChunkStrategy::FixedBytes, 5).with_positions().map(|c| c.position.unwrap()).collect();
+		// "é→" is 5 bytes but two characters
+		assert_eq!(chunks, vec![at(1, 1), at(1, 3), at(2, 4)]);
+	}
+
+	#[test]
+	fn test_auto_splits_along_the_sniffed_strategy() {
+		let mut file = NamedTempFile::new().unwrap();
+		file.write_all(b"<!DOCTYPE html>\n<ul><li>run</li><li>pass</li></ul>").unwrap();
+		let content = std::fs::read(file.path()).unwrap();
+
+		let chunks = Chunks::auto(&content, 24);
+		assert_eq!(chunks.strategy(), ChunkStrategy::TagBoundary);
+		let pieces: Vec<_> = chunks.map(|c| c.bytes).collect();
+		assert_eq!(pieces, [&b"<!DOCTYPE html>\n<ul><li>"[..], b"run</li><li>pass</li>", b"</ul>"]);
+
+		assert_eq!(Chunks::auto(b"1st & 10\n2nd & 4\n", 12).strategy(), ChunkStrategy::LineBoundary);
+	}
+
+	#[test]
+	fn test_positions_are_opt_in() {
+		let chunks: Vec<_> = Chunks::new(b"<p>a</p><p>b</p>", ChunkStrategy::TagBoundary, 10).collect();
+		assert_eq!(chunks.iter().map(|c| c.bytes).collect::<Vec<_>>(), [&b"<p>a</p>"[..], b"<p>b</p>"]);
+		assert!(chunks.iter().all(|c| c.position.is_none()));
+	}
+}
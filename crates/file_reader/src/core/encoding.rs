@@ -0,0 +1,63 @@
+/// Text encoding of a file, as told by its byte order mark
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+	/// UTF-8, with or without a BOM
+	Utf8,
+	Utf16Le,
+	Utf16Be,
+}
+
+impl Encoding {
+	/// Pick an encoding from the first bytes of a file, returning it with the length of its BOM
+	///
+	/// Files without a BOM are taken to be UTF-8.
+	#[must_use]
+	pub fn detect(head: &[u8]) -> (Self, usize) {
+		match head {
+			[0xef, 0xbb, 0xbf, ..] => (Self::Utf8, 3),
+			[0xff, 0xfe, ..] => (Self::Utf16Le, 2),
+			[0xfe, 0xff, ..] => (Self::Utf16Be, 2),
+			_ => (Self::Utf8, 0),
+		}
+	}
+
+	/// Decode `bytes` (without their BOM), or `None` if they aren't valid in this encoding
+	#[must_use]
+	pub fn decode(self, bytes: &[u8]) -> Option<String> {
+		let unit = match self {
+			Self::Utf8 => return std::str::from_utf8(bytes).ok().map(str::to_string),
+			Self::Utf16Le => u16::from_le_bytes,
+			Self::Utf16Be => u16::from_be_bytes,
+		};
+		let units = bytes.chunks_exact(2);
+		if !units.remainder().is_empty() {
+			return None;
+		}
+		char::decode_utf16(units.map(|pair| unit([pair[0], pair[1]]))).collect::<Result<String, _>>().ok()
+	}
+}
+
+/// Decode a whole file's bytes, honoring and stripping any BOM
+///
+/// # Errors
+///
+/// Returns the detected encoding if the bytes aren't valid in it.
+pub fn decode(bytes: &[u8]) -> Result<String, Encoding> {
+	let (encoding, bom_len) = Encoding::detect(bytes);
+	encoding.decode(&bytes[bom_len..]).ok_or(encoding)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_decode_honors_bom() {
+		assert_eq!(decode(b"plain").unwrap(), "plain");
+		assert_eq!(decode(b"\xef\xbb\xbfwith bom").unwrap(), "with bom");
+		assert_eq!(decode(&[0xff, 0xfe, b'h', 0, b'i', 0]).unwrap(), "hi");
+		assert_eq!(decode(&[0xfe, 0xff, 0, b'h', 0, b'i']).unwrap(), "hi");
+		assert_eq!(decode(&[0xff, 0xfe, b'h']), Err(Encoding::Utf16Le));
+		assert_eq!(decode(&[0xc3, 0x28]), Err(Encoding::Utf8));
+	}
+}
@@ -1,5 +1,11 @@
 // mod resumable;
 
+pub mod chunks;
+pub mod encoding;
 pub mod path;
+pub mod sniff;
 
+pub use chunks::{Chunk, Chunks, Position};
+pub use encoding::Encoding;
 pub use path::Path;
+pub use sniff::ChunkStrategy;
@@ -0,0 +1,81 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// How many leading bytes are inspected when sniffing a file
+pub const SNIFF_LEN: usize = 1024;
+
+/// How a file should be split into chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkStrategy {
+	/// Split between HTML/XML elements so tags are never cut in half
+	TagBoundary,
+	/// Split on newlines
+	LineBoundary,
+	/// Split every `chunk_size` bytes, for content with no textual structure
+	FixedBytes,
+}
+
+impl ChunkStrategy {
+	/// Pick a strategy from the first bytes of a file
+	///
+	/// NUL bytes or invalid UTF-8 mean binary; otherwise markup-looking text
+	/// (a doctype, `<html`, or a tag near the start) chooses tag boundaries and
+	/// anything else with newlines chooses line boundaries.
+	#[must_use]
+	pub fn sniff(head: &[u8]) -> Self {
+		if head.contains(&0) {
+			return Self::FixedBytes;
+		}
+		let text = match std::str::from_utf8(head) {
+			Ok(text) => text,
+			// A multi-byte character cut off at the end of the sample is still text
+			Err(e) if e.error_len().is_none() => std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or_default(),
+			Err(_) => return Self::FixedBytes,
+		};
+
+		let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+		let lower = trimmed.get(..trimmed.len().min(64)).unwrap_or(trimmed).to_ascii_lowercase();
+		let starts_with_tag = trimmed.starts_with('<') && trimmed[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '!' || c == '?');
+		if lower.starts_with("<!doctype html") || lower.contains("<html") || starts_with_tag {
+			Self::TagBoundary
+		} else if text.contains('\n') {
+			Self::LineBoundary
+		} else {
+			Self::FixedBytes
+		}
+	}
+
+	/// Sniff the first [`SNIFF_LEN`] bytes of the file at `path`
+	///
+	/// # Errors
+	///
+	/// Returns an I/O error if the file cannot be opened or read.
+	pub fn detect(path: &Path) -> io::Result<Self> {
+		let mut head = Vec::with_capacity(SNIFF_LEN);
+		File::open(path)?.take(SNIFF_LEN as u64).read_to_end(&mut head)?;
+		Ok(Self::sniff(&head))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Write;
+	use tempfile::NamedTempFile;
+
+	fn detect(contents: &[u8]) -> ChunkStrategy {
+		let mut file = NamedTempFile::new().unwrap();
+		file.write_all(contents).unwrap();
+		ChunkStrategy::detect(file.path()).unwrap()
+	}
+
+	#[test]
+	fn test_detect_picks_strategy_by_content() {
+		assert_eq!(detect(b"<!DOCTYPE html>\n<html><body><p>hi</p></body></html>\n"), ChunkStrategy::TagBoundary);
+		assert_eq!(detect(b"  <div class=\"play\">1st & 10</div>\n"), ChunkStrategy::TagBoundary);
+		assert_eq!(detect(b"first line\nsecond line\nthird line\n"), ChunkStrategy::LineBoundary);
+		assert_eq!(detect(&[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00]), ChunkStrategy::FixedBytes);
+		assert_eq!(detect(&[0xff, 0xfe, 0xfd, b'\n']), ChunkStrategy::FixedBytes);
+	}
+}
@@ -1,10 +1,10 @@
 pub mod config;
 pub mod core;
 use config::PathPartError;
-use core::Path;
+use core::{Encoding, Path};
 use std::fmt;
 use std::fs;
-use std::io::{self, Read};
+use std::io;
 use std::path::PathBuf;
 
 #[derive(Debug)]
@@ -21,6 +21,7 @@ pub enum FileReaderError {
 	InvalidFileType,
 	IOError(io::Error),
 	NoFileExtension,
+	InvalidEncoding(Encoding),
 }
 
 impl fmt::Display for FileReaderError {
@@ -31,6 +32,7 @@ impl fmt::Display for FileReaderError {
 			Self::InvalidFileType => write!(f, "Invalid file type"),
 			Self::NoFileExtension => write!(f, "File has no extension"),
 			Self::IOError(e) => write!(f, "IO error: {e}"),
+			Self::InvalidEncoding(encoding) => write!(f, "File is not valid {encoding:?}"),
 		}
 	}
 }
@@ -74,17 +76,19 @@ impl FileReader {
 
 	/// Reads the file content as a string.
 	///
+	/// The encoding is taken from the byte order mark (UTF-8 or UTF-16 in
+	/// either byte order), defaulting to UTF-8, and the BOM is stripped.
+	///
 	/// # Errors
 	///
 	/// Returns [`FileReaderError`] if:
 	/// - File validation fails (see [`validate()`])
 	/// - File cannot be opened or read due to I/O errors
+	/// - The content isn't valid in its detected encoding
 	pub fn read_content(&self) -> Result<String, FileReaderError> {
 		self.validate()?;
-		let mut file = fs::File::open(&self.system_path).map_err(FileReaderError::IOError)?;
-		let mut content = String::new();
-		file.read_to_string(&mut content).map_err(FileReaderError::IOError)?;
-		Ok(content)
+		let bytes = fs::read(&self.system_path).map_err(FileReaderError::IOError)?;
+		core::encoding::decode(&bytes).map_err(FileReaderError::InvalidEncoding)
 	}
 }
 
@@ -126,6 +130,50 @@ mod tests {
 		assert!(matches!(reader.validate(), Err(FileReaderError::InvalidFileType)));
 	}
 
+	fn write_temp(dir: &TempDir, name: &str, bytes: &[u8]) -> String {
+		let path = dir.path().join(name);
+		fs::write(&path, bytes).unwrap();
+		path.to_str().unwrap().to_string()
+	}
+
+	#[test]
+	fn test_read_html() {
+		let temp_dir = TempDir::new().unwrap();
+		let html = "<html><body><p>1st & 10 at NYG 25 — pass</p></body></html>\n";
+		let plain = write_temp(&temp_dir, "plays.html", html.as_bytes());
+		assert_eq!(FileReader::new(&plain, "html").unwrap().read_content().unwrap(), html);
+
+		// A UTF-8 BOM is dropped rather than returned as U+FEFF
+		let with_bom = write_temp(&temp_dir, "bom.html", &[b"\xef\xbb\xbf", html.as_bytes()].concat());
+		assert_eq!(FileReader::new(&with_bom, "html").unwrap().read_content().unwrap(), html);
+	}
+
+	#[test]
+	fn test_read_utf16() {
+		let temp_dir = TempDir::new().unwrap();
+		let text = "Drive — 4th & 1";
+		let le: Vec<u8> = [0xff, 0xfe].into_iter().chain(text.encode_utf16().flat_map(u16::to_le_bytes)).collect();
+		let be: Vec<u8> = [0xfe, 0xff].into_iter().chain(text.encode_utf16().flat_map(u16::to_be_bytes)).collect();
+
+		for (name, bytes) in [("le.txt", le), ("be.txt", be)] {
+			let path = write_temp(&temp_dir, name, &bytes);
+			assert_eq!(FileReader::new(&path, "txt").unwrap().read_content().unwrap(), text, "{name}");
+		}
+
+		// An odd number of bytes after a UTF-16 BOM can't be decoded
+		let truncated = write_temp(&temp_dir, "truncated.txt", &[0xff, 0xfe, b'a', 0, b'b']);
+		let result = FileReader::new(&truncated, "txt").unwrap().read_content();
+		assert!(matches!(result, Err(FileReaderError::InvalidEncoding(Encoding::Utf16Le))));
+	}
+
+	#[test]
+	fn test_read_missing_file() {
+		let temp_dir = TempDir::new().unwrap();
+		let missing = temp_dir.path().join("missing.html");
+		let result = FileReader::new(missing.to_str().unwrap(), "html").unwrap().read_content();
+		assert!(matches!(result, Err(FileReaderError::FileNotFound)));
+	}
+
 	#[test]
 	fn test_empty_file() {
 		let temp_dir = TempDir::new().unwrap();
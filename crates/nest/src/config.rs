@@ -54,6 +54,18 @@ pub struct Config {
 	#[arg(long, env = "RATE_LIMIT", default_value = "100")]
 	pub rate_limit: u32,
 
+	/// Header to key rate limits by (e.g. x-api-key), for the values in `RATE_LIMIT_KEYS`; unset keys by client IP
+	#[arg(long, env = "RATE_LIMIT_HEADER")]
+	pub rate_limit_header: Option<String>,
+
+	/// Comma-separated `RATE_LIMIT_HEADER` values that get their own budget; any other value is keyed by client IP
+	#[arg(long, env = "RATE_LIMIT_KEYS", value_delimiter = ',')]
+	pub rate_limit_keys: Vec<String>,
+
+	/// Route requests by Host header, as comma-separated host=db pairs (e.g. tenant1.example.com=db_1)
+	#[arg(long, env = "TENANTS", value_delimiter = ',')]
+	pub tenants: Vec<String>,
+
 	/// Enable CORS
 	#[arg(long, env = "ENABLE_CORS")]
 	pub enable_cors: bool,
@@ -62,6 +74,22 @@ pub struct Config {
 	#[arg(long, env = "LOG_LEVEL", default_value = "info")]
 	pub log_level: LogLevel,
 
+	/// Log request and response bodies at DEBUG
+	#[arg(long, env = "LOG_BODIES", default_value = "false")]
+	pub log_bodies: bool,
+
+	/// Largest body, in bytes, that will be buffered for logging
+	#[arg(long, env = "LOG_BODY_LIMIT", default_value = "4096")]
+	pub log_body_limit: usize,
+
+	/// Headers whose values are redacted from body logs
+	#[arg(long, env = "LOG_REDACT_HEADERS", value_delimiter = ',', default_value = "authorization,cookie,set-cookie")]
+	pub log_redact_headers: Vec<String>,
+
+	/// JSON fields whose values are redacted from body logs
+	#[arg(long, env = "LOG_REDACT_FIELDS", value_delimiter = ',', default_value = "password,token,secret")]
+	pub log_redact_fields: Vec<String>,
+
 	/// Log file path
 	#[arg(long, env = "LOG_FILE")]
 	pub log_file: Option<String>,
@@ -94,7 +122,7 @@ pub struct Config {
 	#[arg(long, env = "CACHE_TTL", default_value = "300")]
 	pub cache_ttl: u64,
 
-	/// Enable Prometheus metrics
+	/// Enable Prometheus metrics (serves database pool stats at /metrics)
 	#[arg(long, env = "ENABLE_PROMETHEUS")]
 	pub enable_prometheus: bool,
 
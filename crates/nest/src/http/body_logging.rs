@@ -0,0 +1,199 @@
+use crate::config::Config;
+use axum::body::{Body, Bytes, HttpBody};
+use axum::extract::{Request, State};
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::Response;
+use serde_json::Value;
+use std::sync::Arc;
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+const REDACTED: &str = "[REDACTED]";
+
+/// Settings for [`log_bodies`], taken from the `LOG_BODIES` family of config flags
+#[derive(Clone, Debug)]
+pub struct BodyLogging {
+	pub enabled: bool,
+	pub max_bytes: usize,
+	pub redact_headers: Vec<String>,
+	pub redact_fields: Vec<String>,
+}
+
+impl From<&Config> for BodyLogging {
+	fn from(config: &Config) -> Self {
+		Self {
+			enabled: config.log_bodies,
+			max_bytes: config.log_body_limit,
+			redact_headers: config.log_redact_headers.iter().map(|h| h.to_ascii_lowercase()).collect(),
+			redact_fields: config.log_redact_fields.iter().map(|f| f.to_ascii_lowercase()).collect(),
+		}
+	}
+}
+
+/// Logs request and response bodies at DEBUG, tagged with the request id
+///
+/// Bodies are only buffered when their size is known to fit within `max_bytes`;
+/// anything larger or streamed is passed through untouched and logged as omitted.
+/// Buffered bodies are re-emitted so handlers and clients see them unchanged.
+pub async fn log_bodies(State(settings): State<Arc<BodyLogging>>, req: Request, next: Next) -> Response {
+	if !settings.enabled {
+		return next.run(req).await;
+	}
+
+	let request_id = req
+		.headers()
+		.get(REQUEST_ID_HEADER)
+		.and_then(|v| v.to_str().ok())
+		.map_or_else(|| uuid::Uuid::new_v4().to_string(), ToString::to_string);
+
+	let (parts, body) = req.into_parts();
+	let (body, logged) = capture(body, &settings).await;
+	tracing::debug!(
+		request_id = %request_id,
+		method = %parts.method,
+		uri = %parts.uri,
+		headers = ?redact_headers(&parts.headers, &settings),
+		body = %logged,
+		"request body"
+	);
+	let response = next.run(Request::from_parts(parts, body)).await;
+
+	let (parts, body) = response.into_parts();
+	let (body, logged) = capture(body, &settings).await;
+	tracing::debug!(
+		request_id = %request_id,
+		status = %parts.status,
+		headers = ?redact_headers(&parts.headers, &settings),
+		body = %logged,
+		"response body"
+	);
+	Response::from_parts(parts, body)
+}
+
+/// Buffers `body` if it fits within the cap, returning a replacement body and its loggable form
+async fn capture(body: Body, settings: &BodyLogging) -> (Body, String) {
+	let fits = body.size_hint().upper().is_some_and(|len| len <= settings.max_bytes as u64);
+	if !fits {
+		return (body, "<omitted: larger than log limit>".to_string());
+	}
+
+	match axum::body::to_bytes(body, settings.max_bytes).await {
+		Ok(bytes) => {
+			let logged = render(&bytes, settings);
+			(Body::from(bytes), logged)
+		}
+		Err(e) => (Body::empty(), ["<failed to read body: ", &e.to_string(), ">"].concat()),
+	}
+}
+
+fn render(bytes: &Bytes, settings: &BodyLogging) -> String {
+	if bytes.is_empty() {
+		return String::new();
+	}
+
+	serde_json::from_slice::<Value>(bytes).map_or_else(
+		|_| String::from_utf8_lossy(bytes).into_owned(),
+		|mut value| {
+			redact_fields(&mut value, &settings.redact_fields);
+			value.to_string()
+		},
+	)
+}
+
+fn redact_fields(value: &mut Value, fields: &[String]) {
+	match value {
+		Value::Object(map) => {
+			for (key, v) in map.iter_mut() {
+				if fields.iter().any(|f| f.eq_ignore_ascii_case(key)) {
+					*v = Value::String(REDACTED.to_string());
+				} else {
+					redact_fields(v, fields);
+				}
+			}
+		}
+		Value::Array(items) => items.iter_mut().for_each(|v| redact_fields(v, fields)),
+		_ => {}
+	}
+}
+
+fn redact_headers(headers: &HeaderMap, settings: &BodyLogging) -> Vec<(String, String)> {
+	headers
+		.iter()
+		.map(|(name, value)| {
+			let value = if settings.redact_headers.iter().any(|h| h == name.as_str()) {
+				REDACTED.to_string()
+			} else {
+				String::from_utf8_lossy(value.as_bytes()).into_owned()
+			};
+			(name.to_string(), value)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::{CapturedEvent, CapturedLogs};
+	use axum::http::StatusCode;
+	use axum::middleware::from_fn_with_state;
+	use axum::routing::post;
+	use axum::Router;
+	use tower::ServiceExt;
+
+	fn settings(enabled: bool) -> BodyLogging {
+		BodyLogging {
+			enabled,
+			max_bytes: 1024,
+			redact_headers: vec!["authorization".to_string()],
+			redact_fields: vec!["password".to_string()],
+		}
+	}
+
+	async fn send(settings: BodyLogging) -> (String, CapturedLogs) {
+		let captured = CapturedLogs::default();
+		let _guard = captured.install();
+
+		let app = Router::new()
+			.route("/echo", post(|body: String| async move { body }))
+			.layer(from_fn_with_state(Arc::new(settings), log_bodies));
+		let req = Request::post("/echo")
+			.header(REQUEST_ID_HEADER, "req-42")
+			.header("authorization", "Bearer hunter2")
+			.body(Body::from(r#"{"user":"ada","password":"hunter2"}"#))
+			.unwrap();
+
+		let response = app.oneshot(req).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		let echoed = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		(String::from_utf8(echoed.to_vec()).unwrap(), captured)
+	}
+
+	#[tokio::test]
+	async fn test_bodies_logged_when_enabled() {
+		let (echoed, logs) = send(settings(true)).await;
+
+		// The handler still sees the original, unredacted body
+		assert_eq!(echoed, r#"{"user":"ada","password":"hunter2"}"#);
+		let [request]: [CapturedEvent; 1] = logs.with_message("request body").try_into().unwrap();
+		let [response]: [CapturedEvent; 1] = logs.with_message("response body").try_into().unwrap();
+		let redacted = [r#"{"password":""#, REDACTED, r#"","user":"ada"}"#].concat();
+		for event in [&request, &response] {
+			assert_eq!(event.level, tracing::Level::DEBUG);
+			assert_eq!(event.field("request_id"), Some("req-42"));
+			assert_eq!(event.field("body"), Some(redacted.as_str()));
+		}
+		assert_eq!(request.field("method"), Some("POST"));
+		assert!(request.field("headers").unwrap().contains(REDACTED));
+		assert_eq!(response.field("status"), Some("200 OK"));
+		assert!(logs.events().iter().all(|event| event.fields.values().all(|value| !value.contains("hunter2"))));
+	}
+
+	#[tokio::test]
+	async fn test_bodies_not_logged_when_disabled() {
+		let (echoed, logs) = send(settings(false)).await;
+
+		assert_eq!(echoed, r#"{"user":"ada","password":"hunter2"}"#);
+		assert!(logs.with_message("request body").is_empty());
+		assert!(logs.with_message("response body").is_empty());
+	}
+}
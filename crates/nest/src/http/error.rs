@@ -1,5 +1,5 @@
 use axum::body::Body;
-use axum::http::header::WWW_AUTHENTICATE;
+use axum::http::header::{CONTENT_TYPE, WWW_AUTHENTICATE};
 use axum::http::{HeaderMap, HeaderValue, Response, StatusCode};
 use axum::response::IntoResponse;
 use axum::Json;
@@ -22,6 +22,9 @@ pub enum Error {
 	#[error("Invalid Data Schema pased")]
 	InvalidData,
 
+	#[error("invalid value for `{parameter}`: {reason}")]
+	InvalidParameter { parameter: Cow<'static, str>, reason: String },
+
 	#[error("error in the request body")]
 	UnprocessableEntity { errors: HashMap<Cow<'static, str>, Vec<Cow<'static, str>>> },
 
@@ -59,6 +62,13 @@ impl Error {
 		Self::UnprocessableEntity { errors: error_map }
 	}
 
+	pub fn invalid_parameter(parameter: impl Into<Cow<'static, str>>, reason: impl Into<String>) -> Self {
+		Self::InvalidParameter {
+			parameter: parameter.into(),
+			reason: reason.into(),
+		}
+	}
+
 	const fn status_code(&self) -> StatusCode {
 		match self {
 			Self::Unauthorized => StatusCode::UNAUTHORIZED,
@@ -66,6 +76,7 @@ impl Error {
 			Self::NotFound => StatusCode::NOT_FOUND,
 			Self::InvalidData => StatusCode::FORBIDDEN,
 			Self::InvalidEncodedDate(_) => StatusCode::FORBIDDEN,
+			Self::InvalidParameter { .. } => StatusCode::BAD_REQUEST,
 			Self::UnprocessableEntity { .. } => StatusCode::UNPROCESSABLE_ENTITY,
 			Self::Sqlx(_) | Self::Anyhow(_) => StatusCode::INTERNAL_SERVER_ERROR,
 			Self::MaxRecordLimitExceeded => StatusCode::BAD_REQUEST,
@@ -87,6 +98,28 @@ impl IntoResponse for Error {
 
 				return (StatusCode::UNPROCESSABLE_ENTITY, Json(Errors { errors })).into_response();
 			}
+			Self::InvalidParameter { ref parameter, .. } => {
+				/// RFC 7807 problem details, naming the offending parameter
+				#[derive(serde::Serialize)]
+				struct Problem<'a> {
+					#[serde(rename = "type")]
+					kind: &'static str,
+					title: &'static str,
+					status: u16,
+					detail: String,
+					parameter: &'a str,
+				}
+
+				let status = self.status_code();
+				let problem = Problem {
+					kind: "about:blank",
+					title: "Invalid request parameter",
+					status: status.as_u16(),
+					detail: self.to_string(),
+					parameter,
+				};
+				return (status, [(CONTENT_TYPE, HeaderValue::from_static("application/problem+json"))], Json(problem)).into_response();
+			}
 			Self::Unauthorized => {
 				return (
 					self.status_code(),
@@ -0,0 +1,147 @@
+use super::Error;
+use async_trait::async_trait;
+use axum::extract::path::ErrorKind;
+use axum::extract::rejection::PathRejection;
+use axum::extract::{FromRequestParts, Path, RawPathParams};
+use axum::http::request::Parts;
+use axum::response::{IntoResponse, Response};
+use serde::de::DeserializeOwned;
+
+/// `Path<T>` whose parse failures are a 400 problem naming the path parameter
+///
+/// Failures that point at the route rather than the request, like a
+/// parameter count mismatch, keep axum's own response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedPath<T>(pub T);
+
+/// `Query<T>` whose parse failures are a 400 problem naming the query parameter
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ValidatedPath<T>
+where
+	T: DeserializeOwned + Send,
+	S: Send + Sync,
+{
+	type Rejection = Response;
+
+	async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+		let rejection = match Path::<T>::from_request_parts(parts, state).await {
+			Ok(Path(value)) => return Ok(Self(value)),
+			Err(PathRejection::FailedToDeserializePathParams(e)) => e,
+			Err(rejection) => return Err(rejection.into_response()),
+		};
+
+		let raw = RawPathParams::from_request_parts(parts, state).await.ok();
+		// Single-value paths report only the value, so find its name among the raw params
+		let key_for = |value: &str| raw.as_ref().and_then(|raw| raw.iter().find(|&(_, v)| v == value)).map(|(key, _)| key.to_string());
+		let key_at = |index: usize| raw.as_ref().and_then(|raw| raw.iter().nth(index)).map(|(key, _)| key.to_string());
+
+		let (parameter, reason) = match rejection.kind() {
+			ErrorKind::ParseErrorAtKey { key, value, expected_type } => (Some(key.clone()), format!("`{value}` is not a valid {expected_type}")),
+			ErrorKind::ParseErrorAtIndex { index, value, expected_type } => (key_at(*index), format!("`{value}` is not a valid {expected_type}")),
+			ErrorKind::ParseError { value, expected_type } => (key_for(value), format!("`{value}` is not a valid {expected_type}")),
+			ErrorKind::InvalidUtf8InPathParam { key } => (Some(key.clone()), "not valid UTF-8".to_string()),
+			_ => return Err(PathRejection::FailedToDeserializePathParams(rejection).into_response()),
+		};
+		Err(Error::invalid_parameter(parameter.unwrap_or_else(|| "path".to_string()), reason).into_response())
+	}
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+	T: DeserializeOwned + Send,
+	S: Send + Sync,
+{
+	type Rejection = Response;
+
+	async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+		let query = parts.uri.query().unwrap_or_default();
+		let deserializer = serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+
+		serde_path_to_error::deserialize(deserializer).map(Self).map_err(|e| {
+			// Errors about the query as a whole, like a missing field, have no path
+			let parameter = match e.path().to_string() {
+				path if path == "." => "query".to_string(),
+				path => path,
+			};
+			Error::invalid_parameter(parameter, e.inner().to_string()).into_response()
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::body::Body;
+	use axum::http::{header::CONTENT_TYPE, Request, StatusCode};
+	use axum::routing::get;
+	use axum::Router;
+	use serde::Deserialize;
+	use serde_json::Value;
+	use tower::ServiceExt;
+
+	#[derive(Deserialize)]
+	struct Page {
+		limit: u32,
+		#[serde(default)]
+		offset: u32,
+	}
+
+	fn app() -> Router {
+		Router::new()
+			.route("/games/:id", get(|ValidatedPath(id): ValidatedPath<u32>| async move { id.to_string() }))
+			.route(
+				"/seasons/:season/weeks/:week",
+				get(|ValidatedPath((season, week)): ValidatedPath<(u16, u8)>| async move { format!("{season}/{week}") }),
+			)
+			.route(
+				"/games",
+				get(|ValidatedQuery(page): ValidatedQuery<Page>| async move { format!("{}+{}", page.offset, page.limit) }),
+			)
+	}
+
+	async fn get_from(path: &str) -> (StatusCode, Option<String>, String) {
+		let response = app().oneshot(Request::get(path).body(Body::empty()).unwrap()).await.unwrap();
+		let status = response.status();
+		let content_type = response.headers().get(CONTENT_TYPE).map(|v| v.to_str().unwrap().to_string());
+		let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		(status, content_type, String::from_utf8(body.to_vec()).unwrap())
+	}
+
+	async fn problem_at(path: &str) -> Value {
+		let (status, content_type, body) = get_from(path).await;
+		assert_eq!(status, StatusCode::BAD_REQUEST, "{path}: {body}");
+		assert_eq!(content_type.as_deref(), Some("application/problem+json"));
+		serde_json::from_str(&body).unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_bad_path_segment_names_the_parameter() {
+		assert_eq!(get_from("/games/42").await.2, "42");
+		assert_eq!(get_from("/seasons/2024/weeks/7").await.2, "2024/7");
+
+		let problem = problem_at("/games/abc").await;
+		assert_eq!(problem["status"], 400);
+		assert_eq!(problem["parameter"], "id");
+		assert!(problem["detail"].as_str().unwrap().contains("`abc` is not a valid u32"), "{problem}");
+
+		let problem = problem_at("/seasons/2024/weeks/300").await;
+		assert_eq!(problem["parameter"], "week");
+		assert!(problem["detail"].as_str().unwrap().contains("`300`"), "{problem}");
+	}
+
+	#[tokio::test]
+	async fn test_bad_query_parameter_names_the_parameter() {
+		assert_eq!(get_from("/games?limit=10&offset=20").await.2, "20+10");
+
+		let problem = problem_at("/games?limit=ten").await;
+		assert_eq!(problem["parameter"], "limit");
+
+		let problem = problem_at("/games?offset=5").await;
+		assert_eq!(problem["parameter"], "query");
+		assert!(problem["detail"].as_str().unwrap().contains("limit"), "{problem}");
+	}
+}
@@ -0,0 +1,211 @@
+use axum::extract::{Request, State};
+use axum::http::{header::CONTENT_TYPE, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use prometheus::{HistogramOpts, HistogramVec, IntGaugeVec, Opts, Registry, TextEncoder};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Acquire wait buckets, in seconds: sub-millisecond when the pool has slack,
+/// up to the default 30s acquire timeout when it's exhausted
+const ACQUIRE_WAIT_BUCKETS: &[f64] = &[0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0];
+
+tokio::task_local! {
+	/// When the current request arrived, until its first connection is handed out
+	static REQUEST_STARTED: Cell<Option<Instant>>;
+}
+
+/// Acquire wait histogram, fed by pool hooks
+///
+/// sqlx has no hook for the start of an acquire, only for the connection it
+/// hands out, so the wait is measured from when [`time_acquires`] saw the
+/// request arrive to its first connection. Handlers that query straight away
+/// add only their extraction time on top of the pool's own wait.
+#[derive(Clone)]
+pub struct AcquireWait(HistogramVec);
+
+impl AcquireWait {
+	/// # Errors
+	///
+	/// Returns an error if the histogram options are invalid.
+	pub fn new() -> Result<Self, prometheus::Error> {
+		let opts = HistogramOpts::new("db_pool_acquire_wait_seconds", "Time spent waiting for a pooled connection").buckets(ACQUIRE_WAIT_BUCKETS.to_vec());
+		Ok(Self(HistogramVec::new(opts, &["db"])?))
+	}
+
+	/// Add hooks to `options` that record acquires from the `db` pool
+	#[must_use]
+	pub fn instrument(&self, db: &str, options: SqlitePoolOptions) -> SqlitePoolOptions {
+		let (on_connect, on_acquire) = (self.clone(), self.clone());
+		let (connect_db, acquire_db) = (db.to_string(), db.to_string());
+		options
+			.after_connect(move |_, _| {
+				on_connect.observe(&connect_db);
+				Box::pin(async { Ok(()) })
+			})
+			.before_acquire(move |_, _| {
+				on_acquire.observe(&acquire_db);
+				Box::pin(async { Ok(true) })
+			})
+	}
+
+	/// Hooks run on the acquiring task, so only requests inside [`time_acquires`] are recorded
+	fn observe(&self, db: &str) {
+		if let Ok(Some(started)) = REQUEST_STARTED.try_with(Cell::take) {
+			self.0.with_label_values(&[db]).observe(started.elapsed().as_secs_f64());
+		}
+	}
+}
+
+/// Middleware starting the acquire wait clock for each request
+pub async fn time_acquires(req: Request, next: Next) -> Response {
+	REQUEST_STARTED.scope(Cell::new(Some(Instant::now())), next.run(req)).await
+}
+
+/// Saturation metrics for each database pool, labelled by database name
+///
+/// Pool size and idle counts are read from sqlx when the metrics are
+/// rendered. Acquire waits are only recorded for pools built with
+/// [`AcquireWait::instrument`].
+pub struct DbPoolMetrics {
+	pools: HashMap<String, SqlitePool>,
+	registry: Registry,
+	size: IntGaugeVec,
+	idle: IntGaugeVec,
+	in_use: IntGaugeVec,
+	max: IntGaugeVec,
+	acquire_wait: AcquireWait,
+}
+
+impl DbPoolMetrics {
+	/// # Errors
+	///
+	/// Returns an error if the metrics can't be registered.
+	pub fn new(pools: HashMap<String, SqlitePool>, acquire_wait: AcquireWait) -> Result<Self, prometheus::Error> {
+		let gauge = |name: &str, help: &str| IntGaugeVec::new(Opts::new(name, help), &["db"]);
+		let metrics = Self {
+			size: gauge("db_pool_connections", "Open connections in the pool")?,
+			idle: gauge("db_pool_idle_connections", "Open connections not checked out")?,
+			in_use: gauge("db_pool_in_use_connections", "Connections checked out of the pool")?,
+			max: gauge("db_pool_max_connections", "Configured max_connections")?,
+			acquire_wait,
+			registry: Registry::new(),
+			pools,
+		};
+
+		metrics.registry.register(Box::new(metrics.size.clone()))?;
+		metrics.registry.register(Box::new(metrics.idle.clone()))?;
+		metrics.registry.register(Box::new(metrics.in_use.clone()))?;
+		metrics.registry.register(Box::new(metrics.max.clone()))?;
+		metrics.registry.register(Box::new(metrics.acquire_wait.0.clone()))?;
+		Ok(metrics)
+	}
+
+	/// Current pool statistics in the Prometheus text format
+	///
+	/// # Errors
+	///
+	/// Returns an error if encoding fails.
+	pub fn render(&self) -> Result<String, prometheus::Error> {
+		for (db, pool) in &self.pools {
+			let size = i64::from(pool.size());
+			let idle = i64::try_from(pool.num_idle()).unwrap_or(i64::MAX);
+			self.size.with_label_values(&[db]).set(size);
+			self.idle.with_label_values(&[db]).set(idle);
+			self.in_use.with_label_values(&[db]).set((size - idle).max(0));
+			self.max.with_label_values(&[db]).set(i64::from(pool.options().get_max_connections()));
+		}
+
+		TextEncoder::new().encode_to_string(&self.registry.gather())
+	}
+}
+
+/// `GET /metrics`: pool statistics for every database
+pub async fn db_pool_metrics(State(metrics): State<Arc<DbPoolMetrics>>) -> Response {
+	match metrics.render() {
+		Ok(body) => ([(CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+		Err(e) => {
+			tracing::error!(error = %e, "failed to encode pool metrics");
+			StatusCode::INTERNAL_SERVER_ERROR.into_response()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::{body::Body, middleware::from_fn, routing::get, Router};
+	use std::time::Duration;
+	use tower::ServiceExt;
+
+	fn in_use(rendered: &str, n: u32) -> bool {
+		rendered.contains(&["db_pool_in_use_connections{db=\"db_1\"} ", &n.to_string()].concat())
+	}
+
+	async fn pool(acquire_wait: &AcquireWait, max_connections: u32) -> SqlitePool {
+		acquire_wait
+			.instrument("db_1", SqlitePoolOptions::new().max_connections(max_connections))
+			.connect("sqlite::memory:")
+			.await
+			.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_in_use_gauge_tracks_checked_out_connections() {
+		let acquire_wait = AcquireWait::new().unwrap();
+		let pool = pool(&acquire_wait, 3).await;
+		let metrics = DbPoolMetrics::new(HashMap::from([("db_1".to_string(), pool.clone())]), acquire_wait).unwrap();
+
+		let first = pool.acquire().await.unwrap();
+		assert!(in_use(&metrics.render().unwrap(), 1));
+
+		let second = pool.acquire().await.unwrap();
+		let rendered = metrics.render().unwrap();
+		assert!(in_use(&rendered, 2));
+		assert!(rendered.contains("db_pool_max_connections{db=\"db_1\"} 3"));
+		// Outside a request there's nothing to measure the wait from
+		assert!(!rendered.contains("db_pool_acquire_wait_seconds_count"));
+
+		// Connections go back to the pool on a background task
+		drop((first, second));
+		for _ in 0..100 {
+			if in_use(&metrics.render().unwrap(), 0) {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+		assert!(in_use(&metrics.render().unwrap(), 0));
+	}
+
+	#[tokio::test]
+	async fn test_request_waiting_on_an_exhausted_pool_is_recorded() {
+		let acquire_wait = AcquireWait::new().unwrap();
+		let pool = pool(&acquire_wait, 1).await;
+		let metrics = DbPoolMetrics::new(HashMap::from([("db_1".to_string(), pool.clone())]), acquire_wait).unwrap();
+		let app = Router::new()
+			.route(
+				"/count",
+				get(|State(pool): State<SqlitePool>| async move {
+					let (count,): (i64,) = sqlx::query_as("SELECT 1").fetch_one(&pool).await.unwrap();
+					count.to_string()
+				}),
+			)
+			.with_state(pool.clone())
+			.layer(from_fn(time_acquires));
+
+		// The only connection is checked out, so the request queues until it's returned
+		let held = pool.acquire().await.unwrap();
+		let request = tokio::spawn(app.oneshot(axum::http::Request::get("/count").body(Body::empty()).unwrap()));
+		tokio::time::sleep(Duration::from_millis(50)).await;
+		drop(held);
+		assert_eq!(request.await.unwrap().unwrap().status(), StatusCode::OK);
+
+		let rendered = metrics.render().unwrap();
+		assert!(rendered.contains("db_pool_acquire_wait_seconds_count{db=\"db_1\"} 1"));
+		assert!(rendered.contains("db_pool_acquire_wait_seconds_bucket{db=\"db_1\",le=\"0.01\"} 0"));
+	}
+}
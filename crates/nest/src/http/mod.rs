@@ -1,5 +1,13 @@
+pub mod body_logging;
 pub mod error;
+pub mod extract;
+pub mod metrics;
+pub mod tenant;
 
+pub use body_logging::{log_bodies, BodyLogging};
 pub use error::{Error, ResultExt};
+pub use extract::{ValidatedPath, ValidatedQuery};
+pub use metrics::{db_pool_metrics, time_acquires, AcquireWait, DbPoolMetrics};
+pub use tenant::TenantMap;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,118 @@
+use anyhow::{bail, Context, Result};
+use axum::extract::{Request, State};
+use axum::http::{header::HOST, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Which database serves each tenant host, from `TENANTS` entries like `tenant1.example.com=db_1`
+#[derive(Clone, Debug, Default)]
+pub struct TenantMap {
+	hosts: HashMap<String, String>,
+}
+
+impl TenantMap {
+	/// Parse `host=db` entries; hosts are matched case-insensitively and without a port
+	///
+	/// # Errors
+	///
+	/// Returns an error for an entry without `=`, with an empty side, or for a host listed twice.
+	pub fn parse(entries: &[String]) -> Result<Self> {
+		let mut hosts = HashMap::new();
+		for entry in entries.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+			let (host, db) = entry
+				.split_once('=')
+				.map(|(host, db)| (host.trim().to_ascii_lowercase(), db.trim()))
+				.filter(|(host, db)| !host.is_empty() && !db.is_empty())
+				.with_context(|| format!("tenant entry '{entry}' is not host=db"))?;
+			if hosts.insert(host.clone(), db.to_string()).is_some() {
+				bail!("tenant host '{host}' is mapped more than once");
+			}
+		}
+		Ok(Self { hosts })
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.hosts.is_empty()
+	}
+
+	/// Database serving `host`, if it is a known tenant
+	#[must_use]
+	pub fn db_for(&self, host: &str) -> Option<&str> {
+		self.hosts.get(host).map(String::as_str)
+	}
+
+	/// Route each request to its tenant's database routes by `Host` header
+	///
+	/// `db_routes` holds the routes built for each database. Requests for a
+	/// host that isn't a tenant get 404, and a tenant can only reach its own
+	/// database's routes.
+	///
+	/// # Errors
+	///
+	/// Returns an error if a tenant maps to a database with no routes.
+	pub fn router(&self, db_routes: &HashMap<String, Router>) -> Result<Router> {
+		let tenants = self
+			.hosts
+			.iter()
+			.map(|(host, db)| {
+				let routes = db_routes.get(db).with_context(|| format!("tenant '{host}' maps to unknown database '{db}'"))?;
+				Ok((host.clone(), routes.clone()))
+			})
+			.collect::<Result<HashMap<_, _>>>()?;
+
+		Ok(Router::new().fallback(route_by_host).with_state(Arc::new(tenants)))
+	}
+}
+
+async fn route_by_host(State(tenants): State<Arc<HashMap<String, Router>>>, req: Request) -> Response {
+	let Some(routes) = request_host(&req).and_then(|host| tenants.get(&host)) else {
+		return StatusCode::NOT_FOUND.into_response();
+	};
+	routes.clone().oneshot(req).await.unwrap_or_else(|never| match never {})
+}
+
+/// Lowercased host the request was sent to, without its port
+fn request_host(req: &Request) -> Option<String> {
+	let host = req.headers().get(HOST).and_then(|host| host.to_str().ok()).or_else(|| req.uri().host())?;
+	let host = host.rsplit_once(':').map_or(host, |(host, _port)| host);
+	Some(host.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::body::Body;
+	use axum::routing::get;
+
+	async fn get_from(app: &Router, host: &str, path: &str) -> (StatusCode, String) {
+		let req = Request::get(path).header(HOST, host).body(Body::empty()).unwrap();
+		let response = app.clone().oneshot(req).await.unwrap();
+		let status = response.status();
+		let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		(status, String::from_utf8(body.to_vec()).unwrap())
+	}
+
+	#[tokio::test]
+	async fn test_requests_route_to_their_tenants_database() {
+		let db_routes = HashMap::from([
+			("db_1".to_string(), Router::new().route("/api/db_1/whoami", get(|| async { "db_1" }))),
+			("db_2".to_string(), Router::new().route("/api/db_2/whoami", get(|| async { "db_2" }))),
+		]);
+		let tenants = TenantMap::parse(&["Tenant1.example.com=db_1".to_string(), "tenant2.example.com = db_2".to_string()]).unwrap();
+		let app = tenants.router(&db_routes).unwrap();
+
+		assert_eq!(get_from(&app, "tenant1.example.com", "/api/db_1/whoami").await, (StatusCode::OK, "db_1".to_string()));
+		assert_eq!(get_from(&app, "TENANT2.example.com:8000", "/api/db_2/whoami").await, (StatusCode::OK, "db_2".to_string()));
+
+		// A tenant can't reach another tenant's database
+		assert_eq!(get_from(&app, "tenant1.example.com", "/api/db_2/whoami").await.0, StatusCode::NOT_FOUND);
+		assert_eq!(get_from(&app, "unknown.example.com", "/api/db_1/whoami").await.0, StatusCode::NOT_FOUND);
+
+		assert!(TenantMap::parse(&["tenant3.example.com=db_3".to_string()]).unwrap().router(&db_routes).is_err());
+		assert!(TenantMap::parse(&["tenant1.example.com".to_string()]).is_err());
+	}
+}
@@ -2,20 +2,31 @@ pub mod config;
 
 pub mod http;
 
-use crate::http::Error;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+use crate::http::{db_pool_metrics, log_bodies, time_acquires, AcquireWait, BodyLogging, DbPoolMetrics, Error, TenantMap};
 use anyhow::{Context, Result};
-use some_services::rate_limiter::token_bucket::{rate_limit_middleware, TokenBucketRateLimiter};
+use some_services::rate_limiter::{keyed_rate_limit_middleware, HeaderKey, IpKey, KeyedRateLimiter, RateLimitKey};
 use sqlx::sqlite::SqlitePoolOptions;
 use tracing_subscriber::{filter::EnvFilter, fmt::format::JsonFields, util::SubscriberInitExt, Layer};
 
 use crate::config::Config;
-use axum::{middleware::from_fn_with_state, Router};
+use axum::{
+	http::HeaderName,
+	middleware::{from_fn, from_fn_with_state},
+	routing::get,
+	Router,
+};
 use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::future::Future;
+use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
 use tokio::net::TcpListener;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 use tower::ServiceBuilder;
 use tower_http::add_extension::AddExtensionLayer;
 use tower_http::trace::TraceLayer;
@@ -32,10 +43,26 @@ pub trait MultiDbHandler: Send + Sync + 'static {
 pub struct ApiContext {
 	#[allow(dead_code)]
 	config: Arc<Config>,
-	#[allow(dead_code)]
 	dbs: Option<HashMap<String, SqlitePool>>,
+	shutdown: CancellationToken,
+}
+
+impl ApiContext {
+	#[must_use]
+	pub fn db(&self, name: &str) -> Option<&SqlitePool> {
+		self.dbs.as_ref()?.get(name)
+	}
+
+	/// Cancelled when the server starts shutting down
+	#[must_use]
+	pub const fn shutdown(&self) -> &CancellationToken {
+		&self.shutdown
+	}
 }
 
+/// Future started alongside the server by [`ApiBuilder::add_background_task`]
+type BackgroundTask = Box<dyn FnOnce(ApiContext) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
 pub trait Run<M: MigrationHandler> {
 	type Future: Future<Output = Result<()>> + Send + 'static;
 
@@ -49,6 +76,10 @@ pub struct ApiBuilder<M: MigrationHandler> {
 	dbs: Option<HashMap<String, SqlitePool>>,
 	handlers: Vec<Box<dyn MultiDbHandler>>,
 	migration_handler: Option<M>,
+	rate_limit_key: Option<Box<dyn RateLimitKey>>,
+	acquire_wait: Option<AcquireWait>,
+	background_tasks: Vec<BackgroundTask>,
+	shutdown: CancellationToken,
 }
 
 impl<M: MigrationHandler> ApiBuilder<M> {
@@ -58,6 +89,10 @@ impl<M: MigrationHandler> ApiBuilder<M> {
 			dbs: None,
 			handlers: Vec::new(),
 			migration_handler,
+			rate_limit_key: None,
+			acquire_wait: None,
+			background_tasks: Vec::new(),
+			shutdown: CancellationToken::new(),
 		}
 	}
 
@@ -77,14 +112,82 @@ impl<M: MigrationHandler> ApiBuilder<M> {
 		self
 	}
 
+	/// Key rate-limit buckets with `key` instead of the configured header or client IP
+	pub fn set_rate_limit_key(&mut self, key: impl RateLimitKey) -> &mut Self {
+		self.rate_limit_key = Some(Box::new(key));
+		self
+	}
+
+	/// Report acquire waits from pools built with [`AcquireWait::instrument`] on `/metrics`
+	pub fn set_acquire_wait(&mut self, acquire_wait: AcquireWait) -> &mut Self {
+		self.acquire_wait = Some(acquire_wait);
+		self
+	}
+
+	/// Run `task` for the life of the server
+	///
+	/// The task is spawned by [`serve`](Self::serve) with the same [`ApiContext`]
+	/// handlers see. On shutdown, whether from Ctrl-C or [`shutdown_token`](Self::shutdown_token),
+	/// the context's [`shutdown`](ApiContext::shutdown) token is cancelled and
+	/// `serve` waits for every task to return, so tasks should watch it.
+	pub fn add_background_task<F, Fut>(&mut self, task: F) -> &mut Self
+	where
+		F: FnOnce(ApiContext) -> Fut + Send + 'static,
+		Fut: Future<Output = ()> + Send + 'static,
+	{
+		self.background_tasks.push(Box::new(move |context| Box::pin(task(context))));
+		self
+	}
+
+	/// Token that shuts the server down gracefully when cancelled
+	#[must_use]
+	pub fn shutdown_token(&self) -> CancellationToken {
+		self.shutdown.clone()
+	}
+
+	fn rate_limiter(&mut self) -> KeyedRateLimiter {
+		let max_tokens = self.config.rate_limit;
+		if let Some(key) = self.rate_limit_key.take() {
+			return KeyedRateLimiter::new(max_tokens, key);
+		}
+		match self.config.rate_limit_header.as_deref().map(HeaderName::try_from) {
+			Some(Ok(header)) => KeyedRateLimiter::new(max_tokens, HeaderKey::new(header).with_known_keys(self.config.rate_limit_keys.clone())),
+			Some(Err(e)) => {
+				tracing::warn!(error = %e, "invalid RATE_LIMIT_HEADER, keying rate limits by client IP");
+				KeyedRateLimiter::new(max_tokens, IpKey)
+			}
+			None => KeyedRateLimiter::new(max_tokens, IpKey),
+		}
+	}
+
 	pub async fn serve(self) -> Result<()> {
+		let listener = TcpListener::bind("127.0.0.1:8000").await?;
+		self.serve_on(listener).await
+	}
+
+	async fn serve_on(mut self, listener: TcpListener) -> Result<()> {
+		let rate_limiter = Arc::new(self.rate_limiter());
 		let context = ApiContext {
 			config: Arc::new(self.config),
 			dbs: self.dbs.clone(),
+			shutdown: self.shutdown.clone(),
 		};
+		let tenants = TenantMap::parse(&context.config.tenants).context("invalid TENANTS")?;
 		let mut app = Router::new();
 
 		match &self.dbs {
+			// Each tenant only sees its own database's routes
+			Some(dbs) if !tenants.is_empty() => {
+				let mut db_routes = HashMap::new();
+				for (db_name, db_pool) in dbs {
+					let routes = self
+						.handlers
+						.iter()
+						.fold(Router::new(), |routes, handler| routes.merge(handler.create_routes(db_name, Some(db_pool.clone()))));
+					db_routes.insert(db_name.clone(), routes);
+				}
+				app = app.merge(tenants.router(&db_routes)?);
+			}
 			Some(dbs) => {
 				for (db_name, db_pool) in dbs {
 					for handler in &self.handlers {
@@ -99,18 +202,49 @@ impl<M: MigrationHandler> ApiBuilder<M> {
 			}
 		}
 
+		if context.config.enable_prometheus {
+			let acquire_wait = match self.acquire_wait.take() {
+				Some(acquire_wait) => acquire_wait,
+				None => AcquireWait::new().context("could not register pool metrics")?,
+			};
+			let metrics = DbPoolMetrics::new(self.dbs.clone().unwrap_or_default(), acquire_wait).context("could not register pool metrics")?;
+			app = app.merge(Router::new().route("/metrics", get(db_pool_metrics)).with_state(Arc::new(metrics)));
+		}
+
+		let body_logging = Arc::new(BodyLogging::from(context.config.as_ref()));
 		let app = app.layer(
 			ServiceBuilder::new()
-				.layer(from_fn_with_state(
-					Arc::new(TokenBucketRateLimiter::new(context.config.rate_limit.clone())),
-					rate_limit_middleware,
-				))
-				.layer(AddExtensionLayer::new(context))
-				.layer(TraceLayer::new_for_http()),
+				.layer(from_fn_with_state(rate_limiter, keyed_rate_limit_middleware))
+				.layer(AddExtensionLayer::new(context.clone()))
+				.layer(TraceLayer::new_for_http())
+				.layer(from_fn_with_state(body_logging, log_bodies))
+				.layer(from_fn(time_acquires)),
 		);
-		let listener = TcpListener::bind("127.0.0.1:8000").await?;
+
+		let mut tasks = JoinSet::new();
+		for task in self.background_tasks {
+			tasks.spawn(task(context.clone()));
+		}
+
 		tracing::debug!("listening on {}", listener.local_addr()?);
-		axum::serve(listener, app).await?;
+		let shutdown = self.shutdown.clone();
+		let served = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+			.with_graceful_shutdown(async move {
+				tokio::select! {
+					() = shutdown.cancelled() => {}
+					_ = tokio::signal::ctrl_c() => tracing::info!("received Ctrl-C, shutting down"),
+				}
+			})
+			.await;
+
+		// Also reached when the server fails, so tasks don't outlive it either way
+		self.shutdown.cancel();
+		while let Some(result) = tasks.join_next().await {
+			if let Err(e) = result {
+				tracing::error!(error = %e, "background task panicked");
+			}
+		}
+		served?;
 		Ok(())
 	}
 }
@@ -124,11 +258,12 @@ impl<M: MigrationHandler> Run<M> for ApiBuilder<M> {
 	{
 		Box::pin(async move {
 			let mut api_builder = Self::new(config.clone(), Some(migration_handler));
+			let acquire_wait = AcquireWait::new().context("could not register pool metrics")?;
 
 			for (i, db_url) in config.database_urls.split(',').enumerate() {
 				let db_name = format!("db_{}", i + 1);
-				let db_pool = SqlitePoolOptions::new()
-					.max_connections(5)
+				let db_pool = acquire_wait
+					.instrument(&db_name, SqlitePoolOptions::new().max_connections(5))
 					.connect(db_url)
 					.await
 					.context(format!("could not connect to {db_url}"))?;
@@ -141,6 +276,7 @@ impl<M: MigrationHandler> Run<M> for ApiBuilder<M> {
 				api_builder.add_db(db_name, db_pool);
 			}
 
+			api_builder.set_acquire_wait(acquire_wait);
 			for handler in handlers {
 				api_builder.add_handler(handler);
 			}
@@ -176,3 +312,58 @@ pub fn init_tracing(config: &Config) -> Option<()> {
 		.init();
 	None
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::time::Duration;
+
+	struct NoMigrations;
+
+	impl MigrationHandler for NoMigrations {
+		fn run_migrations<'a>(&'a self, _pool: &'a SqlitePool) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'a>> {
+			Box::pin(async { Ok(()) })
+		}
+	}
+
+	#[tokio::test]
+	async fn test_background_task_stops_on_shutdown() {
+		let config = Config::parse_from(["nest", "--database-urls", "sqlite::memory:", "--hmac-key", "test"]);
+		let mut builder = ApiBuilder::<NoMigrations>::new(config, None);
+		let ticks = Arc::new(AtomicUsize::new(0));
+		let counter = ticks.clone();
+		builder.add_background_task(move |context| async move {
+			let mut interval = tokio::time::interval(Duration::from_millis(5));
+			loop {
+				tokio::select! {
+					() = context.shutdown().cancelled() => break,
+					_ = interval.tick() => counter.fetch_add(1, Ordering::SeqCst),
+				};
+			}
+		});
+
+		let shutdown = builder.shutdown_token();
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let server = tokio::spawn(builder.serve_on(listener));
+
+		tokio::time::timeout(Duration::from_secs(5), async {
+			while ticks.load(Ordering::SeqCst) < 3 {
+				tokio::time::sleep(Duration::from_millis(5)).await;
+			}
+		})
+		.await
+		.expect("background task never ran");
+
+		shutdown.cancel();
+		tokio::time::timeout(Duration::from_secs(5), server)
+			.await
+			.expect("server didn't shut down")
+			.unwrap()
+			.unwrap();
+		let stopped_at = ticks.load(Ordering::SeqCst);
+		tokio::time::sleep(Duration::from_millis(30)).await;
+		assert_eq!(ticks.load(Ordering::SeqCst), stopped_at);
+	}
+}
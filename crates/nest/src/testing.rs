@@ -0,0 +1,84 @@
+//! Tracing capture for tests
+//!
+//! [`CapturedLogs`] records every event's fields as they were emitted, so tests
+//! can assert on `request_id` or `accepted` directly instead of matching
+//! against a formatter's rendered output.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+use tracing::field::{Field, Visit};
+use tracing::subscriber::DefaultGuard;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+
+/// One recorded event
+#[derive(Clone, Debug)]
+pub struct CapturedEvent {
+	pub level: Level,
+	/// Every field, `message` included. `%` and plain string values are kept
+	/// as-is; `?` values hold their `Debug` output.
+	pub fields: BTreeMap<String, String>,
+}
+
+impl CapturedEvent {
+	#[must_use]
+	pub fn field(&self, name: &str) -> Option<&str> {
+		self.fields.get(name).map(String::as_str)
+	}
+
+	#[must_use]
+	pub fn message(&self) -> Option<&str> {
+		self.field("message")
+	}
+}
+
+/// Layer that keeps every event it sees, at every level
+#[derive(Clone, Default)]
+pub struct CapturedLogs(Arc<Mutex<Vec<CapturedEvent>>>);
+
+impl CapturedLogs {
+	/// Capture events on the current thread until the guard is dropped
+	#[must_use]
+	pub fn install(&self) -> DefaultGuard {
+		tracing::subscriber::set_default(tracing_subscriber::registry().with(self.clone()))
+	}
+
+	#[must_use]
+	pub fn events(&self) -> Vec<CapturedEvent> {
+		self.0.lock().unwrap_or_else(PoisonError::into_inner).clone()
+	}
+
+	/// Events logged with exactly this message
+	#[must_use]
+	pub fn with_message(&self, message: &str) -> Vec<CapturedEvent> {
+		self.events().into_iter().filter(|event| event.message() == Some(message)).collect()
+	}
+}
+
+impl<S: Subscriber> Layer<S> for CapturedLogs {
+	fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+		let mut fields = Fields::default();
+		event.record(&mut fields);
+		self.0.lock().unwrap_or_else(PoisonError::into_inner).push(CapturedEvent {
+			level: *event.metadata().level(),
+			fields: fields.0,
+		});
+	}
+}
+
+#[derive(Default)]
+struct Fields(BTreeMap<String, String>);
+
+impl Visit for Fields {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.0.insert(field.name().to_string(), value.to_string());
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		let mut rendered = String::new();
+		let _ = write!(rendered, "{value:?}");
+		self.0.insert(field.name().to_string(), rendered);
+	}
+}
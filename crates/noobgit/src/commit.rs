@@ -0,0 +1,44 @@
+use crate::file_system::ContentHash;
+use crate::registry::Change;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Position of a commit in the log, starting at 1
+pub type CommitId = u64;
+
+/// Staged changes recorded together by [`NoobGit::commit`](crate::NoobGit::commit)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Commit {
+	pub id: CommitId,
+	pub message: String,
+	/// Seconds since the Unix epoch
+	pub timestamp: u64,
+	pub changes: Vec<Change>,
+	/// SHA-256 of each changed path's content when it was staged; deleted paths have none
+	pub hashes: HashMap<PathBuf, ContentHash>,
+}
+
+impl Commit {
+	pub(crate) fn new(id: CommitId, message: String, changes: Vec<Change>, hashes: HashMap<PathBuf, ContentHash>) -> Self {
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+		Self {
+			id,
+			message,
+			timestamp,
+			changes,
+			hashes,
+		}
+	}
+}
+
+/// Read the commits saved in `path`, oldest first, or none if there is no log yet
+pub(crate) async fn read_log(path: &Path) -> io::Result<Vec<Commit>> {
+	match tokio::fs::read(path).await {
+		Ok(json) => Ok(serde_json::from_slice(&json)?),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+		Err(e) => Err(e),
+	}
+}
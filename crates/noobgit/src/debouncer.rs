@@ -21,7 +21,8 @@ use std::{fmt::Debug, sync::Mutex, time::Duration};
 use tokio::{select, sync, time::Instant};
 use tracing::trace;
 
-const DEFAULT_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+/// Debounce interval for callers without a reason to pick their own
+pub const DEFAULT_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
 
 pub struct Debouncer {
 	bump: sync::Notify,
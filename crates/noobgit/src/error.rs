@@ -24,6 +24,9 @@ pub enum FileSystemError {
 	#[error("Directory not empty: {0}")]
 	DirectoryNotEmpty(PathBuf),
 
+	#[error("Nothing staged to commit")]
+	NothingToCommit,
+
 	#[error("Unexpected error: {0}")]
 	Unexpected(String),
 }
@@ -38,6 +41,7 @@ impl FileSystemError {
 			Self::PermissionDenied(_) => 403,
 			Self::FileAlreadyExists(_) => 409,
 			Self::DirectoryNotEmpty(_) => 409,
+			Self::NothingToCommit => 409,
 			Self::Unexpected(_) => 500,
 		}
 	}
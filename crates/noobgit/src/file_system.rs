@@ -1,6 +1,14 @@
 use crate::error::{FileSystemError, Result};
+use crate::ignore::IgnoreRules;
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 use tokio::fs;
+use tracing::warn;
+
+/// Hex SHA-256 digest of a file's content
+///
+/// Saved in the state file and commit log, so it must not change between builds.
+pub type ContentHash = String;
 
 pub struct FileSystem {
 	root: PathBuf,
@@ -30,6 +38,43 @@ impl FileSystem {
 		fs::File::create(path).await.map(|_| ()).map_err(FileSystemError::from)
 	}
 
+	/// Walk the root recursively, hashing every file not matched by `ignore`
+	///
+	/// Files that can't be read are logged and left out.
+	///
+	/// # Errors
+	///
+	/// Returns an error if a directory under the root cannot be read.
+	pub async fn scan(&self, ignore: &IgnoreRules) -> Result<Vec<(PathBuf, ContentHash)>> {
+		let mut files = Vec::new();
+		let mut pending = vec![self.root.clone()];
+
+		while let Some(dir) = pending.pop() {
+			let mut entries = fs::read_dir(&dir).await?;
+			while let Some(entry) = entries.next_entry().await? {
+				let path = entry.path();
+				let relative = path.strip_prefix(&self.root).unwrap_or(&path);
+				if ignore.is_ignored(relative) {
+					continue;
+				}
+
+				let file_type = entry.file_type().await?;
+				if file_type.is_dir() {
+					pending.push(path);
+				} else if file_type.is_file() {
+					// An unreadable file stays untracked rather than failing the whole scan
+					match hash_file(&path).await {
+						Ok(hash) => files.push((path, hash)),
+						Err(e) => warn!("NoobGit: Skipping unreadable file {}: {e}", path.display()),
+					}
+				}
+			}
+		}
+
+		files.sort();
+		Ok(files)
+	}
+
 	pub async fn remove<P: AsRef<Path>>(&self, path: P) -> Result<()> {
 		let path = self.root.join(path);
 
@@ -45,6 +90,15 @@ impl FileSystem {
 	}
 }
 
+/// Content hash used to tell real modifications from no-op writes
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub async fn hash_file<P: AsRef<Path>>(path: P) -> Result<ContentHash> {
+	Ok(hex::encode(Sha256::digest(fs::read(path).await?)))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -106,6 +160,14 @@ mod tests {
 		assert!(temp_dir.path().join("test.txt").exists());
 	}
 
+	#[test]
+	async fn test_hash_file_is_sha256_of_content() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("abc.txt");
+		fs::write(&path, "abc").await.unwrap();
+		assert_eq!(hash_file(&path).await.unwrap(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+	}
+
 	#[test]
 	async fn test_remove_nonexistent_path() {
 		let temp_dir = TempDir::new().unwrap();
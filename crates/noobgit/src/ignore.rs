@@ -0,0 +1,119 @@
+use glob::{Pattern, PatternError};
+use std::path::Path;
+use tokio::fs;
+use tracing::warn;
+
+pub const IGNORE_FILE: &str = ".noobgitignore";
+
+/// Former name of [`IGNORE_FILE`], still read alongside it
+pub const LEGACY_IGNORE_FILE: &str = ".noobignore";
+
+/// Glob patterns for paths the watcher skips, read from `.noobgitignore` at the watched root
+///
+/// One pattern per line; blank lines and `#` comments are skipped. A pattern
+/// without a `/` matches any path component (`target`, `*.swp`); one with a
+/// `/` matches the path from the root or any directory above it
+/// (`docs/*.tmp`). A trailing `/` is accepted and ignored.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+	patterns: Vec<Pattern>,
+}
+
+impl IgnoreRules {
+	/// Compile `patterns`, one glob each
+	///
+	/// # Errors
+	///
+	/// Returns an error for the first pattern that isn't a valid glob.
+	pub fn new<I, S>(patterns: I) -> Result<Self, PatternError>
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		Self::default().with_patterns(patterns)
+	}
+
+	/// Parse the contents of an ignore file
+	///
+	/// # Errors
+	///
+	/// Returns an error for the first line that isn't a valid glob.
+	pub fn parse(contents: &str) -> Result<Self, PatternError> {
+		Self::new(contents.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')))
+	}
+
+	/// Load rules from `root/.noobgitignore` and `root/.noobignore`, or no rules if neither is present
+	///
+	/// # Errors
+	///
+	/// Returns an error if either file has a line that isn't a valid glob.
+	pub async fn load(root: &Path) -> Result<Self, PatternError> {
+		let mut rules = Self::default();
+		for name in [IGNORE_FILE, LEGACY_IGNORE_FILE] {
+			if let Ok(contents) = fs::read_to_string(root.join(name)).await {
+				if name == LEGACY_IGNORE_FILE {
+					warn!("NoobGit: {LEGACY_IGNORE_FILE} is deprecated, rename it to {IGNORE_FILE}");
+				}
+				rules.patterns.extend(Self::parse(&contents)?.patterns);
+			}
+		}
+		Ok(rules)
+	}
+
+	/// These rules plus `patterns`
+	///
+	/// # Errors
+	///
+	/// Returns an error for the first pattern that isn't a valid glob.
+	pub fn with_patterns<I, S>(mut self, patterns: I) -> Result<Self, PatternError>
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		for pattern in patterns {
+			self.patterns.push(Pattern::new(pattern.as_ref().trim_end_matches('/'))?);
+		}
+		Ok(self)
+	}
+
+	/// Whether `relative` (a path under the root) should be skipped
+	#[must_use]
+	pub fn is_ignored(&self, relative: &Path) -> bool {
+		self.patterns.iter().any(|pattern| {
+			if pattern.as_str().contains('/') {
+				relative.ancestors().any(|path| pattern.matches_path(path))
+			} else {
+				relative.components().filter_map(|c| c.as_os_str().to_str()).any(|name| pattern.matches(name))
+			}
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_and_match() {
+		let rules = IgnoreRules::parse("# build output\ntarget/\n\n*.log\n.*.sw?\ndocs/*.tmp\n").unwrap();
+		assert!(rules.is_ignored(Path::new("target/debug/app")));
+		assert!(rules.is_ignored(Path::new("logs/server.log")));
+		assert!(rules.is_ignored(Path::new("src/.main.rs.swp")));
+		assert!(rules.is_ignored(Path::new("docs/draft.tmp")));
+		assert!(!rules.is_ignored(Path::new("src/draft.tmp")));
+		assert!(!rules.is_ignored(Path::new("src/main.rs")));
+		assert!(IgnoreRules::parse("[unclosed\n").is_err());
+	}
+
+	#[tokio::test]
+	async fn test_load_reads_legacy_ignore_file() {
+		let root = tempfile::TempDir::new().unwrap();
+		fs::write(root.path().join(IGNORE_FILE), "target/\n").await.unwrap();
+		fs::write(root.path().join(LEGACY_IGNORE_FILE), "*.log\n").await.unwrap();
+
+		let rules = IgnoreRules::load(root.path()).await.unwrap();
+		assert!(rules.is_ignored(Path::new("target/debug/app")));
+		assert!(rules.is_ignored(Path::new("server.log")));
+		assert!(!rules.is_ignored(Path::new("src/main.rs")));
+	}
+}
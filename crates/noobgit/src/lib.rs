@@ -1,43 +1,189 @@
-use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::io;
+use std::mem::{discriminant, Discriminant};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
 use tokio::time::Duration;
+use tracing::warn;
 
+pub mod commit;
 mod debouncer;
 pub mod error;
 pub mod file_system;
+pub mod ignore;
 pub mod registry;
 
+pub use commit::{Commit, CommitId};
 use debouncer::Debouncer;
-use file_system::FileSystem;
+pub use debouncer::DEFAULT_DEBOUNCE_TIMEOUT;
+use error::FileSystemError;
+use file_system::{hash_file, ContentHash, FileSystem};
+use ignore::IgnoreRules;
 use registry::{Change, ChangeType, Registry};
 
+/// Most events handled under a single lock acquisition; a full batch is handled without waiting for the debounce
+const MAX_BATCH: usize = 64;
+
+/// Pending events are handled at the latest this many debounce intervals after the first arrived
+const MAX_WAIT_FACTOR: u32 = 4;
+
+/// Directory under the root for `NoobGit`'s own files, which are never tracked
+pub const STATE_DIR: &str = ".noobgit";
+
+/// Registry saved by [`NoobGit::save`], inside [`STATE_DIR`]
+const STATE_FILE: &str = "state.json";
+
+/// Commits recorded by [`NoobGit::commit`], inside [`STATE_DIR`]
+const LOG_FILE: &str = "log.json";
+
 pub struct NoobGit {
 	root: PathBuf,
 	file_system: FileSystem,
 	registry: Registry,
+	ignore: IgnoreRules,
 	debouncer_duration: Duration,
+	/// Every commit in `.noobgit/log.json`, oldest first
+	commits: Vec<Commit>,
+	/// Event batches handled so far, one per lock acquisition by the watcher
+	batches_handled: usize,
 }
 
 impl NoobGit {
+	/// Watch `root`, picking up the changes saved by a previous run if there are any
+	///
+	/// Paths matched by the root's `.noobgitignore`, or a `.noobignore` under
+	/// its former name, are skipped.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `root` is not a directory or can't be scanned, or
+	/// if its `.noobgitignore` has an invalid pattern.
 	pub async fn new<P: AsRef<Path>>(root: P, debouncer_duration: Duration) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+		Self::with_ignore_patterns(root, Vec::new(), debouncer_duration).await
+	}
+
+	/// Like [`new`](Self::new), also skipping paths matched by any of the glob `patterns`
+	///
+	/// # Errors
+	///
+	/// Returns an error if `root` is not a directory or can't be scanned, or
+	/// if a pattern here or in `.noobgitignore` is not a valid glob.
+	pub async fn with_ignore_patterns<P: AsRef<Path>>(root: P, patterns: Vec<String>, debouncer_duration: Duration) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+		let root = root.as_ref().to_path_buf();
+		let ignore = IgnoreRules::load(&root).await?.with_patterns(patterns)?;
+		let registry = match read_state(&root).await {
+			Ok(registry) => registry,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => Registry::new(),
+			Err(e) => {
+				warn!(error = ?e, "NoobGit: Ignoring unreadable saved state");
+				Registry::new()
+			}
+		};
+		Self::with_registry(root, registry, ignore, debouncer_duration).await
+	}
+
+	/// Watch `root` with the changes saved by a previous run
+	///
+	/// # Errors
+	///
+	/// Returns an error if there is no saved state under `root`, if it can't
+	/// be parsed, or if `root` can't be scanned.
+	pub async fn load<P: AsRef<Path>>(root: P, debouncer_duration: Duration) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
 		let root = root.as_ref().to_path_buf();
+		let ignore = IgnoreRules::load(&root).await?;
+		let registry = read_state(&root).await?;
+		Self::with_registry(root, registry, ignore, debouncer_duration).await
+	}
+
+	async fn with_registry(root: PathBuf, mut registry: Registry, ignore: IgnoreRules, debouncer_duration: Duration) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
 		let file_system = FileSystem::new(&root).await?;
-		let registry = Registry::new();
+		let commits = commit::read_log(&root.join(STATE_DIR).join(LOG_FILE)).await?;
+
+		// Baseline the existing tree so later modifications are detected against it
+		let state_dir = root.join(STATE_DIR);
+		for (path, hash) in file_system.scan(&ignore).await? {
+			if !path.starts_with(&state_dir) {
+				registry.record_baseline(path, hash);
+			}
+		}
 
 		Ok(Self {
 			root,
 			file_system,
 			registry,
+			ignore,
 			debouncer_duration,
+			commits,
+			batches_handled: 0,
 		})
 	}
 
-	pub async fn start_watching(noob_git: Arc<Mutex<NoobGit>>, mut stop_receiver: mpsc::Receiver<()>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+	/// Whether events for `path` are skipped: `NoobGit`'s own state, or a match for the ignore rules
+	fn is_ignored(&self, path: &Path) -> bool {
+		let relative = path.strip_prefix(&self.root).unwrap_or(path);
+		relative.starts_with(STATE_DIR) || self.ignore.is_ignored(relative)
+	}
+
+	/// Write the staged and unstaged changes to `.noobgit/state.json` under the root
+	///
+	/// # Errors
+	///
+	/// Returns an error if the state directory or file can't be written.
+	pub async fn save(&self) -> io::Result<()> {
+		let state_dir = self.root.join(STATE_DIR);
+		tokio::fs::create_dir_all(&state_dir).await?;
+		let json = serde_json::to_vec_pretty(&self.registry)?;
+		tokio::fs::write(state_dir.join(STATE_FILE), json).await
+	}
+
+	/// Record the staged changes as a commit in `.noobgit/log.json` and clear the staging area
+	///
+	/// The saved state is rewritten too, so a restart doesn't bring the committed changes back.
+	///
+	/// # Errors
+	///
+	/// Returns `NothingToCommit` if nothing is staged, or an error if the log
+	/// or state can't be written. Nothing is cleared if the log write fails.
+	pub async fn commit(&mut self, message: String) -> error::Result<CommitId> {
+		if self.registry.staged_changes.is_empty() {
+			return Err(FileSystemError::NothingToCommit);
+		}
+
+		let id = self.commits.last().map_or(1, |commit| commit.id + 1);
+		self
+			.commits
+			.push(Commit::new(id, message, self.registry.staged_changes.clone(), self.registry.staged_hashes.clone()));
+		if let Err(e) = self.write_log().await {
+			self.commits.pop();
+			return Err(e.into());
+		}
+
+		self.registry.staged_changes.clear();
+		self.registry.staged_hashes.clear();
+		self.save().await?;
+		Ok(id)
+	}
+
+	/// Every commit made under this root, oldest first
+	#[must_use]
+	pub fn log(&self) -> Vec<Commit> {
+		self.commits.clone()
+	}
+
+	async fn write_log(&self) -> io::Result<()> {
+		let state_dir = self.root.join(STATE_DIR);
+		tokio::fs::create_dir_all(&state_dir).await?;
+		let json = serde_json::to_vec_pretty(&self.commits)?;
+		tokio::fs::write(state_dir.join(LOG_FILE), json).await
+	}
+
+	pub async fn start_watching(noob_git: Arc<Mutex<NoobGit>>, stop_receiver: mpsc::Receiver<()>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 		println!("NoobGit: Watch began!");
 
-		let (tx, mut rx) = mpsc::channel(100);
+		let (tx, rx) = mpsc::channel(100);
 
 		// Clone necessary references
 		let root_clone = noob_git.lock().await.root.clone();
@@ -67,80 +213,143 @@ impl NoobGit {
 			watcher
 		});
 
-		// Debouncer setup
-		let debouncer_duration = noob_git.lock().await.debouncer_duration;
-		let debouncer = Arc::new(Debouncer::new(debouncer_duration));
-		let debouncer_clone = Arc::clone(&debouncer);
-
-		// Debouncer task
-		let debouncer_handle = tokio::spawn(async move {
-			debouncer_clone.debounce().await;
-		});
-
 		// Event handling task
-		let event_handle = tokio::spawn(async move {
-			loop {
-				tokio::select! {
-						Some(event) = rx.recv() => {
-								println!("Raw event: {:?}", event);
-
-								// Lock NoobGit and process the event if it's debounced
-								let mut noob_git = noob_git_clone.lock().await;
-																if debouncer.bump() {
-																		noob_git.handle_event(&event).await;
-																		}
-										}
-						_ = stop_receiver.recv() => {
-								println!("NoobGit: Stop signal received. Exiting watch...");
-								break;
-						}
-				}
-			}
-		});
+		let debouncer_duration = noob_git.lock().await.debouncer_duration;
+		let event_handle = tokio::spawn(Self::process_events(noob_git_clone, rx, debouncer_duration, stop_receiver));
 
 		// Wait for the event handling task to complete
 		event_handle.await?;
 
 		// Clean up tasks
 		watcher_handle.abort();
-		debouncer_handle.abort();
 
 		println!("NoobGit: Watch ended");
 		Ok(())
 	}
 
-	// Handle the event based on the event kind
-	async fn handle_event(&mut self, event: &Event) {
-		for path in &event.paths {
-			match event.kind {
-				notify::EventKind::Create(_) => {
-					if let Err(e) = self.file_system.add(path).await {
-						println!("Error adding file: {:?}", e);
+	// Hold events until the debouncer settles, so a burst is handled once it's over. A
+	// stream that never settles is still handled once a batch fills or the max wait runs out.
+	async fn process_events(noob_git: Arc<Mutex<Self>>, mut rx: mpsc::Receiver<Event>, debouncer_duration: Duration, mut stop_receiver: mpsc::Receiver<()>) {
+		let mut pending = Vec::with_capacity(MAX_BATCH);
+		let (mut debouncer, mut debouncer_handle) = spawn_debouncer(debouncer_duration);
+		let max_wait = debouncer_duration * MAX_WAIT_FACTOR;
+		let deadline = tokio::time::sleep(max_wait);
+		tokio::pin!(deadline);
+		loop {
+			tokio::select! {
+					received @ 1.. = rx.recv_many(&mut pending, MAX_BATCH) => {
+							if pending.len() == received {
+									deadline.as_mut().reset(tokio::time::Instant::now() + max_wait);
+							}
+							if pending.len() >= MAX_BATCH {
+									Self::flush(&noob_git, &mut pending).await;
+									continue;
+							}
+							// Each event pushes the flush back; once a debouncer has settled,
+							// the next event starts a new one
+							if !debouncer.bump() {
+									(debouncer, debouncer_handle) = spawn_debouncer(debouncer_duration);
+									debouncer.bump();
+							}
 					}
-					self.registry.add_change(Change::new(ChangeType::Create, path.clone()));
-					println!("NoobGit: Create event handled for {:?}", path);
-				}
-				notify::EventKind::Modify(_) => {
-					if let Err(e) = self.file_system.add(path).await {
-						println!("Error updating file: {:?}", e);
+					_ = &mut debouncer_handle, if !pending.is_empty() => {
+							Self::flush(&noob_git, &mut pending).await;
+					}
+					() = &mut deadline, if !pending.is_empty() => {
+							Self::flush(&noob_git, &mut pending).await;
 					}
-					self.registry.add_change(Change::new(ChangeType::Modify, path.clone()));
-					println!("NoobGit: Modify event handled for {:?}", path);
+					_ = stop_receiver.recv() => {
+							println!("NoobGit: Stop signal received. Exiting watch...");
+							break;
+					}
+			}
+		}
+		debouncer_handle.abort();
+
+		// Events that arrived before the stop are still handled
+		while let Ok(event) = rx.try_recv() {
+			pending.push(event);
+		}
+		Self::flush(&noob_git, &mut pending).await;
+	}
+
+	// Handle `events` in batches, so a burst takes the lock once per batch, not once per event
+	async fn flush(noob_git: &Mutex<Self>, events: &mut Vec<Event>) {
+		for batch in events.chunks(MAX_BATCH) {
+			noob_git.lock().await.handle_events(batch).await;
+		}
+		events.clear();
+	}
+
+	// Handle a batch of events, skipping repeats of the same kind for a path
+	async fn handle_events(&mut self, events: &[Event]) {
+		let mut last_kind: HashMap<&PathBuf, Discriminant<EventKind>> = HashMap::new();
+		for event in events {
+			let kind = discriminant(&event.kind);
+			for path in &event.paths {
+				if last_kind.insert(path, kind) != Some(kind) {
+					self.handle_path(event.kind, path).await;
+				}
+			}
+		}
+		self.batches_handled += 1;
+	}
+
+	// Handle a single path based on the event kind
+	async fn handle_path(&mut self, kind: EventKind, path: &Path) {
+		if self.is_ignored(path) {
+			return;
+		}
+		match kind {
+			EventKind::Create(_) => {
+				if let Err(e) = self.file_system.add(path).await {
+					println!("Error adding file: {:?}", e);
+				}
+				if let Ok(hash) = hash_file(path).await {
+					self.registry.record_baseline(path.to_path_buf(), hash);
 				}
-				notify::EventKind::Remove(_) => {
-					if let Err(e) = self.file_system.remove(path).await {
-						println!("Error removing file: {:?}", e);
+				self.registry.add_change(Change::new(ChangeType::Create, path.to_path_buf()));
+				println!("NoobGit: Create event handled for {:?}", path);
+			}
+			EventKind::Modify(_) => {
+				if let Err(e) = self.file_system.add(path).await {
+					println!("Error updating file: {:?}", e);
+				}
+				// Writes that leave the content as it was are not modifications
+				if let Ok(hash) = hash_file(path).await {
+					if self.registry.record_baseline(path.to_path_buf(), hash.clone()) == Some(hash) {
+						return;
 					}
-					self.registry.add_change(Change::new(ChangeType::Delete, path.clone()));
-					println!("NoobGit: Delete event handled for {:?}", path);
 				}
-				_ => {
-					println!("NoobGit: Unhandled event kind: {:?}", event.kind);
+				self.registry.add_change(Change::new(ChangeType::Modify, path.to_path_buf()));
+				println!("NoobGit: Modify event handled for {:?}", path);
+			}
+			EventKind::Remove(_) => {
+				if let Err(e) = self.file_system.remove(path).await {
+					println!("Error removing file: {:?}", e);
 				}
+				self.registry.forget_baseline(path);
+				self.registry.add_change(Change::new(ChangeType::Delete, path.to_path_buf()));
+				println!("NoobGit: Delete event handled for {:?}", path);
+			}
+			_ => {
+				println!("NoobGit: Unhandled event kind: {:?}", kind);
 			}
 		}
 	}
 
+	/// Number of event batches handled, each under a single lock acquisition
+	#[must_use]
+	pub const fn batches_handled(&self) -> usize {
+		self.batches_handled
+	}
+
+	/// Last known content hash of every tracked file, keyed by absolute path
+	#[must_use]
+	pub const fn content_hashes(&self) -> &HashMap<PathBuf, ContentHash> {
+		&self.registry.baseline
+	}
+
 	// Other methods for staging/unstaging changes and notifications
 	pub fn stage_changes(&mut self) {
 		self.registry.stage_changes();
@@ -155,6 +364,21 @@ impl NoobGit {
 	}
 }
 
+/// Start a debouncer that settles once `duration` passes without a bump
+fn spawn_debouncer(duration: Duration) -> (Arc<Debouncer>, JoinHandle<()>) {
+	let debouncer = Arc::new(Debouncer::new(duration));
+	let handle = tokio::spawn({
+		let debouncer = Arc::clone(&debouncer);
+		async move { debouncer.debounce().await }
+	});
+	(debouncer, handle)
+}
+
+async fn read_state(root: &Path) -> io::Result<Registry> {
+	let json = tokio::fs::read(root.join(STATE_DIR).join(STATE_FILE)).await?;
+	Ok(serde_json::from_slice(&json)?)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -180,6 +404,380 @@ mod tests {
 		assert_eq!(noobgit.root, temp_dir.path());
 	}
 
+	#[tokio::test]
+	async fn test_new_records_existing_tree_as_baseline() {
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		tokio::fs::create_dir_all(root.join("nested/deeper")).await.unwrap();
+		tokio::fs::create_dir_all(root.join("target")).await.unwrap();
+		tokio::fs::write(root.join("top.txt"), "top").await.unwrap();
+		tokio::fs::write(root.join("nested/deeper/leaf.txt"), "leaf").await.unwrap();
+		tokio::fs::write(root.join("target/build.out"), "ignored").await.unwrap();
+		tokio::fs::write(root.join("debug.log"), "ignored").await.unwrap();
+		tokio::fs::write(root.join(ignore::IGNORE_FILE), "target/\n*.log\n").await.unwrap();
+
+		let mut noob_git = NoobGit::new(&root, *DEBOUNCER_DURATION).await.unwrap();
+		let baseline = &noob_git.registry.baseline;
+		assert!(baseline.contains_key(&root.join("top.txt")));
+		assert!(baseline.contains_key(&root.join("nested/deeper/leaf.txt")));
+		assert!(!baseline.contains_key(&root.join("target/build.out")));
+		assert!(!baseline.contains_key(&root.join("debug.log")));
+		assert!(noob_git.get_notifications().is_empty());
+
+		// Rewriting identical content is not a modification
+		let leaf = root.join("nested/deeper/leaf.txt");
+		let modify = Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Any)).add_path(leaf.clone());
+		tokio::fs::write(&leaf, "leaf").await.unwrap();
+		noob_git.handle_events(std::slice::from_ref(&modify)).await;
+		assert!(noob_git.get_notifications().is_empty());
+
+		// Changed content is detected against the baseline hash
+		let before = noob_git.registry.baseline_hash(&leaf);
+		tokio::fs::write(&leaf, "leaf v2").await.unwrap();
+		noob_git.handle_events(std::slice::from_ref(&modify)).await;
+		assert_ne!(noob_git.registry.baseline_hash(&leaf), before);
+		let notifications = noob_git.get_notifications();
+		assert_eq!(notifications.len(), 1);
+		assert!(notifications[0].contains("Modified") && notifications[0].contains("leaf.txt"));
+	}
+
+	#[tokio::test]
+	async fn test_new_skips_unreadable_files() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		let readable = root.join("readable.txt");
+		let locked = root.join("locked.txt");
+		tokio::fs::write(&readable, "readable").await.unwrap();
+		tokio::fs::write(&locked, "locked").await.unwrap();
+		tokio::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).await.unwrap();
+		// Permissions don't stop root, in which case the file is simply tracked
+		let unreadable = tokio::fs::read(&locked).await.is_err();
+
+		let noob_git = NoobGit::new(&root, *DEBOUNCER_DURATION).await.unwrap();
+		assert!(noob_git.registry.baseline.contains_key(&readable));
+		assert_eq!(noob_git.registry.baseline.contains_key(&locked), !unreadable);
+	}
+
+	#[tokio::test]
+	async fn test_identical_writes_record_one_change() {
+		let temp_dir = setup_test_dir().await;
+		let path = temp_dir.path().join("notes.txt");
+		tokio::fs::write(&path, "draft").await.unwrap();
+		let mut noob_git = NoobGit::new(temp_dir.path(), *DEBOUNCER_DURATION).await.unwrap();
+
+		// Separate batches, as when an editor saves the same buffer twice
+		let modify = Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Any)).add_path(path.clone());
+		for _ in 0..2 {
+			tokio::fs::write(&path, "final").await.unwrap();
+			noob_git.handle_events(std::slice::from_ref(&modify)).await;
+		}
+		assert_eq!(noob_git.get_notifications().len(), 1);
+
+		let hash = noob_git.content_hashes()[&path].clone();
+		noob_git.stage_changes();
+		assert_eq!(noob_git.registry.staged_hashes.get(&path), Some(&hash));
+	}
+
+	#[tokio::test]
+	async fn test_ignored_paths_produce_no_notifications() {
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		tokio::fs::write(root.join(ignore::IGNORE_FILE), "target/\n").await.unwrap();
+		tokio::fs::create_dir_all(root.join("target/debug")).await.unwrap();
+		tokio::fs::create_dir_all(root.join("src")).await.unwrap();
+
+		assert!(NoobGit::with_ignore_patterns(&root, vec!["[unclosed".to_string()], *DEBOUNCER_DURATION).await.is_err());
+		let mut noob_git = NoobGit::with_ignore_patterns(&root, vec!["*.swp".to_string(), "src/*.tmp".to_string()], *DEBOUNCER_DURATION)
+			.await
+			.unwrap();
+
+		// Matched by the given patterns or by .noobgitignore
+		let ignored = [root.join("src/.main.rs.swp"), root.join("src/scratch.tmp"), root.join("target/debug/app")];
+		let tracked = [root.join("src/main.rs"), root.join("notes.tmp")];
+		for path in ignored.iter().chain(&tracked) {
+			tokio::fs::write(path, "content").await.unwrap();
+		}
+
+		let create = |path: &PathBuf| Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(path.clone());
+		noob_git.handle_events(&ignored.iter().map(create).collect::<Vec<_>>()).await;
+		assert!(noob_git.get_notifications().is_empty());
+		assert!(ignored.iter().all(|path| !noob_git.registry.baseline.contains_key(path)));
+
+		noob_git.handle_events(&tracked.iter().map(create).collect::<Vec<_>>()).await;
+		let notifications = noob_git.get_notifications();
+		assert_eq!(notifications.len(), tracked.len());
+		for path in &tracked {
+			assert!(notifications.contains(&format!("Created {}", path.display())));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_saved_changes_survive_a_restart() {
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		assert!(NoobGit::load(&root, *DEBOUNCER_DURATION).await.is_err(), "nothing has been saved yet");
+
+		let mut noob_git = NoobGit::new(&root, *DEBOUNCER_DURATION).await.unwrap();
+		let staged = root.join("staged.txt");
+		let unstaged = root.join("unstaged.txt");
+		tokio::fs::write(&staged, "staged").await.unwrap();
+		noob_git
+			.handle_events(&[Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(staged.clone())])
+			.await;
+		noob_git.stage_changes();
+		tokio::fs::write(&unstaged, "unstaged").await.unwrap();
+		noob_git
+			.handle_events(&[Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(unstaged.clone())])
+			.await;
+		noob_git.save().await.unwrap();
+
+		// Events for the state file itself are not changes
+		let state_file = root.join(STATE_DIR).join(STATE_FILE);
+		noob_git
+			.handle_events(&[Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(state_file)])
+			.await;
+		assert_eq!(noob_git.get_notifications(), vec![format!("Created {}", unstaged.display())]);
+
+		for restarted in [
+			NoobGit::new(&root, *DEBOUNCER_DURATION).await.unwrap(),
+			NoobGit::load(&root, *DEBOUNCER_DURATION).await.unwrap(),
+		] {
+			assert_eq!(restarted.get_notifications(), noob_git.get_notifications());
+			assert_eq!(restarted.registry.staged_changes.len(), 1);
+			assert_eq!(restarted.registry.staged_changes[0].path, staged);
+			assert!(restarted.registry.baseline.contains_key(&unstaged));
+			assert!(!restarted.registry.baseline.keys().any(|path| path.starts_with(root.join(STATE_DIR))));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_commit_records_staged_changes_in_the_log() {
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		let mut noob_git = NoobGit::new(&root, *DEBOUNCER_DURATION).await.unwrap();
+		assert!(matches!(noob_git.commit("empty".to_string()).await, Err(FileSystemError::NothingToCommit)));
+
+		let paths = [root.join("first.txt"), root.join("second.txt")];
+		for path in &paths {
+			tokio::fs::write(path, "content").await.unwrap();
+			noob_git
+				.handle_events(&[Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(path.clone())])
+				.await;
+		}
+		noob_git.stage_changes();
+
+		let id = noob_git.commit("Add two files".to_string()).await.unwrap();
+		assert!(noob_git.registry.staged_changes.is_empty());
+		assert!(noob_git.registry.staged_hashes.is_empty());
+
+		let log = noob_git.log();
+		assert_eq!(log.len(), 1);
+		assert_eq!((log[0].id, log[0].message.as_str()), (id, "Add two files"));
+		let committed: Vec<_> = log[0].changes.iter().map(|change| &change.path).collect();
+		assert_eq!(committed, paths.iter().collect::<Vec<_>>());
+		// The digest of "content", which must read back the same under any toolchain
+		let content_hash = "ed7002b439e9ac845f22357d822bac1444730fbdb6016d3ec9432297b9ec9f73";
+		assert!(paths.iter().all(|path| log[0].hashes.get(path).map(String::as_str) == Some(content_hash)));
+		let saved = tokio::fs::read_to_string(root.join(STATE_DIR).join(LOG_FILE)).await.unwrap();
+		assert!(saved.contains(content_hash));
+
+		// The log is read back after a restart, and the committed changes stay committed
+		let restarted = NoobGit::new(&root, *DEBOUNCER_DURATION).await.unwrap();
+		assert_eq!(restarted.log().len(), 1);
+		assert_eq!(restarted.log()[0].changes.len(), 2);
+		assert!(restarted.registry.staged_changes.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_event_burst_is_handled_in_batches() {
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		let existing: Vec<_> = (0..30).map(|i| root.join(format!("existing{i}.txt"))).collect();
+		for path in &existing {
+			tokio::fs::write(path, "before").await.unwrap();
+		}
+
+		let noob_git = Arc::new(Mutex::new(NoobGit::new(&root, *DEBOUNCER_DURATION).await.unwrap()));
+
+		// Like a checkout: new files are created, tracked files rewritten, each reported several times
+		let created: Vec<_> = (0..30).map(|i| root.join(format!("created{i}.txt"))).collect();
+		for path in &created {
+			tokio::fs::write(path, "new").await.unwrap();
+		}
+		for path in &existing {
+			tokio::fs::write(path, "after").await.unwrap();
+		}
+
+		let modify = || Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Any));
+		let mut events: Vec<Event> = created
+			.iter()
+			.map(|path| Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(path.clone()))
+			.collect();
+		for _ in 0..2 {
+			events.extend(created.iter().chain(&existing).map(|path| modify().add_path(path.clone())));
+		}
+		events.extend(existing.iter().map(|path| modify().add_path(path.clone())));
+
+		let (tx, rx) = mpsc::channel(events.len());
+		for event in &events {
+			tx.send(event.clone()).await.unwrap();
+		}
+		let (stop_tx, stop_rx) = mpsc::channel(1);
+		let process_handle = tokio::spawn(NoobGit::process_events(Arc::clone(&noob_git), rx, Duration::from_millis(20), stop_rx));
+
+		let expected_changes = created.len() + existing.len();
+		tokio::time::timeout(Duration::from_secs(5), async {
+			while noob_git.lock().await.get_notifications().len() < expected_changes {
+				tokio::time::sleep(Duration::from_millis(5)).await;
+			}
+		})
+		.await
+		.expect("burst was not processed");
+		stop_tx.send(()).await.unwrap();
+		process_handle.await.unwrap();
+
+		let noob_git = noob_git.lock().await;
+		assert_eq!(noob_git.batches_handled(), events.len().div_ceil(MAX_BATCH));
+
+		// Every file is recorded once, whatever the number of events it got
+		let notifications = noob_git.get_notifications();
+		assert_eq!(notifications.len(), expected_changes);
+		for path in &created {
+			assert!(notifications.contains(&format!("Created {}", path.display())));
+		}
+		for path in &existing {
+			assert!(notifications.contains(&format!("Modified {}", path.display())));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_short_debounce_handles_every_write() {
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		let debouncer_duration = Duration::from_millis(5);
+
+		let noob_git = Arc::new(Mutex::new(NoobGit::new(&root, debouncer_duration).await.unwrap()));
+		let (stop_tx, stop_rx) = mpsc::channel(1);
+		let watch_handle = tokio::spawn(NoobGit::start_watching(Arc::clone(&noob_git), stop_rx));
+
+		// Long enough for the watcher to start, and for the first debouncer to settle
+		tokio::time::sleep(Duration::from_millis(100)).await;
+
+		// Bursts separated by quiet spells longer than the debounce
+		let files: Vec<_> = (0..6).map(|i| root.join(format!("file{i}.txt"))).collect();
+		for burst in files.chunks(2) {
+			for path in burst {
+				tokio::fs::write(path, "content").await.unwrap();
+			}
+			tokio::time::sleep(debouncer_duration * 10).await;
+		}
+
+		let all_created = |notifications: &[String]| files.iter().all(|path| notifications.contains(&format!("Created {}", path.display())));
+		let handled = tokio::time::timeout(Duration::from_secs(5), async {
+			while !all_created(&noob_git.lock().await.get_notifications()) {
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+		})
+		.await;
+
+		stop_tx.send(()).await.unwrap();
+		watch_handle.await.unwrap().unwrap();
+		assert!(handled.is_ok(), "not every write was handled: {:?}", noob_git.lock().await.get_notifications());
+		// Each burst settles before the next one starts
+		assert!(noob_git.lock().await.batches_handled() >= files.chunks(2).len());
+	}
+
+	#[tokio::test]
+	async fn test_steady_stream_is_flushed_by_the_max_wait() {
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		let debouncer_duration = Duration::from_millis(50);
+		let noob_git = Arc::new(Mutex::new(NoobGit::new(&root, debouncer_duration).await.unwrap()));
+
+		let (tx, rx) = mpsc::channel(8);
+		let (stop_tx, stop_rx) = mpsc::channel(1);
+		let process_handle = tokio::spawn(NoobGit::process_events(Arc::clone(&noob_git), rx, debouncer_duration, stop_rx));
+
+		// Events spaced well inside the debounce, for far longer than the max wait
+		let mut handled_mid_stream = false;
+		for i in 0..60 {
+			let path = root.join(["file", &i.to_string(), ".txt"].concat());
+			tx.send(Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(path))
+				.await
+				.unwrap();
+			tokio::time::sleep(Duration::from_millis(10)).await;
+			handled_mid_stream |= !noob_git.lock().await.get_notifications().is_empty();
+		}
+		stop_tx.send(()).await.unwrap();
+		process_handle.await.unwrap();
+
+		assert!(handled_mid_stream, "nothing was handled until the stream stopped");
+		assert_eq!(noob_git.lock().await.get_notifications().len(), 60);
+	}
+
+	#[tokio::test]
+	async fn test_full_batch_is_handled_without_waiting() {
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		let noob_git = Arc::new(Mutex::new(NoobGit::new(&root, *DEBOUNCER_DURATION).await.unwrap()));
+
+		let (tx, rx) = mpsc::channel(MAX_BATCH);
+		let (_stop_tx, stop_rx) = mpsc::channel(1);
+		tokio::spawn(NoobGit::process_events(Arc::clone(&noob_git), rx, *DEBOUNCER_DURATION, stop_rx));
+		for i in 0..MAX_BATCH {
+			let path = root.join(["file", &i.to_string(), ".txt"].concat());
+			tx.send(Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(path))
+				.await
+				.unwrap();
+		}
+
+		// Well before the six second debounce could settle; timing out means the batch waited for it
+		tokio::time::timeout(Duration::from_secs(1), async {
+			while noob_git.lock().await.get_notifications().len() < MAX_BATCH {
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+		})
+		.await
+		.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_events_within_debounce_are_handled_together() {
+		let temp_dir = setup_test_dir().await;
+		let root = temp_dir.path().to_path_buf();
+		let debouncer_duration = Duration::from_millis(200);
+		let noob_git = Arc::new(Mutex::new(NoobGit::new(&root, debouncer_duration).await.unwrap()));
+
+		let (tx, rx) = mpsc::channel(8);
+		let (stop_tx, stop_rx) = mpsc::channel(1);
+		let process_handle = tokio::spawn(NoobGit::process_events(Arc::clone(&noob_git), rx, debouncer_duration, stop_rx));
+
+		// Writes spaced well inside the debounce window
+		let files: Vec<_> = (0..3).map(|i| root.join(format!("file{i}.txt"))).collect();
+		for path in &files {
+			tokio::fs::write(path, "content").await.unwrap();
+			tx.send(Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(path.clone()))
+				.await
+				.unwrap();
+			tokio::time::sleep(Duration::from_millis(20)).await;
+		}
+		assert!(noob_git.lock().await.get_notifications().is_empty(), "events were handled before the debounce settled");
+
+		tokio::time::timeout(Duration::from_secs(5), async {
+			while noob_git.lock().await.get_notifications().len() < files.len() {
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+		})
+		.await
+		.expect("writes were not handled once the debounce settled");
+		stop_tx.send(()).await.unwrap();
+		process_handle.await.unwrap();
+
+		assert_eq!(noob_git.lock().await.batches_handled(), 1);
+	}
+
 	#[tokio::test]
 	async fn test_start_watching_terminates_on_stop_signal() {
 		let temp_dir = setup_test_dir().await;
@@ -326,7 +924,9 @@ mod tests {
 		let temp_dir = setup_test_dir().await;
 		let root = temp_dir.path().to_path_buf();
 
-		let noob_git = Arc::new(Mutex::new(NoobGit::new(&root, *DEBOUNCER_DURATION).await.unwrap()));
+		// Short enough that each operation below settles before the next one
+		let debouncer_duration = Duration::from_millis(20);
+		let noob_git = Arc::new(Mutex::new(NoobGit::new(&root, debouncer_duration).await.unwrap()));
 		let (stop_tx, stop_rx) = mpsc::channel(1);
 
 		// Start watching in a separate task
@@ -343,15 +943,15 @@ mod tests {
 		let file2_path = root.join("file2.txt");
 
 		tokio::fs::write(&file1_path, "File 1 content").await.unwrap();
-		tokio::time::sleep(Duration::from_millis(5)).await;
+		tokio::time::sleep(debouncer_duration * 5).await;
 		tokio::fs::write(&file2_path, "File 2 content").await.unwrap();
-		tokio::time::sleep(Duration::from_millis(5)).await;
+		tokio::time::sleep(debouncer_duration * 5).await;
 		tokio::fs::write(&file1_path, "File 1 modified").await.unwrap();
-		tokio::time::sleep(Duration::from_millis(5)).await;
+		tokio::time::sleep(debouncer_duration * 5).await;
 		tokio::fs::remove_file(&file2_path).await.unwrap();
 
 		// Wait for events to be processed
-		tokio::time::sleep(Duration::from_millis(10)).await;
+		tokio::time::sleep(debouncer_duration * 5).await;
 
 		// Stop watching
 		stop_tx.send(()).await.unwrap();
@@ -1,14 +1,16 @@
-use std::collections::VecDeque;
-use std::path::PathBuf;
+use crate::file_system::ContentHash;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ChangeType {
 	Create,
 	Modify,
 	Delete,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Change {
 	pub change_type: ChangeType,
 	pub path: PathBuf,
@@ -20,11 +22,19 @@ impl Change {
 	}
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Registry {
 	pub unstaged_changes: VecDeque<Change>,
 	pub staged_changes: Vec<Change>,
 	pub max_changes: usize,
+	/// Last known content hash of each tracked file
+	///
+	/// Not persisted: it describes the tree, which is rescanned on startup.
+	#[serde(skip)]
+	pub baseline: HashMap<PathBuf, ContentHash>,
+	/// Content hash of each staged path as of the last `stage_changes`; deleted paths have none
+	#[serde(default)]
+	pub staged_hashes: HashMap<PathBuf, ContentHash>,
 }
 
 impl Registry {
@@ -33,6 +43,8 @@ impl Registry {
 			unstaged_changes: VecDeque::new(),
 			staged_changes: Vec::new(),
 			max_changes: 100,
+			baseline: HashMap::new(),
+			staged_hashes: HashMap::new(),
 		}
 	}
 
@@ -43,14 +55,35 @@ impl Registry {
 		self.unstaged_changes.push_back(change);
 	}
 
+	/// Record `hash` as the known content of `path`, returning the previous hash
+	pub fn record_baseline(&mut self, path: PathBuf, hash: ContentHash) -> Option<ContentHash> {
+		self.baseline.insert(path, hash)
+	}
+
+	#[must_use]
+	pub fn baseline_hash(&self, path: &Path) -> Option<ContentHash> {
+		self.baseline.get(path).cloned()
+	}
+
+	pub fn forget_baseline(&mut self, path: &Path) {
+		self.baseline.remove(path);
+	}
+
 	pub fn stage_changes(&mut self) {
 		println!("stage_changes called!");
-		self.staged_changes.extend(self.unstaged_changes.drain(..));
+		for change in self.unstaged_changes.drain(..) {
+			match self.baseline.get(&change.path) {
+				Some(hash) => self.staged_hashes.insert(change.path.clone(), hash.clone()),
+				None => self.staged_hashes.remove(&change.path),
+			};
+			self.staged_changes.push(change);
+		}
 	}
 
 	pub fn unstage_changes(&mut self) {
 		println!("unstage_changes called!");
 		self.unstaged_changes.extend(self.staged_changes.drain(..));
+		self.staged_hashes.clear();
 	}
 
 	pub fn get_notifications(&self) -> Vec<String> {
@@ -110,6 +143,23 @@ mod tests {
 		assert_eq!(registry.staged_changes.len(), 2);
 	}
 
+	#[test]
+	fn test_stage_changes_snapshots_hashes() {
+		let mut registry = Registry::new();
+		registry.record_baseline(PathBuf::from("/test/file1.txt"), "staged".to_string());
+		registry.add_change(Change::new(ChangeType::Modify, PathBuf::from("/test/file1.txt")));
+		registry.add_change(Change::new(ChangeType::Delete, PathBuf::from("/test/file2.txt")));
+		registry.stage_changes();
+
+		// Later edits don't change what was staged
+		registry.record_baseline(PathBuf::from("/test/file1.txt"), "edited".to_string());
+		assert_eq!(registry.staged_hashes.len(), 1);
+		assert_eq!(registry.staged_hashes.get(Path::new("/test/file1.txt")).map(String::as_str), Some("staged"));
+
+		registry.unstage_changes();
+		assert!(registry.staged_hashes.is_empty());
+	}
+
 	#[test]
 	fn test_unstage_changes() {
 		let mut registry = Registry::new();
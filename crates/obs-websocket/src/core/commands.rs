@@ -24,20 +24,29 @@ impl CommandExecutor {
 		self.state_handle.execute_command(command).await
 	}
 
-	pub fn build_request(&self, command: &ObsCommand) -> Result<JsonValue, PollingError> {
-		match command {
+	/// Build the OBS requests for a command, in the order they must be sent
+	pub fn build_requests(&self, command: &ObsCommand) -> Result<Vec<JsonValue>, PollingError> {
+		let request = match command {
 			ObsCommand::StartStream => ObsRequestBuilder::start_stream(),
 			ObsCommand::StopStream => ObsRequestBuilder::stop_stream(),
 			ObsCommand::StartRecording => ObsRequestBuilder::start_recording(),
 			ObsCommand::StopRecording => ObsRequestBuilder::stop_recording(),
 			ObsCommand::SwitchScene(name) => ObsRequestBuilder::switch_scene(name),
+			ObsCommand::TransitionToScene {
+				scene_name,
+				transition_name,
+				duration_ms,
+			} => return ObsRequestBuilder::transition_to_scene(scene_name, transition_name, *duration_ms),
 			ObsCommand::SetInputMute(name, muted) => ObsRequestBuilder::set_input_mute(name, *muted),
 			ObsCommand::SetInputVolume(name, volume) => ObsRequestBuilder::set_input_volume(name, *volume),
 			ObsCommand::ToggleStudioMode(enabled) => ObsRequestBuilder::toggle_studio_mode(*enabled),
 			ObsCommand::StartVirtualCamera => ObsRequestBuilder::start_virtual_camera(),
 			ObsCommand::StopVirtualCamera => ObsRequestBuilder::stop_virtual_camera(),
+			ObsCommand::GetVirtualCameraStatus => ObsRequestBuilder::get_virtual_camera_status(),
 			ObsCommand::StartReplayBuffer => ObsRequestBuilder::start_replay_buffer(),
 			ObsCommand::StopReplayBuffer => ObsRequestBuilder::stop_replay_buffer(),
+			ObsCommand::SaveReplayBuffer => ObsRequestBuilder::save_replay_buffer(),
+			ObsCommand::GetReplayBufferStatus => ObsRequestBuilder::get_replay_buffer_status(),
 			ObsCommand::GetInputMute(name) => ObsRequestBuilder::get_input_mute(name),
 			ObsCommand::GetInputVolume(name) => ObsRequestBuilder::get_input_volume(name),
 			ObsCommand::SetYouTubeStream {
@@ -50,6 +59,7 @@ impl CommandExecutor {
 				tags,
 			} => ObsRequestBuilder::set_youtube_stream(stream_key, title, description, category, *privacy, *unlisted, tags.clone()),
 			ObsCommand::Custom(json) => Ok(json.clone()),
-		}
+		}?;
+		Ok(vec![request])
 	}
 }
@@ -1,5 +1,5 @@
 use super::*;
-use crate::{authenticate, MessageHandler, MessageProcessor, ObsCommand, ObsConfig, ObsEvent, ObsPollingManager, PollingConfig};
+use crate::{authenticate, EventRecorder, MessageHandler, MessageProcessor, ObsCommand, ObsConfig, ObsEvent, ObsPollingManager, PollingConfig, RecordingConfig};
 use futures_util::{
 	future,
 	sink::SinkExt,
@@ -50,6 +50,8 @@ pub enum ConnectionError {
 pub struct ConnectionManager {
 	state_handle: StateHandle,
 	msg_handler: MessageHandler,
+	/// Where each connection records its incoming events; off when `None`
+	recording: Option<RecordingConfig>,
 }
 
 impl ConnectionManager {
@@ -57,6 +59,7 @@ impl ConnectionManager {
 		Self {
 			state_handle,
 			msg_handler: MessageHandler::new(),
+			recording: None,
 		}
 	}
 
@@ -89,6 +92,15 @@ impl ConnectionManager {
 			return Err(ConnectionError::Authentication(error_msg));
 		}
 
+		// Recording is best-effort; a bad directory shouldn't keep us from connecting
+		let recorder = self.recording.clone().and_then(|recording| match EventRecorder::start(recording) {
+			Ok(recorder) => Some(recorder),
+			Err(e) => {
+				tracing::warn!("Failed to start event recording, continuing without it: {}", e);
+				None
+			}
+		});
+
 		// Set up channels
 		let (cmd_tx, cmd_rx) = mpsc::channel(10);
 		let (mut event_tx, event_rx) = async_broadcast::broadcast(3);
@@ -102,7 +114,7 @@ impl ConnectionManager {
 		self.state_handle.set_event_receiver(event_rx).await?;
 
 		// Start connection tasks
-		let connection_handle = self.start_connection_tasks(sink, stream, cmd_rx, event_tx, polling_config).await;
+		let connection_handle = self.start_connection_tasks(sink, stream, cmd_rx, event_tx, recorder, polling_config).await;
 		self.state_handle.set_connection_handle(connection_handle).await?;
 
 		// Transition to connected state
@@ -197,6 +209,7 @@ impl ConnectionManager {
 		stream: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
 		cmd_rx: mpsc::Receiver<InternalCommand>,
 		event_tx: async_broadcast::Sender<ObsEvent>,
+		recorder: Option<EventRecorder>,
 		config: PollingConfig,
 	) -> JoinHandle<()> {
 		let message_processor = self.msg_handler.processor();
@@ -209,7 +222,7 @@ impl ConnectionManager {
 		});
 
 		let message_task = tokio::spawn(async move {
-			message_processing_loop(stream, sink, event_tx, state_handle, message_processor).await;
+			message_processing_loop(stream, sink, event_tx, recorder, state_handle, message_processor).await;
 		});
 
 		tokio::spawn(async move {
@@ -229,6 +242,7 @@ async fn message_processing_loop(
 	mut stream: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
 	sink: Arc<Mutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, TungsteniteMessage>>>,
 	event_tx: async_broadcast::Sender<ObsEvent>,
+	recorder: Option<EventRecorder>,
 	state_handle: StateHandle,
 	message_processor: MessageProcessor,
 ) {
@@ -244,6 +258,9 @@ async fn message_processing_loop(
 						last_activity = Instant::now();
 						// TODO: need to update the message mod to work with expected type directly
 						 if let Ok(event) = message_processor.process_message(text.to_string()).await {
+							if let Some(recorder) = &recorder {
+								recorder.record(&event);
+							}
 							let _ = event_tx.broadcast(event).await;
 						}
 					}
@@ -285,6 +302,12 @@ async fn message_processing_loop(
 		}
 	}
 
+	if let Some(recorder) = recorder {
+		if let Err(e) = recorder.finish().await {
+			tracing::error!("Event recording ended with an error: {}", e);
+		}
+	}
+
 	// Connection ended, transition state to disconnected
 	tracing::info!("Message processing loop ended, transitioning to disconnected");
 	let _ = state_handle.transition_to_disconnected().await;
@@ -328,6 +351,13 @@ impl ObsConnection {
 		}
 	}
 
+	/// Record every incoming event to disk, from the next connect on
+	#[must_use]
+	pub fn with_recording(mut self, recording: RecordingConfig) -> Self {
+		self.connection_manager.recording = Some(recording);
+		self
+	}
+
 	/// Connect to OBS with polling requests
 	pub async fn connect(&self, config: PollingConfig) -> Result<(), ConnectionError> {
 		self.connection_manager.establish_connection(config).await
@@ -365,4 +395,10 @@ impl ObsConnection {
 	pub async fn is_healthy(&self) -> Result<bool, ConnectionError> {
 		self.connection_manager.is_healthy().await
 	}
+
+	/// Set the password used to authenticate the next connection
+	pub async fn update_password(&self, password: String) -> Result<(), ConnectionError> {
+		self.connection_manager.state_handle.update_password(password).await?;
+		Ok(())
+	}
 }
@@ -66,6 +66,7 @@ pub enum StateMessage {
 	TakeEventReceiver(oneshot::Sender<Option<async_broadcast::Receiver<ObsEvent>>>),
 	TakeConnectionHandle(oneshot::Sender<Option<tokio::task::JoinHandle<()>>>),
 	UpdateConfig(ObsConfig),
+	UpdatePassword(String),
 
 	// Command execution (for convenience)
 	ExecuteCommand(ObsCommand, oneshot::Sender<Result<(), StateError>>),
@@ -219,6 +220,9 @@ impl StateActor {
 				StateMessage::UpdateConfig(config) => {
 					self.state.config = config;
 				}
+				StateMessage::UpdatePassword(password) => {
+					self.state.config.password = password;
+				}
 				StateMessage::ExecuteCommand(command, reply) => {
 					let result = self.execute_command_internal(command).await;
 					let _ = reply.send(result);
@@ -369,4 +373,9 @@ impl StateHandle {
 	pub async fn update_config(&self, config: ObsConfig) -> Result<(), StateError> {
 		self.sender.send(StateMessage::UpdateConfig(config)).await.map_err(|_| StateError::ActorUnavailable)
 	}
+
+	/// Replace only the password, leaving the rest of the config as it is
+	pub async fn update_password(&self, password: String) -> Result<(), StateError> {
+		self.sender.send(StateMessage::UpdatePassword(password)).await.map_err(|_| StateError::ActorUnavailable)
+	}
 }
@@ -21,6 +21,8 @@ pub mod core;
 pub mod messages;
 #[cfg(feature = "websocket")]
 mod polling;
+#[cfg(feature = "websocket")]
+mod recording;
 
 // Feature-gated exports
 #[cfg(feature = "websocket")]
@@ -35,6 +37,8 @@ pub use messages::{MessageHandler, MessageProcessor};
 use polling::{ObsPollingManager, ObsRequestBuilder};
 #[cfg(feature = "websocket")]
 pub use polling::{PollingConfig, PollingFrequency};
+#[cfg(feature = "websocket")]
+pub use recording::{EventRecorder, RecordingConfig};
 
 /// Errors for obs-websocket crate
 #[cfg(feature = "websocket")]
@@ -71,6 +75,16 @@ impl ObsWebSocketManager {
 		}
 	}
 
+	/// Record every incoming event to rotating JSONL files, off by default
+	///
+	/// Recording is best-effort: if the directory can't be created, the
+	/// connection goes ahead without it and a warning is logged.
+	#[must_use]
+	pub fn with_recording(mut self, recording: RecordingConfig) -> Self {
+		self.obs_connection = self.obs_connection.with_recording(recording);
+		self
+	}
+
 	pub async fn connect(&self, config: PollingConfig) -> Result<(), ObsWebsocketError> {
 		self.obs_connection.connect(config).await?;
 		Ok(())
@@ -96,6 +110,17 @@ impl ObsWebSocketManager {
 		Ok(result)
 	}
 
+	/// Rotate the OBS password without tearing down the live connection
+	///
+	/// The new password is used from the next [`connect`](Self::connect) on.
+	/// The live connection is left alone: OBS keeps an identified session open
+	/// when its password changes, and its `Reidentify` request can't carry new
+	/// authentication, so there is nothing to re-run until a reconnect.
+	pub async fn update_credentials(&self, password: String) -> Result<(), ObsWebsocketError> {
+		self.obs_connection.update_password(password).await?;
+		Ok(())
+	}
+
 	pub async fn execute_command(&self, command: ObsCommand) -> Result<(), ObsWebsocketError> {
 		let _ = self.obs_connection.execute_command(command).await;
 		Ok(())
@@ -10,7 +10,7 @@ use tracing::{error, info, instrument, warn};
 #[derive(Debug)]
 enum OutboundMessage {
 	Poll(Vec<serde_json::Value>),
-	Command(serde_json::Value),
+	Command(Vec<serde_json::Value>),
 	Disconnect,
 }
 
@@ -69,8 +69,8 @@ impl ObsPollingManager {
 				OutboundMessage::Poll(batch) => {
 					Self::handle_poll_batch(&mut s_g, batch).await;
 				}
-				OutboundMessage::Command(cmd) => {
-					Self::handle_command(&mut s_g, cmd).await;
+				OutboundMessage::Command(requests) => {
+					Self::handle_command(&mut s_g, requests).await;
 				}
 				OutboundMessage::Disconnect => {
 					warn!("Disconnect requested, outbound worker exiting");
@@ -106,20 +106,28 @@ impl ObsPollingManager {
 		}
 	}
 
-	/// Handle sending a single command
+	/// Handle sending a command's requests in order
+	///
+	/// Sent under one sink lock so multi-step commands are never interleaved with polls.
 	async fn handle_command(
 		sink: &mut futures_util::stream::SplitSink<tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>, TungsteniteMessage>,
-		cmd: serde_json::Value,
+		requests: Vec<serde_json::Value>,
 	) {
-		if let Ok(request_text) = serde_json::to_string(&cmd) {
+		for cmd in requests {
+			let Ok(request_text) = serde_json::to_string(&cmd) else {
+				continue;
+			};
 			if let Err(e) = sink.send(TungsteniteMessage::Text(request_text.into())).await {
 				error!("Command send failed: {e}");
-			} else if let Err(e) = sink.flush().await {
-				error!("Command flush failed: {e}");
-			} else {
-				info!("Successfully sent command request");
+				return;
 			}
 		}
+
+		if let Err(e) = sink.flush().await {
+			error!("Command flush failed: {e}");
+		} else {
+			info!("Successfully sent command request");
+		}
 	}
 
 	/// Initialize timers for polling intervals
@@ -184,11 +192,11 @@ impl ObsPollingManager {
 
 		match internal_cmd {
 			InternalCommand::Execute(obs_cmd) => {
-				// Build the request using the command executor's builder
-				let request = self.command_executor.build_request(&obs_cmd)?;
+				// Build the requests using the command executor's builder
+				let requests = self.command_executor.build_requests(&obs_cmd)?;
 
 				// Commands are higher priority - await send to ensure delivery
-				if let Err(e) = self.outbound_tx.send(OutboundMessage::Command(request)).await {
+				if let Err(e) = self.outbound_tx.send(OutboundMessage::Command(requests)).await {
 					error!("Failed to queue command #{}: {e}", counters.cmd_counter);
 					return Err(PollingError::CriticalLoopTermination {
 						reason: format!("Command queue failure on command #{}: {e}", counters.cmd_counter),
@@ -279,3 +287,66 @@ impl ObsPollingManager {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::StateActor;
+	use crate::{ObsCommand, ObsConfig};
+	use futures_util::StreamExt;
+	use serde_json::Value;
+	use std::sync::Arc;
+	use tokio::net::TcpListener;
+	use tokio::sync::Mutex;
+	use tokio::time::timeout;
+	use tokio_tungstenite::{accept_async, connect_async};
+
+	#[tokio::test]
+	async fn test_transition_override_is_set_before_scene_change() {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = ["ws://", &listener.local_addr().unwrap().to_string()].concat();
+
+		// Mock OBS: record the first two requests it receives
+		let mock_obs = tokio::spawn(async move {
+			let (stream, _) = listener.accept().await.unwrap();
+			let mut ws = accept_async(stream).await.unwrap();
+			let mut received = Vec::new();
+			while received.len() < 2 {
+				match ws.next().await {
+					Some(Ok(TungsteniteMessage::Text(text))) => received.push(serde_json::from_str::<Value>(&text).unwrap()),
+					Some(Ok(_)) => {}
+					other => panic!("mock OBS connection ended early: {other:?}"),
+				}
+			}
+			received
+		});
+
+		let (ws, _) = connect_async(url).await.unwrap();
+		let (sink, _stream) = ws.split();
+		let (_state_actor, state_handle) = StateActor::new(ObsConfig::default());
+		let manager = ObsPollingManager::new(PollingConfig::from(Vec::new()), CommandExecutor::new(state_handle), Arc::new(Mutex::new(sink)));
+
+		let (cmd_tx, cmd_rx) = mpsc::channel(1);
+		let polling = tokio::spawn(manager.start_polling_loop(cmd_rx));
+		cmd_tx
+			.send(InternalCommand::Execute(ObsCommand::TransitionToScene {
+				scene_name: "Gameplay".to_string(),
+				transition_name: "Fade".to_string(),
+				duration_ms: 750,
+			}))
+			.await
+			.unwrap();
+
+		let received = timeout(Duration::from_secs(5), mock_obs).await.unwrap().unwrap();
+		let request_types: Vec<_> = received.iter().map(|r| r["d"]["requestType"].as_str().unwrap()).collect();
+		// The override is scoped to the target scene, so the global transition is never touched
+		assert_eq!(request_types, ["SetSceneSceneTransitionOverride", "SetCurrentProgramScene"]);
+		assert_eq!(received[0]["d"]["requestData"]["sceneName"], "Gameplay");
+		assert_eq!(received[0]["d"]["requestData"]["transitionName"], "Fade");
+		assert_eq!(received[0]["d"]["requestData"]["transitionDuration"], 750);
+		assert_eq!(received[1]["d"]["requestData"]["sceneName"], "Gameplay");
+
+		cmd_tx.send(InternalCommand::Disconnect).await.unwrap();
+		timeout(Duration::from_secs(1), polling).await.unwrap().unwrap().unwrap();
+	}
+}
@@ -63,6 +63,26 @@ impl ObsRequestBuilder {
 		)
 	}
 
+	/// Switch to a scene through a transition
+	///
+	/// The transition is set as the target scene's override rather than as the
+	/// current transition, so other scene switches keep the user's transition.
+	/// The override stays on the scene until the next `transition_to_scene`
+	/// for it replaces it. The requests must be sent in the returned order.
+	pub fn transition_to_scene(scene_name: &str, transition_name: &str, duration_ms: u32) -> Result<Vec<serde_json::Value>> {
+		Ok(vec![
+			Self::create_request(
+				ObsRequestType::SetSceneSceneTransitionOverride,
+				Some(SetSceneSceneTransitionOverrideParams {
+					scene_name: scene_name.to_string(),
+					transition_name: transition_name.to_string(),
+					transition_duration: duration_ms,
+				}),
+			)?,
+			Self::switch_scene(scene_name)?,
+		])
+	}
+
 	/// Mute/unmute audio source
 	pub fn set_input_mute(input_name: &str, muted: bool) -> Result<serde_json::Value> {
 		Self::create_request(
@@ -100,6 +120,11 @@ impl ObsRequestBuilder {
 		Self::create_request(ObsRequestType::StopVirtualCam, None::<()>)
 	}
 
+	/// Get virtual camera status
+	pub fn get_virtual_camera_status() -> Result<serde_json::Value> {
+		Self::create_request(ObsRequestType::GetVirtualCamStatus, None::<()>)
+	}
+
 	/// Start replay buffer
 	pub fn start_replay_buffer() -> Result<serde_json::Value> {
 		Self::create_request(ObsRequestType::StartReplayBuffer, None::<()>)
@@ -110,6 +135,16 @@ impl ObsRequestBuilder {
 		Self::create_request(ObsRequestType::StopReplayBuffer, None::<()>)
 	}
 
+	/// Save the replay buffer to disk
+	pub fn save_replay_buffer() -> Result<serde_json::Value> {
+		Self::create_request(ObsRequestType::SaveReplayBuffer, None::<()>)
+	}
+
+	/// Get replay buffer status
+	pub fn get_replay_buffer_status() -> Result<serde_json::Value> {
+		Self::create_request(ObsRequestType::GetReplayBufferStatus, None::<()>)
+	}
+
 	/// Get stream status
 	pub fn get_stream_status() -> Result<serde_json::Value> {
 		Self::create_request(ObsRequestType::GetStreamStatus, None::<()>)
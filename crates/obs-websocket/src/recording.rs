@@ -0,0 +1,271 @@
+use crate::ObsEvent;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+/// Where and how to record incoming events for debugging streams after the fact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingConfig {
+	/// Directory the JSONL files are written to, created if missing
+	pub directory: PathBuf,
+	/// Start a new file once the current one reaches this many bytes
+	pub max_file_bytes: u64,
+	/// Start a new file once the current one has been open this long
+	pub max_file_age_secs: u64,
+	/// Events waiting to be written; further events are dropped while it's full
+	#[serde(default = "default_queue_capacity")]
+	pub queue_capacity: usize,
+}
+
+const fn default_queue_capacity() -> usize {
+	1024
+}
+
+impl Default for RecordingConfig {
+	fn default() -> Self {
+		Self {
+			directory: PathBuf::from("obs-recordings"),
+			max_file_bytes: 10 * 1024 * 1024,
+			max_file_age_secs: 60 * 60,
+			queue_capacity: default_queue_capacity(),
+		}
+	}
+}
+
+/// One line of a recording
+#[derive(Serialize)]
+struct RecordedEvent<'a> {
+	/// RFC 3339 with milliseconds, in UTC
+	timestamp: String,
+	event: &'a ObsEvent,
+}
+
+/// Records events to rotating JSONL files off the event path
+///
+/// `record` only queues the event, so a slow disk never holds up live
+/// handlers; a blocking task drains the queue and does the writing. The
+/// queue is bounded by [`RecordingConfig::queue_capacity`], and events that
+/// arrive while it's full are dropped and counted rather than waited on.
+pub struct EventRecorder {
+	events_tx: mpsc::Sender<(DateTime<Utc>, ObsEvent)>,
+	writer_handle: JoinHandle<io::Result<()>>,
+	dropped: AtomicU64,
+}
+
+impl EventRecorder {
+	/// Create the recording directory and start the writer task
+	///
+	/// # Errors
+	///
+	/// Returns an error if the recording directory can't be created.
+	pub fn start(config: RecordingConfig) -> io::Result<Self> {
+		fs::create_dir_all(&config.directory)?;
+		let (events_tx, mut events_rx) = mpsc::channel(config.queue_capacity.max(1));
+
+		let writer_handle = tokio::task::spawn_blocking(move || {
+			let mut writer = RotatingWriter::new(config);
+			while let Some((timestamp, event)) = events_rx.blocking_recv() {
+				if let Err(e) = writer.write(timestamp, &event) {
+					tracing::error!("Failed to record OBS event, stopping recording: {}", e);
+					return Err(e);
+				}
+			}
+			Ok(())
+		});
+
+		Ok(Self {
+			events_tx,
+			writer_handle,
+			dropped: AtomicU64::new(0),
+		})
+	}
+
+	/// Queue an event for recording, stamped with the current time
+	///
+	/// The event is dropped if the queue is full.
+	pub fn record(&self, event: &ObsEvent) {
+		match self.events_tx.try_send((Utc::now(), event.clone())) {
+			Err(mpsc::error::TrySendError::Full(_)) => {
+				if self.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+					tracing::warn!("OBS event recording can't keep up, dropping events until the queue drains");
+				}
+			}
+			// Closed only once the writer has stopped on an error it already logged
+			Ok(()) | Err(mpsc::error::TrySendError::Closed(_)) => {}
+		}
+	}
+
+	/// Events dropped so far because the queue was full
+	pub fn dropped(&self) -> u64 {
+		self.dropped.load(Ordering::Relaxed)
+	}
+
+	/// Stop recording once every queued event is on disk
+	///
+	/// # Errors
+	///
+	/// Returns the error that stopped the writer early, if any.
+	pub async fn finish(self) -> io::Result<()> {
+		let dropped = self.dropped();
+		if dropped > 0 {
+			tracing::warn!("OBS event recording dropped {} events while its queue was full", dropped);
+		}
+		drop(self.events_tx);
+		self.writer_handle.await.map_err(io::Error::other)?
+	}
+}
+
+/// JSONL file writer that moves to a new file by size or age
+struct RotatingWriter {
+	config: RecordingConfig,
+	current: Option<CurrentFile>,
+	files_opened: u64,
+}
+
+struct CurrentFile {
+	writer: BufWriter<File>,
+	opened_at: Instant,
+	bytes_written: u64,
+}
+
+impl RotatingWriter {
+	const fn new(config: RecordingConfig) -> Self {
+		Self {
+			config,
+			current: None,
+			files_opened: 0,
+		}
+	}
+
+	fn write(&mut self, timestamp: DateTime<Utc>, event: &ObsEvent) -> io::Result<()> {
+		let mut line = Vec::new();
+		serde_json::to_writer(
+			&mut line,
+			&RecordedEvent {
+				timestamp: timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+				event,
+			},
+		)?;
+		line.push(b'\n');
+
+		let mut current = match self.current.take() {
+			Some(current) if !self.should_rotate(&current) => current,
+			_ => self.open(timestamp)?,
+		};
+		current.writer.write_all(&line)?;
+		// Flushed per event so a crash loses as little of the recording as possible
+		current.writer.flush()?;
+		current.bytes_written += line.len() as u64;
+		self.current = Some(current);
+		Ok(())
+	}
+
+	fn should_rotate(&self, current: &CurrentFile) -> bool {
+		current.bytes_written >= self.config.max_file_bytes || current.opened_at.elapsed() >= Duration::from_secs(self.config.max_file_age_secs)
+	}
+
+	fn open(&mut self, timestamp: DateTime<Utc>) -> io::Result<CurrentFile> {
+		// Timestamp then sequence, so names sort in recording order within and across runs
+		let mut name = String::from("obs-events-");
+		let _ = write!(name, "{}-{:06}.jsonl", timestamp.format("%Y%m%dT%H%M%S%.3fZ"), self.files_opened);
+		let file = File::create(self.config.directory.join(name))?;
+		self.files_opened += 1;
+		Ok(CurrentFile {
+			writer: BufWriter::new(file),
+			opened_at: Instant::now(),
+			bytes_written: 0,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::types::{CurrentProgramSceneData, StreamStateData};
+	use serde_json::Value;
+
+	fn temp_dir(name: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(["obs-recording-", name, "-", &std::process::id().to_string()].concat());
+		let _ = fs::remove_dir_all(&dir);
+		dir
+	}
+
+	#[tokio::test]
+	async fn test_recorded_events_are_jsonl_in_order() {
+		let directory = temp_dir("order");
+		let recorder = EventRecorder::start(RecordingConfig {
+			directory: directory.clone(),
+			// Small enough that the events below span several files
+			max_file_bytes: 150,
+			max_file_age_secs: 3600,
+			queue_capacity: 16,
+		})
+		.unwrap();
+
+		let scenes = ["Intro", "Main", "Q&A", "Outro"];
+		for scene in scenes {
+			recorder.record(&ObsEvent::CurrentProgramSceneChanged(CurrentProgramSceneData { scene_name: scene.to_string() }));
+		}
+		recorder.record(&ObsEvent::StreamStateChanged(StreamStateData { streaming: false, timecode: None }));
+		recorder.finish().await.unwrap();
+
+		let mut files: Vec<PathBuf> = fs::read_dir(&directory).unwrap().map(|entry| entry.unwrap().path()).collect();
+		files.sort();
+		assert!(files.len() > 1, "expected the recording to rotate");
+
+		let lines: Vec<Value> = files
+			.iter()
+			.flat_map(|path| {
+				fs::read_to_string(path)
+					.unwrap()
+					.lines()
+					.map(|line| serde_json::from_str(line).unwrap())
+					.collect::<Vec<Value>>()
+			})
+			.collect();
+		assert_eq!(lines.len(), 5);
+
+		let recorded_scenes: Vec<&str> = lines[..4].iter().map(|line| line["event"]["data"]["sceneName"].as_str().unwrap()).collect();
+		assert_eq!(recorded_scenes, scenes);
+		assert_eq!(lines[4]["event"]["type"], "streamStateChanged");
+
+		let timestamps: Vec<DateTime<Utc>> = lines.iter().map(|line| line["timestamp"].as_str().unwrap().parse().unwrap()).collect();
+		assert!(timestamps.windows(2).all(|pair| pair[0] <= pair[1]));
+
+		fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_events_are_dropped_while_the_queue_is_full() {
+		// A writer that hasn't taken anything off the queue yet
+		let (events_tx, mut events_rx) = mpsc::channel(2);
+		let recorder = EventRecorder {
+			events_tx,
+			writer_handle: tokio::spawn(async { Ok(()) }),
+			dropped: AtomicU64::new(0),
+		};
+		let scene = |name: &str| ObsEvent::CurrentProgramSceneChanged(CurrentProgramSceneData { scene_name: name.to_string() });
+
+		for name in ["Intro", "Main", "Q&A", "Outro"] {
+			recorder.record(&scene(name));
+		}
+		assert_eq!(recorder.dropped(), 2);
+
+		// The queued events are the oldest ones, and room frees up as the writer catches up
+		let queued = |event: (DateTime<Utc>, ObsEvent)| match event.1 {
+			ObsEvent::CurrentProgramSceneChanged(data) => data.scene_name,
+			other => panic!("unexpected event {other:?}"),
+		};
+		assert_eq!(queued(events_rx.try_recv().unwrap()), "Intro");
+		recorder.record(&scene("Credits"));
+		assert_eq!(recorder.dropped(), 2);
+		assert_eq!(queued(events_rx.try_recv().unwrap()), "Main");
+		assert_eq!(queued(events_rx.try_recv().unwrap()), "Credits");
+	}
+}
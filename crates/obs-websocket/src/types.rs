@@ -9,13 +9,25 @@ pub enum ObsCommand {
 	StartRecording,
 	StopRecording,
 	SwitchScene(String),
+	/// Switch scenes through the named transition instead of cutting
+	TransitionToScene {
+		scene_name: String,
+		transition_name: String,
+		duration_ms: u32,
+	},
 	SetInputMute(String, bool),
 	SetInputVolume(String, f64),
 	ToggleStudioMode(bool),
 	StartVirtualCamera,
 	StopVirtualCamera,
+	/// Answered with `ObsEvent::VirtualCamStatusResponse`
+	GetVirtualCameraStatus,
 	StartReplayBuffer,
 	StopReplayBuffer,
+	/// Write the replay buffer's contents to disk
+	SaveReplayBuffer,
+	/// Answered with `ObsEvent::ReplayBufferStatusResponse`
+	GetReplayBufferStatus,
 	GetInputMute(String),
 	GetInputVolume(String),
 	SetYouTubeStream {
@@ -44,8 +56,9 @@ pub enum ObsRequestType {
 	SetCurrentProgramScene,
 	GetSceneTransitionList,
 	GetCurrentSceneTransition,
-	#[allow(dead_code)]
-	SetCurrrentSceneTransition,
+	SetCurrentSceneTransition,
+	SetCurrentSceneTransitionDuration,
+	SetSceneSceneTransitionOverride,
 	#[allow(dead_code)]
 	TriggerSceneTransition,
 
@@ -83,6 +96,7 @@ pub enum ObsRequestType {
 	GetReplayBufferStatus,
 	StartReplayBuffer,
 	StopReplayBuffer,
+	SaveReplayBuffer,
 
 	// Studio Mode
 	GetStudioModeEnabled,
@@ -113,7 +127,9 @@ impl ObsRequestType {
 			Self::SetCurrentProgramScene => "SetCurrentProgramScene",
 			Self::GetSceneTransitionList => "GetSceneTransitionList",
 			Self::GetCurrentSceneTransition => "GetCurrentSceneTransition",
-			Self::SetCurrrentSceneTransition => "SetCurrrentSceneTransition",
+			Self::SetCurrentSceneTransition => "SetCurrentSceneTransition",
+			Self::SetCurrentSceneTransitionDuration => "SetCurrentSceneTransitionDuration",
+			Self::SetSceneSceneTransitionOverride => "SetSceneSceneTransitionOverride",
 			Self::TriggerSceneTransition => "TriggerSceneTransition",
 			Self::GetInputList => "GetInputList",
 			Self::GetInputMute => "GetInputMute",
@@ -140,6 +156,7 @@ impl ObsRequestType {
 			Self::GetReplayBufferStatus => "GetReplayBufferStatus",
 			Self::StartReplayBuffer => "StartReplayBuffer",
 			Self::StopReplayBuffer => "StopReplayBuffer",
+			Self::SaveReplayBuffer => "SaveReplayBuffer",
 			Self::GetStudioModeEnabled => "GetStudioModeEnabled",
 			Self::SetStudioModeEnabled => "SetStudioModeEnabled",
 			Self::GetSourceFilter => "GetSourceFilter",
@@ -179,11 +196,14 @@ impl ObsRequestType {
 			"GetReplayBufferStatus" => Self::GetReplayBufferStatus,
 			"StartReplayBuffer" => Self::StartReplayBuffer,
 			"StopReplayBuffer" => Self::StopReplayBuffer,
+			"SaveReplayBuffer" => Self::SaveReplayBuffer,
 			"GetStudioModeEnabled" => Self::GetStudioModeEnabled,
 			"SetStudioModeEnabled" => Self::SetStudioModeEnabled,
 			"GetStats" => Self::GetStats,
 			"GetCurrentSceneTransition" => Self::GetCurrentSceneTransition,
-			// "SetCurrentSceneTransition" => Self::SetCurrentSceneTransition,
+			"SetCurrentSceneTransition" => Self::SetCurrentSceneTransition,
+			"SetCurrentSceneTransitionDuration" => Self::SetCurrentSceneTransitionDuration,
+			"SetSceneSceneTransitionOverride" => Self::SetSceneSceneTransitionOverride,
 			"GetSceneTransitionList" => Self::GetSceneTransitionList,
 			"GetSourceFilterList" => Self::GetSourceFilterList,
 			"GetHotkeyList" => Self::GetHotkeyList,
@@ -298,6 +318,16 @@ pub struct SetCurrentProgramSceneParams {
 	pub scene_name: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct SetSceneSceneTransitionOverrideParams {
+	#[serde(rename = "sceneName")]
+	pub scene_name: String,
+	#[serde(rename = "transitionName")]
+	pub transition_name: String,
+	#[serde(rename = "transitionDuration")]
+	pub transition_duration: u32,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SetInputMuteParams {
 	pub n: String,
@@ -310,12 +340,6 @@ pub struct SetInputVolumeParams {
 	pub v: f64,
 }
 
-#[derive(Debug, Clone, Serialize)]
-#[allow(dead_code)]
-pub struct SetCurrrentSceneTransitionParams {
-	pub n: String,
-}
-
 #[derive(Debug, Clone, Serialize)]
 #[allow(dead_code)]
 pub struct SetCurrentProfileParams {
@@ -0,0 +1,87 @@
+use base64::engine::Engine;
+use futures_util::{SinkExt, StreamExt};
+use obs_websocket::{ObsConfig, ObsWebSocketManager, PollingConfig, RetryConfig};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+const SALT: &str = "salt";
+const CHALLENGE: &str = "challenge";
+
+/// Authentication string the client sends for `password`
+fn auth_response(password: &str) -> String {
+	let secret = Sha256::new().chain_update(password).chain_update(SALT).finalize();
+	base64::engine::general_purpose::STANDARD.encode(Sha256::new().chain_update(secret).chain_update(CHALLENGE).finalize())
+}
+
+/// Minimal OBS requiring a password, which the test can change at any time
+///
+/// Each connection's identify attempt is reported on the returned channel as
+/// whether it was accepted. Rejected connections are closed, as OBS does.
+async fn mock_obs(password: &str) -> (u16, Arc<Mutex<String>>, mpsc::UnboundedReceiver<bool>) {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let port = listener.local_addr().unwrap().port();
+	let password = Arc::new(Mutex::new(password.to_string()));
+	let (attempts_tx, attempts_rx) = mpsc::unbounded_channel();
+
+	let current = password.clone();
+	tokio::spawn(async move {
+		while let Ok((socket, _)) = listener.accept().await {
+			let current = current.clone();
+			let attempts_tx = attempts_tx.clone();
+			tokio::spawn(async move {
+				let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+				let hello = json!({"op": 0, "d": {"rpcVersion": 1, "authentication": {"challenge": CHALLENGE, "salt": SALT}}});
+				ws.send(Message::Text(hello.to_string().into())).await.unwrap();
+
+				while let Some(Ok(message)) = ws.next().await {
+					let Message::Text(text) = message else { continue };
+					let message: Value = serde_json::from_str(&text).unwrap();
+					if message["op"].as_u64() != Some(1) {
+						continue;
+					}
+
+					let expected = auth_response(&current.lock().unwrap());
+					let accepted = message["d"]["authentication"].as_str() == Some(expected.as_str());
+					let _ = attempts_tx.send(accepted);
+					if !accepted {
+						let _ = ws.close(None).await;
+						return;
+					}
+					let identified = json!({"op": 2, "d": {"negotiatedRpcVersion": 1}});
+					ws.send(Message::Text(identified.to_string().into())).await.unwrap();
+				}
+			});
+		}
+	});
+
+	(port, password, attempts_rx)
+}
+
+#[tokio::test]
+async fn test_reconnect_uses_rotated_password() {
+	let (port, obs_password, mut attempts) = mock_obs("old-password").await;
+	let config = ObsConfig {
+		host: "127.0.0.1".to_string(),
+		port,
+		password: "old-password".to_string(),
+	};
+	let manager = ObsWebSocketManager::new(config, RetryConfig::default());
+
+	manager.connect(PollingConfig::from(Vec::new())).await.unwrap();
+	assert_eq!(attempts.recv().await, Some(true));
+
+	// Rotating the password leaves the live connection up
+	*obs_password.lock().unwrap() = "new-password".to_string();
+	manager.update_credentials("new-password".to_string()).await.unwrap();
+	assert!(manager.is_healthy().await.unwrap());
+	assert_eq!(manager.connection_info().await.unwrap().port, port);
+
+	manager.disconnect().await.unwrap();
+	manager.connect(PollingConfig::from(Vec::new())).await.unwrap();
+	assert_eq!(attempts.recv().await, Some(true));
+	assert!(manager.is_healthy().await.unwrap());
+}
@@ -0,0 +1,165 @@
+use futures_util::{SinkExt, StreamExt};
+use obs_websocket::types::{ReplayBufferStatusData, VirtualCamStatusData};
+use obs_websocket::{ObsCommand, ObsConfig, ObsEvent, ObsWebSocketManager, PollingConfig, RetryConfig};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Minimal OBS: no auth, acknowledges requests and tracks output state
+///
+/// The client's connect-time `*-init` requests go unanswered, so the only
+/// status responses are the ones the tests ask for. Every other request type
+/// is forwarded to the returned channel.
+async fn mock_obs() -> (u16, mpsc::UnboundedReceiver<String>) {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let port = listener.local_addr().unwrap().port();
+	let (requests_tx, requests_rx) = mpsc::unbounded_channel();
+
+	tokio::spawn(async move {
+		let (socket, _) = listener.accept().await.unwrap();
+		let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+		let (mut virtual_cam, mut replay_buffer) = (false, false);
+
+		let hello = json!({"op": 0, "d": {"obsWebSocketVersion": "5.0.0", "rpcVersion": 1}});
+		ws.send(Message::Text(hello.to_string().into())).await.unwrap();
+
+		while let Some(Ok(message)) = ws.next().await {
+			// The client pings as soon as it connects
+			let Message::Text(text) = message else { continue };
+			let message: Value = serde_json::from_str(&text).unwrap();
+			match message["op"].as_u64() {
+				Some(1) => {
+					let identified = json!({"op": 2, "d": {"negotiatedRpcVersion": 1}});
+					ws.send(Message::Text(identified.to_string().into())).await.unwrap();
+				}
+				Some(6) => {
+					let request_type = message["d"]["requestType"].as_str().unwrap_or_default().to_string();
+					let request_id = message["d"]["requestId"].as_str().unwrap_or_default().to_string();
+					if request_id.ends_with("-init") {
+						continue;
+					}
+
+					let output_active = match request_type.as_str() {
+						"StartVirtualCam" | "StopVirtualCam" => {
+							virtual_cam = request_type == "StartVirtualCam";
+							None
+						}
+						"StartReplayBuffer" | "StopReplayBuffer" => {
+							replay_buffer = request_type == "StartReplayBuffer";
+							None
+						}
+						"GetVirtualCamStatus" => Some(virtual_cam),
+						"GetReplayBufferStatus" => Some(replay_buffer),
+						_ => None,
+					};
+
+					let mut d = json!({
+						"requestType": request_type,
+						"requestId": request_id,
+						"requestStatus": {"result": true, "code": 100},
+					});
+					if let Some(active) = output_active {
+						d["responseData"] = json!({"outputActive": active});
+					}
+					ws.send(Message::Text(json!({"op": 7, "d": d}).to_string().into())).await.unwrap();
+					let _ = requests_tx.send(request_type);
+				}
+				_ => {}
+			}
+		}
+	});
+
+	(port, requests_rx)
+}
+
+async fn connect(port: u16) -> ObsWebSocketManager {
+	let config = ObsConfig {
+		host: "127.0.0.1".to_string(),
+		port,
+		password: String::new(),
+	};
+	let manager = ObsWebSocketManager::new(config, RetryConfig::default());
+	// No polling, so only our own requests reach the mock
+	manager.connect(PollingConfig::from(Vec::new())).await.unwrap();
+	manager
+}
+
+/// Next event matching `pick`, skipping everything else
+///
+/// The event channel drops the oldest entries when full, so overflow errors
+/// are expected while catching up.
+async fn wait_for<T>(manager: &ObsWebSocketManager, mut pick: impl FnMut(ObsEvent) -> Option<T>) -> T {
+	tokio::time::timeout(Duration::from_secs(5), async {
+		loop {
+			if let Ok(event) = manager.next_event().await {
+				if let Some(found) = pick(event) {
+					return found;
+				}
+			}
+		}
+	})
+	.await
+	.expect("timed out waiting for event")
+}
+
+async fn recorded(requests: &mut mpsc::UnboundedReceiver<String>, count: usize) -> Vec<String> {
+	let mut seen = Vec::with_capacity(count);
+	while seen.len() < count {
+		let request = tokio::time::timeout(Duration::from_secs(5), requests.recv()).await.expect("timed out waiting for request");
+		seen.push(request.expect("mock OBS hung up"));
+	}
+	seen
+}
+
+#[tokio::test]
+async fn test_virtual_camera_controls() {
+	let (port, mut requests) = mock_obs().await;
+	let manager = connect(port).await;
+
+	manager.execute_command(ObsCommand::StartVirtualCamera).await.unwrap();
+	manager.execute_command(ObsCommand::GetVirtualCameraStatus).await.unwrap();
+	let status = wait_for(&manager, |event| match event {
+		ObsEvent::VirtualCamStatusResponse(VirtualCamStatusData { active }) => Some(active),
+		_ => None,
+	})
+	.await;
+	assert!(status);
+
+	manager.execute_command(ObsCommand::StopVirtualCamera).await.unwrap();
+	manager.execute_command(ObsCommand::GetVirtualCameraStatus).await.unwrap();
+	let status = wait_for(&manager, |event| match event {
+		ObsEvent::VirtualCamStatusResponse(VirtualCamStatusData { active }) => Some(active),
+		_ => None,
+	})
+	.await;
+	assert!(!status);
+
+	assert_eq!(
+		recorded(&mut requests, 4).await,
+		["StartVirtualCam", "GetVirtualCamStatus", "StopVirtualCam", "GetVirtualCamStatus"]
+	);
+}
+
+#[tokio::test]
+async fn test_replay_buffer_controls() {
+	let (port, mut requests) = mock_obs().await;
+	let manager = connect(port).await;
+
+	manager.execute_command(ObsCommand::StartReplayBuffer).await.unwrap();
+	manager.execute_command(ObsCommand::SaveReplayBuffer).await.unwrap();
+	manager.execute_command(ObsCommand::GetReplayBufferStatus).await.unwrap();
+	let status = wait_for(&manager, |event| match event {
+		ObsEvent::ReplayBufferStatusResponse(ReplayBufferStatusData { active }) => Some(active),
+		_ => None,
+	})
+	.await;
+	assert!(status);
+
+	manager.execute_command(ObsCommand::StopReplayBuffer).await.unwrap();
+	assert_eq!(
+		recorded(&mut requests, 4).await,
+		["StartReplayBuffer", "SaveReplayBuffer", "GetReplayBufferStatus", "StopReplayBuffer"]
+	);
+}
@@ -5,7 +5,7 @@ use tracing::instrument;
 
 use crate::{
 	error::DedupCacheError,
-	metrics::{namespace_of, DEDUP_WAITERS, FETCH_DURATION},
+	metrics::{namespace_of, record_eviction, DEDUP_WAITERS, FETCH_DURATION},
 	store::CacheStore,
 };
 
@@ -34,11 +34,22 @@ pub struct DedupCache {
 	in_flight: Cache<String, Arc<[u8]>>,
 }
 
+fn in_flight_cache(max_in_flight: u64) -> Cache<String, Arc<[u8]>> {
+	Cache::builder()
+		.max_capacity(max_in_flight)
+		.eviction_listener(|key: Arc<String>, _, cause| {
+			if cause.was_evicted() {
+				record_eviction(namespace_of(&key));
+			}
+		})
+		.build()
+}
+
 impl DedupCache {
 	pub fn new(store: Arc<CacheStore>, max_in_flight: u64) -> Self {
 		Self {
 			store,
-			in_flight: Cache::builder().max_capacity(max_in_flight).build(),
+			in_flight: in_flight_cache(max_in_flight),
 		}
 	}
 
@@ -188,3 +199,24 @@ impl DedupCache {
 		Arc::clone(&self.store)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::metrics::CACHE_EVICTIONS;
+
+	#[tokio::test]
+	async fn test_size_evictions_are_counted() {
+		let cache = in_flight_cache(2);
+		for id in 0..5 {
+			cache.insert(["test:evict:", &id.to_string()].concat(), Arc::from(&b"x"[..])).await;
+			cache.run_pending_tasks().await;
+		}
+		// Explicit invalidation is not an eviction
+		cache.invalidate_all();
+		cache.run_pending_tasks().await;
+
+		let evicted = CACHE_EVICTIONS.as_ref().unwrap().with_label_values(&["test:evict"]).get();
+		assert!((evicted - 3.0).abs() < f64::EPSILON);
+	}
+}
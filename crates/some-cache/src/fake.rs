@@ -0,0 +1,159 @@
+//! Minimal in-process Redis for tests
+//!
+//! Speaks just enough RESP2 for `CacheStore`: `GET`, `SET` (with `NX`/`EX`),
+//! `SETEX`, `TTL`, `EXPIRE` and `DEL`. TTLs are stored but never count down,
+//! and any other command (e.g. the client's `CLIENT SETINFO` handshake) is
+//! answered with `+OK`.
+
+use std::{
+	collections::HashMap,
+	io,
+	sync::{Arc, Mutex, PoisonError},
+};
+use tokio::{
+	io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+	net::{TcpListener, TcpStream},
+	task::JoinHandle,
+};
+
+use crate::config::CacheConfig;
+
+const OK: &[u8] = b"+OK\r\n";
+const NIL: &[u8] = b"$-1\r\n";
+
+/// Key → (value, TTL in seconds, -1 for none)
+type Keyspace = Arc<Mutex<HashMap<Vec<u8>, (Vec<u8>, i64)>>>;
+
+/// Redis stand-in listening on a random local port, stopped when dropped
+pub struct FakeRedis {
+	url: String,
+	server: JoinHandle<()>,
+}
+
+impl FakeRedis {
+	/// Start listening on `127.0.0.1`
+	///
+	/// # Errors
+	///
+	/// Returns an error if no local port can be bound.
+	pub async fn start() -> io::Result<Self> {
+		let listener = TcpListener::bind("127.0.0.1:0").await?;
+		let url = ["redis://", &listener.local_addr()?.to_string()].concat();
+		let keyspace = Keyspace::default();
+
+		let server = tokio::spawn(async move {
+			while let Ok((socket, _)) = listener.accept().await {
+				tokio::spawn(serve(socket, Arc::clone(&keyspace)));
+			}
+		});
+		Ok(Self { url, server })
+	}
+
+	#[must_use]
+	pub fn url(&self) -> &str {
+		&self.url
+	}
+
+	/// `CacheConfig` pointing at this server, with sliding TTL turned off
+	#[must_use]
+	pub fn config(&self) -> CacheConfig {
+		CacheConfig {
+			redis_url: self.url.clone(),
+			touch_probability: Some(0.0),
+			..CacheConfig::default()
+		}
+	}
+}
+
+impl Drop for FakeRedis {
+	fn drop(&mut self) {
+		self.server.abort();
+	}
+}
+
+async fn serve(socket: TcpStream, keyspace: Keyspace) {
+	let mut reader = BufReader::new(socket);
+	while let Ok(Some(command)) = read_command(&mut reader).await {
+		let reply = execute(&keyspace, &command);
+		if reader.get_mut().write_all(&reply).await.is_err() {
+			return;
+		}
+	}
+}
+
+/// Next command as its arguments, or `None` once the client hangs up
+async fn read_command(reader: &mut BufReader<TcpStream>) -> io::Result<Option<Vec<Vec<u8>>>> {
+	let Some(count) = read_header(reader, b'*').await? else { return Ok(None) };
+
+	let mut args = Vec::with_capacity(count);
+	for _ in 0..count {
+		let Some(len) = read_header(reader, b'$').await? else { return Ok(None) };
+		// The argument is followed by its own CRLF
+		let mut arg = vec![0; len + 2];
+		reader.read_exact(&mut arg).await?;
+		arg.truncate(len);
+		args.push(arg);
+	}
+	Ok(Some(args))
+}
+
+/// The length in a `<marker><n>\r\n` line
+async fn read_header(reader: &mut BufReader<TcpStream>, marker: u8) -> io::Result<Option<usize>> {
+	let mut line = Vec::new();
+	if reader.read_until(b'\n', &mut line).await? == 0 {
+		return Ok(None);
+	}
+
+	line
+		.strip_prefix(&[marker])
+		.and_then(|rest| std::str::from_utf8(rest).ok())
+		.and_then(|digits| digits.trim_end().parse().ok())
+		.map(Some)
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed RESP header"))
+}
+
+fn execute(keyspace: &Keyspace, command: &[Vec<u8>]) -> Vec<u8> {
+	let Some((name, args)) = command.split_first() else {
+		return b"-ERR empty command\r\n".to_vec();
+	};
+	let mut keys = keyspace.lock().unwrap_or_else(PoisonError::into_inner);
+
+	match (name.to_ascii_uppercase().as_slice(), args) {
+		(b"GET", [key]) => keys.get(key).map_or_else(|| NIL.to_vec(), |(value, _)| bulk(value)),
+		(b"TTL", [key]) => integer(keys.get(key).map_or(-2, |(_, ttl)| *ttl)),
+		(b"SETEX", [key, ttl, value]) => {
+			keys.insert(key.clone(), (value.clone(), number(ttl)));
+			OK.to_vec()
+		}
+		(b"SET", [key, value, options @ ..]) => {
+			if options.iter().any(|option| option.eq_ignore_ascii_case(b"NX")) && keys.contains_key(key) {
+				return NIL.to_vec();
+			}
+			let ttl = options
+				.iter()
+				.position(|option| option.eq_ignore_ascii_case(b"EX"))
+				.and_then(|i| options.get(i + 1))
+				.map_or(-1, |ttl| number(ttl));
+			keys.insert(key.clone(), (value.clone(), ttl));
+			OK.to_vec()
+		}
+		(b"EXPIRE", [key, ttl]) => integer(keys.get_mut(key).map_or(0, |(_, current)| {
+			*current = number(ttl);
+			1
+		})),
+		(b"DEL", names) => integer(names.iter().filter(|name| keys.remove(*name).is_some()).count().try_into().unwrap_or(i64::MAX)),
+		_ => OK.to_vec(),
+	}
+}
+
+fn number(arg: &[u8]) -> i64 {
+	std::str::from_utf8(arg).ok().and_then(|digits| digits.parse().ok()).unwrap_or(-1)
+}
+
+fn integer(n: i64) -> Vec<u8> {
+	[b":", n.to_string().as_bytes(), b"\r\n"].concat()
+}
+
+fn bulk(value: &[u8]) -> Vec<u8> {
+	[b"$", value.len().to_string().as_bytes(), b"\r\n", value, b"\r\n"].concat()
+}
@@ -17,6 +17,7 @@
 //! * `CacheConfig`   — construction parameters (no bin-specific deps).
 //! * `CacheError` / `DedupCacheError` — error types without axum/SDK deps.
 //! * Prometheus metrics and recording macros.
+//! * `FakeRedis` — in-process Redis for tests, behind the `fake-redis` feature.
 //!
 //! ## Bin responsibilities
 //!
@@ -33,6 +34,8 @@ pub mod config;
 pub mod dedup;
 pub mod entry;
 pub mod error;
+#[cfg(any(test, feature = "fake-redis"))]
+pub mod fake;
 pub mod metrics;
 pub mod store;
 pub mod stream;
@@ -41,5 +44,7 @@ pub use config::CacheConfig;
 pub use dedup::DedupCache;
 pub use entry::CacheEntry;
 pub use error::{CacheError, DedupCacheError};
+#[cfg(any(test, feature = "fake-redis"))]
+pub use fake::FakeRedis;
 pub use store::CacheStore;
 pub use stream::StreamHandle;
@@ -3,7 +3,7 @@
 //
 // What redis_exporter already covers (do not duplicate here):
 //   - Global hit/miss rate          → keyspace_hits / keyspace_misses
-//   - Memory usage, eviction        → used_memory, evicted_keys
+//   - Memory usage, Redis eviction  → used_memory, evicted_keys
 //   - Connection count, latency     → connected_clients, latency_histogram
 //   - Command throughput            → instantaneous_ops_per_sec
 //   - Per-keyspace key counts       → db{N}_keys
@@ -23,6 +23,10 @@
 //      thundering-herd guard is actually firing and how much upstream pressure
 //      it absorbs. redis_exporter cannot see this.
 //
+//   4. CACHE_EVICTIONS — entries the in-process moka layer (DedupCache's
+//      in-flight map) dropped for size or expiry. This cache never touches
+//      Redis, so `evicted_keys` does not include it.
+//
 // If none of these are being read in dashboards, delete this file and rely
 // solely on redis_exporter + the official Grafana dashboard.
 
@@ -41,6 +45,27 @@ pub static CACHE_HITS: Lazy<Result<CounterVec, prometheus::Error>> =
 pub static CACHE_MISSES: Lazy<Result<CounterVec, prometheus::Error>> =
 	Lazy::new(|| register_counter_vec!("cache_misses_total", "Cache misses by logical namespace", &["namespace"]));
 
+/// Count one lookup against `namespace` as a hit or a miss
+pub fn record_lookup(namespace: &str, hit: bool) {
+	if let Ok(c) = if hit { &*CACHE_HITS } else { &*CACHE_MISSES } {
+		c.with_label_values(&[namespace]).inc();
+	}
+}
+
+// ── In-process evictions ──────────────────────────────────────────────────────
+//
+// Label: `namespace`, derived from the evicted key.
+// Only size- and expiry-driven removals count; explicit invalidation does not.
+
+pub static CACHE_EVICTIONS: Lazy<Result<CounterVec, prometheus::Error>> =
+	Lazy::new(|| register_counter_vec!("cache_evictions_total", "Entries evicted from the in-process dedup cache by namespace", &["namespace"]));
+
+pub fn record_eviction(namespace: &str) {
+	if let Ok(c) = &*CACHE_EVICTIONS {
+		c.with_label_values(&[namespace]).inc();
+	}
+}
+
 // ── Upstream fetch latency ────────────────────────────────────────────────────
 //
 // Label: `namespace` — same as above.
@@ -87,3 +112,28 @@ pub fn namespace_of(key: &str) -> &str {
 		None => key,
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{CacheStore, FakeRedis};
+
+	fn count(counter: &Lazy<Result<CounterVec, prometheus::Error>>, namespace: &str) -> f64 {
+		counter.as_ref().unwrap().with_label_values(&[namespace]).get()
+	}
+
+	#[tokio::test]
+	async fn test_lookups_count_hits_and_misses() {
+		let redis = FakeRedis::start().await.unwrap();
+		let store = CacheStore::new(redis.config()).unwrap();
+
+		assert_eq!(store.get::<String>("test:lookup:a").await.unwrap(), None);
+		store.set("test:lookup:a", &"cached".to_string(), None).await.unwrap();
+		for _ in 0..3 {
+			assert_eq!(store.get::<String>("test:lookup:a").await.unwrap().as_deref(), Some("cached"));
+		}
+
+		assert!((count(&CACHE_HITS, "test:lookup") - 3.0).abs() < f64::EPSILON);
+		assert!((count(&CACHE_MISSES, "test:lookup") - 1.0).abs() < f64::EPSILON);
+	}
+}
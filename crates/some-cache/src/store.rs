@@ -1,4 +1,4 @@
-use redis::{AsyncCommands, Client};
+use redis::{AsyncCommands, Client, ExistenceCheck, SetExpiry, SetOptions};
 use serde::{Deserialize, Serialize};
 use tokio::time::{sleep, Duration};
 use tracing::{info, instrument, warn};
@@ -8,7 +8,7 @@ use crate::{
 	config::CacheConfig,
 	entry::{BinaryCacheEntry, CacheEntry},
 	error::CacheError,
-	metrics::{namespace_of, CACHE_HITS, CACHE_MISSES},
+	metrics::{namespace_of, record_lookup},
 };
 
 // ── Payload encoding ──────────────────────────────────────────────────────────
@@ -195,15 +195,11 @@ impl CacheStore {
 
 		match raw {
 			None => {
-				if let Ok(c) = &*CACHE_MISSES {
-					c.with_label_values(&[ns]).inc();
-				}
+				record_lookup(ns, false);
 				Ok((None, 0))
 			}
 			Some(bytes) => {
-				if let Ok(c) = &*CACHE_HITS {
-					c.with_label_values(&[ns]).inc();
-				}
+				record_lookup(ns, true);
 
 				let decoded = self.decode_payload(&bytes)?;
 				let entry: CacheEntry<T> = serde_json::from_slice(&decoded)?;
@@ -313,15 +309,11 @@ impl CacheStore {
 
 		match raw {
 			None => {
-				if let Ok(c) = &*CACHE_MISSES {
-					c.with_label_values(&[ns]).inc();
-				}
+				record_lookup(ns, false);
 				Ok(None)
 			}
 			Some(bytes) => {
-				if let Ok(c) = &*CACHE_HITS {
-					c.with_label_values(&[ns]).inc();
-				}
+				record_lookup(ns, true);
 
 				let decoded = self.decode_payload(&bytes)?;
 				let entry: BinaryCacheEntry = serde_json::from_slice(&decoded)?;
@@ -341,6 +333,36 @@ impl CacheStore {
 		}
 	}
 
+	/// Claims `key` for `ttl` seconds unless it is already claimed; returns whether this caller got it.
+	///
+	/// The check and the write are one Redis command, so of several callers
+	/// racing for the same key exactly one wins. A claim is a bare marker, not
+	/// a cache entry; release it early with [`delete`](Self::delete).
+	///
+	/// # Errors
+	///
+	/// Returns an error if Redis can't be reached after retrying.
+	#[instrument(skip(self), fields(key = %key))]
+	pub async fn claim(&self, key: &str, ttl: u64) -> Result<bool, CacheError> {
+		let cache_key = self.make_key(key);
+		let options = SetOptions::default().conditional_set(ExistenceCheck::NX).with_expiration(SetExpiry::EX(ttl));
+
+		let claimed: Option<String> = self
+			.with_retry("claim", || {
+				let redis_client = self.redis_client.clone();
+				let cache_key = cache_key.clone();
+
+				Box::pin(async move {
+					let mut con = redis_client.get_multiplexed_async_connection().await?;
+					let result: Option<String> = con.set_options(&cache_key, 1, options).await?;
+					Result::<_, CacheError>::Ok(result)
+				})
+			})
+			.await?;
+
+		Ok(claimed.is_some())
+	}
+
 	#[instrument(skip(self), fields(key = %key))]
 	pub async fn delete(&self, key: &str) -> Result<bool, CacheError> {
 		let cache_key = self.make_key(key);
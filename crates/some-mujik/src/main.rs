@@ -1,3 +1,4 @@
+mod midi;
 mod music_sheet;
 
 use anyhow::{Context, Result};
@@ -46,6 +47,17 @@ enum Command {
 		#[arg(short, long, default_value = "output")]
 		name: String,
 	},
+
+	/// Render a standard MIDI file
+	Midi {
+		/// Path to the .mid file
+		#[arg(short, long)]
+		input: PathBuf,
+
+		/// Output WAV file
+		#[arg(short, long)]
+		output: PathBuf,
+	},
 }
 
 fn main() -> Result<()> {
@@ -68,6 +80,12 @@ fn main() -> Result<()> {
 			let output_path = music_sheet::process_music_sheet(&input, &output_dir, &name)?;
 			println!("Sound effect created: {}", output_path.display());
 		}
+
+		Command::Midi { input, output } => {
+			println!("Rendering MIDI file: {}", input.display());
+			midi::process_midi(&input, &output)?;
+			println!("Sound saved to: {}", output.display());
+		}
 	}
 
 	Ok(())
@@ -0,0 +1,235 @@
+use anyhow::{anyhow, Context, Result};
+use midly::{MetaMessage, MidiMessage, Smf, Timing, Track, TrackEventKind};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+
+use crate::music_sheet::write_wav_file;
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// MIDI's default tempo when a file has no tempo event: 120 BPM
+const DEFAULT_TEMPO_US: u32 = 500_000;
+
+/// A note from a MIDI file, placed on an absolute timeline
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedNote {
+	pub key: u8,
+	pub velocity: u8,
+	pub start_ms: f64,
+	pub duration_ms: f64,
+}
+
+impl TimedNote {
+	/// Equal-tempered frequency, with key 69 = A4 = 440 Hz
+	pub fn frequency(&self) -> f32 {
+		440.0 * 2.0_f32.powf((f32::from(self.key) - 69.0) / 12.0)
+	}
+}
+
+/// Converts absolute ticks to milliseconds, following tempo changes
+enum TickClock {
+	Metrical { ticks_per_beat: f64, tempo_changes: Vec<(u64, u32)> },
+	Timecode { ticks_per_ms: f64 },
+}
+
+impl TickClock {
+	fn new(timing: Timing, tracks: &[Track<'_>]) -> Self {
+		match timing {
+			Timing::Metrical(ticks_per_beat) => {
+				// Tempo events normally live in track 0, but collect them from every track
+				let mut tempo_changes = Vec::new();
+				for track in tracks {
+					let mut tick = 0u64;
+					for event in track {
+						tick += u64::from(event.delta.as_int());
+						if let TrackEventKind::Meta(MetaMessage::Tempo(us_per_beat)) = event.kind {
+							tempo_changes.push((tick, us_per_beat.as_int()));
+						}
+					}
+				}
+				tempo_changes.sort_by_key(|(tick, _)| *tick);
+
+				Self::Metrical {
+					ticks_per_beat: f64::from(ticks_per_beat.as_int()),
+					tempo_changes,
+				}
+			}
+			Timing::Timecode(fps, ticks_per_frame) => Self::Timecode {
+				ticks_per_ms: f64::from(fps.as_f32()) * f64::from(ticks_per_frame) / 1000.0,
+			},
+		}
+	}
+
+	fn to_ms(&self, tick: u64) -> f64 {
+		match self {
+			Self::Metrical { ticks_per_beat, tempo_changes } => {
+				let span_ms = |ticks: u64, us_per_beat: u32| ticks as f64 * f64::from(us_per_beat) / ticks_per_beat / 1000.0;
+
+				let mut ms = 0.0;
+				let mut last_tick = 0;
+				let mut tempo = DEFAULT_TEMPO_US;
+				for &(change_tick, us_per_beat) in tempo_changes.iter().take_while(|(t, _)| *t <= tick) {
+					ms += span_ms(change_tick - last_tick, tempo);
+					last_tick = change_tick;
+					tempo = us_per_beat;
+				}
+				ms + span_ms(tick - last_tick, tempo)
+			}
+			Self::Timecode { ticks_per_ms } => tick as f64 / ticks_per_ms,
+		}
+	}
+
+	fn note(&self, key: u8, velocity: u8, start_tick: u64, end_tick: u64) -> TimedNote {
+		let start_ms = self.to_ms(start_tick);
+		TimedNote {
+			key,
+			velocity,
+			start_ms,
+			duration_ms: self.to_ms(end_tick) - start_ms,
+		}
+	}
+}
+
+/// Parse a standard MIDI file into notes, ordered by start time
+///
+/// Note-on with velocity 0 is treated as note-off, as most sequencers write it.
+/// A note still held when its track ends is cut off at the track's last event.
+pub fn parse_midi(bytes: &[u8]) -> Result<Vec<TimedNote>> {
+	let smf = Smf::parse(bytes).map_err(|e| anyhow!("Invalid MIDI file: {}", e))?;
+	let clock = TickClock::new(smf.header.timing, &smf.tracks);
+
+	let mut notes = Vec::new();
+	for track in &smf.tracks {
+		let mut tick = 0u64;
+		// (channel, key) -> (start tick, velocity)
+		let mut held: HashMap<(u8, u8), (u64, u8)> = HashMap::new();
+
+		for event in track {
+			tick += u64::from(event.delta.as_int());
+			let TrackEventKind::Midi { channel, message } = event.kind else {
+				continue;
+			};
+
+			let (key, velocity) = match message {
+				MidiMessage::NoteOn { key, vel } => (key.as_int(), vel.as_int()),
+				MidiMessage::NoteOff { key, .. } => (key.as_int(), 0),
+				_ => continue,
+			};
+
+			let id = (channel.as_int(), key);
+			let released = if velocity > 0 { held.insert(id, (tick, velocity)) } else { held.remove(&id) };
+			if let Some((start, start_velocity)) = released {
+				notes.push(clock.note(key, start_velocity, start, tick));
+			}
+		}
+
+		for ((_, key), (start, velocity)) in held {
+			notes.push(clock.note(key, velocity, start, tick));
+		}
+	}
+
+	notes.sort_by(|a, b| a.start_ms.total_cmp(&b.start_ms).then(a.key.cmp(&b.key)));
+	Ok(notes)
+}
+
+fn ms_to_samples(ms: f64) -> usize {
+	(ms * f64::from(SAMPLE_RATE) / 1000.0).round() as usize
+}
+
+/// Mix notes into mono 16-bit samples, scaling each by its velocity
+pub fn render_notes(notes: &[TimedNote]) -> Vec<i16> {
+	let total_ms = notes.iter().map(|n| n.start_ms + n.duration_ms).fold(0.0, f64::max);
+	let mut mix = vec![0.0_f32; ms_to_samples(total_ms)];
+
+	for note in notes {
+		let start = ms_to_samples(note.start_ms);
+		let end = ms_to_samples(note.start_ms + note.duration_ms).min(mix.len());
+		let amplitude = 0.5 * f32::from(note.velocity) / 127.0;
+		let step = note.frequency() * 2.0 * PI / SAMPLE_RATE as f32;
+
+		for (t, sample) in mix[start..end].iter_mut().enumerate() {
+			*sample += amplitude * (t as f32 * step).sin();
+		}
+	}
+
+	// Chords can sum past full scale; scale down rather than clip
+	let peak = mix.iter().fold(1.0_f32, |peak, s| peak.max(s.abs()));
+	mix.iter().map(|s| (s / peak * i16::MAX as f32) as i16).collect()
+}
+
+/// Render a MIDI file to a WAV file
+pub fn process_midi(input: &Path, output: &Path) -> Result<()> {
+	let bytes = fs::read(input).context(format!("Failed to read MIDI file: {}", input.display()))?;
+	let notes = parse_midi(&bytes)?;
+	if notes.is_empty() {
+		return Err(anyhow!("No notes found in {}", input.display()));
+	}
+
+	write_wav_file(&render_notes(&notes), output)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Format 0, 480 ticks per beat, 60 BPM: A4 for one beat, then C5 for one beat
+	const TWO_NOTES: &[u8] = &[
+		b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0x01, 0xE0, //
+		b'M', b'T', b'r', b'k', 0, 0, 0, 29, //
+		0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40, // tempo: 1,000,000 us per beat
+		0x00, 0x90, 69, 100, // A4 on
+		0x83, 0x60, 0x80, 69, 0, // A4 off after 480 ticks
+		0x00, 0x90, 72, 100, // C5 on
+		0x83, 0x60, 0x90, 72, 0, // C5 off (note-on, velocity 0) after 480 ticks
+		0x00, 0xFF, 0x2F, 0x00, // end of track
+	];
+
+	/// Energy of `frequency` in `samples` (Goertzel)
+	fn tone_power(samples: &[i16], frequency: f32) -> f32 {
+		let coeff = 2.0 * (2.0 * PI * frequency / SAMPLE_RATE as f32).cos();
+		let (mut s1, mut s2) = (0.0_f32, 0.0_f32);
+		for &sample in samples {
+			let s0 = f32::from(sample) / f32::from(i16::MAX) + coeff * s1 - s2;
+			s2 = s1;
+			s1 = s0;
+		}
+		s1 * s1 + s2 * s2 - coeff * s1 * s2
+	}
+
+	#[test]
+	fn test_parse_midi_applies_tempo() {
+		let notes = parse_midi(TWO_NOTES).unwrap();
+
+		assert_eq!(notes.len(), 2);
+		assert_eq!((notes[0].key, notes[0].velocity), (69, 100));
+		assert!(notes[0].start_ms.abs() < 1e-9);
+		assert!((notes[0].duration_ms - 1000.0).abs() < 1e-9);
+		assert_eq!(notes[1].key, 72);
+		assert!((notes[1].start_ms - 1000.0).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_process_midi_renders_expected_tones() {
+		let dir = std::env::temp_dir().join(["some-mujik-midi-", &std::process::id().to_string()].concat());
+		fs::create_dir_all(&dir).unwrap();
+		let input = dir.join("two_notes.mid");
+		let output = dir.join("two_notes.wav");
+		fs::write(&input, TWO_NOTES).unwrap();
+
+		process_midi(&input, &output).unwrap();
+
+		let mut reader = hound::WavReader::open(&output).unwrap();
+		assert_eq!(reader.spec().sample_rate, SAMPLE_RATE);
+		assert_eq!(reader.duration(), 2 * SAMPLE_RATE);
+
+		let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+		let (first, second) = samples.split_at(SAMPLE_RATE as usize);
+		let (a4, c5) = (440.0, 523.25);
+		assert!(tone_power(first, a4) > 100.0 * tone_power(first, c5));
+		assert!(tone_power(second, c5) > 100.0 * tone_power(second, a4));
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -101,7 +101,7 @@ fn generate_sine_wave(frequency: f32, duration: Duration) -> Result<Vec<i16>> {
 	Ok(samples)
 }
 
-fn write_wav_file(samples: &[i16], path: &Path) -> Result<()> {
+pub fn write_wav_file(samples: &[i16], path: &Path) -> Result<()> {
 	use hound::{SampleFormat, WavSpec, WavWriter};
 
 	let spec = WavSpec {
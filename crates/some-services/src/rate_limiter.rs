@@ -1,3 +1,5 @@
+pub mod keyed;
 pub mod token_bucket;
 
+pub use keyed::{keyed_rate_limit_middleware, GlobalKey, HeaderKey, IpKey, KeyedRateLimiter, RateLimitKey};
 pub use token_bucket::{RateLimitError, TokenBucketRateLimiter};
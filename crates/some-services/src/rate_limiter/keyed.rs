@@ -0,0 +1,222 @@
+use super::token_bucket::{RateLimitError, TokenBucketRateLimiter};
+use axum::{
+	body::Body,
+	extract::{ConnectInfo, State},
+	http::{HeaderName, Request},
+	middleware::Next,
+	response::IntoResponse,
+};
+use moka::sync::Cache;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Most buckets kept at once; past this the least useful ones are evicted
+const MAX_BUCKETS: u64 = 10_000;
+
+/// Decides which bucket a request draws from
+///
+/// Implement this to key on anything the request carries, e.g. a user id
+/// placed in the extensions by an auth layer that runs first.
+pub trait RateLimitKey: Send + Sync + 'static {
+	/// Bucket key for the request; `None` draws from one bucket shared by all unkeyed requests
+	fn key(&self, request: &Request<Body>) -> Option<String>;
+}
+
+impl<K: RateLimitKey + ?Sized> RateLimitKey for Box<K> {
+	fn key(&self, request: &Request<Body>) -> Option<String> {
+		(**self).key(request)
+	}
+}
+
+/// One bucket for every request
+pub struct GlobalKey;
+
+impl RateLimitKey for GlobalKey {
+	fn key(&self, _request: &Request<Body>) -> Option<String> {
+		None
+	}
+}
+
+/// Keys by client IP
+///
+/// Needs the server to be started with
+/// `into_make_service_with_connect_info::<SocketAddr>()`.
+pub struct IpKey;
+
+impl RateLimitKey for IpKey {
+	fn key(&self, request: &Request<Body>) -> Option<String> {
+		request.extensions().get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip().to_string())
+	}
+}
+
+/// Keys by the value of a request header, such as an API key
+///
+/// The header is client-controlled: unless its values are checked, a client
+/// can send a new one with every request and get a fresh budget each time.
+/// Only use it bare behind a layer that has already authenticated the value,
+/// otherwise list the valid values with [`HeaderKey::with_known_keys`].
+pub struct HeaderKey {
+	header: HeaderName,
+	known: Option<HashSet<String>>,
+}
+
+impl HeaderKey {
+	#[must_use]
+	pub const fn new(header: HeaderName) -> Self {
+		Self { header, known: None }
+	}
+
+	/// Only these header values get their own bucket; requests with any other
+	/// value, or none, are keyed by client IP like [`IpKey`]
+	#[must_use]
+	pub fn with_known_keys(mut self, keys: impl IntoIterator<Item = String>) -> Self {
+		self.known = Some(keys.into_iter().collect());
+		self
+	}
+}
+
+impl RateLimitKey for HeaderKey {
+	fn key(&self, request: &Request<Body>) -> Option<String> {
+		let value = request.headers().get(&self.header).and_then(|value| value.to_str().ok());
+		let Some(known) = &self.known else {
+			return value.map(str::to_string);
+		};
+		match value {
+			Some(value) if known.contains(value) => Some(value.to_string()),
+			_ => IpKey.key(request),
+		}
+	}
+}
+
+/// Token buckets per key, each with the same budget
+///
+/// At most [`MAX_BUCKETS`] are kept. A bucket left untouched for a whole
+/// refill period is dropped, since by then it has refilled and a returning
+/// key loses nothing by starting from a fresh one.
+pub struct KeyedRateLimiter {
+	max_tokens: u32,
+	refill_period_ms: u64,
+	key: Box<dyn RateLimitKey>,
+	shared: TokenBucketRateLimiter,
+	buckets: Cache<String, Arc<TokenBucketRateLimiter>>,
+}
+
+impl KeyedRateLimiter {
+	#[must_use]
+	pub fn new(max_tokens: u32, key: impl RateLimitKey) -> Self {
+		Self::new_with_refill_period(max_tokens, 60_000, key)
+	}
+
+	#[must_use]
+	pub fn new_with_refill_period(max_tokens: u32, refill_period_ms: u64, key: impl RateLimitKey) -> Self {
+		Self {
+			max_tokens,
+			refill_period_ms,
+			key: Box::new(key),
+			shared: TokenBucketRateLimiter::new_with_refill_period(max_tokens, refill_period_ms),
+			buckets: Cache::builder().max_capacity(MAX_BUCKETS).time_to_idle(Duration::from_millis(refill_period_ms)).build(),
+		}
+	}
+
+	fn bucket(&self, key: String) -> Arc<TokenBucketRateLimiter> {
+		self
+			.buckets
+			.get_with(key, || Arc::new(TokenBucketRateLimiter::new_with_refill_period(self.max_tokens, self.refill_period_ms)))
+	}
+
+	/// Attempts to allow a request by consuming a token from its key's bucket.
+	///
+	/// # Errors
+	///
+	/// Returns `RateLimitError::ClockError` if there's a system time error.
+	pub fn allow_request(&self, request: &Request<Body>) -> Result<bool, RateLimitError> {
+		self.key.key(request).map_or_else(|| self.shared.allow_request(), |key| self.bucket(key).allow_request())
+	}
+}
+
+/// Rejects requests whose bucket is empty with `429 Too Many Requests`
+///
+/// # Errors
+///
+/// Returns `RateLimitError::RateLimited` when the request's bucket is empty.
+pub async fn keyed_rate_limit_middleware(State(limiter): State<Arc<KeyedRateLimiter>>, request: Request<Body>, next: Next) -> Result<impl IntoResponse, RateLimitError> {
+	match limiter.allow_request(&request) {
+		Ok(true) => Ok(next.run(request).await),
+		Ok(false) => Err(RateLimitError::RateLimited),
+		Err(e) => Err(e),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::{http::StatusCode, middleware::from_fn_with_state, routing::get, Router};
+	use tower::ServiceExt;
+
+	fn app(limiter: KeyedRateLimiter) -> Router {
+		Router::new()
+			.route("/", get(|| async { "ok" }))
+			.layer(from_fn_with_state(Arc::new(limiter), keyed_rate_limit_middleware))
+	}
+
+	async fn status(app: &Router, api_key: &str) -> StatusCode {
+		let request = Request::get("/").header("x-api-key", api_key).body(Body::empty()).unwrap();
+		app.clone().oneshot(request).await.unwrap().status()
+	}
+
+	#[tokio::test]
+	async fn test_header_keys_get_independent_budgets() {
+		let app = app(KeyedRateLimiter::new(2, HeaderKey::new(HeaderName::from_static("x-api-key"))));
+
+		// Same key shares one budget
+		assert_eq!(status(&app, "alice").await, StatusCode::OK);
+		assert_eq!(status(&app, "alice").await, StatusCode::OK);
+		assert_eq!(status(&app, "alice").await, StatusCode::TOO_MANY_REQUESTS);
+
+		// A different key has its own
+		assert_eq!(status(&app, "bob").await, StatusCode::OK);
+		assert_eq!(status(&app, "bob").await, StatusCode::OK);
+	}
+
+	#[tokio::test]
+	async fn test_unknown_header_values_fall_back_to_the_client_ip() {
+		let key = HeaderKey::new(HeaderName::from_static("x-api-key")).with_known_keys(["alice".to_string()]);
+		let app = app(KeyedRateLimiter::new(2, key));
+		let from = |api_key: &str| {
+			let mut request = Request::get("/").header("x-api-key", api_key).body(Body::empty()).unwrap();
+			request.extensions_mut().insert(ConnectInfo("10.0.0.1:5000".parse::<SocketAddr>().unwrap()));
+			request
+		};
+
+		// Made-up keys all draw from the caller's IP bucket instead of a fresh one each
+		for (api_key, expected) in [("forged-1", StatusCode::OK), ("forged-2", StatusCode::OK), ("forged-3", StatusCode::TOO_MANY_REQUESTS)] {
+			assert_eq!(app.clone().oneshot(from(api_key)).await.unwrap().status(), expected);
+		}
+		assert_eq!(app.clone().oneshot(from("alice")).await.unwrap().status(), StatusCode::OK);
+	}
+
+	#[test]
+	fn test_bucket_count_is_bounded() {
+		let limiter = KeyedRateLimiter::new(2, GlobalKey);
+		for i in 0..MAX_BUCKETS * 2 {
+			limiter.bucket(i.to_string());
+		}
+		limiter.buckets.run_pending_tasks();
+		assert!(limiter.buckets.entry_count() <= MAX_BUCKETS);
+	}
+
+	#[test]
+	fn test_ip_key_uses_connect_info() {
+		let request = |addr: &str| {
+			let mut request = Request::get("/").body(Body::empty()).unwrap();
+			request.extensions_mut().insert(ConnectInfo(addr.parse::<SocketAddr>().unwrap()));
+			request
+		};
+
+		assert_eq!(IpKey.key(&request("10.0.0.1:5000")).as_deref(), Some("10.0.0.1"));
+		assert_eq!(IpKey.key(&request("10.0.0.1:6000")), IpKey.key(&request("10.0.0.1:5000")));
+		assert_eq!(IpKey.key(&Request::get("/").body(Body::empty()).unwrap()), None);
+	}
+}
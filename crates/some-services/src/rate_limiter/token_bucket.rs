@@ -177,7 +177,7 @@ mod tests {
 
 		// Should have ~5 tokens now
 		let available = limiter.get_current_tokens();
-		assert!(available >= 4 && available <= 6); // Allow some variance
+		assert!((4..=6).contains(&available)); // Allow some variance
 
 		// Should allow requests again
 		assert!(limiter.allow_request().unwrap());
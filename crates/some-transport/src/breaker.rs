@@ -0,0 +1,274 @@
+use crate::error::{Result, TransportError};
+use crate::traits::Transport;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Where a [`CircuitBreaker`] is in its open/close cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+	/// Calls pass through to the inner transport
+	Closed,
+	/// Too many consecutive failures; sends fail fast until the cooldown ends
+	Open,
+	/// The cooldown ended; the next send is let through to probe for recovery
+	HalfOpen,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+	consecutive_failures: u32,
+	opened_at: Option<Instant>,
+	/// When the current half-open probe was let through; a probe whose send was
+	/// cancelled expires after another cooldown rather than wedging the breaker
+	probe_started: Option<Instant>,
+}
+
+/// Circuit breaker around any [`Transport`]
+///
+/// After `failure_threshold` consecutive send failures the breaker opens and
+/// `send`, `send_to_subject` and `broadcast` return [`TransportError::CircuitOpen`]
+/// without touching the inner transport. Once `cooldown` has passed a single
+/// probe send is let through: success closes the breaker, failure reopens it
+/// for another cooldown. Subscriptions and channel management always pass through.
+///
+/// Clones share the same breaker state.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker<T> {
+	inner: T,
+	state: Arc<Mutex<BreakerInner>>,
+	failure_threshold: u32,
+	cooldown: Duration,
+}
+
+impl<T> CircuitBreaker<T> {
+	/// Wrap `inner`, opening after `failure_threshold` consecutive failures for `cooldown`
+	#[must_use]
+	pub fn new(inner: T, failure_threshold: u32, cooldown: Duration) -> Self {
+		Self {
+			inner,
+			state: Arc::new(Mutex::new(BreakerInner {
+				consecutive_failures: 0,
+				opened_at: None,
+				probe_started: None,
+			})),
+			failure_threshold: failure_threshold.max(1),
+			cooldown,
+		}
+	}
+
+	/// The wrapped transport
+	#[must_use]
+	pub const fn inner(&self) -> &T {
+		&self.inner
+	}
+
+	/// Current breaker state
+	///
+	/// An open breaker whose cooldown has passed reports `HalfOpen`, since the
+	/// next send will be let through as a probe.
+	#[must_use]
+	pub fn state(&self) -> BreakerState {
+		let state = self.lock();
+		match state.opened_at {
+			None => BreakerState::Closed,
+			Some(opened_at) if state.probe_started.is_some() || opened_at.elapsed() >= self.cooldown => BreakerState::HalfOpen,
+			Some(_) => BreakerState::Open,
+		}
+	}
+
+	/// Number of failures since the last success
+	#[must_use]
+	pub fn consecutive_failures(&self) -> u32 {
+		self.lock().consecutive_failures
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Check whether a send may go through, claiming the probe slot when half-open
+	fn admit(&self) -> Result<()> {
+		let mut state = self.lock();
+		let Some(opened_at) = state.opened_at else {
+			return Ok(());
+		};
+
+		let since = state.probe_started.unwrap_or(opened_at);
+		let elapsed = since.elapsed();
+		if elapsed < self.cooldown {
+			return Err(TransportError::CircuitOpen(self.cooldown.saturating_sub(elapsed)));
+		}
+		state.probe_started = Some(Instant::now());
+		drop(state);
+		Ok(())
+	}
+
+	fn record<R>(&self, result: Result<R>) -> Result<R> {
+		let mut state = self.lock();
+		if result.is_ok() {
+			state.consecutive_failures = 0;
+			state.opened_at = None;
+		} else {
+			state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+			if state.probe_started.is_some() || state.consecutive_failures >= self.failure_threshold {
+				state.opened_at = Some(Instant::now());
+			}
+		}
+		state.probe_started = None;
+		result
+	}
+}
+
+#[async_trait::async_trait]
+impl<E, T> Transport<E> for CircuitBreaker<T>
+where
+	E: Clone + Send + Sync + 'static,
+	T: Transport<E>,
+{
+	type Receiver = T::Receiver;
+
+	async fn open_channel(&self, connection_key: &str) -> Self::Receiver {
+		self.inner.open_channel(connection_key).await
+	}
+
+	async fn close_channel(&self, connection_key: &str) -> Result<()> {
+		self.inner.close_channel(connection_key).await
+	}
+
+	async fn send(&self, connection_key: &str, event: E) -> Result<()> {
+		self.admit()?;
+		self.record(self.inner.send(connection_key, event).await)
+	}
+
+	async fn broadcast(&self, event: E) -> Result<usize> {
+		self.admit()?;
+		self.record(self.inner.broadcast(event).await)
+	}
+
+	async fn send_to_subject(&self, subject: &str, event: E) -> Result<()> {
+		self.admit()?;
+		self.record(self.inner.send_to_subject(subject, event).await)
+	}
+
+	async fn request(&self, subject: &str, event: E) -> Result<E> {
+		self.admit()?;
+		self.record(self.inner.request(subject, event).await)
+	}
+
+	async fn subscribe_to_subject(&self, subject: &str) -> Self::Receiver {
+		self.inner.subscribe_to_subject(subject).await
+	}
+
+	async fn subscribe(&self) -> Self::Receiver {
+		self.inner.subscribe().await
+	}
+
+	fn total_receivers(&self) -> usize {
+		self.inner.total_receivers()
+	}
+
+	fn is_closed(&self) -> bool {
+		self.inner.is_closed()
+	}
+
+	fn active_channels(&self) -> usize {
+		self.inner.active_channels()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+	/// Transport whose sends fail while `failing` is set
+	#[derive(Clone, Default)]
+	struct FlakyTransport {
+		failing: Arc<AtomicBool>,
+		calls: Arc<AtomicUsize>,
+	}
+
+	#[async_trait::async_trait]
+	impl Transport<u32> for FlakyTransport {
+		type Receiver = ();
+
+		async fn open_channel(&self, _connection_key: &str) -> Self::Receiver {}
+
+		async fn close_channel(&self, _connection_key: &str) -> Result<()> {
+			Ok(())
+		}
+
+		async fn send(&self, _connection_key: &str, _event: u32) -> Result<()> {
+			self.calls.fetch_add(1, Ordering::SeqCst);
+			if self.failing.load(Ordering::SeqCst) {
+				Err(TransportError::SendFailed("flaky".into()))
+			} else {
+				Ok(())
+			}
+		}
+
+		async fn broadcast(&self, event: u32) -> Result<usize> {
+			self.send("", event).await.map(|()| 1)
+		}
+
+		async fn send_to_subject(&self, subject: &str, event: u32) -> Result<()> {
+			self.send(subject, event).await
+		}
+
+		async fn request(&self, subject: &str, event: u32) -> Result<u32> {
+			self.send(subject, event).await.map(|()| event)
+		}
+
+		async fn subscribe_to_subject(&self, _subject: &str) -> Self::Receiver {}
+
+		async fn subscribe(&self) -> Self::Receiver {}
+
+		fn total_receivers(&self) -> usize {
+			0
+		}
+
+		fn is_closed(&self) -> bool {
+			false
+		}
+
+		fn active_channels(&self) -> usize {
+			0
+		}
+	}
+
+	#[tokio::test]
+	async fn test_breaker_opens_short_circuits_and_recovers() {
+		let flaky = FlakyTransport::default();
+		let breaker = CircuitBreaker::new(flaky.clone(), 3, Duration::from_millis(50));
+
+		flaky.failing.store(true, Ordering::SeqCst);
+		for _ in 0..3 {
+			assert!(matches!(breaker.send("conn", 1).await, Err(TransportError::SendFailed(_))));
+		}
+		assert_eq!(breaker.state(), BreakerState::Open);
+		assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+
+		// Open: every send fails fast without reaching the inner transport
+		assert!(matches!(breaker.send("conn", 1).await, Err(TransportError::CircuitOpen(_))));
+		assert!(matches!(breaker.broadcast(1).await, Err(TransportError::CircuitOpen(_))));
+		assert!(matches!(breaker.send_to_subject("subj", 1).await, Err(TransportError::CircuitOpen(_))));
+		assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+
+		// A failed probe after the cooldown reopens the breaker
+		tokio::time::sleep(Duration::from_millis(60)).await;
+		assert_eq!(breaker.state(), BreakerState::HalfOpen);
+		assert!(matches!(breaker.send("conn", 1).await, Err(TransportError::SendFailed(_))));
+		assert_eq!(breaker.state(), BreakerState::Open);
+		assert!(matches!(breaker.send("conn", 1).await, Err(TransportError::CircuitOpen(_))));
+		assert_eq!(flaky.calls.load(Ordering::SeqCst), 4);
+
+		// A successful probe closes it again
+		flaky.failing.store(false, Ordering::SeqCst);
+		tokio::time::sleep(Duration::from_millis(60)).await;
+		assert!(breaker.send("conn", 1).await.is_ok());
+		assert_eq!(breaker.state(), BreakerState::Closed);
+		assert_eq!(breaker.consecutive_failures(), 0);
+		assert_eq!(breaker.broadcast(1).await.unwrap(), 1);
+		assert_eq!(flaky.calls.load(Ordering::SeqCst), 6);
+	}
+}
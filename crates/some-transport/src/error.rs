@@ -28,6 +28,10 @@ pub enum TransportError {
 	#[error("Channel overflowed, {0} messages dropped")]
 	Overflowed(u64),
 
+	/// An ordered receiver gave up waiting for a missing message and skipped past it
+	#[error("Sequence gap: expected {expected}, resuming at {resumed_at}")]
+	SequenceGap { expected: u64, resumed_at: u64 },
+
 	/// Connection not found for the given key
 	#[error("Connection not found: {0}")]
 	ConnectionNotFound(String),
@@ -50,11 +54,20 @@ pub enum TransportError {
 	#[error("Deserialization error: {0}")]
 	DeserializationError(String),
 
+	/// The publisher's schema fingerprint differs from the one this receiver expects
+	#[cfg(feature = "nats")]
+	#[error("Schema mismatch: expected fingerprint {expected}, got {got}")]
+	SchemaMismatch { expected: String, got: String },
+
 	/// NATS-specific error
 	#[cfg(feature = "nats")]
 	#[error("NATS error: {0}")]
 	NatsError(String),
 
+	/// A circuit breaker is open after repeated failures; retry after the given cooldown
+	#[error("Circuit open, retry in {0:?}")]
+	CircuitOpen(std::time::Duration),
+
 	/// Invalid method call for this transport (e.g., subject not supported)
 	#[error("Invalid operation for this transport: {0}")]
 	InvalidOperation(String),
@@ -9,18 +9,20 @@
 //! - Lock-free using async_broadcast channels
 //! - Per-connection channels for isolated communication
 //! - Global broadcast support
-//! - Overflow handling with configurable behavior
+//! - Named subjects with request/reply
+//! - Per-subscriber buffers with their own overflow policy
+//! - Per-subject sequence numbers, with optional in-order delivery
 //!
 //! # Example
 //!
 //! ```rust,no_run
-//! use transport::inmem::InMemTransport;
-//! use transport::traits::Transport;
+//! use some_transport::inmem::InMemTransport;
+//! use some_transport::traits::Transport;
 //!
 //! #[tokio::main]
 //! async fn main() {
 //!     // Create transport with buffer size
-//!     let (transport, mut main_rx) = InMemTransport::<String>::with_receiver(100);
+//!     let (transport, mut main_rx) = InMemTransport::<String>::with_receiver(100).await;
 //!     
 //!     // Subscribe to broadcasts
 //!     tokio::spawn(async move {
@@ -44,6 +46,22 @@
 //!     }
 //! }
 //! ```
+//!
+//! # Migrating from the shared broadcast channel
+//!
+//! Broadcasts used to go through one `async_broadcast` channel shared by every
+//! subscriber. Each subscriber now has its own buffer, which changes the public API:
+//!
+//! - [`InMemReceiver`] wraps a `Receiver<Envelope<E>>` and its field is private;
+//!   build one with [`InMemReceiver::new`] and reach the channel through
+//!   [`inner`](InMemReceiver::inner) or [`into_inner`](InMemReceiver::into_inner)
+//!   instead of `.0`.
+//! - `InMemTransport::main_sender()` is gone, as there is no shared sender to
+//!   return. Use [`total_receivers`](crate::Transport::total_receivers) or
+//!   [`subscriber_policies`](InMemTransport::subscriber_policies) for diagnostics.
+//! - [`InMemTransport::is_closed`](crate::Transport::is_closed) reports whether
+//!   [`close`](InMemTransport::close) was called; whether one subscriber's
+//!   channel is closed is [`InMemReceiver::is_closed`].
 
 #![cfg(feature = "inmem")]
 
@@ -51,5 +69,5 @@ mod receiver;
 mod transport;
 
 // Re-export public types
-pub use receiver::InMemReceiver;
-pub use transport::InMemTransport;
+pub use receiver::{Envelope, InMemReceiver};
+pub use transport::{InMemTransport, OverflowPolicy};
@@ -4,6 +4,46 @@ use crate::error::{Result, TransportError};
 use crate::receiver::ReceiverTrait;
 use async_broadcast::{Receiver, RecvError, TryRecvError};
 use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A message on an in-memory channel, with the moment it stops being worth delivering
+#[derive(Clone, Debug)]
+pub struct Envelope<E> {
+	pub event: E,
+	/// Set when the transport has a message TTL; `None` never expires
+	pub expires_at: Option<Instant>,
+	/// Position among the messages published on the same subject (the
+	/// broadcast stream, one connection channel or a named subject), assigned at publish
+	pub sequence: u64,
+	/// Subject the sender waits for a reply on, if it was sent as a request
+	pub reply: Option<String>,
+}
+
+impl<E> Envelope<E> {
+	/// Wraps an event that never expires.
+	#[inline]
+	pub const fn new(event: E) -> Self {
+		Self {
+			event,
+			expires_at: None,
+			sequence: 0,
+			reply: None,
+		}
+	}
+
+	fn is_expired(&self) -> bool {
+		self.expires_at.is_some_and(|expires_at| expires_at <= Instant::now())
+	}
+}
+
+impl<E> From<E> for Envelope<E> {
+	fn from(event: E) -> Self {
+		Self::new(event)
+	}
+}
 
 /// In-memory receiver implementation using `async_broadcast`.
 ///
@@ -11,45 +51,221 @@ use async_trait::async_trait;
 /// the generic `ReceiverTrait` interface, allowing it to work seamlessly
 /// with the transport-agnostic `TransportReceiver` wrapper.
 ///
+/// Receivers handed out by [`InMemTransport`](super::InMemTransport) own their
+/// buffer, so [`dropped`](Self::dropped) reports only messages this receiver lost.
+/// Messages whose TTL ran out while buffered are skipped and counted in
+/// [`expired`](Self::expired).
+///
+/// Concurrent publishers can interleave so that messages reach a receiver out
+/// of sequence. An [`ordered`](Self::ordered) receiver buffers them and hands
+/// them over in sequence order instead.
+///
 /// # Example
 /// ```rust,no_run
 /// use async_broadcast::broadcast;
-/// use transport::inmem::InMemReceiver;
-/// use transport::receiver::{TransportReceiver, ReceiverTrait};
+/// use some_transport::inmem::InMemReceiver;
+/// use some_transport::receiver::{TransportReceiver, ReceiverTrait};
+///
+/// use some_transport::inmem::Envelope;
 ///
-/// let (tx, rx) = broadcast::<String>(10);
-/// let receiver = InMemReceiver(rx);
+/// let (tx, rx) = broadcast::<Envelope<String>>(10);
+/// let receiver = InMemReceiver::new(rx);
 /// let mut transport_rx = TransportReceiver::new(receiver);
 ///
 /// // Now you can use it like any other transport receiver
 /// // let msg = transport_rx.recv().await?;
 /// ```
 #[derive(Clone)]
-pub struct InMemReceiver<E>(pub Receiver<E>);
+pub struct InMemReceiver<E> {
+	receiver: Receiver<Envelope<E>>,
+	dropped: Arc<AtomicU64>,
+	expired: u64,
+	reorder: Option<Reorder<E>>,
+}
+
+/// Messages that arrived ahead of their turn on an ordered receiver
+#[derive(Clone)]
+struct Reorder<E> {
+	/// Sequence number to hand over next
+	next: u64,
+	/// How many later messages may wait on a missing one before it is given up on
+	window: usize,
+	/// Waiting messages by sequence; `None` marks one that expired in the buffer
+	pending: BTreeMap<u64, Option<Envelope<E>>>,
+}
+
+impl<E> Reorder<E> {
+	fn push(&mut self, sequence: u64, envelope: Option<Envelope<E>>) {
+		// Anything behind `next` was already skipped over as a gap
+		if sequence >= self.next {
+			self.pending.insert(sequence, envelope);
+		}
+	}
+
+	/// The next message in sequence, or a gap once the missing one is given up on
+	///
+	/// `flush` gives up straight away, for when nothing more can arrive.
+	fn pop(&mut self, flush: bool) -> Option<Result<Envelope<E>>> {
+		while let Some(slot) = self.pending.remove(&self.next) {
+			self.next += 1;
+			if let Some(envelope) = slot {
+				return Some(Ok(envelope));
+			}
+		}
+
+		let &resumed_at = self.pending.keys().next()?;
+		if !flush && self.pending.len() <= self.window {
+			return None;
+		}
+		let expected = std::mem::replace(&mut self.next, resumed_at);
+		Some(Err(TransportError::SequenceGap { expected, resumed_at }))
+	}
+}
 
 impl<E> InMemReceiver<E> {
 	/// Creates a new in-memory receiver from an `async_broadcast::Receiver`.
 	#[inline]
-	pub fn new(receiver: Receiver<E>) -> Self {
-		Self(receiver)
+	pub fn new(receiver: Receiver<Envelope<E>>) -> Self {
+		Self::with_drop_counter(receiver, Arc::default())
+	}
+
+	/// Creates a receiver whose drop count is shared with the sending side.
+	#[inline]
+	pub(super) const fn with_drop_counter(receiver: Receiver<Envelope<E>>, dropped: Arc<AtomicU64>) -> Self {
+		Self {
+			receiver,
+			dropped,
+			expired: 0,
+			reorder: None,
+		}
+	}
+
+	/// Hands messages over in sequence order, starting at `first_sequence`.
+	///
+	/// Messages that arrive early wait until the ones before them have been
+	/// received. Once more than `window` are waiting on a missing message (or
+	/// the channel closes), `recv` returns [`TransportError::SequenceGap`] and
+	/// carries on from the oldest waiting one; if the missing message turns
+	/// up later it is discarded.
+	#[must_use]
+	pub fn ordered(mut self, first_sequence: u64, window: usize) -> Self {
+		self.reorder = Some(Reorder {
+			next: first_sequence,
+			window,
+			pending: BTreeMap::new(),
+		});
+		self
+	}
+
+	/// Number of messages discarded because this receiver's buffer was full.
+	#[inline]
+	#[must_use]
+	pub fn dropped(&self) -> u64 {
+		self.dropped.load(Ordering::Relaxed)
+	}
+
+	/// Number of messages skipped because their TTL ran out before they were received.
+	#[inline]
+	#[must_use]
+	pub const fn expired(&self) -> u64 {
+		self.expired
+	}
+
+	/// Whether this subscriber's channel is closed.
+	///
+	/// True once the transport is closed. Messages buffered before the close
+	/// can still be received.
+	#[inline]
+	#[must_use]
+	pub fn is_closed(&self) -> bool {
+		self.receiver.is_closed()
 	}
 
 	/// Returns a reference to the underlying receiver.
 	#[inline]
-	pub fn inner(&self) -> &Receiver<E> {
-		&self.0
+	pub const fn inner(&self) -> &Receiver<Envelope<E>> {
+		&self.receiver
 	}
 
 	/// Returns a mutable reference to the underlying receiver.
 	#[inline]
-	pub fn inner_mut(&mut self) -> &mut Receiver<E> {
-		&mut self.0
+	pub const fn inner_mut(&mut self) -> &mut Receiver<Envelope<E>> {
+		&mut self.receiver
 	}
 
 	/// Consumes the wrapper and returns the underlying receiver.
 	#[inline]
-	pub fn into_inner(self) -> Receiver<E> {
-		self.0
+	pub fn into_inner(self) -> Receiver<Envelope<E>> {
+		self.receiver
+	}
+}
+
+impl<E: Clone> InMemReceiver<E> {
+	/// Receives the next message with its sequence number.
+	///
+	/// # Errors
+	///
+	/// As [`ReceiverTrait::recv`], plus [`TransportError::SequenceGap`] on an
+	/// [`ordered`](Self::ordered) receiver that skipped a missing message.
+	pub async fn recv_envelope(&mut self) -> Result<Envelope<E>> {
+		loop {
+			if let Some(ready) = self.reorder.as_mut().and_then(|reorder| reorder.pop(false)) {
+				return ready;
+			}
+			let received = match self.receiver.recv().await {
+				Ok(envelope) => envelope,
+				Err(RecvError::Closed) => return self.flush().unwrap_or(Err(TransportError::Closed)),
+				Err(RecvError::Overflowed(n)) => return Err(TransportError::Overflowed(n)),
+			};
+			if let Some(envelope) = self.accept(received) {
+				return Ok(envelope);
+			}
+		}
+	}
+
+	/// Like [`recv_envelope`](Self::recv_envelope), without waiting.
+	///
+	/// # Errors
+	///
+	/// As [`ReceiverTrait::try_recv`], plus [`TransportError::SequenceGap`] on
+	/// an [`ordered`](Self::ordered) receiver that skipped a missing message.
+	pub fn try_recv_envelope(&mut self) -> Result<Envelope<E>> {
+		loop {
+			if let Some(ready) = self.reorder.as_mut().and_then(|reorder| reorder.pop(false)) {
+				return ready;
+			}
+			let received = match self.receiver.try_recv() {
+				Ok(envelope) => envelope,
+				Err(TryRecvError::Closed) => return self.flush().unwrap_or(Err(TransportError::Closed)),
+				Err(TryRecvError::Overflowed(n)) => return Err(TransportError::Overflowed(n)),
+				Err(TryRecvError::Empty) => return Err(TransportError::Other("Channel empty".into())),
+			};
+			if let Some(envelope) = self.accept(received) {
+				return Ok(envelope);
+			}
+		}
+	}
+
+	/// Counts an expired message, or queues the message if this receiver is
+	/// ordered; returns it only if it can be handed over now
+	fn accept(&mut self, envelope: Envelope<E>) -> Option<Envelope<E>> {
+		let expired = envelope.is_expired();
+		if expired {
+			self.expired += 1;
+		}
+		match &mut self.reorder {
+			// Expired messages keep their place so they aren't mistaken for a gap
+			Some(reorder) => {
+				reorder.push(envelope.sequence, (!expired).then_some(envelope));
+				None
+			}
+			None => (!expired).then_some(envelope),
+		}
+	}
+
+	/// Hands over what an ordered receiver still holds once nothing more can arrive
+	fn flush(&mut self) -> Option<Result<Envelope<E>>> {
+		self.reorder.as_mut().and_then(|reorder| reorder.pop(true))
 	}
 }
 
@@ -59,26 +275,21 @@ where
 	E: Clone + Send + Sync + 'static,
 {
 	async fn recv(&mut self) -> Result<E> {
-		match self.0.recv().await {
-			Ok(event) => Ok(event),
-			Err(RecvError::Closed) => Err(TransportError::Closed),
-			Err(RecvError::Overflowed(n)) => Err(TransportError::Overflowed(n)),
-		}
+		self.recv_envelope().await.map(|envelope| envelope.event)
 	}
 
 	fn try_recv(&mut self) -> Result<E> {
-		match self.0.try_recv() {
-			Ok(event) => Ok(event),
-			Err(TryRecvError::Closed) => Err(TransportError::Closed),
-			Err(TryRecvError::Overflowed(n)) => Err(TransportError::Overflowed(n)),
-			Err(TryRecvError::Empty) => Err(TransportError::Other("Channel empty".into())),
-		}
+		self.try_recv_envelope().map(|envelope| envelope.event)
+	}
+
+	async fn recv_request(&mut self) -> Result<(E, Option<String>)> {
+		self.recv_envelope().await.map(|envelope| (envelope.event, envelope.reply))
 	}
 }
 
 // Implement From for ergonomic conversions
-impl<E> From<Receiver<E>> for InMemReceiver<E> {
-	fn from(receiver: Receiver<E>) -> Self {
+impl<E> From<Receiver<Envelope<E>>> for InMemReceiver<E> {
+	fn from(receiver: Receiver<Envelope<E>>) -> Self {
 		Self::new(receiver)
 	}
 }
@@ -91,11 +302,11 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_inmem_receiver_recv() {
-		let (mut tx, rx) = broadcast::<String>(10);
+		let (tx, rx) = broadcast::<Envelope<String>>(10);
 		let receiver = InMemReceiver::new(rx);
 		let mut transport_rx = TransportReceiver::new(receiver);
 
-		tx.broadcast("test message".to_string()).await.ok();
+		tx.broadcast("test message".to_string().into()).await.ok();
 
 		let result = transport_rx.recv().await;
 		assert!(result.is_ok());
@@ -104,11 +315,11 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_inmem_receiver_try_recv() {
-		let (mut tx, rx) = broadcast::<i32>(10);
+		let (tx, rx) = broadcast::<Envelope<i32>>(10);
 		let receiver = InMemReceiver::new(rx);
 		let mut transport_rx = TransportReceiver::new(receiver);
 
-		tx.broadcast(42).await.ok();
+		tx.broadcast(42.into()).await.ok();
 
 		// Small delay to ensure message is delivered
 		tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
@@ -120,7 +331,7 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_inmem_receiver_closed() {
-		let (tx, rx) = broadcast::<String>(10);
+		let (tx, rx) = broadcast::<Envelope<String>>(10);
 		let receiver = InMemReceiver::new(rx);
 		let mut transport_rx = TransportReceiver::new(receiver);
 
@@ -132,9 +343,38 @@ mod tests {
 		assert!(matches!(result.unwrap_err(), TransportError::Closed));
 	}
 
+	#[tokio::test]
+	async fn test_ordered_receiver_reorders_and_reports_gaps() {
+		let (tx, rx) = broadcast::<Envelope<u64>>(10);
+		let mut receiver = InMemReceiver::new(rx).ordered(0, 2);
+		let send = |sequence: u64| {
+			let tx = tx.clone();
+			async move {
+				tx.broadcast(Envelope { sequence, ..sequence.into() }).await.unwrap();
+			}
+		};
+
+		for sequence in [1, 0, 3, 4, 5] {
+			send(sequence).await;
+		}
+		assert_eq!(receiver.recv_envelope().await.unwrap().sequence, 0);
+		assert_eq!(receiver.recv_envelope().await.unwrap().sequence, 1);
+
+		// 2 never arrives; with three messages waiting on it, it is skipped
+		assert!(matches!(receiver.recv_envelope().await, Err(TransportError::SequenceGap { expected: 2, resumed_at: 3 })));
+		assert_eq!(receiver.try_recv_envelope().unwrap().sequence, 3);
+
+		// The missing message arriving late is discarded
+		send(2).await;
+		drop(tx);
+		assert_eq!(receiver.recv_envelope().await.unwrap().sequence, 4);
+		assert_eq!(receiver.recv_envelope().await.unwrap().sequence, 5);
+		assert!(matches!(receiver.recv_envelope().await, Err(TransportError::Closed)));
+	}
+
 	#[tokio::test]
 	async fn test_from_conversion() {
-		let (_tx, rx) = broadcast::<String>(10);
+		let (_tx, rx) = broadcast::<Envelope<String>>(10);
 		let _receiver: InMemReceiver<String> = rx.into();
 	}
 }
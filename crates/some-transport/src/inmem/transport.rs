@@ -1,12 +1,58 @@
 #![cfg(feature = "inmem")]
 
-use super::receiver::InMemReceiver; // ← Import local implementation
+use super::receiver::{Envelope, InMemReceiver}; // ← Import local implementation
 use crate::error::{Result, TransportError};
 use crate::receiver::TransportReceiver; // ← Import from shared core
 use crate::traits::Transport;
-use async_broadcast::{broadcast, Sender};
+use async_broadcast::{broadcast, Sender, TrySendError};
 use dashmap::DashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// What a subscriber's buffer does when a broadcast arrives and it is full.
+///
+/// The policy only affects the subscriber it is set on; other subscribers
+/// keep receiving at their own pace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+	/// Evict the oldest buffered message to make room. The receiver sees
+	/// `TransportError::Overflowed` before resuming with newer messages.
+	#[default]
+	DropOldest,
+	/// Discard the incoming message and keep what is already buffered.
+	DropNewest,
+}
+
+/// Sending half of one subscriber's private buffer.
+struct Subscriber<E> {
+	/// Subject subscribed to; `None` for the broadcast stream
+	subject: Option<String>,
+	sender: Sender<Envelope<E>>,
+	policy: OverflowPolicy,
+	dropped: Arc<AtomicU64>,
+}
+
+impl<E: Clone> Subscriber<E> {
+	/// Offers `event` to this subscriber, returning false once its receiver is gone.
+	fn deliver(&self, event: &Envelope<E>) -> bool {
+		match self.sender.try_broadcast(event.clone()) {
+			Ok(None) => true,
+			Ok(Some(_)) | Err(TrySendError::Full(_)) => {
+				self.dropped.fetch_add(1, Ordering::Relaxed);
+				true
+			}
+			Err(TrySendError::Closed(_)) => false,
+			Err(TrySendError::Inactive(_)) => self.sender.receiver_count() > 0,
+		}
+	}
+}
+
+/// Sending half of one connection channel.
+struct Channel<E> {
+	sender: Sender<Envelope<E>>,
+	next_sequence: AtomicU64,
+}
 
 /// In-memory transport implementation using async_broadcast.
 ///
@@ -20,15 +66,24 @@ use std::sync::Arc;
 ///
 /// # Architecture
 ///
-/// - **Main channel**: Global broadcast to all subscribers
+/// - **Broadcast**: Fans out to every subscriber, each with its own bounded
+///   buffer and [`OverflowPolicy`], so a slow consumer only drops its own messages
 /// - **Connection channels**: Isolated channels per connection key
+/// - **Subjects**: [`send_to_subject`](Transport::send_to_subject) reaches only
+///   subscribers of that subject, and [`request`](Transport::request) waits
+///   for one of them to reply. Unlike NATS a request never times out, so
+///   bound it yourself if the responder may not answer
 /// - **Lock-free**: Uses `DashMap` and `async_broadcast` for concurrency
+/// - **Sequencing**: Every message carries a sequence number counted per subject
+///   (the broadcast stream, one connection channel or a named subject). Concurrent publishers
+///   can still reach a subscriber out of order; see
+///   [`subscribe_ordered`](Self::subscribe_ordered)
 ///
 /// # Example
 ///
 /// ```rust,no_run
-/// use transport::inmem::InMemTransport;
-/// use transport::traits::Transport;
+/// use some_transport::inmem::InMemTransport;
+/// use some_transport::traits::Transport;
 ///
 /// #[tokio::main]
 /// async fn main() {
@@ -36,7 +91,7 @@ use std::sync::Arc;
 ///     let transport = InMemTransport::<String>::new(100);
 ///     
 ///     // Subscribe to global broadcasts
-///     let mut rx = transport.subscribe();
+///     let mut rx = transport.subscribe().await;
 ///     
 ///     // Broadcast to all subscribers
 ///     transport.broadcast("Hello world!".to_string()).await.ok();
@@ -51,9 +106,16 @@ pub struct InMemTransport<E>
 where
 	E: Clone + Send + Sync + 'static,
 {
-	main_sender: Sender<E>,
-	_keep_alive: async_broadcast::Receiver<E>, // Keep channel open
-	connection_channels: Arc<DashMap<String, Sender<E>>>,
+	buffer_size: usize,
+	subscribers: Arc<DashMap<u64, Subscriber<E>>>,
+	next_subscriber_id: Arc<AtomicU64>,
+	connection_channels: Arc<DashMap<String, Channel<E>>>,
+	next_broadcast_sequence: Arc<AtomicU64>,
+	/// Next sequence number for each named subject
+	subject_sequences: Arc<DashMap<String, u64>>,
+	next_inbox: Arc<AtomicU64>,
+	message_ttl: Option<Duration>,
+	closed: Arc<AtomicBool>,
 }
 
 impl<E> InMemTransport<E>
@@ -64,33 +126,169 @@ where
 	///
 	/// # Arguments
 	///
-	/// * `buffer_size` - Maximum number of messages to buffer per channel.
-	///   When overflow occurs, older messages are dropped.
+	/// * `buffer_size` - Default number of messages buffered per subscriber.
+	///   When a subscriber falls behind, its oldest messages are dropped.
 	///
 	/// # Example
 	///
 	/// ```rust,no_run
-	/// use transport::inmem::InMemTransport;
+	/// use some_transport::inmem::InMemTransport;
 	///
 	/// let transport = InMemTransport::<String>::new(100);
 	/// ```
 	#[must_use]
 	pub fn new(buffer_size: usize) -> Self {
-		let (mut main_sender, keep_alive) = broadcast::<E>(buffer_size);
-		main_sender.set_await_active(false);
-		main_sender.set_overflow(true);
-
 		Self {
-			main_sender,
-			_keep_alive: keep_alive,
+			buffer_size,
+			subscribers: Arc::new(DashMap::new()),
+			next_subscriber_id: Arc::new(AtomicU64::new(0)),
 			connection_channels: Arc::new(DashMap::new()),
+			next_broadcast_sequence: Arc::new(AtomicU64::new(0)),
+			subject_sequences: Arc::new(DashMap::new()),
+			next_inbox: Arc::new(AtomicU64::new(0)),
+			message_ttl: None,
+			closed: Arc::new(AtomicBool::new(false)),
+		}
+	}
+
+	/// Expires every message sent or broadcast `ttl` after it was published.
+	///
+	/// Receivers skip expired messages instead of delivering them, counting
+	/// them in [`InMemReceiver::expired`]. Use this for live state, where a
+	/// message that arrives late is worse than none.
+	#[must_use]
+	pub const fn with_message_ttl(mut self, ttl: Duration) -> Self {
+		self.message_ttl = Some(ttl);
+		self
+	}
+
+	/// Wraps an event being published now.
+	fn envelope(&self, event: E, sequence: u64) -> Envelope<E> {
+		Envelope {
+			event,
+			expires_at: self.message_ttl.map(|ttl| Instant::now() + ttl),
+			sequence,
+			reply: None,
+		}
+	}
+
+	/// Offers `event` to every subscriber of `subject` (`None` for the broadcast
+	/// stream), returning how many are still listening
+	fn publish(&self, subject: Option<&str>, event: E, reply: Option<String>) -> Result<usize> {
+		if self.is_closed() {
+			return Err(TransportError::Closed);
+		}
+		// Never awaits a subscriber: a full buffer drops for that subscriber only
+		let sequence = subject.map_or_else(
+			|| self.next_broadcast_sequence.fetch_add(1, Ordering::SeqCst),
+			|subject| {
+				let mut next = self.subject_sequences.entry(subject.to_owned()).or_default();
+				*next += 1;
+				*next - 1
+			},
+		);
+		let event = Envelope {
+			reply,
+			..self.envelope(event, sequence)
+		};
+		let mut delivered = 0;
+		self.subscribers.retain(|_, subscriber| {
+			if subscriber.subject.as_deref() != subject {
+				return true;
+			}
+			let alive = subscriber.deliver(&event);
+			delivered += usize::from(alive);
+			alive
+		});
+		Ok(delivered)
+	}
+
+	/// Subscribes to broadcasts with a private buffer of `capacity` messages.
+	///
+	/// When the buffer is full, `policy` decides which message this subscriber
+	/// loses; the loss is counted on the receiver and nobody else is affected.
+	///
+	/// # Example
+	///
+	/// ```rust,no_run
+	/// use some_transport::inmem::{InMemTransport, OverflowPolicy};
+	///
+	/// let transport = InMemTransport::<String>::new(100);
+	/// let audit_log = transport.subscribe_with_policy(1_000, OverflowPolicy::DropNewest);
+	/// ```
+	#[must_use]
+	pub fn subscribe_with_policy(&self, capacity: usize, policy: OverflowPolicy) -> TransportReceiver<E, InMemReceiver<E>> {
+		TransportReceiver::new(self.subscribe_to(None, capacity, policy).1)
+	}
+
+	/// Registers a subscriber of `subject`, returning its id with the receiver
+	fn subscribe_to(&self, subject: Option<&str>, capacity: usize, policy: OverflowPolicy) -> (u64, InMemReceiver<E>) {
+		let (mut sender, receiver) = broadcast::<Envelope<E>>(capacity);
+		sender.set_await_active(false);
+		sender.set_overflow(policy == OverflowPolicy::DropOldest);
+
+		let dropped = Arc::new(AtomicU64::new(0));
+		let id = self.next_subscriber_id.fetch_add(1, Ordering::Relaxed);
+		self.subscribers.insert(
+			id,
+			Subscriber {
+				subject: subject.map(str::to_owned),
+				sender,
+				policy,
+				dropped: Arc::clone(&dropped),
+			},
+		);
+		// Checked after inserting, so a concurrent `close` can't miss this subscriber
+		if self.is_closed() {
+			if let Some((_, subscriber)) = self.subscribers.remove(&id) {
+				subscriber.sender.close();
+			}
+		}
+
+		(id, InMemReceiver::with_drop_counter(receiver, dropped))
+	}
+
+	/// Subscribes to broadcasts, receiving them strictly in sequence order.
+	///
+	/// Broadcasts racing each other can land in a subscriber's buffer out of
+	/// order; this receiver holds early ones back until the messages before
+	/// them arrive. If more than `window` are held back waiting on one that
+	/// was dropped (the buffer overflowed, or the message expired before being
+	/// delivered), `recv()` returns `TransportError::SequenceGap` and carries
+	/// on past it. See [`InMemReceiver::ordered`].
+	#[must_use]
+	pub fn subscribe_ordered(&self, capacity: usize, policy: OverflowPolicy, window: usize) -> TransportReceiver<E, InMemReceiver<E>> {
+		let receiver = self.subscribe_with_policy(capacity, policy);
+		// Read once subscribed: every broadcast numbered from here on reaches this receiver
+		let first_sequence = self.next_broadcast_sequence.load(Ordering::SeqCst);
+		TransportReceiver::new(receiver.into_inner().ordered(first_sequence, window))
+	}
+
+	/// Shuts the transport down, ending every subscriber and connection channel.
+	///
+	/// Like a NATS drain, receivers still get the messages already buffered
+	/// for them; after that `recv()` returns `TransportError::Closed`, so
+	/// receive loops terminate instead of waiting forever. Sends, broadcasts and
+	/// new subscriptions on a closed transport fail or come back closed. Clones
+	/// share the shutdown.
+	pub fn close(&self) {
+		if self.closed.swap(true, Ordering::SeqCst) {
+			return;
+		}
+		for subscriber in self.subscribers.iter() {
+			subscriber.sender.close();
+		}
+		self.subscribers.clear();
+		for channel in self.connection_channels.iter() {
+			channel.sender.close();
 		}
+		self.connection_channels.clear();
 	}
 
-	/// Returns the underlying sender (for diagnostics only).
+	/// Overflow policies of the live subscribers (for diagnostics only).
 	#[must_use]
-	pub fn main_sender(&self) -> &Sender<E> {
-		&self.main_sender
+	pub fn subscriber_policies(&self) -> Vec<OverflowPolicy> {
+		self.subscribers.iter().filter(|s| s.sender.receiver_count() > 0).map(|s| s.policy).collect()
 	}
 }
 
@@ -102,10 +300,21 @@ where
 	type Receiver = TransportReceiver<E, InMemReceiver<E>>;
 
 	async fn open_channel(&self, connection_key: &str) -> Self::Receiver {
-		let (mut sender, receiver) = broadcast::<E>(100);
+		let (mut sender, receiver) = broadcast::<Envelope<E>>(100);
 		sender.set_await_active(false);
 		sender.set_overflow(true);
-		self.connection_channels.insert(connection_key.to_string(), sender);
+		self.connection_channels.insert(
+			connection_key.to_string(),
+			Channel {
+				sender,
+				next_sequence: AtomicU64::new(0),
+			},
+		);
+		if self.is_closed() {
+			if let Some((_, channel)) = self.connection_channels.remove(connection_key) {
+				channel.sender.close();
+			}
+		}
 
 		TransportReceiver::new(InMemReceiver::new(receiver))
 	}
@@ -116,44 +325,61 @@ where
 	}
 
 	async fn send(&self, connection_key: &str, event: E) -> Result<()> {
-		if let Some(sender) = self.connection_channels.get(connection_key) {
-			sender.broadcast(event).await.map(|_| ()).map_err(|e| TransportError::SendFailed(e.to_string()))
+		if self.is_closed() {
+			return Err(TransportError::Closed);
+		}
+		if let Some(channel) = self.connection_channels.get(connection_key) {
+			let sequence = channel.next_sequence.fetch_add(1, Ordering::SeqCst);
+			channel
+				.sender
+				.broadcast(self.envelope(event, sequence))
+				.await
+				.map(|_| ())
+				.map_err(|e| TransportError::SendFailed(e.to_string()))
 		} else {
 			Err(TransportError::ConnectionNotFound(connection_key.to_string()))
 		}
 	}
 
 	async fn broadcast(&self, event: E) -> Result<usize> {
-		self
-			.main_sender
-			.broadcast(event)
-			.await
-			.map(|res| res.is_some() as usize)
-			.map_err(|e| TransportError::BroadcastFailed(e.to_string()))
+		self.publish(None, event, None)
+	}
+
+	async fn send_to_subject(&self, subject: &str, event: E) -> Result<()> {
+		self.publish(Some(subject), event, None).map(|_| ())
 	}
 
-	async fn send_to_subject(&self, _subject: &str, _event: E) -> Result<()> {
-		Err(TransportError::InvalidOperation(format!(
-			"broadcast is not supported on this transport (async_broadcast channel only!"
-		)))
+	/// Fails straight away if nobody is subscribed to `subject`.
+	async fn request(&self, subject: &str, event: E) -> Result<E> {
+		let inbox = ["_INBOX.", &self.next_inbox.fetch_add(1, Ordering::Relaxed).to_string()].concat();
+		let (id, mut replies) = self.subscribe_to(Some(&inbox), 1, OverflowPolicy::DropNewest);
+
+		let result = match self.publish(Some(subject), event, Some(inbox)) {
+			Ok(0) => Err(TransportError::SendFailed(["no responders on ", subject].concat())),
+			Ok(_) => replies.recv_envelope().await.map(|envelope| envelope.event),
+			Err(e) => Err(e),
+		};
+		// Nothing publishes to the inbox again, so nothing would prune it
+		self.subscribers.remove(&id);
+		result
 	}
 
 	async fn subscribe(&self) -> TransportReceiver<E, InMemReceiver<E>> {
-		let receiver = self.main_sender.new_receiver();
-		TransportReceiver::new(InMemReceiver::new(receiver))
+		self.subscribe_with_policy(self.buffer_size, OverflowPolicy::default())
 	}
 
-	async fn subscribe_to_subject(&self, _subject: &str) -> TransportReceiver<E, InMemReceiver<E>> {
-		let receiver = self.main_sender.new_receiver();
-		TransportReceiver::new(InMemReceiver::new(receiver))
+	/// Receives only what is sent to `subject`, not broadcasts.
+	async fn subscribe_to_subject(&self, subject: &str) -> TransportReceiver<E, InMemReceiver<E>> {
+		TransportReceiver::new(self.subscribe_to(Some(subject), self.buffer_size, OverflowPolicy::default()).1)
 	}
 
 	fn total_receivers(&self) -> usize {
-		self.main_sender.receiver_count()
+		self.subscribers.iter().filter(|s| s.sender.receiver_count() > 0).count()
 	}
 
+	/// The broadcast side stays open until [`close`](InMemTransport::close) is called.
 	fn is_closed(&self) -> bool {
-		self.main_sender.is_closed()
+		self.closed.load(Ordering::SeqCst)
 	}
 
 	fn active_channels(&self) -> usize {
@@ -174,12 +400,12 @@ where
 	/// # Example
 	///
 	/// ```rust,no_run
-	/// use transport::inmem::InMemTransport;
-	/// use transport::traits::Transport;
+	/// use some_transport::inmem::InMemTransport;
+	/// use some_transport::traits::Transport;
 	///
 	/// #[tokio::main]
 	/// async fn main() {
-	///     let (transport, mut rx) = InMemTransport::<String>::with_receiver(100);
+	///     let (transport, mut rx) = InMemTransport::<String>::with_receiver(100).await;
 	///     
 	///     // Can immediately start receiving
 	///     tokio::spawn(async move {
@@ -205,7 +431,7 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_broadcast() {
-		let (transport, mut rx) = InMemTransport::<String>::with_receiver(10);
+		let (transport, mut rx) = InMemTransport::<String>::with_receiver(10).await;
 
 		transport.broadcast("test message".to_string()).await.unwrap();
 
@@ -215,7 +441,7 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_multiple_subscribers() {
-		let (transport, mut rx1) = InMemTransport::<i32>::with_receiver(10);
+		let (transport, mut rx1) = InMemTransport::<i32>::with_receiver(10).await;
 		let mut rx2 = transport.subscribe().await;
 		let mut rx3 = transport.subscribe().await;
 
@@ -261,7 +487,7 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_total_receivers() {
-		let (transport, _rx1) = InMemTransport::<String>::with_receiver(10);
+		let (transport, _rx1) = InMemTransport::<String>::with_receiver(10).await;
 		assert_eq!(transport.total_receivers(), 1);
 
 		let _rx2 = transport.subscribe().await;
@@ -271,12 +497,167 @@ mod tests {
 		assert_eq!(transport.total_receivers(), 3);
 	}
 
+	#[tokio::test]
+	async fn test_slow_subscriber_drops_only_its_own_messages() {
+		let transport = InMemTransport::<u32>::new(16);
+		let mut fast = transport.subscribe().await;
+		let mut slow = transport.subscribe_with_policy(2, OverflowPolicy::DropNewest);
+		let mut lagging = transport.subscribe_with_policy(2, OverflowPolicy::DropOldest);
+
+		let mut received = Vec::new();
+		for i in 0..10 {
+			assert_eq!(transport.broadcast(i).await.unwrap(), 3);
+			received.push(fast.recv().await.unwrap());
+		}
+
+		// The fast subscriber loses nothing
+		assert_eq!(received, (0..10).collect::<Vec<_>>());
+		assert_eq!(fast.inner().dropped(), 0);
+
+		// DropNewest keeps the first messages it buffered
+		assert_eq!(slow.inner().dropped(), 8);
+		assert_eq!(slow.recv().await.unwrap(), 0);
+		assert_eq!(slow.recv().await.unwrap(), 1);
+
+		// DropOldest reports the gap, then resumes with the latest messages
+		assert_eq!(lagging.inner().dropped(), 8);
+		assert!(matches!(lagging.recv().await, Err(TransportError::Overflowed(8))));
+		assert_eq!(lagging.recv().await.unwrap(), 8);
+		assert_eq!(lagging.recv().await.unwrap(), 9);
+	}
+
+	#[tokio::test]
+	async fn test_dropped_subscriber_is_pruned() {
+		let transport = InMemTransport::<u32>::new(4);
+		let _kept = transport.subscribe().await;
+		let gone = transport.subscribe_with_policy(1, OverflowPolicy::DropNewest);
+		let policies = transport.subscriber_policies();
+		assert_eq!(policies.len(), 2);
+		assert!(policies.contains(&OverflowPolicy::DropOldest) && policies.contains(&OverflowPolicy::DropNewest));
+
+		drop(gone);
+		assert_eq!(transport.broadcast(1).await.unwrap(), 1);
+		assert_eq!(transport.total_receivers(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_expired_messages_are_dropped() {
+		let transport = InMemTransport::<u32>::new(8).with_message_ttl(Duration::from_millis(20));
+		let mut rx = transport.subscribe().await;
+
+		transport.broadcast(1).await.unwrap();
+		tokio::time::sleep(Duration::from_millis(40)).await;
+		transport.broadcast(2).await.unwrap();
+
+		// The stale message is skipped, not delivered
+		assert_eq!(rx.recv().await.unwrap(), 2);
+		assert_eq!(rx.inner().expired(), 1);
+		assert_eq!(rx.inner().dropped(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_sequence_numbers_count_per_subject() {
+		let transport = InMemTransport::<u32>::new(8);
+		let mut rx = transport.subscribe().await;
+		let mut channel_rx = transport.open_channel("conn").await;
+
+		transport.broadcast(1).await.unwrap();
+		transport.send("conn", 2).await.unwrap();
+		transport.broadcast(3).await.unwrap();
+
+		assert_eq!(rx.inner_mut().recv_envelope().await.unwrap().sequence, 0);
+		assert_eq!(rx.inner_mut().recv_envelope().await.unwrap().sequence, 1);
+		assert_eq!(channel_rx.inner_mut().recv_envelope().await.unwrap().sequence, 0);
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+	async fn test_ordered_subscriber_sees_concurrent_broadcasts_in_sequence() {
+		const PUBLISHERS: u64 = 8;
+		const PER_PUBLISHER: u64 = 200;
+		let total = PUBLISHERS * PER_PUBLISHER;
+
+		let transport = InMemTransport::<u64>::new(8);
+		// Joining late: numbering starts from wherever the transport is
+		transport.broadcast(0).await.unwrap();
+		let mut rx = transport.subscribe_ordered(usize::try_from(total).unwrap(), OverflowPolicy::DropNewest, 64);
+
+		let publishers: Vec<_> = (0..PUBLISHERS)
+			.map(|publisher| {
+				let transport = transport.clone();
+				tokio::spawn(async move {
+					for i in 0..PER_PUBLISHER {
+						transport.broadcast(publisher * PER_PUBLISHER + i).await.unwrap();
+						tokio::task::yield_now().await;
+					}
+				})
+			})
+			.collect();
+		for publisher in publishers {
+			publisher.await.unwrap();
+		}
+		transport.close();
+
+		let mut expected = 1;
+		while let Ok(envelope) = rx.inner_mut().recv_envelope().await {
+			assert_eq!(envelope.sequence, expected);
+			expected += 1;
+		}
+		assert_eq!(expected, total + 1);
+		assert_eq!(rx.inner().dropped(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_request_is_answered_on_its_reply_subject() {
+		let transport = InMemTransport::<u32>::new(8);
+		let mut broadcasts = transport.subscribe().await;
+		let mut doubler = transport.subscribe_to_subject("double").await;
+		let responder = transport.clone();
+		tokio::spawn(async move {
+			while let Ok((n, Some(reply))) = doubler.recv_request().await {
+				responder.send_to_subject(&reply, n * 2).await.unwrap();
+			}
+		});
+
+		assert_eq!(transport.request("double", 21).await.unwrap(), 42);
+		assert!(matches!(transport.request("triple", 1).await, Err(TransportError::SendFailed(_))));
+
+		// Subject traffic stays off the broadcast stream, and the inboxes are gone
+		assert!(broadcasts.try_recv().is_err());
+		assert_eq!(transport.total_receivers(), 2);
+	}
+
 	#[tokio::test]
 	async fn test_is_closed() {
 		let transport = InMemTransport::<String>::new(10);
 		assert!(!transport.is_closed());
 	}
 
+	#[tokio::test]
+	async fn test_close_ends_awaiting_receivers() {
+		let (transport, mut rx) = InMemTransport::<u32>::with_receiver(10).await;
+		let mut channel_rx = transport.open_channel("conn").await;
+		transport.broadcast(1).await.unwrap();
+
+		let waiting = tokio::spawn(async move { channel_rx.recv().await });
+		tokio::task::yield_now().await;
+		assert!(!rx.inner().is_closed());
+		transport.clone().close();
+		assert!(rx.inner().is_closed());
+
+		// The awaiting receiver is woken instead of hanging
+		let result = tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap();
+		assert!(matches!(result, Err(TransportError::Closed)));
+
+		// Buffered messages drain before the close is reported
+		assert_eq!(rx.recv().await.unwrap(), 1);
+		assert!(matches!(rx.recv().await, Err(TransportError::Closed)));
+
+		assert!(transport.is_closed());
+		assert_eq!(transport.active_channels(), 0);
+		assert!(matches!(transport.broadcast(2).await, Err(TransportError::Closed)));
+		assert!(matches!(transport.subscribe().await.recv().await, Err(TransportError::Closed)));
+	}
+
 	#[tokio::test]
 	async fn test_active_channels() {
 		let transport = InMemTransport::<String>::new(10);
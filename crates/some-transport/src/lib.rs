@@ -21,9 +21,9 @@
 //!
 //! #[cfg(feature = "inmem")]
 //! async fn example_inmem() {
-//!     use transport::InMemTransport;
+//!     use some_transport::InMemTransport;
 //!     
-//!     let (transport, mut rx) = InMemTransport::<String>::with_receiver(100);
+//!     let (transport, mut rx) = InMemTransport::<String>::with_receiver(100).await;
 //!     
 //!     transport.broadcast("Hello!".to_string()).await.ok();
 //!     
@@ -51,11 +51,13 @@
 //! ```
 
 // Core modules (always available)
+pub mod breaker;
 pub mod error;
 pub mod receiver;
 pub mod traits;
 
 // Re-export core types
+pub use breaker::{BreakerState, CircuitBreaker};
 pub use error::TransportError;
 pub use receiver::{ReceiverTrait, TransportReceiver};
 pub use traits::Transport;
@@ -75,10 +77,10 @@ pub mod nats;
 
 // Re-export transport types
 #[cfg(feature = "inmem")]
-pub use inmem::{InMemReceiver, InMemTransport};
+pub use inmem::{Envelope, InMemReceiver, InMemTransport, OverflowPolicy};
 
 #[cfg(feature = "nats")]
-pub use nats::{NatsConnectionPool, NatsReceiver, NatsTransport};
+pub use nats::{ConnectionState, JsonCodec, MessageCodec, NatsConnectionPool, NatsReceiver, NatsTransport, ProstCodec, ScopedSubscription};
 
 // Type aliases for convenience and ergonomics
 #[cfg(feature = "inmem")]
@@ -1,11 +1,22 @@
 #![cfg(feature = "nats")]
 
+mod codec;
+mod expiry;
 mod jetstream;
 mod pool;
 mod receiver;
+mod schema;
+mod scoped;
 mod transport;
 
+pub use codec::{JsonCodec, MessageCodec, ProstCodec};
+pub use expiry::EXPIRES_AT_HEADER;
 pub use jetstream::{AckHandle, DurableConsumer, JetStreamConfig, JetStreamPublisher};
 pub use pool::NatsConnectionPool;
 pub use receiver::NatsReceiver;
+pub use schema::{schema_fingerprint, SCHEMA_FINGERPRINT_HEADER};
+pub use scoped::ScopedSubscription;
 pub use transport::NatsTransport;
+
+/// Connection state reported by [`NatsTransport::connection_state_watch`].
+pub use async_nats::connection::State as ConnectionState;
@@ -0,0 +1,121 @@
+#![cfg(feature = "nats")]
+
+use crate::error::{Result, TransportError};
+use prost::Message;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Wire format used by [`NatsTransport`](super::NatsTransport) and its receivers.
+///
+/// A transport is parameterized over its codec, so different event channels
+/// can use different formats over the same connection without forking the
+/// transport. Codecs are cloned into every receiver, so keep them cheap.
+///
+/// # Example
+/// ```rust
+/// use some_transport::error::{Result, TransportError};
+/// use some_transport::nats::MessageCodec;
+///
+/// #[derive(Clone, Default)]
+/// struct TextCodec;
+///
+/// impl MessageCodec<String> for TextCodec {
+///     fn encode(&self, event: &String) -> Result<Vec<u8>> {
+///         Ok(event.as_bytes().to_vec())
+///     }
+///
+///     fn decode(&self, payload: &[u8]) -> Result<String> {
+///         String::from_utf8(payload.to_vec()).map_err(|e| TransportError::DeserializationError(e.to_string()))
+///     }
+/// }
+/// ```
+pub trait MessageCodec<E>: Clone + Send + Sync + 'static {
+	/// Serializes an event into a message payload.
+	///
+	/// # Errors
+	///
+	/// Returns [`TransportError::SerializationError`] if the event can't be encoded.
+	fn encode(&self, event: &E) -> Result<Vec<u8>>;
+
+	/// Deserializes a message payload into an event.
+	///
+	/// # Errors
+	///
+	/// Returns [`TransportError::DeserializationError`] if the payload isn't a valid event.
+	fn decode(&self, payload: &[u8]) -> Result<E>;
+}
+
+/// Protocol Buffers via `prost`, the transport's default wire format.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProstCodec;
+
+impl<E> MessageCodec<E> for ProstCodec
+where
+	E: Message + Default,
+{
+	fn encode(&self, event: &E) -> Result<Vec<u8>> {
+		let mut bytes = Vec::with_capacity(event.encoded_len());
+		event.encode(&mut bytes).map_err(|e| TransportError::SerializationError(e.to_string()))?;
+		Ok(bytes)
+	}
+
+	fn decode(&self, payload: &[u8]) -> Result<E> {
+		E::decode(payload).map_err(|e| TransportError::DeserializationError(e.to_string()))
+	}
+}
+
+/// JSON via `serde_json`, for events that aren't protobuf messages or
+/// channels read by tools that don't speak protobuf.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl<E> MessageCodec<E> for JsonCodec
+where
+	E: Serialize + DeserializeOwned,
+{
+	fn encode(&self, event: &E) -> Result<Vec<u8>> {
+		let mut bytes = Vec::new();
+		serde_json::to_writer(&mut bytes, event).map_err(|e| TransportError::SerializationError(e.to_string()))?;
+		Ok(bytes)
+	}
+
+	fn decode(&self, payload: &[u8]) -> Result<E> {
+		serde_json::from_slice(payload).map_err(|e| TransportError::DeserializationError(e.to_string()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde::Deserialize;
+
+	#[derive(Clone, PartialEq, Message)]
+	struct ProtoEvent {
+		#[prost(string, tag = "1")]
+		message: String,
+	}
+
+	#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+	struct JsonEvent {
+		id: u32,
+		message: String,
+	}
+
+	#[test]
+	fn test_prost_codec_round_trip() {
+		let event = ProtoEvent { message: "hi".to_string() };
+		let bytes = ProstCodec.encode(&event).unwrap();
+		assert_eq!(bytes, event.encode_to_vec());
+		assert_eq!(MessageCodec::<ProtoEvent>::decode(&ProstCodec, &bytes).unwrap(), event);
+	}
+
+	#[test]
+	fn test_json_codec_round_trip() {
+		let event = JsonEvent { id: 3, message: "hi".to_string() };
+		let bytes = JsonCodec.encode(&event).unwrap();
+		assert_eq!(bytes, br#"{"id":3,"message":"hi"}"#);
+		assert_eq!(MessageCodec::<JsonEvent>::decode(&JsonCodec, &bytes).unwrap(), event);
+
+		let garbled = MessageCodec::<JsonEvent>::decode(&JsonCodec, b"not json");
+		assert!(matches!(garbled, Err(TransportError::DeserializationError(_))));
+	}
+}
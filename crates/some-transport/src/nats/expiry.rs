@@ -0,0 +1,56 @@
+#![cfg(feature = "nats")]
+
+use async_nats::HeaderMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// NATS header carrying when a message stops being worth delivering, in Unix milliseconds
+///
+/// Publisher and receiver compare wall clocks, so keep TTLs well above the
+/// expected clock skew between hosts.
+pub const EXPIRES_AT_HEADER: &str = "Expires-At";
+
+fn unix_millis(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH).map_or(0, |since| u64::try_from(since.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Stamp a message published now as expiring after `ttl`
+pub(super) fn stamp_expiry(headers: &mut HeaderMap, ttl: Duration) {
+	let expires_at = unix_millis(SystemTime::now()).saturating_add(u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX));
+	headers.insert(EXPIRES_AT_HEADER, expires_at.to_string());
+}
+
+/// Whether a message's expiry header is in the past
+///
+/// Messages without the header (or with one that doesn't parse) never expire.
+pub(super) fn is_expired(headers: Option<&HeaderMap>) -> bool {
+	headers
+		.and_then(|h| h.get(EXPIRES_AT_HEADER))
+		.and_then(|value| value.as_str().parse::<u64>().ok())
+		.is_some_and(|expires_at| expires_at <= unix_millis(SystemTime::now()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_expiry_header_round_trip() {
+		let mut fresh = HeaderMap::new();
+		stamp_expiry(&mut fresh, Duration::from_secs(60));
+		assert!(!is_expired(Some(&fresh)));
+
+		let mut stale = HeaderMap::new();
+		stale.insert(EXPIRES_AT_HEADER, unix_millis(SystemTime::now() - Duration::from_secs(1)).to_string());
+		assert!(is_expired(Some(&stale)));
+	}
+
+	#[test]
+	fn test_missing_or_garbled_expiry_never_expires() {
+		let mut garbled = HeaderMap::new();
+		garbled.insert(EXPIRES_AT_HEADER, "soon");
+
+		assert!(!is_expired(None));
+		assert!(!is_expired(Some(&HeaderMap::new())));
+		assert!(!is_expired(Some(&garbled)));
+	}
+}
@@ -118,8 +118,8 @@ mod tests {
 
 		// Both should succeed (assuming NATS is running)
 		if let (Ok(c1), Ok(c2)) = (client1, client2) {
-			// Verify they're the same Arc pointer
-			assert!(Arc::ptr_eq(&c1, &c2));
+			// Verify they're the same connection
+			assert_eq!(c1.server_info().client_id, c2.server_info().client_id);
 		}
 	}
 
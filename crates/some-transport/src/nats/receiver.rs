@@ -1,12 +1,15 @@
 #![cfg(feature = "nats")]
 
+use super::codec::{MessageCodec, ProstCodec};
+use super::expiry::is_expired;
+use super::schema::decode_checked;
 use crate::error::{Result, TransportError};
 use crate::receiver::ReceiverTrait;
 use async_nats::Subscriber;
 use async_trait::async_trait;
 use futures::StreamExt;
-use prost::Message;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 /// NATS receiver implementation.
 ///
@@ -14,7 +17,7 @@ use std::marker::PhantomData;
 /// the generic `ReceiverTrait` interface, allowing it to work seamlessly
 /// with the transport-agnostic `TransportReceiver` wrapper.
 ///
-/// Messages are automatically serialized/deserialized using Protocol Buffers.
+/// Messages are decoded with the receiver's [`MessageCodec`], Protocol Buffers by default.
 ///
 /// # Example
 /// ```rust,no_run
@@ -37,11 +40,14 @@ use std::marker::PhantomData;
 /// }
 /// ```
 
-pub struct NatsReceiver<E>
+pub struct NatsReceiver<E, C = ProstCodec>
 where
 	E: Clone + Send + Sync + 'static,
 {
 	subscription: Subscriber,
+	codec: C,
+	expected_schema: Option<Arc<str>>,
+	expired: u64,
 	_marker: PhantomData<E>,
 }
 
@@ -54,9 +60,44 @@ where
 	pub fn new(subscription: Subscriber) -> Self {
 		Self {
 			subscription,
+			codec: ProstCodec,
+			expected_schema: None,
+			expired: 0,
 			_marker: PhantomData,
 		}
 	}
+}
+
+impl<E, C> NatsReceiver<E, C>
+where
+	E: Clone + Send + Sync + 'static,
+{
+	/// Decodes messages with `codec` instead of the current one.
+	#[must_use]
+	pub fn with_codec<D: MessageCodec<E>>(self, codec: D) -> NatsReceiver<E, D> {
+		NatsReceiver {
+			subscription: self.subscription,
+			codec,
+			expected_schema: self.expected_schema,
+			expired: self.expired,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Reject messages whose schema fingerprint header differs from `fingerprint`
+	/// with [`TransportError::SchemaMismatch`] instead of attempting to decode them.
+	#[must_use]
+	pub fn with_expected_schema(mut self, fingerprint: impl Into<Arc<str>>) -> Self {
+		self.expected_schema = Some(fingerprint.into());
+		self
+	}
+
+	/// Number of messages dropped because their TTL ran out before they were received.
+	#[inline]
+	#[must_use]
+	pub const fn expired(&self) -> u64 {
+		self.expired
+	}
 
 	/// Returns a reference to the underlying subscription.
 	#[inline]
@@ -78,21 +119,31 @@ where
 }
 
 #[async_trait]
-impl<E> ReceiverTrait<E> for NatsReceiver<E>
+impl<E, C> ReceiverTrait<E> for NatsReceiver<E, C>
 where
-	E: Clone + Send + Sync + Message + Default + 'static,
+	E: Clone + Send + Sync + 'static,
+	C: MessageCodec<E>,
 {
 	async fn recv(&mut self) -> Result<E> {
-		match self.subscription.next().await {
-			Some(msg) => E::decode(&msg.payload[..]).map_err(|e| TransportError::DeserializationError(e.to_string())),
-			None => Err(TransportError::Closed),
-		}
+		self.recv_request().await.map(|(event, _)| event)
 	}
 
 	fn try_recv(&mut self) -> Result<E> {
 		// NATS Subscriber doesn't have a true non-blocking try_recv
 		Err(TransportError::Other("Channel empty".into()))
 	}
+
+	async fn recv_request(&mut self) -> Result<(E, Option<String>)> {
+		while let Some(msg) = self.subscription.next().await {
+			if is_expired(msg.headers.as_ref()) {
+				self.expired += 1;
+				continue;
+			}
+			let event = decode_checked(&self.codec, &msg.payload[..], msg.headers.as_ref(), self.expected_schema.as_deref())?;
+			return Ok((event, msg.reply.map(|reply| reply.to_string())));
+		}
+		Err(TransportError::Closed)
+	}
 }
 
 // Implement From for ergonomic conversions
@@ -109,8 +160,9 @@ where
 mod tests {
 	use super::*;
 	use crate::receiver::TransportReceiver;
+	use prost::Message;
 
-	#[derive(Debug, Clone, Message, PartialEq)]
+	#[derive(Clone, Message, PartialEq)]
 	struct TestEvent {
 		#[prost(uint32, tag = "1")]
 		id: u32,
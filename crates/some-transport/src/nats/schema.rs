@@ -0,0 +1,108 @@
+#![cfg(feature = "nats")]
+
+use super::codec::MessageCodec;
+use crate::error::{Result, TransportError};
+use async_nats::HeaderMap;
+
+/// NATS header carrying the publisher's schema fingerprint
+pub const SCHEMA_FINGERPRINT_HEADER: &str = "Schema-Fingerprint";
+
+/// Stable fingerprint of an event schema description
+///
+/// Pass anything that changes whenever the wire shape does: the `.proto`
+/// source, or a hand-maintained list of fields and tags. The hash is 64-bit
+/// FNV-1a, so producers and consumers built separately still agree on it.
+/// Prost types can instead `#[derive(SchemaFingerprint)]` (from
+/// `enum-name-derive`) and pass `SCHEMA_FINGERPRINT`, which follows the type.
+///
+/// # Example
+/// ```rust
+/// use some_transport::nats::schema_fingerprint;
+///
+/// let v1 = schema_fingerprint("UnifiedEvent { 1: ObsStatus, 2: ObsCommand }");
+/// let v2 = schema_fingerprint("UnifiedEvent { 1: ObsStatus, 2: ObsCommand, 3: TabMetaData }");
+/// assert_ne!(v1, v2);
+/// ```
+#[must_use]
+pub fn schema_fingerprint(schema: &str) -> String {
+	const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+	const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+	let hash = schema.bytes().fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+	hash.to_string()
+}
+
+/// Headers to publish with, if the transport has a fingerprint configured
+pub(super) fn fingerprint_headers(fingerprint: &str) -> HeaderMap {
+	let mut headers = HeaderMap::new();
+	headers.insert(SCHEMA_FINGERPRINT_HEADER, fingerprint);
+	headers
+}
+
+/// Decode a payload, first checking its fingerprint against the expected one
+///
+/// The check only applies when both sides opted in: messages without the
+/// header (older publishers) and receivers without an expectation decode as before.
+pub(super) fn decode_checked<E, C: MessageCodec<E>>(codec: &C, payload: &[u8], headers: Option<&HeaderMap>, expected: Option<&str>) -> Result<E> {
+	let got = headers.and_then(|h| h.get(SCHEMA_FINGERPRINT_HEADER)).map(async_nats::HeaderValue::as_str);
+
+	if let (Some(expected), Some(got)) = (expected, got) {
+		if expected != got {
+			return Err(TransportError::SchemaMismatch {
+				expected: expected.to_string(),
+				got: got.to_string(),
+			});
+		}
+	}
+
+	codec.decode(payload)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::nats::ProstCodec;
+	use prost::Message;
+
+	#[derive(Clone, PartialEq, Message)]
+	struct TestEvent {
+		#[prost(string, tag = "1")]
+		message: String,
+	}
+
+	fn encoded() -> Vec<u8> {
+		TestEvent { message: "hi".to_string() }.encode_to_vec()
+	}
+
+	#[test]
+	fn test_fingerprint_is_stable() {
+		assert_eq!(schema_fingerprint("TestEvent { 1: string }"), schema_fingerprint("TestEvent { 1: string }"));
+		assert_ne!(schema_fingerprint("TestEvent { 1: string }"), schema_fingerprint("TestEvent { 1: bytes }"));
+	}
+
+	#[test]
+	fn test_mismatched_fingerprint_is_reported() {
+		let published = schema_fingerprint("TestEvent v1");
+		let expected = schema_fingerprint("TestEvent v2");
+		let headers = fingerprint_headers(&published);
+
+		let result = decode_checked::<TestEvent, _>(&ProstCodec, &encoded(), Some(&headers), Some(&expected));
+		match result {
+			Err(TransportError::SchemaMismatch { expected: e, got }) => {
+				assert_eq!(e, expected);
+				assert_eq!(got, published);
+			}
+			other => panic!("expected SchemaMismatch, got {other:?}"),
+		}
+	}
+
+	#[test]
+	fn test_missing_fingerprint_on_either_side_decodes() {
+		let fingerprint = schema_fingerprint("TestEvent v1");
+		let headers = fingerprint_headers(&fingerprint);
+
+		assert!(decode_checked::<TestEvent, _>(&ProstCodec, &encoded(), None, Some(&fingerprint)).is_ok());
+		assert!(decode_checked::<TestEvent, _>(&ProstCodec, &encoded(), Some(&headers), None).is_ok());
+		assert!(decode_checked::<TestEvent, _>(&ProstCodec, &encoded(), Some(&headers), Some(&fingerprint)).is_ok());
+	}
+}
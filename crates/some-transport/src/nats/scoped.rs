@@ -0,0 +1,94 @@
+#![cfg(feature = "nats")]
+
+use super::codec::{MessageCodec, ProstCodec};
+use super::receiver::NatsReceiver;
+use crate::error::Result;
+use crate::receiver::TransportReceiver;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// RAII guard around a NATS subscription.
+///
+/// The subscription lives exactly as long as the guard. Dropping it drops the
+/// underlying `async_nats::Subscriber`, which sends the `UNSUB` to the server,
+/// and decrements the owning transport's subscription count.
+///
+/// Use [`ScopedSubscription::into_manual`] to opt out and take over the
+/// lifetime of the receiver explicitly.
+///
+/// # Example
+/// ```rust,no_run
+/// # use some_transport::NatsTransport;
+/// # use prost::Message;
+/// # #[derive(Clone, PartialEq, Message)]
+/// # pub struct MyEvent {
+/// #     #[prost(string, tag = "1")]
+/// #     pub data: String,
+/// # }
+/// # async fn example(transport: NatsTransport<MyEvent>) {
+/// {
+///     let mut sub = transport.subscribe_scoped("scene.changed").await.unwrap();
+///     let _event = sub.recv().await;
+/// } // unsubscribed here
+/// assert_eq!(transport.active_subscriptions(), 0);
+/// # }
+/// ```
+pub struct ScopedSubscription<E, C = ProstCodec>
+where
+	E: Clone + Send + Sync + 'static,
+	C: MessageCodec<E>,
+{
+	receiver: TransportReceiver<E, NatsReceiver<E, C>>,
+	_tracked: Tracked,
+}
+
+/// Decrements the transport's subscription count when dropped.
+struct Tracked(Arc<AtomicUsize>);
+
+impl Drop for Tracked {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::Relaxed);
+	}
+}
+
+impl<E, C> ScopedSubscription<E, C>
+where
+	E: Clone + Send + Sync + 'static,
+	C: MessageCodec<E>,
+{
+	/// Wraps a receiver, counting it against `active` until dropped.
+	pub(super) fn new(receiver: TransportReceiver<E, NatsReceiver<E, C>>, active: Arc<AtomicUsize>) -> Self {
+		active.fetch_add(1, Ordering::Relaxed);
+		Self {
+			receiver,
+			_tracked: Tracked(active),
+		}
+	}
+
+	/// Receives the next message from the subscription.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the subscription is closed or the payload fails to decode.
+	pub async fn recv(&mut self) -> Result<E> {
+		self.receiver.recv().await
+	}
+
+	/// Attempts to receive a message without blocking.
+	///
+	/// # Errors
+	///
+	/// Returns an error if no message is immediately available.
+	pub fn try_recv(&mut self) -> Result<E> {
+		self.receiver.try_recv()
+	}
+
+	/// Releases the guard and hands back the plain receiver.
+	///
+	/// The subscription is no longer tracked and stays open until the
+	/// returned receiver is dropped.
+	#[must_use]
+	pub fn into_manual(self) -> TransportReceiver<E, NatsReceiver<E, C>> {
+		self.receiver
+	}
+}
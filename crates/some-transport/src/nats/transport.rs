@@ -1,15 +1,20 @@
 #![cfg(feature = "nats")]
 
+use super::codec::{MessageCodec, ProstCodec};
+use super::expiry::stamp_expiry;
 use super::pool::NatsConnectionPool;
 use super::receiver::NatsReceiver;
+use super::schema::{decode_checked, fingerprint_headers};
+use super::scoped::ScopedSubscription;
 use crate::error::{Result, TransportError};
 use crate::receiver::TransportReceiver;
 use crate::traits::Transport;
-use async_nats::Client;
-use prost::Message;
+use async_nats::{connection::State, Client, HeaderMap};
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
 
 /// NATS-based transport implementation.
 ///
@@ -31,6 +36,12 @@ use std::sync::Arc;
 /// failures. Operations perform lightweight connection state checks to
 /// fail-fast when the connection is known to be down.
 ///
+/// # Codecs
+///
+/// Events are encoded with a [`MessageCodec`], Protocol Buffers by default.
+/// Use [`with_codec`](Self::with_codec) to give a channel a different wire
+/// format, e.g. [`JsonCodec`](super::JsonCodec).
+///
 /// # Example
 /// ```rust,no_run
 /// # use some_transport::NatsTransport;
@@ -53,24 +64,32 @@ use std::sync::Arc;
 /// # }
 /// ```
 #[derive(Clone)]
-pub struct NatsTransport<E>
+pub struct NatsTransport<E, C = ProstCodec>
 where
-	E: Clone + Send + Sync + Message + Default + 'static,
+	E: Clone + Send + Sync + 'static,
 {
 	client: Client,
+	codec: C,
 	active_channels: Arc<AtomicUsize>,
+	active_subscriptions: Arc<AtomicUsize>,
+	schema: Option<Arc<str>>,
+	message_ttl: Option<Duration>,
 	_marker: PhantomData<E>,
 }
 
 impl<E> NatsTransport<E>
 where
-	E: Clone + Send + Sync + Message + Default + 'static,
+	E: Clone + Send + Sync + 'static,
 {
 	/// Creates a new NATS transport from a connected client.
 	pub fn new(client: Client) -> Self {
 		Self {
 			client,
+			codec: ProstCodec,
 			active_channels: Arc::new(AtomicUsize::new(0)),
+			active_subscriptions: Arc::new(AtomicUsize::new(0)),
+			schema: None,
+			message_ttl: None,
 			_marker: PhantomData,
 		}
 	}
@@ -116,28 +135,175 @@ where
 	pub fn from_client(client: Client) -> Self {
 		Self {
 			client,
+			codec: ProstCodec,
 			active_channels: Arc::new(AtomicUsize::new(0)),
+			active_subscriptions: Arc::new(AtomicUsize::new(0)),
+			schema: None,
+			message_ttl: None,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<E, C> NatsTransport<E, C>
+where
+	E: Clone + Send + Sync + 'static,
+{
+	/// Encodes and decodes events with `codec` instead of the current one.
+	///
+	/// Clones made afterwards share the connection and counters but keep
+	/// their own codec, so one client can serve channels in different formats.
+	///
+	/// # Example
+	/// ```rust,no_run
+	/// # use some_transport::{JsonCodec, NatsTransport};
+	/// # #[derive(Clone, serde::Serialize, serde::Deserialize)]
+	/// # pub struct ChatLine { pub text: String }
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+	/// let chat = NatsTransport::<ChatLine>::connect_pooled("nats://localhost:4222").await?.with_codec(JsonCodec);
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn with_codec<D: MessageCodec<E>>(self, codec: D) -> NatsTransport<E, D> {
+		NatsTransport {
+			client: self.client,
+			codec,
+			active_channels: self.active_channels,
+			active_subscriptions: self.active_subscriptions,
+			schema: self.schema,
+			message_ttl: self.message_ttl,
 			_marker: PhantomData,
 		}
 	}
 
+	/// Returns the codec events are encoded with.
+	#[must_use]
+	pub const fn codec(&self) -> &C {
+		&self.codec
+	}
+
+	/// Watches the connection state, sampling it every `interval`.
+	///
+	/// Clients from the pool are connected without an event callback, so the
+	/// state is polled; receivers only wake when it actually changes. Polling
+	/// stops once every receiver has been dropped.
+	#[must_use]
+	pub fn connection_state_watch(&self, interval: Duration) -> watch::Receiver<State> {
+		let client = self.client.clone();
+		let (tx, rx) = watch::channel(client.connection_state());
+
+		tokio::spawn(async move {
+			let mut ticker = tokio::time::interval(interval);
+			loop {
+				ticker.tick().await;
+				if tx.is_closed() {
+					break;
+				}
+				tx.send_if_modified(|state| {
+					let now = client.connection_state();
+					let changed = *state != now;
+					*state = now;
+					changed
+				});
+			}
+		});
+
+		rx
+	}
+}
+
+impl<E, C> NatsTransport<E, C>
+where
+	E: Clone + Send + Sync + 'static,
+	C: MessageCodec<E>,
+{
+	/// Attaches a schema fingerprint (see [`schema_fingerprint`](super::schema_fingerprint),
+	/// or a derived `E::SCHEMA_FINGERPRINT`) to every published message, and makes receivers from this transport reject
+	/// messages published under a different fingerprint with
+	/// [`TransportError::SchemaMismatch`].
+	#[must_use]
+	pub fn with_schema_fingerprint(mut self, fingerprint: impl Into<Arc<str>>) -> Self {
+		self.schema = Some(fingerprint.into());
+		self
+	}
+
+	/// Stamps every published message with an expiry `ttl` from now.
+	///
+	/// Receivers drop expired messages instead of delivering them, counting
+	/// them in [`NatsReceiver::expired`]. Use this for live state, where a
+	/// message that arrives late is worse than none.
+	#[must_use]
+	pub const fn with_message_ttl(mut self, ttl: Duration) -> Self {
+		self.message_ttl = Some(ttl);
+		self
+	}
+
 	/// Returns a reference to the underlying NATS client.
 	pub fn client(&self) -> &Client {
 		&self.client
 	}
 
+	/// Subscribes to a subject, returning a guard that unsubscribes on drop.
+	///
+	/// Prefer this over [`Transport::subscribe_to_subject`] so a forgotten
+	/// subscription cannot outlive the scope that created it. The manual API
+	/// remains available when the lifetime must be managed explicitly.
+	pub async fn subscribe_scoped(&self, subject: &str) -> Result<ScopedSubscription<E, C>> {
+		let subscription = self.client.subscribe(subject.to_owned()).await.map_err(|e| TransportError::NatsError(e.to_string()))?;
+
+		Ok(ScopedSubscription::new(
+			TransportReceiver::new(self.receiver(subscription)),
+			Arc::clone(&self.active_subscriptions),
+		))
+	}
+
+	/// Returns the number of live scoped subscriptions across all clones.
+	pub fn active_subscriptions(&self) -> usize {
+		self.active_subscriptions.load(Ordering::Relaxed)
+	}
+
 	/// Checks if the connection is currently active.
 	///
 	/// Returns an error immediately if the connection is down, avoiding
 	/// wasted work. The async_nats client will automatically reconnect
 	/// when the network recovers.
 	fn check_connection(&self) -> Result<()> {
-		if self.client.connection_state() != async_nats::connection::State::Connected {
+		if self.client.connection_state() != State::Connected {
 			return Err(TransportError::NatsError("Connection not established".to_string()));
 		}
 		Ok(())
 	}
 
+	/// Wraps a subscription, carrying over the expected schema fingerprint.
+	fn receiver(&self, subscription: async_nats::Subscriber) -> NatsReceiver<E, C> {
+		let receiver = NatsReceiver::new(subscription).with_codec(self.codec.clone());
+		match &self.schema {
+			Some(fingerprint) => receiver.with_expected_schema(Arc::clone(fingerprint)),
+			None => receiver,
+		}
+	}
+
+	/// Headers for an outgoing message: the schema fingerprint and expiry, if set.
+	fn headers(&self) -> HeaderMap {
+		let mut headers = self.schema.as_deref().map(fingerprint_headers).unwrap_or_default();
+		if let Some(ttl) = self.message_ttl {
+			stamp_expiry(&mut headers, ttl);
+		}
+		headers
+	}
+
+	/// Publishes an encoded event, tagged with the schema fingerprint and expiry if set.
+	async fn publish(&self, subject: String, bytes: Vec<u8>) -> std::result::Result<(), async_nats::PublishError> {
+		let headers = self.headers();
+		if headers.is_empty() {
+			self.client.publish(subject, bytes.into()).await
+		} else {
+			self.client.publish_with_headers(subject, headers, bytes.into()).await
+		}
+	}
+
 	/// Generates a subject name for a connection-specific channel.
 	fn channel_subject(connection_key: &str) -> String {
 		format!("channel.{connection_key}")
@@ -148,11 +314,12 @@ where
 }
 
 #[async_trait::async_trait]
-impl<E> Transport<E> for NatsTransport<E>
+impl<E, C> Transport<E> for NatsTransport<E, C>
 where
-	E: Clone + Send + Sync + Message + Default + 'static,
+	E: Clone + Send + Sync + 'static,
+	C: MessageCodec<E>,
 {
-	type Receiver = TransportReceiver<E, NatsReceiver<E>>;
+	type Receiver = TransportReceiver<E, NatsReceiver<E, C>>;
 
 	async fn open_channel(&self, connection_key: &str) -> Self::Receiver {
 		let subject = Self::channel_subject(connection_key);
@@ -160,7 +327,7 @@ where
 
 		self.active_channels.fetch_add(1, Ordering::Relaxed);
 
-		TransportReceiver::new(NatsReceiver::new(subscription))
+		TransportReceiver::new(self.receiver(subscription))
 	}
 
 	async fn close_channel(&self, _connection_key: &str) -> Result<()> {
@@ -175,10 +342,9 @@ where
 		self.check_connection()?;
 
 		let subject = Self::channel_subject(connection_key);
-		let mut bytes = Vec::new();
-		event.encode(&mut bytes).map_err(|e| TransportError::SerializationError(e.to_string()))?;
+		let bytes = self.codec.encode(&event)?;
 
-		self.client.publish(subject, bytes.into()).await.map_err(|e| TransportError::SendFailed(e.to_string()))?;
+		self.publish(subject, bytes).await.map_err(|e| TransportError::SendFailed(e.to_string()))?;
 
 		Ok(())
 	}
@@ -193,27 +359,36 @@ where
 		// Early escape if connection is down
 		self.check_connection()?;
 
-		let mut bytes = Vec::new();
-		event.encode(&mut bytes).map_err(|e| TransportError::SerializationError(e.to_string()))?;
+		let bytes = self.codec.encode(&event)?;
 
-		self
+		self.publish(subject.to_owned(), bytes).await.map_err(|e| TransportError::BroadcastFailed(e.to_string()))?;
+
+		Ok(())
+	}
+
+	/// Waits up to the client's request timeout.
+	async fn request(&self, subject: &str, event: E) -> Result<E> {
+		self.check_connection()?;
+
+		let bytes = self.codec.encode(&event)?;
+		let reply = self
 			.client
-			.publish(subject.to_owned(), bytes.into())
+			.request_with_headers(subject.to_owned(), self.headers(), bytes.into())
 			.await
-			.map_err(|e| TransportError::BroadcastFailed(e.to_string()))?;
+			.map_err(|e| TransportError::NatsError(e.to_string()))?;
 
-		Ok(())
+		decode_checked(&self.codec, &reply.payload[..], reply.headers.as_ref(), self.schema.as_deref())
 	}
 
 	async fn subscribe_to_subject(&self, subject: &str) -> Self::Receiver {
 		let subscription = self.client.subscribe(subject.to_owned()).await.expect("Failed to subscribe to broadcast");
 
-		TransportReceiver::new(NatsReceiver::new(subscription))
+		TransportReceiver::new(self.receiver(subscription))
 	}
 
 	async fn subscribe(&self) -> Self::Receiver {
 		let subscription = self.client.subscribe(Self::BROADCAST_SUBJECT.to_string()).await.expect("Failed to subscribe to broadcast");
-		TransportReceiver::new(NatsReceiver::new(subscription))
+		TransportReceiver::new(self.receiver(subscription))
 	}
 
 	fn total_receivers(&self) -> usize {
@@ -224,7 +399,7 @@ where
 
 	fn is_closed(&self) -> bool {
 		// Check actual connection state
-		self.client.connection_state() != async_nats::connection::State::Connected
+		self.client.connection_state() != State::Connected
 	}
 
 	fn active_channels(&self) -> usize {
@@ -235,7 +410,7 @@ where
 // Convenience constructors
 impl<E> NatsTransport<E>
 where
-	E: Clone + Send + Sync + Message + Default + 'static,
+	E: Clone + Send + Sync + prost::Message + Default + 'static,
 {
 	/// Creates transport and returns it with an initial broadcast receiver.
 	pub async fn with_receiver(client: Client) -> (Self, TransportReceiver<E, NatsReceiver<E>>) {
@@ -265,12 +440,13 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::nats::schema_fingerprint;
 	use prost::Message;
 	use std::time::Duration;
 	use tokio::time::timeout;
 
 	// Test event type
-	#[derive(Clone, Debug, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message)]
 	struct TestEvent {
 		#[prost(uint64, tag = "1")]
 		id: u64,
@@ -331,8 +507,8 @@ mod tests {
 		assert_eq!(t2.active_channels(), 0);
 
 		// They should share the same underlying connection
-		// (verified by pointer equality of Client)
-		assert!(Arc::ptr_eq(&t1.client, &t2.client));
+		// (verified by the server-assigned client id)
+		assert_eq!(t1.client.server_info().client_id, t2.client.server_info().client_id);
 	}
 
 	#[tokio::test]
@@ -343,10 +519,9 @@ mod tests {
 		}
 
 		let client = async_nats::connect(nats_url()).await.unwrap();
-		let arc_client = Arc::new(client);
-		let transport = NatsTransport::<TestEvent>::from_client(arc_client.clone());
+		let transport = NatsTransport::<TestEvent>::from_client(client.clone());
 
-		assert!(Arc::ptr_eq(&transport.client, &arc_client));
+		assert_eq!(transport.client.server_info().client_id, client.server_info().client_id);
 	}
 
 	#[tokio::test]
@@ -537,7 +712,7 @@ mod tests {
 		let t2 = t1.clone();
 
 		// Both should share the same client
-		assert!(Arc::ptr_eq(&t1.client, &t2.client));
+		assert_eq!(t1.client.server_info().client_id, t2.client.server_info().client_id);
 
 		// Both should work independently
 		let mut r1 = t1.open_channel("clone-test-1").await;
@@ -720,6 +895,97 @@ mod tests {
 		assert_eq!(received_ids, (0..10).collect::<Vec<_>>());
 	}
 
+	#[tokio::test]
+	async fn test_scoped_subscription_unsubscribes_on_drop() {
+		if !nats_available().await {
+			println!("Skipping test: NATS not available");
+			return;
+		}
+
+		let transport = NatsTransport::<TestEvent>::connect(nats_url()).await.unwrap();
+		let event = TestEvent {
+			id: 5,
+			message: "Scoped".to_string(),
+		};
+
+		{
+			let mut sub = transport.subscribe_scoped("scoped-test").await.unwrap();
+			assert_eq!(transport.active_subscriptions(), 1);
+
+			transport.send_to_subject("scoped-test", event.clone()).await.unwrap();
+			let received = timeout(Duration::from_secs(2), sub.recv()).await.expect("Timeout").expect("Failed to receive");
+			assert_eq!(received, event);
+		}
+
+		assert_eq!(transport.active_subscriptions(), 0);
+
+		// Manual subscriptions are untracked and outlive the guard
+		let manual = transport.subscribe_scoped("scoped-test").await.unwrap().into_manual();
+		assert_eq!(transport.active_subscriptions(), 0);
+		drop(manual);
+	}
+
+	#[tokio::test]
+	async fn test_schema_mismatch_is_reported() {
+		if !nats_available().await {
+			println!("Skipping test: NATS not available");
+			return;
+		}
+
+		let client = async_nats::connect(nats_url()).await.unwrap();
+		let producer = NatsTransport::<TestEvent>::new(client.clone()).with_schema_fingerprint(schema_fingerprint("TestEvent v1"));
+		let consumer = NatsTransport::<TestEvent>::new(client).with_schema_fingerprint(schema_fingerprint("TestEvent v2"));
+		let mut receiver = consumer.open_channel("test-schema").await;
+
+		let event = TestEvent {
+			id: 7,
+			message: "new shape".to_string(),
+		};
+		producer.send("test-schema", event).await.unwrap();
+
+		let result = timeout(Duration::from_secs(2), receiver.recv()).await.expect("Timeout waiting for message");
+		assert!(matches!(
+			result,
+			Err(TransportError::SchemaMismatch { expected, got })
+				if expected == schema_fingerprint("TestEvent v2") && got == schema_fingerprint("TestEvent v1")
+		));
+	}
+
+	// Plain UTF-8 payloads, so the wire bytes are easy to check
+	#[derive(Clone)]
+	struct TextCodec;
+
+	impl MessageCodec<String> for TextCodec {
+		fn encode(&self, event: &String) -> Result<Vec<u8>> {
+			Ok(event.as_bytes().to_vec())
+		}
+
+		fn decode(&self, payload: &[u8]) -> Result<String> {
+			String::from_utf8(payload.to_vec()).map_err(|e| TransportError::DeserializationError(e.to_string()))
+		}
+	}
+
+	#[tokio::test]
+	async fn test_custom_codec_round_trip() {
+		if !nats_available().await {
+			println!("Skipping test: NATS not available");
+			return;
+		}
+
+		let transport = NatsTransport::<String>::connect(nats_url()).await.unwrap().with_codec(TextCodec);
+		let mut receiver = transport.open_channel("test-codec").await;
+		let mut raw = transport.client().subscribe("channel.test-codec").await.unwrap();
+
+		transport.send("test-codec", "hello codec".to_string()).await.unwrap();
+
+		let received = timeout(Duration::from_secs(2), receiver.recv()).await.expect("Timeout").expect("Failed to receive");
+		assert_eq!(received, "hello codec");
+
+		// The payload on the wire is the codec's, not protobuf
+		let wire = timeout(Duration::from_secs(2), futures::StreamExt::next(&mut raw)).await.expect("Timeout").unwrap();
+		assert_eq!(&wire.payload[..], b"hello codec");
+	}
+
 	#[tokio::test]
 	async fn test_error_invalid_url() {
 		let result = NatsTransport::<TestEvent>::connect("invalid://url:99999").await;
@@ -55,6 +55,16 @@ where
 		self.inner.recv().await
 	}
 
+	/// Receives a message with the subject to reply on, if it was sent as a request.
+	///
+	/// # Errors
+	///
+	/// Same as [`recv`](Self::recv).
+	#[inline]
+	pub async fn recv_request(&mut self) -> Result<(E, Option<String>)> {
+		self.inner.recv_request().await
+	}
+
 	/// Attempts to receive a message without blocking.
 	#[inline]
 	pub fn try_recv(&mut self) -> Result<E> {
@@ -131,4 +141,13 @@ where
 	/// Returns immediately with either a message or an error indicating
 	/// the channel is empty, closed, or overflowed.
 	fn try_recv(&mut self) -> Result<E>;
+
+	/// Receives the next message with the subject its sender waits for a reply on.
+	///
+	/// The subject is `None` for messages that weren't sent as a
+	/// [`Transport::request`](crate::Transport::request), and always `None` on
+	/// receivers that don't support replies.
+	async fn recv_request(&mut self) -> Result<(E, Option<String>)> {
+		self.recv().await.map(|event| (event, None))
+	}
 }
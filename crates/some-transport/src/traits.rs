@@ -27,6 +27,13 @@ where
 	/// Sends an event to Nats based on passed subject.
 	async fn send_to_subject(&self, subject: &str, event: E) -> Result<()>;
 
+	/// Sends `event` as a request on `subject` and waits for the reply.
+	///
+	/// Responders get the subject to answer on from
+	/// [`ReceiverTrait::recv_request`](crate::ReceiverTrait::recv_request) and
+	/// reply with [`send_to_subject`](Self::send_to_subject).
+	async fn request(&self, subject: &str, event: E) -> Result<E>;
+
 	/// Sends an event to Nats based on passed subject.
 	async fn subscribe_to_subject(&self, subject: &str) -> Self::Receiver;
 
@@ -3,15 +3,26 @@
 /// Generic over discrete event types `e ∈ E` with:
 /// - Finite discrete outcome sets
 /// - Deterministic state transitions: `R_w = R_{w-1} ⊕ e_w`
+/// - Optionally, a probability distribution over each period's outcomes
 /// - Additive utility functions
 ///
 /// Team records (Win/Loss/Tie) are one implementation of this generic framework.
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::Hash;
 
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "serde")]
+mod persist;
+mod trajectory;
+
+pub use trajectory::{Trajectory, TrajectoryStep};
+
 /// Entity identifier (team, player, etc.)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityId(pub u8);
 
 /// Generic trait for discrete event outcomes
@@ -46,6 +57,7 @@ pub trait CumulativeRecord: Clone + PartialEq + Eq + Hash + Debug + Default {
 
 /// Generic state R_w: cumulative records for all entities at end of period w
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State<R: CumulativeRecord> {
 	records: HashMap<EntityId, R>,
 }
@@ -97,6 +109,7 @@ impl<R: CumulativeRecord> Default for State<R> {
 
 /// Period outcomes for all entities: e_w
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PeriodOutcomes<O: EventOutcome> {
 	outcomes: HashMap<EntityId, O>,
 }
@@ -138,144 +151,646 @@ impl<O: EventOutcome> Default for PeriodOutcomes<O> {
 }
 
 /// Hierarchical weights for entity importance
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HierarchicalWeights {
 	/// Weight for primary entity's own outcome
 	pub w_primary: f64,
-	/// Weight for tier-1 rivals (e.g., divisional)
-	pub w_tier1: f64,
-	/// Weight for tier-2 rivals (e.g., conference)
-	pub w_tier2: f64,
-	/// Weight for tier-3 rivals (e.g., other-conference)
-	pub w_tier3: f64,
+	/// Weight for each rival tier, most important first (e.g., divisional, conference, other-conference)
+	///
+	/// Lines up with [`EntityHierarchy::tiers`].
+	pub w_tiers: Vec<f64>,
 }
 
 impl Default for HierarchicalWeights {
 	fn default() -> Self {
-		Self {
-			w_primary: 1.0,
-			w_tier1: 0.6,
-			w_tier2: 0.3,
-			w_tier3: 0.1,
-		}
+		Self::three_tier(1.0, 0.6, 0.3, 0.1)
 	}
 }
 
 impl HierarchicalWeights {
+	/// Weights for the classic three rival tiers
+	#[must_use]
+	pub fn three_tier(w_primary: f64, w_tier1: f64, w_tier2: f64, w_tier3: f64) -> Self {
+		Self {
+			w_primary,
+			w_tiers: vec![w_tier1, w_tier2, w_tier3],
+		}
+	}
+
 	/// Validate that weights satisfy constraints
 	///
 	/// # Errors
 	///
 	/// Returns an error if:
 	/// - `w_primary` is not positive
-	/// - Tier weights are not properly ordered (w_tier1 > w_tier2 > w_tier3)
-	/// - `w_tier3` is negative
+	/// - Tier weights are not strictly decreasing
+	/// - The last tier weight is negative
 	pub fn validate(&self) -> Result<(), String> {
 		if self.w_primary <= 0.0 {
 			return Err("w_primary must be positive".to_string());
 		}
-		if self.w_tier1 <= self.w_tier2 {
-			return Err("w_tier1 must be greater than w_tier2".to_string());
-		}
-		if self.w_tier2 <= self.w_tier3 {
-			return Err("w_tier2 must be greater than w_tier3".to_string());
+		for (idx, pair) in self.w_tiers.windows(2).enumerate() {
+			if pair[0] <= pair[1] {
+				return Err(["tier-", &(idx + 1).to_string(), " weight must be greater than tier-", &(idx + 2).to_string(), " weight"].concat());
+			}
 		}
-		if self.w_tier3 < 0.0 {
-			return Err("w_tier3 must be non-negative".to_string());
+		if self.w_tiers.last().is_some_and(|&weight| weight < 0.0) {
+			return Err("tier weights must be non-negative".to_string());
 		}
 		Ok(())
 	}
 }
 
 /// Generic hierarchical structure
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityHierarchy {
 	/// Primary entity (e.g., favorite team)
 	pub primary: EntityId,
-	/// Tier-1 rivals
-	pub tier1_rivals: Vec<EntityId>,
-	/// Tier-2 rivals
-	pub tier2_rivals: Vec<EntityId>,
-	/// Tier-3 rivals
-	pub tier3_rivals: Vec<EntityId>,
+	/// Rivals grouped by tier, most important first
+	pub tiers: Vec<Vec<EntityId>>,
 }
 
 impl EntityHierarchy {
+	/// Start building a validated hierarchy around `primary`
+	#[must_use]
+	pub const fn builder(primary: EntityId) -> EntityHierarchyBuilder {
+		EntityHierarchyBuilder { primary, tiers: Vec::new() }
+	}
+
+	/// Hierarchy with the classic three rival tiers
+	#[must_use]
+	pub fn three_tier(primary: EntityId, tier1_rivals: Vec<EntityId>, tier2_rivals: Vec<EntityId>, tier3_rivals: Vec<EntityId>) -> Self {
+		Self {
+			primary,
+			tiers: vec![tier1_rivals, tier2_rivals, tier3_rivals],
+		}
+	}
+
 	pub fn all_entities(&self) -> Vec<EntityId> {
 		let mut entities = vec![self.primary];
-		entities.extend(&self.tier1_rivals);
-		entities.extend(&self.tier2_rivals);
-		entities.extend(&self.tier3_rivals);
+		entities.extend(self.tiers.iter().flatten());
 		entities
 	}
+
+	/// Validate that every entity appears exactly once
+	///
+	/// # Errors
+	///
+	/// Returns an error if:
+	/// - The primary entity also appears as a rival
+	/// - A rival appears more than once, within or across tiers
+	pub fn validate(&self) -> Result<(), String> {
+		let tier_name = |idx: usize| ["tier-", &(idx + 1).to_string()].concat();
+		let mut seen: HashMap<EntityId, usize> = HashMap::new();
+
+		for (idx, rivals) in self.tiers.iter().enumerate() {
+			for &rival in rivals {
+				let id = rival.0.to_string();
+				if rival == self.primary {
+					return Err(["entity ", &id, " is the primary and cannot also be a ", &tier_name(idx), " rival"].concat());
+				}
+				if let Some(first) = seen.insert(rival, idx) {
+					return Err(["entity ", &id, " appears as both a ", &tier_name(first), " and a ", &tier_name(idx), " rival"].concat());
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Builder for [`EntityHierarchy`] that validates on `build`
+#[derive(Debug, Clone)]
+pub struct EntityHierarchyBuilder {
+	primary: EntityId,
+	tiers: Vec<Vec<EntityId>>,
+}
+
+impl EntityHierarchyBuilder {
+	/// Add the next tier of rivals, less important than the ones before it
+	#[must_use]
+	pub fn tier(mut self, rivals: impl IntoIterator<Item = EntityId>) -> Self {
+		self.tiers.push(rivals.into_iter().collect());
+		self
+	}
+
+	/// Add rivals to tier `idx` (0-based) of the classic three-tier layout
+	fn three_tier_rivals(mut self, idx: usize, rivals: impl IntoIterator<Item = EntityId>) -> Self {
+		if self.tiers.len() < 3 {
+			self.tiers.resize_with(3, Vec::new);
+		}
+		self.tiers[idx].extend(rivals);
+		self
+	}
+
+	#[must_use]
+	pub fn tier1_rivals(self, rivals: impl IntoIterator<Item = EntityId>) -> Self {
+		self.three_tier_rivals(0, rivals)
+	}
+
+	#[must_use]
+	pub fn tier2_rivals(self, rivals: impl IntoIterator<Item = EntityId>) -> Self {
+		self.three_tier_rivals(1, rivals)
+	}
+
+	#[must_use]
+	pub fn tier3_rivals(self, rivals: impl IntoIterator<Item = EntityId>) -> Self {
+		self.three_tier_rivals(2, rivals)
+	}
+
+	/// Build the hierarchy
+	///
+	/// # Errors
+	///
+	/// Returns an error under the same conditions as [`EntityHierarchy::validate`].
+	pub fn build(self) -> Result<EntityHierarchy, String> {
+		let hierarchy = EntityHierarchy {
+			primary: self.primary,
+			tiers: self.tiers,
+		};
+		hierarchy.validate()?;
+		Ok(hierarchy)
+	}
+}
+
+/// Weighted set of primary entities optimized together (e.g., a fantasy roster)
+///
+/// Each member's score contributes `w_primary * weight * score`, and rival
+/// differences are taken against the best-scoring member in the period.
+/// A single member with weight 1.0 reproduces the single-primary engine.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrimaryPortfolio {
+	members: Vec<(EntityId, f64)>,
+}
+
+impl PrimaryPortfolio {
+	/// Portfolio of just `primary` with weight 1.0
+	#[must_use]
+	pub fn single(primary: EntityId) -> Self {
+		Self { members: vec![(primary, 1.0)] }
+	}
+
+	/// Build a portfolio from `(entity, weight)` pairs
+	///
+	/// # Errors
+	///
+	/// Returns an error if:
+	/// - The portfolio is empty
+	/// - A weight is not positive
+	/// - An entity appears more than once
+	pub fn new(members: impl IntoIterator<Item = (EntityId, f64)>) -> Result<Self, String> {
+		let members: Vec<_> = members.into_iter().collect();
+		if members.is_empty() {
+			return Err("portfolio must contain at least one primary".to_string());
+		}
+		for (idx, &(entity, weight)) in members.iter().enumerate() {
+			let id = entity.0.to_string();
+			if weight <= 0.0 {
+				return Err(["portfolio weight for entity ", &id, " must be positive"].concat());
+			}
+			if members[..idx].iter().any(|&(other, _)| other == entity) {
+				return Err(["entity ", &id, " appears more than once in the portfolio"].concat());
+			}
+		}
+		Ok(Self { members })
+	}
+
+	#[must_use]
+	pub fn members(&self) -> &[(EntityId, f64)] {
+		&self.members
+	}
+
+	/// Sum of member weights
+	#[must_use]
+	pub fn total_weight(&self) -> f64 {
+		self.members.iter().map(|&(_, weight)| weight).sum()
+	}
+
+	/// Weighted score of all members and the best single member score
+	fn scores<O: EventOutcome>(&self, period_outcomes: &PeriodOutcomes<O>) -> (f64, f64) {
+		self.members.iter().fold((0.0, f64::NEG_INFINITY), |(weighted, best), &(entity, weight)| {
+			let score = period_outcomes.get_score(entity);
+			(weight.mul_add(score, weighted), best.max(score))
+		})
+	}
+}
+
+/// Feasible period outcomes paired with the probability of each occurring
+///
+/// Used by [`GenericOptimalityEngine::expected_value_function`] to take the
+/// expectation over next states instead of the best case.
+#[derive(Debug, Clone)]
+pub struct ProbabilisticOutcomes<O: EventOutcome> {
+	outcomes: Vec<(PeriodOutcomes<O>, f64)>,
+}
+
+/// How far probabilities may drift from summing to one
+const PROBABILITY_TOLERANCE: f64 = 1e-9;
+
+impl<O: EventOutcome> ProbabilisticOutcomes<O> {
+	/// Build a distribution from `(outcomes, probability)` pairs
+	///
+	/// # Errors
+	///
+	/// Returns an error if:
+	/// - The distribution is empty
+	/// - A probability is negative or not finite
+	/// - The probabilities don't sum to 1
+	pub fn new(outcomes: impl IntoIterator<Item = (PeriodOutcomes<O>, f64)>) -> Result<Self, String> {
+		let outcomes: Vec<_> = outcomes.into_iter().collect();
+		if outcomes.is_empty() {
+			return Err("distribution must contain at least one outcome".to_string());
+		}
+		if outcomes.iter().any(|&(_, p)| !p.is_finite() || p < 0.0) {
+			return Err("probabilities must be finite and non-negative".to_string());
+		}
+		let total: f64 = outcomes.iter().map(|&(_, p)| p).sum();
+		if (total - 1.0).abs() > PROBABILITY_TOLERANCE {
+			return Err(["probabilities must sum to 1, got ", &total.to_string()].concat());
+		}
+		Ok(Self { outcomes })
+	}
+
+	#[must_use]
+	pub fn outcomes(&self) -> &[(PeriodOutcomes<O>, f64)] {
+		&self.outcomes
+	}
 }
 
+impl HierarchicalWeights {
+	/// Every combination of each weight scaled by `1 - p`, `1` or `1 + p`
+	///
+	/// Includes combinations that fail [`validate`](Self::validate), e.g. ones
+	/// that reorder the tiers; callers skip those.
+	fn perturbations(&self, p: f64) -> Vec<Self> {
+		let factors = [1.0 - p, 1.0, 1.0 + p];
+		let nominal: Vec<f64> = std::iter::once(self.w_primary).chain(self.w_tiers.iter().copied()).collect();
+
+		// Earlier weights vary slowest, so the primary's factor is outermost
+		let mut grid = vec![Vec::with_capacity(nominal.len())];
+		for weight in nominal {
+			grid = grid
+				.into_iter()
+				.flat_map(|prefix: Vec<f64>| {
+					factors.map(|factor| {
+						let mut scaled = prefix.clone();
+						scaled.push(weight * factor);
+						scaled
+					})
+				})
+				.collect();
+		}
+
+		grid
+			.into_iter()
+			.map(|scaled| Self {
+				w_primary: scaled[0],
+				w_tiers: scaled[1..].to_vec(),
+			})
+			.collect()
+	}
+}
+
+/// How season optimality moves when the hierarchical weights are perturbed
+///
+/// Built by [`GenericOptimalityEngine::sensitivity`]. The nominal weights are
+/// part of the grid, so `min <= nominal <= max`.
+#[derive(Debug, Clone)]
+pub struct SensitivityReport {
+	/// Season optimality under the engine's own weights
+	pub nominal: f64,
+	/// Lowest score over the perturbed weights
+	pub min: f64,
+	/// Highest score over the perturbed weights
+	pub max: f64,
+	/// Weights giving `min`
+	pub min_weights: HierarchicalWeights,
+	/// Weights giving `max`
+	pub max_weights: HierarchicalWeights,
+	/// Legal weight combinations evaluated
+	pub evaluated: usize,
+	/// Combinations skipped because they failed validation
+	pub skipped: usize,
+}
+
+impl SensitivityReport {
+	/// Width of the score range, 0 when the verdict doesn't depend on the weights
+	#[must_use]
+	pub fn spread(&self) -> f64 {
+		self.max - self.min
+	}
+}
+
+/// How a rival's score difference against the primary contributes to utility
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RivalDiffMode {
+	/// `max(0, primary - rival)`: a rival outperforming the primary contributes zero
+	#[default]
+	Clamped,
+	/// `primary - rival`: a rival outperforming the primary is a penalty,
+	/// so period utility can be negative
+	Signed,
+}
+
+impl RivalDiffMode {
+	/// Rival contribution before tier weighting
+	fn apply(self, primary_score: f64, rival_score: f64) -> f64 {
+		match self {
+			Self::Clamped => (primary_score - rival_score).max(0.0),
+			Self::Signed => primary_score - rival_score,
+		}
+	}
+}
+
+/// How `optimal_outcome` chooses among outcomes of equal value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+	/// The first tied outcome in the order the feasible outcomes were given
+	#[default]
+	First,
+	/// The tied outcome with the highest weighted portfolio score, then the first
+	HighestPrimaryScore,
+}
+
+/// How [`period_optimality`](GenericOptimalityEngine::period_optimality) scores an observed value against the optimal one
+///
+/// Values are measured from the worst achievable value (the floor), so the
+/// normalized metrics stay in `[0, 1]` in either [`RivalDiffMode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OptimalityMetric {
+	/// `(observed - floor) / (optimal - floor)`: 1 for an optimal period
+	#[default]
+	Ratio,
+	/// `optimal - observed`: utility left on the table, 0 for an optimal period
+	AbsoluteGap,
+	/// `(optimal - observed) / (optimal - floor)`: share of the achievable
+	/// utility given up, 0 for an optimal period
+	NormalizedRegret,
+}
+
+impl OptimalityMetric {
+	/// Score `observed` against `optimal`, both measured from `floor`
+	///
+	/// When nothing beats the floor there is nothing to normalize by: the ratio
+	/// is 0 and no regret is counted, since no choice could have done better.
+	#[must_use]
+	pub fn score(self, observed: f64, optimal: f64, floor: f64) -> f64 {
+		let achievable = optimal - floor;
+		match self {
+			Self::Ratio if achievable > 0.0 => ((observed - floor) / achievable).clamp(0.0, 1.0),
+			Self::NormalizedRegret if achievable > 0.0 => ((optimal - observed) / achievable).clamp(0.0, 1.0),
+			Self::Ratio | Self::NormalizedRegret => 0.0,
+			Self::AbsoluteGap => (optimal - observed).max(0.0),
+		}
+	}
+}
+
+/// Values this close are considered equal when collecting ties
+///
+/// Totals are sums over periods and rivals in different orders, so exact
+/// equality would miss ties that differ only by rounding.
+const TIE_TOLERANCE: f64 = 1e-9;
+
 /// Value function cache for dynamic programming
 type ValueCache<R> = HashMap<(usize, State<R>), f64>;
 
+/// Weighted rival contributions keyed by (rival, its outcome, primary score bits)
+///
+/// A rival's contribution depends only on its own outcome and the primary's
+/// score, not on the other rivals, so it is shared across every feasible
+/// combination that agrees on those two.
+type ContributionCache<O> = HashMap<(EntityId, Option<O>, u64), f64>;
+
 /// Generic path-dependent optimality calculator
 ///
 /// Works with any event type implementing EventOutcome and CumulativeRecord
 pub struct GenericOptimalityEngine<R: CumulativeRecord> {
 	hierarchy: EntityHierarchy,
+	portfolio: PrimaryPortfolio,
 	weights: HierarchicalWeights,
 	pub value_cache: ValueCache<R>,
+	/// Expected values, kept apart from the best-case values in `value_cache`
+	expected_cache: ValueCache<R>,
+	contribution_cache: RefCell<ContributionCache<R::Outcome>>,
+	contributions_computed: Cell<u64>,
+	/// Lookups `value_function` answered from `value_cache`
+	value_cache_hits: u64,
 	max_periods: usize,
+	/// Discount factor γ ∈ [0, 1] applied to each period of future value
+	discount: f64,
+	diff_mode: RivalDiffMode,
+	tie_break: TieBreak,
+	metric: OptimalityMetric,
+	/// Periods fed through [`observe_period`](Self::observe_period) so far
+	periods_observed: usize,
+	/// Sum of their optimality scores
+	observed_total: f64,
 }
 
 impl<R: CumulativeRecord> GenericOptimalityEngine<R> {
 	pub fn new(hierarchy: EntityHierarchy, weights: HierarchicalWeights, max_periods: usize) -> Result<Self, String> {
+		Self::with_discount(hierarchy, weights, max_periods, 1.0)
+	}
+
+	/// Create an engine that discounts future value by `discount` per period
+	///
+	/// With γ = 1 every remaining period counts equally, as in [`new`](Self::new);
+	/// smaller values favor near periods over distant ones.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the weights or hierarchy are invalid, if there isn't
+	/// exactly one tier weight per hierarchy tier, or if `discount` is not in `[0, 1]`.
+	pub fn with_discount(hierarchy: EntityHierarchy, weights: HierarchicalWeights, max_periods: usize, discount: f64) -> Result<Self, String> {
 		weights.validate()?;
+		hierarchy.validate()?;
+		if weights.w_tiers.len() != hierarchy.tiers.len() {
+			return Err(
+				[
+					"hierarchy has ",
+					&hierarchy.tiers.len().to_string(),
+					" tiers but ",
+					&weights.w_tiers.len().to_string(),
+					" tier weights were given",
+				]
+				.concat(),
+			);
+		}
+		if !(0.0..=1.0).contains(&discount) {
+			return Err(["discount must be in [0, 1], got ", &discount.to_string()].concat());
+		}
 		Ok(Self {
+			portfolio: PrimaryPortfolio::single(hierarchy.primary),
 			hierarchy,
 			weights,
 			value_cache: HashMap::new(),
+			expected_cache: HashMap::new(),
+			contribution_cache: RefCell::new(HashMap::new()),
+			contributions_computed: Cell::new(0),
+			value_cache_hits: 0,
 			max_periods,
+			discount,
+			diff_mode: RivalDiffMode::default(),
+			tie_break: TieBreak::default(),
+			metric: OptimalityMetric::default(),
+			periods_observed: 0,
+			observed_total: 0.0,
 		})
 	}
 
-	/// Utility function U(R_w, e_w): immediate reward for period w
-	pub fn period_utility(&self, _state: &State<R>, period_outcomes: &PeriodOutcomes<R::Outcome>) -> f64 {
-		let primary_score = period_outcomes.get_score(self.hierarchy.primary);
+	/// Select how rival differences contribute to period utility
+	///
+	/// Clears the value cache since cached values depend on the mode.
+	#[must_use]
+	pub fn with_diff_mode(mut self, diff_mode: RivalDiffMode) -> Self {
+		self.diff_mode = diff_mode;
+		self.clear_cache();
+		self
+	}
 
-		// Primary entity contribution
-		let mut utility = self.weights.w_primary * primary_score;
+	#[must_use]
+	pub const fn diff_mode(&self) -> RivalDiffMode {
+		self.diff_mode
+	}
 
-		// Tier-1 rivals contribution
-		for &rival in &self.hierarchy.tier1_rivals {
-			let rival_score = period_outcomes.get_score(rival);
-			let diff = (primary_score - rival_score).max(0.0);
-			utility += self.weights.w_tier1 * diff;
-		}
+	/// Select how `optimal_outcome` resolves ties
+	#[must_use]
+	pub const fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+		self.tie_break = tie_break;
+		self
+	}
+
+	#[must_use]
+	pub const fn tie_break(&self) -> TieBreak {
+		self.tie_break
+	}
 
-		// Tier-2 rivals contribution
-		for &rival in &self.hierarchy.tier2_rivals {
-			let rival_score = period_outcomes.get_score(rival);
-			let diff = (primary_score - rival_score).max(0.0);
-			utility += self.weights.w_tier2 * diff;
+	/// Select how period and season optimality scores are computed
+	#[must_use]
+	pub const fn with_metric(mut self, metric: OptimalityMetric) -> Self {
+		self.metric = metric;
+		self
+	}
+
+	#[must_use]
+	pub const fn metric(&self) -> OptimalityMetric {
+		self.metric
+	}
+
+	/// Optimize for a weighted portfolio of primaries instead of the hierarchy's single primary
+	///
+	/// Clears the value cache since cached values depend on the portfolio.
+	///
+	/// # Errors
+	///
+	/// Returns an error if a portfolio member is also one of the hierarchy's rivals.
+	pub fn with_portfolio(mut self, portfolio: PrimaryPortfolio) -> Result<Self, String> {
+		for &(entity, _) in portfolio.members() {
+			if self.hierarchy.tiers.iter().any(|tier| tier.contains(&entity)) {
+				return Err(["entity ", &entity.0.to_string(), " is in the portfolio and cannot also be a rival"].concat());
+			}
 		}
+		self.portfolio = portfolio;
+		self.clear_cache();
+		Ok(self)
+	}
+
+	#[must_use]
+	pub const fn portfolio(&self) -> &PrimaryPortfolio {
+		&self.portfolio
+	}
 
-		// Tier-3 rivals contribution
-		for &rival in &self.hierarchy.tier3_rivals {
-			let rival_score = period_outcomes.get_score(rival);
-			let diff = (primary_score - rival_score).max(0.0);
-			utility += self.weights.w_tier3 * diff;
+	#[must_use]
+	pub const fn weights(&self) -> &HierarchicalWeights {
+		&self.weights
+	}
+
+	#[must_use]
+	pub const fn discount(&self) -> f64 {
+		self.discount
+	}
+
+	/// Weighted contribution of one rival, memoized across outcome combinations
+	fn rival_contribution(&self, rival: EntityId, weight: f64, primary_score: f64, period_outcomes: &PeriodOutcomes<R::Outcome>) -> f64 {
+		let outcome = period_outcomes.get_outcome(rival);
+		let key = (rival, outcome, primary_score.to_bits());
+		if let Some(&contribution) = self.contribution_cache.borrow().get(&key) {
+			return contribution;
 		}
 
-		utility
+		self.contributions_computed.set(self.contributions_computed.get() + 1);
+		let rival_score = outcome.map_or(0.0, |o| o.score());
+		let contribution = weight * self.diff_mode.apply(primary_score, rival_score);
+		self.contribution_cache.borrow_mut().insert(key, contribution);
+		contribution
+	}
+
+	/// Number of rival contributions actually computed rather than served from the memo
+	#[must_use]
+	pub const fn rival_contributions_computed(&self) -> u64 {
+		self.contributions_computed.get()
+	}
+
+	/// Number of `value_function` calls answered from the value cache
+	#[must_use]
+	pub const fn value_cache_hits(&self) -> u64 {
+		self.value_cache_hits
+	}
+
+	/// Utility function U(R_w, e_w): immediate reward for period w
+	///
+	/// Rival differences are taken against the best-scoring portfolio member.
+	pub fn period_utility(&self, _state: &State<R>, period_outcomes: &PeriodOutcomes<R::Outcome>) -> f64 {
+		tiered_utility(&self.hierarchy, &self.portfolio, &self.weights, period_outcomes, |rival, weight, primary_score| {
+			self.rival_contribution(rival, weight, primary_score, period_outcomes)
+		})
 	}
 
 	/// Maximum possible utility for a single period
 	pub fn max_period_utility(&self) -> f64 {
-		self.weights.w_primary.mul_add(
-			1.0,
-			f64::from(self.hierarchy.tier1_rivals.len() as u32) * self.weights.w_tier1
-				+ f64::from(self.hierarchy.tier2_rivals.len() as u32) * self.weights.w_tier2
-				+ f64::from(self.hierarchy.tier3_rivals.len() as u32) * self.weights.w_tier3,
-		)
+		self.weights.w_primary.mul_add(self.portfolio.total_weight(), self.weighted_rival_count())
+	}
+
+	/// Minimum possible utility for a single period
+	///
+	/// Zero in `Clamped` mode. In `Signed` mode every rival can beat a
+	/// scoreless primary, so the floor is the negated weighted rival count.
+	#[must_use]
+	pub fn min_period_utility(&self) -> f64 {
+		match self.diff_mode {
+			RivalDiffMode::Clamped => 0.0,
+			RivalDiffMode::Signed => -self.weighted_rival_count(),
+		}
 	}
 
-	/// Value function V_w(R_{w-1}): max achievable cumulative utility from period w onward
+	/// Sum of tier weights over all rivals
+	fn weighted_rival_count(&self) -> f64 {
+		self
+			.hierarchy
+			.tiers
+			.iter()
+			.zip(&self.weights.w_tiers)
+			.map(|(rivals, weight)| f64::from(rivals.len() as u32) * weight)
+			.sum()
+	}
+
+	/// Lowest discounted cumulative utility achievable from `period` through `max_periods`
+	fn value_floor(&self, period: usize) -> f64 {
+		let remaining = (self.max_periods + 1).saturating_sub(period);
+		let periods = if self.discount < 1.0 {
+			// Geometric sum 1 + γ + … + γ^(remaining - 1)
+			(1.0 - self.discount.powi(i32::try_from(remaining).unwrap_or(i32::MAX))) / (1.0 - self.discount)
+		} else {
+			f64::from(u32::try_from(remaining).unwrap_or(u32::MAX))
+		};
+		self.min_period_utility() * periods
+	}
+
+	/// Value function V_w(R_{w-1}): max achievable discounted cumulative utility from period w onward
 	pub fn value_function(&mut self, period: usize, state: &State<R>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> f64 {
 		// Terminal condition
 		if period > self.max_periods {
@@ -285,16 +800,17 @@ impl<R: CumulativeRecord> GenericOptimalityEngine<R> {
 		// Check cache
 		let cache_key = (period, state.clone());
 		if let Some(&cached_value) = self.value_cache.get(&cache_key) {
+			self.value_cache_hits += 1;
 			return cached_value;
 		}
 
-		// Compute max over all feasible outcomes
-		let mut max_value: f64 = 0.0;
+		// Compute max over all feasible outcomes (negative totals are possible in signed mode)
+		let mut max_value: f64 = if feasible_outcomes.is_empty() { 0.0 } else { f64::NEG_INFINITY };
 		for outcome in feasible_outcomes {
 			let immediate_utility = self.period_utility(state, outcome);
 			let next_state = state.apply_period(outcome);
 			let future_value = self.value_function(period + 1, &next_state, feasible_outcomes);
-			let total_value: f64 = immediate_utility + future_value;
+			let total_value: f64 = self.discount.mul_add(future_value, immediate_utility);
 			max_value = max_value.max(total_value);
 		}
 
@@ -303,28 +819,94 @@ impl<R: CumulativeRecord> GenericOptimalityEngine<R> {
 		max_value
 	}
 
-	/// Compute optimal outcome e*_w for a given state
-	pub fn optimal_outcome(&mut self, period: usize, state: &State<R>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> Option<PeriodOutcomes<R::Outcome>> {
-		if period > self.max_periods || feasible_outcomes.is_empty() {
-			return None;
+	/// Expected value function `E[V_w(R_{w-1})]`: expected cumulative utility from period w onward
+	///
+	/// The stochastic counterpart of [`value_function`](Self::value_function):
+	/// each period's outcome is drawn from `distribution`, so the value is the
+	/// probability-weighted sum over next states rather than the best one.
+	pub fn expected_value_function(&mut self, period: usize, state: &State<R>, distribution: &ProbabilisticOutcomes<R::Outcome>) -> f64 {
+		if period > self.max_periods {
+			return 0.0;
 		}
 
-		let mut best_outcome = None;
-		let mut best_value = f64::NEG_INFINITY;
+		let cache_key = (period, state.clone());
+		if let Some(&cached_value) = self.expected_cache.get(&cache_key) {
+			return cached_value;
+		}
 
-		for outcome in feasible_outcomes {
+		let mut expected_value = 0.0;
+		for (outcome, probability) in distribution.outcomes() {
+			if *probability == 0.0 {
+				continue;
+			}
 			let immediate_utility = self.period_utility(state, outcome);
 			let next_state = state.apply_period(outcome);
-			let future_value = self.value_function(period + 1, &next_state, feasible_outcomes);
-			let total_value = immediate_utility + future_value;
+			let future_value = self.expected_value_function(period + 1, &next_state, distribution);
+			expected_value = probability.mul_add(self.discount.mul_add(future_value, immediate_utility), expected_value);
+		}
 
-			if total_value > best_value {
-				best_value = total_value;
-				best_outcome = Some(outcome.clone());
-			}
+		self.expected_cache.insert(cache_key, expected_value);
+		expected_value
+	}
+
+	/// Compute optimal outcome e*_w for a given state
+	///
+	/// Ties between equally valued outcomes are resolved by the engine's [`TieBreak`].
+	pub fn optimal_outcome(&mut self, period: usize, state: &State<R>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> Option<PeriodOutcomes<R::Outcome>> {
+		let tied = self.optimal_outcomes_tied(period, state, feasible_outcomes);
+
+		match self.tie_break {
+			TieBreak::First => tied.into_iter().next(),
+			TieBreak::HighestPrimaryScore => tied
+				.into_iter()
+				.map(|outcome| (self.portfolio.scores(&outcome).0, outcome))
+				.reduce(|best, candidate| if candidate.0 > best.0 { candidate } else { best })
+				.map(|(_, outcome)| outcome),
 		}
+	}
 
-		best_outcome
+	/// Optimal path e*_1..e*_N from `start_state`, one outcome per period through `max_periods`
+	///
+	/// Rolls forward greedily, applying each period's [`optimal_outcome`](Self::optimal_outcome)
+	/// to get the next state. The path is empty if there are no feasible outcomes.
+	pub fn optimal_policy(&mut self, start_state: &State<R>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> Vec<PeriodOutcomes<R::Outcome>> {
+		let mut state = start_state.clone();
+		let mut path = Vec::with_capacity(self.max_periods);
+		for period in 1..=self.max_periods {
+			let Some(outcome) = self.optimal_outcome(period, &state, feasible_outcomes) else {
+				break;
+			};
+			state = state.apply_period(&outcome);
+			path.push(outcome);
+		}
+		path
+	}
+
+	/// Every outcome achieving the optimal value for a given state, in enumeration order
+	pub fn optimal_outcomes_tied(&mut self, period: usize, state: &State<R>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> Vec<PeriodOutcomes<R::Outcome>> {
+		if period > self.max_periods {
+			return Vec::new();
+		}
+
+		let values: Vec<f64> = feasible_outcomes
+			.iter()
+			.map(|outcome| {
+				let immediate_utility = self.period_utility(state, outcome);
+				let next_state = state.apply_period(outcome);
+				let future_value = self.value_function(period + 1, &next_state, feasible_outcomes);
+				self.discount.mul_add(future_value, immediate_utility)
+			})
+			.collect();
+
+		let best_value = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+		let tolerance = TIE_TOLERANCE * best_value.abs().max(1.0);
+
+		feasible_outcomes
+			.iter()
+			.zip(values)
+			.filter(|&(_, value)| best_value - value <= tolerance)
+			.map(|(outcome, _)| outcome.clone())
+			.collect()
 	}
 
 	/// Observed cumulative utility V^obs_w(R_{w-1})
@@ -336,10 +918,17 @@ impl<R: CumulativeRecord> GenericOptimalityEngine<R> {
 		let immediate_utility = self.period_utility(state, observed_outcome);
 		let next_state = state.apply_period(observed_outcome);
 		let future_value = self.value_function(period + 1, &next_state, feasible_outcomes);
-		immediate_utility + future_value
+		self.discount.mul_add(future_value, immediate_utility)
 	}
 
-	/// Per-period optimality score: Optimality_w ∈ [0, 1]
+	/// Per-period optimality score under the engine's [`OptimalityMetric`]
+	///
+	/// Scores are normalized against the worst achievable value from this
+	/// period onward. With the default `Ratio` metric that is
+	/// `(observed - floor) / (optimal - floor)` ∈ [0, 1]; in `Clamped` mode the
+	/// floor is zero, giving the plain ratio `observed / optimal`. In `Signed`
+	/// mode the floor is negative, so penalties shift both values before
+	/// normalizing rather than collapsing every negative season to 0.
 	pub fn period_optimality(
 		&mut self,
 		period: usize,
@@ -349,15 +938,12 @@ impl<R: CumulativeRecord> GenericOptimalityEngine<R> {
 	) -> f64 {
 		let observed_val = self.observed_value(period, state, observed_outcome, feasible_outcomes);
 		let optimal_val = self.value_function(period, state, feasible_outcomes);
+		let floor = self.value_floor(period);
 
-		if optimal_val > 0.0 {
-			(observed_val / optimal_val).clamp(0.0, 1.0)
-		} else {
-			0.0
-		}
+		self.metric.score(observed_val, optimal_val, floor)
 	}
 
-	/// Season-level optimality: average across all periods
+	/// Season-level optimality: average of the per-period scores
 	pub fn season_optimality(&mut self, observed_periods: &[(State<R>, PeriodOutcomes<R::Outcome>)], feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> f64 {
 		if observed_periods.is_empty() {
 			return 0.0;
@@ -373,8 +959,253 @@ impl<R: CumulativeRecord> GenericOptimalityEngine<R> {
 		total / (observed_periods.len() as f64)
 	}
 
+	/// Season optimality with a bootstrap confidence interval: `(estimate, lower, upper)`
+	///
+	/// Per-period optimality scores are resampled with replacement `iterations`
+	/// times and the interval is read off the percentiles of the resampled means.
+	/// The estimate is the same value [`season_optimality`](Self::season_optimality)
+	/// returns. Resampling is seeded, so the same inputs always give the same bounds.
+	#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	pub fn season_optimality_ci(
+		&mut self,
+		observed_periods: &[(State<R>, PeriodOutcomes<R::Outcome>)],
+		feasible_outcomes: &[PeriodOutcomes<R::Outcome>],
+		iterations: usize,
+		confidence: f64,
+	) -> (f64, f64, f64) {
+		let scores: Vec<f64> = observed_periods
+			.iter()
+			.enumerate()
+			.map(|(period_idx, (state, outcome))| self.period_optimality(period_idx + 1, state, outcome, feasible_outcomes))
+			.collect();
+		if scores.is_empty() {
+			return (0.0, 0.0, 0.0);
+		}
+
+		let n = scores.len();
+		let estimate = scores.iter().sum::<f64>() / (n as f64);
+		if iterations == 0 {
+			return (estimate, estimate, estimate);
+		}
+
+		let mut rng = SplitMix64(BOOTSTRAP_SEED);
+		let mut means: Vec<f64> = (0..iterations).map(|_| (0..n).map(|_| scores[rng.below(n)]).sum::<f64>() / (n as f64)).collect();
+		means.sort_by(f64::total_cmp);
+
+		let tail = (1.0 - confidence.clamp(0.0, 1.0)) / 2.0;
+		let percentile = |q: f64| means[((means.len() - 1) as f64 * q).round() as usize];
+		(estimate, percentile(tail), percentile(1.0 - tail))
+	}
+
+	/// Season optimality over a grid of weights around the engine's own
+	///
+	/// Each weight is scaled by `1 - weight_perturbation`, `1` and
+	/// `1 + weight_perturbation` in every combination, skipping combinations
+	/// that fail [`HierarchicalWeights::validate`]. The engine's weights are
+	/// restored afterwards; the value cache is cleared since it was filled
+	/// under the perturbed weights.
+	pub fn sensitivity(
+		&mut self,
+		observed_periods: &[(State<R>, PeriodOutcomes<R::Outcome>)],
+		feasible_outcomes: &[PeriodOutcomes<R::Outcome>],
+		weight_perturbation: f64,
+	) -> SensitivityReport {
+		let nominal_weights = self.weights.clone();
+		let nominal = self.season_optimality(observed_periods, feasible_outcomes);
+		let mut report = SensitivityReport {
+			nominal,
+			min: nominal,
+			max: nominal,
+			min_weights: nominal_weights.clone(),
+			max_weights: nominal_weights.clone(),
+			evaluated: 0,
+			skipped: 0,
+		};
+
+		for weights in nominal_weights.perturbations(weight_perturbation.abs()) {
+			if weights.validate().is_err() {
+				report.skipped += 1;
+				continue;
+			}
+
+			self.weights = weights;
+			self.clear_cache();
+			let score = self.season_optimality(observed_periods, feasible_outcomes);
+			report.evaluated += 1;
+			if score < report.min {
+				report.min = score;
+				report.min_weights = self.weights.clone();
+			}
+			if score > report.max {
+				report.max = score;
+				report.max_weights = self.weights.clone();
+			}
+		}
+
+		self.weights = nominal_weights;
+		self.clear_cache();
+		report
+	}
+
+	/// Every state reachable from `start_state` by applying feasible outcomes forward
+	///
+	/// Starts at `start_period` and steps through at most `periods` periods,
+	/// stopping early at the engine's horizon. The start state and every
+	/// intermediate state are included, so the size of the set is the number of
+	/// DP states a full [`value_function`](Self::value_function) pass can visit.
+	#[must_use]
+	pub fn reachable_states(&self, start_period: usize, start_state: &State<R>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>], periods: usize) -> HashSet<State<R>> {
+		let mut reachable = HashSet::from([start_state.clone()]);
+		let mut frontier = vec![start_state.clone()];
+
+		let last_period = start_period.saturating_add(periods).min(self.max_periods + 1);
+		for _ in start_period..last_period {
+			let mut next = Vec::new();
+			for state in &frontier {
+				for outcome in feasible_outcomes {
+					let next_state = state.apply_period(outcome);
+					if reachable.insert(next_state.clone()) {
+						next.push(next_state);
+					}
+				}
+			}
+			frontier = next;
+		}
+
+		reachable
+	}
+
+	/// Whether `a` is at least as good as `b` for every entity the utility reads:
+	/// no portfolio member scores lower and no rival scores higher
+	fn at_least_as_good(&self, a: &PeriodOutcomes<R::Outcome>, b: &PeriodOutcomes<R::Outcome>) -> bool {
+		self.portfolio.members().iter().all(|&(member, _)| a.get_score(member) >= b.get_score(member))
+			&& self.hierarchy.tiers.iter().flatten().all(|&rival| a.get_score(rival) <= b.get_score(rival))
+	}
+
+	/// Indices of the feasible outcomes that are dominated by another one
+	///
+	/// An outcome is dominated when another is at least as good for every
+	/// portfolio member and rival and strictly better for one of them. Of
+	/// outcomes that agree on all of those scores, every one but the first is
+	/// reported too. Entities outside the portfolio and hierarchy are ignored,
+	/// since the utility never reads them.
+	#[must_use]
+	pub fn dominated_outcomes(&self, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> Vec<usize> {
+		(0..feasible_outcomes.len())
+			.filter(|&i| {
+				let outcome = &feasible_outcomes[i];
+				feasible_outcomes
+					.iter()
+					.enumerate()
+					.any(|(j, other)| j != i && self.at_least_as_good(other, outcome) && (j < i || !self.at_least_as_good(outcome, other)))
+			})
+			.collect()
+	}
+
+	/// The feasible outcomes without the [`dominated_outcomes`](Self::dominated_outcomes), in their original order
+	///
+	/// Period utility never decreases as a portfolio member's score rises or a
+	/// rival's falls, in either [`RivalDiffMode`], and doesn't depend on the
+	/// state. A dominated outcome is therefore never worth more than the one
+	/// dominating it, so [`value_function`](Self::value_function) and the
+	/// optimality scores are unchanged over the pruned set while the DP visits
+	/// fewer branches. Only the choice among equally valued outcomes can differ.
+	#[must_use]
+	pub fn prune_dominated(&self, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> Vec<PeriodOutcomes<R::Outcome>> {
+		let dominated: HashSet<usize> = self.dominated_outcomes(feasible_outcomes).into_iter().collect();
+		feasible_outcomes
+			.iter()
+			.enumerate()
+			.filter(|(i, _)| !dominated.contains(i))
+			.map(|(_, outcome)| outcome.clone())
+			.collect()
+	}
+
+	/// Record the next period of a season in progress, returning its optimality score
+	///
+	/// Periods are numbered in the order they are observed, starting at 1, so
+	/// feeding a season in one period at a time keeps the same running average as
+	/// [`season_optimality`](Self::season_optimality) over the whole season. The
+	/// value function cache is reused, so each new period only costs the DP
+	/// states it hasn't visited yet.
+	pub fn observe_period(&mut self, state: &State<R>, observed_outcome: &PeriodOutcomes<R::Outcome>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> f64 {
+		let optimality = self.period_optimality(self.periods_observed + 1, state, observed_outcome, feasible_outcomes);
+		self.periods_observed += 1;
+		self.observed_total += optimality;
+		optimality
+	}
+
+	/// Average optimality over the periods observed so far, 0 before the first
+	#[must_use]
+	#[allow(clippy::cast_precision_loss)]
+	pub fn running_optimality(&self) -> f64 {
+		if self.periods_observed == 0 {
+			return 0.0;
+		}
+		self.observed_total / self.periods_observed as f64
+	}
+
+	#[must_use]
+	pub const fn periods_observed(&self) -> usize {
+		self.periods_observed
+	}
+
+	/// Forget observed periods to start tracking a new season
+	pub const fn reset_observations(&mut self) {
+		self.periods_observed = 0;
+		self.observed_total = 0.0;
+	}
+
 	pub fn clear_cache(&mut self) {
 		self.value_cache.clear();
+		self.expected_cache.clear();
+		self.contribution_cache.get_mut().clear();
+	}
+}
+
+/// Period utility with each rival's weighted contribution supplied by `contribution`
+///
+/// Takes `(rival, tier weight, primary score)`; rival differences are taken
+/// against the best-scoring portfolio member.
+fn tiered_utility<O: EventOutcome>(
+	hierarchy: &EntityHierarchy,
+	portfolio: &PrimaryPortfolio,
+	weights: &HierarchicalWeights,
+	period_outcomes: &PeriodOutcomes<O>,
+	mut contribution: impl FnMut(EntityId, f64, f64) -> f64,
+) -> f64 {
+	let (portfolio_score, primary_score) = portfolio.scores(period_outcomes);
+
+	// Primary entities contribution
+	let mut utility = weights.w_primary * portfolio_score;
+
+	// Rival contributions, tier by tier
+	for (rivals, &weight) in hierarchy.tiers.iter().zip(&weights.w_tiers) {
+		for &rival in rivals {
+			utility += contribution(rival, weight, primary_score);
+		}
+	}
+
+	utility
+}
+
+const BOOTSTRAP_SEED: u64 = 0x5EA5_0DA7_A5EE_D001;
+
+/// Small deterministic generator for bootstrap resampling (`SplitMix64`)
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+	const fn next_u64(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^ (z >> 31)
+	}
+
+	/// Index in `0..n`
+	fn below(&mut self, n: usize) -> usize {
+		usize::try_from(self.next_u64() % n as u64).unwrap_or(0)
 	}
 }
 
@@ -384,6 +1215,7 @@ impl<R: CumulativeRecord> GenericOptimalityEngine<R> {
 
 /// Game outcome for team sports
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameOutcome {
 	Loss,
 	Tie,
@@ -410,6 +1242,7 @@ impl EventOutcome for GameOutcome {
 
 /// Team record: R_w(t) = (wins, losses, ties)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeamRecord {
 	pub wins: u8,
 	pub losses: u8,
@@ -451,6 +1284,7 @@ pub type TeamOptimalityEngine = GenericOptimalityEngine<TeamRecord>;
 
 /// Turnover outcome per game
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TurnoverOutcome {
 	Zero,
 	One,
@@ -480,6 +1314,7 @@ impl EventOutcome for TurnoverOutcome {
 
 /// Cumulative turnover record
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TurnoverRecord {
 	pub total_turnovers: u16,
 	pub games_played: u8,
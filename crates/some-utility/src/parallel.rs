@@ -0,0 +1,90 @@
+use crate::{tiered_utility, CumulativeRecord, EntityHierarchy, GenericOptimalityEngine, HierarchicalWeights, PeriodOutcomes, PrimaryPortfolio, RivalDiffMode, State};
+use dashmap::DashMap;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+/// Value cache shared between worker threads
+type SharedValueCache<R> = DashMap<(usize, State<R>), f64>;
+
+/// The engine's inputs to the value function, borrowed so they can be shared across threads
+///
+/// The engine itself is not `Sync` because of its single-threaded contribution memo.
+struct ParallelValue<'a, R: CumulativeRecord> {
+	hierarchy: &'a EntityHierarchy,
+	portfolio: &'a PrimaryPortfolio,
+	weights: &'a HierarchicalWeights,
+	diff_mode: RivalDiffMode,
+	discount: f64,
+	max_periods: usize,
+	cache: SharedValueCache<R>,
+}
+
+impl<R> ParallelValue<'_, R>
+where
+	R: CumulativeRecord + Send + Sync,
+	R::Outcome: Send + Sync,
+{
+	fn period_utility(&self, period_outcomes: &PeriodOutcomes<R::Outcome>) -> f64 {
+		tiered_utility(self.hierarchy, self.portfolio, self.weights, period_outcomes, |rival, weight, primary_score| {
+			weight * self.diff_mode.apply(primary_score, period_outcomes.get_score(rival))
+		})
+	}
+
+	fn value(&self, period: usize, state: &State<R>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> f64 {
+		if period > self.max_periods {
+			return 0.0;
+		}
+
+		let cache_key = (period, state.clone());
+		if let Some(cached_value) = self.cache.get(&cache_key).map(|value| *value) {
+			return cached_value;
+		}
+
+		// Two threads may race to fill the same state; both compute the same value
+		let max_value = if feasible_outcomes.is_empty() {
+			0.0
+		} else {
+			feasible_outcomes
+				.par_iter()
+				.map(|outcome| {
+					let immediate_utility = self.period_utility(outcome);
+					let next_state = state.apply_period(outcome);
+					let future_value = self.value(period + 1, &next_state, feasible_outcomes);
+					self.discount.mul_add(future_value, immediate_utility)
+				})
+				.reduce(|| f64::NEG_INFINITY, f64::max)
+		};
+
+		self.cache.insert(cache_key, max_value);
+		max_value
+	}
+}
+
+impl<R> GenericOptimalityEngine<R>
+where
+	R: CumulativeRecord + Send + Sync,
+	R::Outcome: Send + Sync,
+{
+	/// Parallel counterpart of [`value_function`](Self::value_function)
+	///
+	/// Evaluates the max over `feasible_outcomes` on the rayon thread pool,
+	/// with memoized values shared between threads. Worth it when there are
+	/// many feasible outcomes or many periods left; for small problems the
+	/// sequential version is faster. Values already in the engine's cache are
+	/// reused, and everything computed here is kept there afterwards.
+	pub fn value_function_parallel(&mut self, period: usize, state: &State<R>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> f64 {
+		let parallel = ParallelValue {
+			hierarchy: &self.hierarchy,
+			portfolio: &self.portfolio,
+			weights: &self.weights,
+			diff_mode: self.diff_mode,
+			discount: self.discount,
+			max_periods: self.max_periods,
+			cache: self.value_cache.drain().collect(),
+		};
+
+		let value = parallel.value(period, state, feasible_outcomes);
+		let cache = parallel.cache;
+		self.value_cache.extend(cache);
+		value
+	}
+}
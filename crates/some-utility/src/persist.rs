@@ -0,0 +1,74 @@
+use crate::{CumulativeRecord, EntityHierarchy, GenericOptimalityEngine, HierarchicalWeights, PrimaryPortfolio, RivalDiffMode, State};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Value cache on disk, with the engine settings its values were computed under
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "R: Serialize", deserialize = "R: DeserializeOwned"))]
+struct SavedCache<R: CumulativeRecord> {
+	max_periods: usize,
+	hierarchy: EntityHierarchy,
+	portfolio: PrimaryPortfolio,
+	weights: HierarchicalWeights,
+	discount: f64,
+	diff_mode: RivalDiffMode,
+	/// `(period, state, value)`, since JSON object keys can't be tuples
+	entries: Vec<(usize, State<R>, f64)>,
+}
+
+impl<R> GenericOptimalityEngine<R>
+where
+	R: CumulativeRecord + Serialize + DeserializeOwned,
+{
+	/// Write the value cache to `path` as JSON, for a warm start in a later process
+	///
+	/// # Errors
+	///
+	/// Returns an error if the file can't be written.
+	pub fn save_cache(&self, path: impl AsRef<Path>) -> Result<(), String> {
+		let saved = SavedCache {
+			max_periods: self.max_periods,
+			hierarchy: self.hierarchy.clone(),
+			portfolio: self.portfolio.clone(),
+			weights: self.weights.clone(),
+			discount: self.discount,
+			diff_mode: self.diff_mode,
+			entries: self.value_cache.iter().map(|((period, state), &value)| (*period, state.clone(), value)).collect(),
+		};
+		let mut file = BufWriter::new(File::create(path).map_err(|e| e.to_string())?);
+		serde_json::to_writer(&mut file, &saved).map_err(|e| e.to_string())?;
+		file.flush().map_err(|e| e.to_string())
+	}
+
+	/// Add the values saved by [`save_cache`](Self::save_cache) to the value cache
+	///
+	/// # Errors
+	///
+	/// Returns an error if the file can't be read or parsed, or if it was saved
+	/// by an engine whose values would differ from this one's: a different
+	/// horizon, hierarchy, portfolio, weights, discount or diff mode. The value
+	/// cache is left untouched on error.
+	pub fn load_cache(&mut self, path: impl AsRef<Path>) -> Result<(), String> {
+		let json = std::fs::read(path).map_err(|e| e.to_string())?;
+		let saved: SavedCache<R> = serde_json::from_slice(&json).map_err(|e| e.to_string())?;
+
+		if saved.max_periods != self.max_periods {
+			let (saved_periods, periods) = (saved.max_periods.to_string(), self.max_periods.to_string());
+			return Err(["cache was saved for ", &saved_periods, " periods, engine has ", &periods].concat());
+		}
+		if saved.hierarchy != self.hierarchy {
+			return Err("cache was saved for a different hierarchy".to_string());
+		}
+		if saved.portfolio != self.portfolio {
+			return Err("cache was saved for a different portfolio".to_string());
+		}
+		if saved.weights != self.weights || saved.discount.to_bits() != self.discount.to_bits() || saved.diff_mode != self.diff_mode {
+			return Err("cache was saved with different weights, discount or diff mode".to_string());
+		}
+
+		self.value_cache.extend(saved.entries.into_iter().map(|(period, state, value)| ((period, state), value)));
+		Ok(())
+	}
+}
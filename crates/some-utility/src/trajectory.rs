@@ -0,0 +1,129 @@
+use crate::{CumulativeRecord, EntityId, EventOutcome, GenericOptimalityEngine, PeriodOutcomes, State};
+use std::fmt::Write;
+
+/// One period of a [`Trajectory`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryStep<O: EventOutcome> {
+	/// Period number, starting at 1
+	pub period: usize,
+	/// Outcome chosen for this period
+	pub outcomes: PeriodOutcomes<O>,
+	/// Immediate utility `U_w` of the chosen outcome
+	pub utility: f64,
+	/// Discounted utility accumulated through this period
+	pub cumulative: f64,
+}
+
+/// Optimal path with its per-period utilities, for reporting
+///
+/// Built by [`GenericOptimalityEngine::optimal_trajectory`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory<O: EventOutcome> {
+	pub steps: Vec<TrajectoryStep<O>>,
+}
+
+impl<O: EventOutcome> TrajectoryStep<O> {
+	/// Chosen outcome as `entity:description` pairs ordered by entity, e.g. `0:win;1:loss`
+	fn outcome_label(&self) -> String {
+		let mut items: Vec<(&EntityId, &O)> = self.outcomes.outcomes.iter().collect();
+		items.sort_by_key(|(entity, _)| **entity);
+		let mut label = String::new();
+		for (index, (entity, outcome)) in items.iter().enumerate() {
+			if index > 0 {
+				label.push(';');
+			}
+			let _ = write!(label, "{}:{}", entity.0, outcome.description());
+		}
+		label
+	}
+}
+
+impl<O: EventOutcome> Trajectory<O> {
+	#[must_use]
+	pub const fn len(&self) -> usize {
+		self.steps.len()
+	}
+
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.steps.is_empty()
+	}
+
+	/// CSV with a `period,outcome,utility,cumulative` header and one row per period
+	#[must_use]
+	pub fn to_csv(&self) -> String {
+		let mut csv = String::from("period,outcome,utility,cumulative\n");
+		for step in &self.steps {
+			let _ = writeln!(csv, "{},{},{},{}", step.period, csv_field(&step.outcome_label()), step.utility, step.cumulative);
+		}
+		csv
+	}
+
+	/// JSON array with one `{period, outcome, utility, cumulative}` object per period
+	///
+	/// Utilities that aren't finite are written as `null`.
+	#[cfg(feature = "serde")]
+	#[must_use]
+	pub fn to_json(&self) -> String {
+		let rows: Vec<JsonRow> = self
+			.steps
+			.iter()
+			.map(|step| JsonRow {
+				period: step.period,
+				outcome: step.outcome_label(),
+				utility: step.utility,
+				cumulative: step.cumulative,
+			})
+			.collect();
+		let mut json = Vec::new();
+		// Writing plain rows into a Vec can't fail
+		let _ = serde_json::to_writer(&mut json, &rows);
+		String::from_utf8(json).unwrap_or_default()
+	}
+}
+
+/// One period as written by [`Trajectory::to_json`]
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct JsonRow {
+	period: usize,
+	outcome: String,
+	utility: f64,
+	cumulative: f64,
+}
+
+/// Quote a CSV field only when it needs it
+fn csv_field(value: &str) -> String {
+	if value.contains([',', '"', '\n']) {
+		["\"", &value.replace('"', "\"\""), "\""].concat()
+	} else {
+		value.to_string()
+	}
+}
+
+impl<R: CumulativeRecord> GenericOptimalityEngine<R> {
+	/// [`optimal_policy`](Self::optimal_policy) with each period's utility and the running value
+	///
+	/// The final `cumulative` equals `value_function(1, start_state, feasible_outcomes)`.
+	pub fn optimal_trajectory(&mut self, start_state: &State<R>, feasible_outcomes: &[PeriodOutcomes<R::Outcome>]) -> Trajectory<R::Outcome> {
+		let policy = self.optimal_policy(start_state, feasible_outcomes);
+
+		let mut state = start_state.clone();
+		let mut cumulative = 0.0;
+		let mut weight: f64 = 1.0;
+		let mut steps = Vec::with_capacity(policy.len());
+		for (index, outcomes) in policy.into_iter().enumerate() {
+			let utility = self.period_utility(&state, &outcomes);
+			cumulative = weight.mul_add(utility, cumulative);
+			weight *= self.discount;
+			state = state.apply_period(&outcomes);
+			steps.push(TrajectoryStep {
+				period: index + 1,
+				outcomes,
+				utility,
+				cumulative,
+			});
+		}
+		Trajectory { steps }
+	}
+}
@@ -7,33 +7,22 @@ mod comprehensive_tests {
 	// ========================================================================
 
 	fn create_simple_hierarchy() -> EntityHierarchy {
-		EntityHierarchy {
-			primary: EntityId(0),
-			tier1_rivals: vec![EntityId(1), EntityId(2)],
-			tier2_rivals: vec![EntityId(3)],
-			tier3_rivals: vec![EntityId(4)],
-		}
+		EntityHierarchy::three_tier(EntityId(0), vec![EntityId(1), EntityId(2)], vec![EntityId(3)], vec![EntityId(4)])
 	}
 
 	fn create_nfl_hierarchy() -> EntityHierarchy {
-		EntityHierarchy {
-			primary: EntityId(0),                                                                                                         // Your team
-			tier1_rivals: vec![EntityId(1), EntityId(2), EntityId(3)],                                                                    // Division
-			tier2_rivals: vec![EntityId(4), EntityId(5), EntityId(6), EntityId(7), EntityId(8), EntityId(9), EntityId(10), EntityId(11)], // Conference
-			tier3_rivals: vec![EntityId(12), EntityId(13), EntityId(14), EntityId(15)],                                                   // Other conference
-		}
+		EntityHierarchy::three_tier(
+			EntityId(0),                                                                                                    // Your team
+			vec![EntityId(1), EntityId(2), EntityId(3)],                                                                    // Division
+			vec![EntityId(4), EntityId(5), EntityId(6), EntityId(7), EntityId(8), EntityId(9), EntityId(10), EntityId(11)], // Conference
+			vec![EntityId(12), EntityId(13), EntityId(14), EntityId(15)],                                                   // Other conference
+		)
 	}
 
 	fn create_perfect_week(hierarchy: &EntityHierarchy) -> PeriodOutcomes<GameOutcome> {
 		let mut outcomes = PeriodOutcomes::new();
 		outcomes.set_outcome(hierarchy.primary, GameOutcome::Win);
-		for &rival in &hierarchy.tier1_rivals {
-			outcomes.set_outcome(rival, GameOutcome::Loss);
-		}
-		for &rival in &hierarchy.tier2_rivals {
-			outcomes.set_outcome(rival, GameOutcome::Loss);
-		}
-		for &rival in &hierarchy.tier3_rivals {
+		for &rival in hierarchy.tiers.iter().flatten() {
 			outcomes.set_outcome(rival, GameOutcome::Loss);
 		}
 		outcomes
@@ -42,13 +31,7 @@ mod comprehensive_tests {
 	fn create_worst_week(hierarchy: &EntityHierarchy) -> PeriodOutcomes<GameOutcome> {
 		let mut outcomes = PeriodOutcomes::new();
 		outcomes.set_outcome(hierarchy.primary, GameOutcome::Loss);
-		for &rival in &hierarchy.tier1_rivals {
-			outcomes.set_outcome(rival, GameOutcome::Win);
-		}
-		for &rival in &hierarchy.tier2_rivals {
-			outcomes.set_outcome(rival, GameOutcome::Win);
-		}
-		for &rival in &hierarchy.tier3_rivals {
+		for &rival in hierarchy.tiers.iter().flatten() {
 			outcomes.set_outcome(rival, GameOutcome::Win);
 		}
 		outcomes
@@ -58,13 +41,13 @@ mod comprehensive_tests {
 		let mut outcomes = PeriodOutcomes::new();
 		outcomes.set_outcome(hierarchy.primary, GameOutcome::Win);
 		// Half rivals win, half lose
-		for (idx, &rival) in hierarchy.tier1_rivals.iter().enumerate() {
+		for (idx, &rival) in hierarchy.tiers[0].iter().enumerate() {
 			outcomes.set_outcome(rival, if idx % 2 == 0 { GameOutcome::Loss } else { GameOutcome::Win });
 		}
-		for (idx, &rival) in hierarchy.tier2_rivals.iter().enumerate() {
+		for (idx, &rival) in hierarchy.tiers[1].iter().enumerate() {
 			outcomes.set_outcome(rival, if idx % 2 == 0 { GameOutcome::Loss } else { GameOutcome::Win });
 		}
-		for (idx, &rival) in hierarchy.tier3_rivals.iter().enumerate() {
+		for (idx, &rival) in hierarchy.tiers[2].iter().enumerate() {
 			outcomes.set_outcome(rival, if idx % 2 == 0 { GameOutcome::Loss } else { GameOutcome::Win });
 		}
 		outcomes
@@ -175,56 +158,67 @@ mod comprehensive_tests {
 
 	#[test]
 	fn test_valid_weights() {
-		let weights = HierarchicalWeights {
-			w_primary: 1.0,
-			w_tier1: 0.6,
-			w_tier2: 0.3,
-			w_tier3: 0.1,
-		};
+		let weights = HierarchicalWeights::three_tier(1.0, 0.6, 0.3, 0.1);
 		assert!(weights.validate().is_ok());
 	}
 
 	#[test]
 	fn test_invalid_weights_primary_zero() {
-		let weights = HierarchicalWeights {
-			w_primary: 0.0,
-			w_tier1: 0.6,
-			w_tier2: 0.3,
-			w_tier3: 0.1,
-		};
+		let weights = HierarchicalWeights::three_tier(0.0, 0.6, 0.3, 0.1);
 		assert!(weights.validate().is_err());
 	}
 
 	#[test]
 	fn test_invalid_weights_tier_ordering() {
 		// tier2 > tier1
-		let weights = HierarchicalWeights {
-			w_primary: 1.0,
-			w_tier1: 0.3,
-			w_tier2: 0.6,
-			w_tier3: 0.1,
-		};
+		let weights = HierarchicalWeights::three_tier(1.0, 0.3, 0.6, 0.1);
 		assert!(weights.validate().is_err());
 
 		// tier3 > tier2
-		let weights2 = HierarchicalWeights {
-			w_primary: 1.0,
-			w_tier1: 0.6,
-			w_tier2: 0.3,
-			w_tier3: 0.5,
-		};
+		let weights2 = HierarchicalWeights::three_tier(1.0, 0.6, 0.3, 0.5);
 		assert!(weights2.validate().is_err());
 	}
 
 	#[test]
 	fn test_invalid_weights_negative() {
+		let weights = HierarchicalWeights::three_tier(1.0, 0.6, 0.3, -0.1);
+		assert!(weights.validate().is_err());
+	}
+
+	#[test]
+	fn test_five_tier_hierarchy() {
+		// Division, conference, league, historical and casual rivals
+		let hierarchy = EntityHierarchy::builder(EntityId(0))
+			.tier([EntityId(1)])
+			.tier([EntityId(2), EntityId(3)])
+			.tier([EntityId(4)])
+			.tier([EntityId(5)])
+			.tier([EntityId(6), EntityId(7)])
+			.build()
+			.unwrap();
 		let weights = HierarchicalWeights {
 			w_primary: 1.0,
-			w_tier1: 0.6,
-			w_tier2: 0.3,
-			w_tier3: -0.1,
+			w_tiers: vec![0.8, 0.4, 0.2, 0.1, 0.05],
 		};
-		assert!(weights.validate().is_err());
+		let engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights, 17).unwrap();
+		let state = State::<TeamRecord>::new();
+
+		// Every tier counts, each with its own weight
+		assert!((engine.max_period_utility() - (1.0 + 0.8 + 2.0 * 0.4 + 0.2 + 0.1 + 2.0 * 0.05)).abs() < 1e-10);
+		assert!((engine.period_utility(&state, &create_perfect_week(&hierarchy)) - engine.max_period_utility()).abs() < 1e-10);
+
+		let mut outcomes = create_perfect_week(&hierarchy);
+		outcomes.set_outcome(EntityId(5), GameOutcome::Win);
+		assert!((engine.max_period_utility() - engine.period_utility(&state, &outcomes) - 0.1).abs() < 1e-10);
+
+		// Weights must stay strictly decreasing and line up with the tiers
+		let unordered = HierarchicalWeights {
+			w_primary: 1.0,
+			w_tiers: vec![0.8, 0.4, 0.2, 0.2, 0.05],
+		};
+		assert!(unordered.validate().unwrap_err().contains("tier-3"));
+		let result: Result<TeamOptimalityEngine, _> = GenericOptimalityEngine::new(hierarchy, HierarchicalWeights::default(), 17);
+		assert!(result.is_err());
 	}
 
 	// ========================================================================
@@ -286,13 +280,13 @@ mod comprehensive_tests {
 		// Primary wins, tier1 rival loses
 		let mut outcomes1 = PeriodOutcomes::new();
 		outcomes1.set_outcome(hierarchy.primary, GameOutcome::Win);
-		outcomes1.set_outcome(hierarchy.tier1_rivals[0], GameOutcome::Loss);
+		outcomes1.set_outcome(hierarchy.tiers[0][0], GameOutcome::Loss);
 		let utility1 = engine.period_utility(&state, &outcomes1);
 
 		// Primary wins, tier3 rival loses (should be less valuable)
 		let mut outcomes2 = PeriodOutcomes::new();
 		outcomes2.set_outcome(hierarchy.primary, GameOutcome::Win);
-		outcomes2.set_outcome(hierarchy.tier3_rivals[0], GameOutcome::Loss);
+		outcomes2.set_outcome(hierarchy.tiers[2][0], GameOutcome::Loss);
 		let utility2 = engine.period_utility(&state, &outcomes2);
 
 		assert!(utility1 > utility2, "Tier1 rival loss should be more valuable than tier3");
@@ -408,6 +402,33 @@ mod comprehensive_tests {
 		assert!(optimal.is_none());
 	}
 
+	#[test]
+	fn test_optimal_outcome_ties() {
+		let hierarchy = EntityHierarchy::three_tier(EntityId(0), vec![EntityId(1)], vec![], vec![]);
+		let weights = HierarchicalWeights::three_tier(0.5, 0.5, 0.3, 0.1);
+		let week = |primary, rival| {
+			let mut outcomes = PeriodOutcomes::new();
+			outcomes.set_outcome(EntityId(0), primary);
+			outcomes.set_outcome(EntityId(1), rival);
+			outcomes
+		};
+
+		// 0.5 * 0.5 + 0.5 * (0.5 - 0.0) == 0.5 * 1.0 + 0.5 * 0.0
+		let tie_over_loss = week(GameOutcome::Tie, GameOutcome::Loss);
+		let win_over_win = week(GameOutcome::Win, GameOutcome::Win);
+		let feasible = vec![week(GameOutcome::Loss, GameOutcome::Win), tie_over_loss.clone(), win_over_win.clone()];
+		let state = State::<TeamRecord>::new();
+
+		let mut engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy, weights, 1).unwrap();
+		assert_eq!(engine.tie_break(), TieBreak::First);
+		assert_eq!(engine.optimal_outcomes_tied(1, &state, &feasible), vec![tie_over_loss.clone(), win_over_win.clone()]);
+		assert_eq!(engine.optimal_outcome(1, &state, &feasible), Some(tie_over_loss));
+
+		let mut engine = engine.with_tie_break(TieBreak::HighestPrimaryScore);
+		assert_eq!(engine.optimal_outcome(1, &state, &feasible), Some(win_over_win));
+		assert!(engine.optimal_outcomes_tied(2, &state, &feasible).is_empty());
+	}
+
 	// ========================================================================
 	// Optimality Score Tests
 	// ========================================================================
@@ -528,6 +549,368 @@ mod comprehensive_tests {
 		assert!((season_opt - 0.5).abs() < 0.1);
 	}
 
+	#[test]
+	fn test_season_optimality_ci_contains_point_estimate() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let mut engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights, 17).unwrap();
+
+		let mut state = State::<TeamRecord>::new();
+		let mut observed = vec![];
+		let perfect = create_perfect_week(&hierarchy);
+		let mixed = create_mixed_week(&hierarchy);
+		let worst = create_worst_week(&hierarchy);
+
+		for i in 0..9 {
+			let outcome = [&perfect, &mixed, &worst][i % 3];
+			observed.push((state.clone(), outcome.clone()));
+			state = state.apply_period(outcome);
+		}
+
+		let feasible = vec![perfect, mixed, worst];
+		let season_opt = engine.season_optimality(&observed, &feasible);
+		let (estimate, lower, upper) = engine.season_optimality_ci(&observed, &feasible, 2000, 0.95);
+
+		assert_eq!(estimate, season_opt);
+		assert!(lower <= estimate && estimate <= upper);
+		assert!(lower < upper, "mixed results should produce a non-degenerate interval");
+		assert_eq!(engine.season_optimality_ci(&observed, &feasible, 2000, 0.95), (estimate, lower, upper));
+	}
+
+	#[test]
+	fn test_sensitivity_bounds_bracket_nominal_score() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let mut engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights.clone(), 17).unwrap();
+
+		let mut state = State::<TeamRecord>::new();
+		let mut observed = vec![];
+		let perfect = create_perfect_week(&hierarchy);
+		let mixed = create_mixed_week(&hierarchy);
+		let worst = create_worst_week(&hierarchy);
+
+		for i in 0..6 {
+			let outcome = [&mixed, &perfect, &worst][i % 3];
+			observed.push((state.clone(), outcome.clone()));
+			state = state.apply_period(outcome);
+		}
+
+		let feasible = vec![perfect, mixed, worst];
+		let season_opt = engine.season_optimality(&observed, &feasible);
+		let report = engine.sensitivity(&observed, &feasible, 0.5);
+
+		assert_eq!(report.nominal, season_opt);
+		assert!(report.min <= report.nominal && report.nominal <= report.max);
+		assert!(report.spread() > 0.0, "mixed weeks should depend on the rival weights");
+		// Halving the tier-1 weight puts it below tier 2, so some combinations are illegal
+		assert!(report.skipped > 0);
+		assert_eq!(report.evaluated + report.skipped, 81);
+		assert!(report.min_weights.validate().is_ok() && report.max_weights.validate().is_ok());
+
+		// The engine is left as it was
+		assert_eq!(engine.weights().w_tiers[0], weights.w_tiers[0]);
+		assert_eq!(engine.season_optimality(&observed, &feasible), season_opt);
+	}
+
+	#[test]
+	fn test_discount_weights_near_periods_over_distant_ones() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let perfect = create_perfect_week(&hierarchy);
+		let worst = create_worst_week(&hierarchy);
+		let feasible = vec![perfect.clone(), worst.clone()];
+
+		// A bad opening week followed by perfect ones
+		let mut state = State::<TeamRecord>::new();
+		let mut observed = vec![];
+		for outcome in [&worst, &perfect, &perfect, &perfect] {
+			observed.push((state.clone(), outcome.clone()));
+			state = state.apply_period(outcome);
+		}
+
+		let mut undiscounted: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights.clone(), 4).unwrap();
+		let mut discounted: TeamOptimalityEngine = GenericOptimalityEngine::with_discount(hierarchy.clone(), weights.clone(), 4, 0.5).unwrap();
+		assert_eq!(undiscounted.discount(), 1.0);
+
+		// The opening week is scored against the whole remaining season; with γ = 0.5
+		// the perfect weeks after it count for less, so the miss weighs more
+		let first_undiscounted = undiscounted.period_optimality(1, &observed[0].0, &observed[0].1, &feasible);
+		let first_discounted = discounted.period_optimality(1, &observed[0].0, &observed[0].1, &feasible);
+		assert!((first_undiscounted - 3.0 / 4.0).abs() < 1e-9, "expected 3/4, got {first_undiscounted}");
+		// Observed 0.5 + 0.25 + 0.125 of a perfect week, against 1 + 0.5 + 0.25 + 0.125
+		assert!((first_discounted - 0.875 / 1.875).abs() < 1e-9, "expected 7/15, got {first_discounted}");
+
+		let season_undiscounted = undiscounted.season_optimality(&observed, &feasible);
+		let season_discounted = discounted.season_optimality(&observed, &feasible);
+		assert!(season_discounted < season_undiscounted);
+
+		assert!(GenericOptimalityEngine::<TeamRecord>::with_discount(hierarchy.clone(), weights.clone(), 4, 1.5).is_err());
+		assert!(GenericOptimalityEngine::<TeamRecord>::with_discount(hierarchy, weights, 4, f64::NAN).is_err());
+	}
+
+	#[cfg(feature = "rayon")]
+	#[test]
+	fn test_parallel_value_function_matches_sequential() {
+		let hierarchy = create_simple_hierarchy();
+		let entities: Vec<EntityId> = std::iter::once(hierarchy.primary)
+			.chain(hierarchy.tiers[0].iter().copied())
+			.chain(hierarchy.tiers[1].iter().copied())
+			.chain(hierarchy.tiers[2].iter().copied())
+			.collect();
+
+		// Every win/loss combination: 32 feasible outcomes per period
+		let feasible: Vec<PeriodOutcomes<GameOutcome>> = (0..1u32 << entities.len())
+			.map(|mask| {
+				let mut outcomes = PeriodOutcomes::new();
+				for (bit, &entity) in entities.iter().enumerate() {
+					outcomes.set_outcome(entity, if mask & (1 << bit) == 0 { GameOutcome::Loss } else { GameOutcome::Win });
+				}
+				outcomes
+			})
+			.collect();
+
+		for (diff_mode, discount) in [(RivalDiffMode::Clamped, 1.0), (RivalDiffMode::Signed, 0.9)] {
+			let engine = || -> TeamOptimalityEngine {
+				GenericOptimalityEngine::with_discount(hierarchy.clone(), HierarchicalWeights::default(), 4, discount)
+					.unwrap()
+					.with_diff_mode(diff_mode)
+			};
+			let mut sequential = engine();
+			let mut parallel = engine();
+
+			let mut state = State::<TeamRecord>::new();
+			for period in 1..=4 {
+				let expected = sequential.value_function(period, &state, &feasible);
+				assert_eq!(parallel.value_function_parallel(period, &state, &feasible), expected, "{diff_mode:?} period {period}");
+				state = state.apply_period(&feasible[period * 7]);
+			}
+			// Later sequential calls reuse what the parallel pass memoized
+			assert_eq!(parallel.value_cache.len(), sequential.value_cache.len());
+		}
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_saved_cache_warm_starts_a_new_engine() {
+		let hierarchy = create_simple_hierarchy();
+		let feasible = vec![create_worst_week(&hierarchy), create_mixed_week(&hierarchy), create_perfect_week(&hierarchy)];
+		let path = std::env::temp_dir().join(format!("some-utility-cache-{}.json", std::process::id()));
+		let engine = |max_periods| -> TeamOptimalityEngine { GenericOptimalityEngine::new(hierarchy.clone(), HierarchicalWeights::default(), max_periods).unwrap() };
+
+		let mut cold = engine(6);
+		let mut state = State::<TeamRecord>::new();
+		let mut expected = Vec::new();
+		for outcome in &feasible {
+			expected.push(cold.value_function(1, &state, &feasible));
+			state = state.apply_period(outcome);
+		}
+		cold.save_cache(&path).unwrap();
+
+		let mut warm = engine(6);
+		warm.load_cache(&path).unwrap();
+		assert_eq!(warm.value_cache, cold.value_cache);
+
+		// Every lookup is answered straight from the loaded cache
+		let mut state = State::<TeamRecord>::new();
+		for (outcome, &value) in feasible.iter().zip(&expected) {
+			assert_eq!(warm.value_function(1, &state, &feasible), value);
+			state = state.apply_period(outcome);
+		}
+		assert_eq!(warm.value_cache_hits(), 3);
+		assert_eq!(warm.rival_contributions_computed(), 0);
+
+		// Values from a different horizon or hierarchy would be wrong here
+		let mut shorter = engine(5);
+		assert!(shorter.load_cache(&path).unwrap_err().contains("periods"));
+		let other_hierarchy = EntityHierarchy::three_tier(EntityId(0), vec![EntityId(1)], vec![EntityId(2), EntityId(3)], vec![EntityId(4)]);
+		let mut other: TeamOptimalityEngine = GenericOptimalityEngine::new(other_hierarchy, HierarchicalWeights::default(), 6).unwrap();
+		assert!(other.load_cache(&path).unwrap_err().contains("hierarchy"));
+		assert!(shorter.value_cache.is_empty() && other.value_cache.is_empty());
+
+		std::fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn test_optimal_policy_covers_every_period() {
+		let hierarchy = create_simple_hierarchy();
+		let mut engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), HierarchicalWeights::default(), 6).unwrap();
+		let perfect = create_perfect_week(&hierarchy);
+		let feasible = vec![create_worst_week(&hierarchy), create_mixed_week(&hierarchy), perfect.clone()];
+
+		let start = State::<TeamRecord>::new();
+		let policy = engine.optimal_policy(&start, &feasible);
+
+		assert_eq!(policy.len(), 6);
+		assert!(policy.iter().all(|outcome| feasible.contains(outcome)));
+		// Perfect weeks dominate, so the best path takes one every period
+		assert!(policy.iter().all(|outcome| *outcome == perfect));
+
+		// Following the path realizes the optimal value
+		let mut state = start.clone();
+		let mut realized = 0.0;
+		for outcome in &policy {
+			realized += engine.period_utility(&state, outcome);
+			state = state.apply_period(outcome);
+		}
+		assert!((realized - engine.value_function(1, &start, &feasible)).abs() < 1e-9);
+
+		assert!(engine.optimal_policy(&start, &[]).is_empty());
+	}
+
+	#[test]
+	fn test_trajectory_csv_has_one_row_per_period() {
+		let hierarchy = create_simple_hierarchy();
+		let mut engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), HierarchicalWeights::default(), 4).unwrap();
+		let feasible = vec![create_worst_week(&hierarchy), create_mixed_week(&hierarchy), create_perfect_week(&hierarchy)];
+
+		let start = State::<TeamRecord>::new();
+		let trajectory = engine.optimal_trajectory(&start, &feasible);
+		let csv = trajectory.to_csv();
+		let mut lines = csv.lines();
+
+		assert_eq!(lines.next(), Some("period,outcome,utility,cumulative"));
+		let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+		assert_eq!(rows.len(), 4);
+		for (index, row) in rows.iter().enumerate() {
+			assert_eq!(row.len(), 4);
+			assert_eq!(row[0].parse::<usize>().unwrap(), index + 1);
+			assert!(row[1].starts_with("0:win;1:loss"));
+			row[2].parse::<f64>().unwrap();
+			row[3].parse::<f64>().unwrap();
+		}
+
+		let total: f64 = rows.last().unwrap()[3].parse().unwrap();
+		assert!((total - engine.value_function(1, &start, &feasible)).abs() < 1e-9);
+
+		#[cfg(feature = "serde")]
+		{
+			let json = trajectory.to_json();
+			assert!(json.starts_with(r#"[{"period":1,"outcome":"0:win;1:loss"#));
+			assert_eq!(json.matches("\"period\"").count(), 4);
+			assert_eq!(engine.optimal_trajectory(&start, &[]).to_json(), "[]");
+		}
+	}
+
+	#[test]
+	fn test_observe_period_matches_batch_season_optimality() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let mut engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights, 17).unwrap();
+
+		let perfect = create_perfect_week(&hierarchy);
+		let mixed = create_mixed_week(&hierarchy);
+		let worst = create_worst_week(&hierarchy);
+		let feasible = vec![perfect.clone(), mixed.clone(), worst.clone()];
+
+		let mut state = State::<TeamRecord>::new();
+		let mut observed = vec![];
+		assert_eq!(engine.running_optimality(), 0.0);
+
+		for week in 0..8 {
+			let outcome = [&perfect, &worst, &mixed, &perfect][week % 4];
+			engine.observe_period(&state, outcome, &feasible);
+			observed.push((state.clone(), outcome.clone()));
+			state = state.apply_period(outcome);
+
+			// The running average after week N is the batch score of the first N weeks
+			let batch = engine.season_optimality(&observed, &feasible);
+			assert!((engine.running_optimality() - batch).abs() < 1e-12);
+			assert_eq!(engine.periods_observed(), week + 1);
+		}
+
+		engine.reset_observations();
+		assert_eq!(engine.periods_observed(), 0);
+		assert_eq!(engine.running_optimality(), 0.0);
+	}
+
+	#[test]
+	fn test_reachable_states_over_small_horizon() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights, 3).unwrap();
+
+		let feasible = vec![create_perfect_week(&hierarchy), create_mixed_week(&hierarchy), create_worst_week(&hierarchy)];
+		let start = State::<TeamRecord>::new();
+
+		// A state is fixed by how many of each week were played, so after k weeks
+		// there are C(k + 2, 2) of them: 1 + 3 + 6 + 10 over a 3-week horizon
+		let reachable = engine.reachable_states(1, &start, &feasible, 3);
+		assert_eq!(reachable.len(), 20);
+		assert!(reachable.contains(&start));
+
+		// Stepping further than the horizon stops at the last period
+		assert_eq!(engine.reachable_states(1, &start, &feasible, 10).len(), 20);
+		assert_eq!(engine.reachable_states(2, &start, &feasible, 10).len(), 10);
+		assert_eq!(engine.reachable_states(4, &start, &feasible, 10).len(), 1);
+	}
+
+	#[test]
+	fn test_pruning_dominated_outcomes_keeps_the_optimum() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let mut engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights.clone(), 4).unwrap();
+
+		// Neither of these beats the other: each has a different division rival winning
+		let mut first_rival_wins = create_mixed_week(&hierarchy);
+		first_rival_wins.set_outcome(EntityId(1), GameOutcome::Win);
+		first_rival_wins.set_outcome(EntityId(2), GameOutcome::Loss);
+		let mut second_rival_wins = first_rival_wins.clone();
+		second_rival_wins.set_outcome(EntityId(1), GameOutcome::Loss);
+		second_rival_wins.set_outcome(EntityId(2), GameOutcome::Win);
+
+		// The same week with the primary only tying is never the better choice
+		let mut primary_ties = first_rival_wins.clone();
+		primary_ties.set_outcome(hierarchy.primary, GameOutcome::Tie);
+		// Outcomes for entities outside the hierarchy don't make a difference
+		let mut unrelated_differs = second_rival_wins.clone();
+		unrelated_differs.set_outcome(EntityId(99), GameOutcome::Win);
+
+		let feasible = vec![first_rival_wins.clone(), primary_ties, second_rival_wins.clone(), unrelated_differs];
+		assert_eq!(engine.dominated_outcomes(&feasible), vec![1, 3]);
+		let pruned = engine.prune_dominated(&feasible);
+		assert_eq!(pruned, vec![first_rival_wins, second_rival_wins]);
+
+		let state = State::<TeamRecord>::new();
+		let full_value = engine.value_function(1, &state, &feasible);
+		let full_policy = engine.optimal_policy(&state, &feasible);
+
+		let mut pruned_engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy, weights, 4).unwrap();
+		assert!((pruned_engine.value_function(1, &state, &pruned) - full_value).abs() < 1e-12);
+		assert_eq!(pruned_engine.optimal_policy(&state, &pruned), full_policy);
+	}
+
+	#[test]
+	fn test_expected_value_over_two_outcome_period() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let mut engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights, 2).unwrap();
+
+		// Perfect week: 1.0 + 0.6 * 2 + 0.3 + 0.1 = 2.6; worst week: 0 in clamped mode
+		let distribution = ProbabilisticOutcomes::new([(create_perfect_week(&hierarchy), 0.25), (create_worst_week(&hierarchy), 0.75)]).unwrap();
+		let state = State::new();
+
+		let last_period = engine.expected_value_function(2, &state, &distribution);
+		assert!((last_period - 0.25 * 2.6).abs() < 1e-9);
+
+		let season = engine.expected_value_function(1, &state, &distribution);
+		assert!((season - 2.0 * 0.25 * 2.6).abs() < 1e-9);
+
+		// The deterministic path still takes the best case
+		let feasible = [create_perfect_week(&hierarchy), create_worst_week(&hierarchy)];
+		assert!((engine.value_function(1, &state, &feasible) - 2.0 * 2.6).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_probabilistic_outcomes_rejects_invalid_distributions() {
+		let hierarchy = create_simple_hierarchy();
+		let week = create_perfect_week(&hierarchy);
+
+		assert!(ProbabilisticOutcomes::<GameOutcome>::new([]).is_err());
+		assert!(ProbabilisticOutcomes::new([(week.clone(), 0.5)]).is_err());
+		assert!(ProbabilisticOutcomes::new([(week.clone(), -0.5), (week.clone(), 1.5)]).is_err());
+		assert!(ProbabilisticOutcomes::new([(week, 1.0)]).is_ok());
+	}
+
 	#[test]
 	fn test_season_optimality_empty() {
 		let hierarchy = create_simple_hierarchy();
@@ -652,7 +1035,7 @@ mod comprehensive_tests {
 		let state = State::<TurnoverRecord>::new();
 		let mut perfect = PeriodOutcomes::new();
 		perfect.set_outcome(hierarchy.primary, TurnoverOutcome::Zero);
-		for &rival in &hierarchy.tier1_rivals {
+		for &rival in &hierarchy.tiers[0] {
 			perfect.set_outcome(rival, TurnoverOutcome::ThreePlus);
 		}
 
@@ -715,12 +1098,7 @@ mod comprehensive_tests {
 			}
 		}
 
-		let hierarchy = EntityHierarchy {
-			primary: EntityId(0),
-			tier1_rivals: tier1,
-			tier2_rivals: tier2,
-			tier3_rivals: tier3,
-		};
+		let hierarchy = EntityHierarchy::three_tier(EntityId(0), tier1, tier2, tier3);
 
 		let weights = HierarchicalWeights::default();
 		let result: Result<TeamOptimalityEngine, _> = GenericOptimalityEngine::new(hierarchy, weights, 17);
@@ -731,21 +1109,238 @@ mod comprehensive_tests {
 	#[test]
 	fn test_zero_weight_tier() {
 		let hierarchy = create_simple_hierarchy();
-		let weights = HierarchicalWeights {
-			w_primary: 1.0,
-			w_tier1: 0.5,
-			w_tier2: 0.2,
-			w_tier3: 0.0, // Don't care about tier3
-		};
+		let weights = HierarchicalWeights::three_tier(1.0, 0.5, 0.2, 0.0); // Don't care about tier3
 		let engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights, 17).unwrap();
 
 		let state = State::<TeamRecord>::new();
 		let mut outcomes = PeriodOutcomes::new();
 		outcomes.set_outcome(hierarchy.primary, GameOutcome::Win);
-		outcomes.set_outcome(hierarchy.tier3_rivals[0], GameOutcome::Win);
+		outcomes.set_outcome(hierarchy.tiers[2][0], GameOutcome::Win);
 
 		let utility = engine.period_utility(&state, &outcomes);
 		// Tier3 result shouldn't affect utility
 		assert_eq!(utility, 1.0); // Only primary win counts
 	}
+
+	// ========================================================================
+	// Rival Difference Mode Tests
+	// ========================================================================
+
+	#[test]
+	fn test_signed_mode_penalizes_rival_wins() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let clamped: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights.clone(), 17).unwrap();
+		let signed: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights.clone(), 17)
+			.unwrap()
+			.with_diff_mode(RivalDiffMode::Signed);
+
+		let state = State::<TeamRecord>::new();
+		let worst = create_worst_week(&hierarchy);
+
+		// Clamped: rivals winning contribute nothing
+		assert_eq!(clamped.period_utility(&state, &worst), 0.0);
+
+		// Signed: each rival win is a weighted penalty
+		let expected = -(2.0 * weights.w_tiers[0] + weights.w_tiers[1] + weights.w_tiers[2]);
+		assert!((signed.period_utility(&state, &worst) - expected).abs() < 1e-10);
+		assert!((signed.min_period_utility() - expected).abs() < 1e-10);
+		assert_eq!(clamped.min_period_utility(), 0.0);
+		assert_eq!(signed.max_period_utility(), clamped.max_period_utility());
+	}
+
+	#[test]
+	fn test_signed_mode_optimality_stays_bounded() {
+		let hierarchy = create_simple_hierarchy();
+		let mut engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), HierarchicalWeights::default(), 2)
+			.unwrap()
+			.with_diff_mode(RivalDiffMode::Signed);
+
+		let state = State::<TeamRecord>::new();
+		let perfect = create_perfect_week(&hierarchy);
+		let worst = create_worst_week(&hierarchy);
+		let feasible = vec![perfect.clone(), worst.clone()];
+
+		// Optimal value is positive, worst path is negative
+		assert!(engine.value_function(1, &state, &[worst.clone()]) < 0.0);
+		engine.clear_cache();
+
+		let best = engine.period_optimality(1, &state, &perfect, &feasible);
+		let bad = engine.period_optimality(1, &state, &worst, &feasible);
+		assert!((best - 1.0).abs() < 1e-10);
+		assert!((0.0..1.0).contains(&bad));
+	}
+
+	// ========================================================================
+	// Optimality Metric Tests
+	// ========================================================================
+
+	#[test]
+	fn test_metric_scores_for_known_values() {
+		assert!((OptimalityMetric::Ratio.score(3.0, 4.0, 0.0) - 0.75).abs() < 1e-10);
+		assert!((OptimalityMetric::AbsoluteGap.score(3.0, 4.0, 0.0) - 1.0).abs() < 1e-10);
+		assert!((OptimalityMetric::NormalizedRegret.score(3.0, 4.0, 0.0) - 0.25).abs() < 1e-10);
+
+		// Measured from a negative floor: 5 of the 6 achievable units were reached
+		assert!((OptimalityMetric::Ratio.score(3.0, 4.0, -2.0) - 5.0 / 6.0).abs() < 1e-10);
+		assert!((OptimalityMetric::AbsoluteGap.score(3.0, 4.0, -2.0) - 1.0).abs() < 1e-10);
+		assert!((OptimalityMetric::NormalizedRegret.score(3.0, 4.0, -2.0) - 1.0 / 6.0).abs() < 1e-10);
+
+		// Nothing achievable: no ratio and nothing to regret
+		for metric in [OptimalityMetric::Ratio, OptimalityMetric::AbsoluteGap, OptimalityMetric::NormalizedRegret] {
+			assert_eq!(metric.score(0.0, 0.0, 0.0), 0.0);
+		}
+	}
+
+	#[test]
+	fn test_engine_scores_periods_with_selected_metric() {
+		let hierarchy = create_simple_hierarchy();
+		let state = State::<TeamRecord>::new();
+		let perfect = create_perfect_week(&hierarchy);
+		let worst = create_worst_week(&hierarchy);
+		let feasible = vec![perfect, worst.clone()];
+
+		let engine = |metric| -> TeamOptimalityEngine {
+			GenericOptimalityEngine::new(hierarchy.clone(), HierarchicalWeights::default(), 1)
+				.unwrap()
+				.with_metric(metric)
+		};
+		let mut ratio = engine(OptimalityMetric::Ratio);
+		let mut gap = engine(OptimalityMetric::AbsoluteGap);
+		let mut regret = engine(OptimalityMetric::NormalizedRegret);
+		assert_eq!(ratio.metric(), OptimalityMetric::default());
+
+		// The worst week scores nothing, leaving the whole best week on the table
+		let optimal = ratio.max_period_utility();
+		assert_eq!(ratio.period_optimality(1, &state, &worst, &feasible), 0.0);
+		assert!((gap.period_optimality(1, &state, &worst, &feasible) - optimal).abs() < 1e-10);
+		assert!((regret.period_optimality(1, &state, &worst, &feasible) - 1.0).abs() < 1e-10);
+	}
+
+	#[test]
+	fn test_rival_contributions_are_memoized_across_combinations() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights.clone(), 17).unwrap();
+		let state = State::<TeamRecord>::new();
+
+		// Every outcome combination for the primary and its four rivals
+		let outcomes = [GameOutcome::Loss, GameOutcome::Tie, GameOutcome::Win];
+		let entities = hierarchy.all_entities();
+		let mut combinations = 0;
+		for index in 0..outcomes.len().pow(entities.len() as u32) {
+			let mut period = PeriodOutcomes::new();
+			let mut rest = index;
+			for &entity in &entities {
+				period.set_outcome(entity, outcomes[rest % outcomes.len()]);
+				rest /= outcomes.len();
+			}
+
+			let primary = period.get_score(hierarchy.primary);
+			let diff = |rival: EntityId| (primary - period.get_score(rival)).max(0.0);
+			let naive = weights.w_primary * primary
+				+ hierarchy.tiers[0].iter().map(|&r| weights.w_tiers[0] * diff(r)).sum::<f64>()
+				+ hierarchy.tiers[1].iter().map(|&r| weights.w_tiers[1] * diff(r)).sum::<f64>()
+				+ hierarchy.tiers[2].iter().map(|&r| weights.w_tiers[2] * diff(r)).sum::<f64>();
+
+			assert!((engine.period_utility(&state, &period) - naive).abs() < 1e-10);
+			combinations += 1;
+		}
+
+		// Naive evaluation computes every rival once per combination; the memo
+		// computes each (rival, outcome, primary score) triple once
+		let rivals = entities.len() - 1;
+		assert_eq!(combinations, 243);
+		assert_eq!(engine.rival_contributions_computed(), (rivals * outcomes.len() * outcomes.len()) as u64);
+		assert!(engine.rival_contributions_computed() < (combinations * rivals) as u64);
+	}
+
+	// ========================================================================
+	// Hierarchy Validation Tests
+	// ========================================================================
+
+	#[test]
+	fn test_builder_rejects_duplicate_entity_across_tiers() {
+		let result = EntityHierarchy::builder(EntityId(0))
+			.tier1_rivals([EntityId(1), EntityId(2)])
+			.tier2_rivals([EntityId(3), EntityId(2)])
+			.build();
+
+		let err = result.unwrap_err();
+		assert!(err.contains("entity 2"), "unexpected error: {err}");
+		assert!(err.contains("tier-1") && err.contains("tier-2"), "unexpected error: {err}");
+
+		// Same rules apply to hand-built hierarchies and the engine
+		let hierarchy = EntityHierarchy::three_tier(EntityId(0), vec![EntityId(1), EntityId(1)], vec![], vec![]);
+		assert!(hierarchy.validate().is_err());
+		let engine: Result<TeamOptimalityEngine, _> = GenericOptimalityEngine::new(hierarchy, HierarchicalWeights::default(), 17);
+		assert!(engine.is_err());
+	}
+
+	#[test]
+	fn test_builder_rejects_primary_as_rival() {
+		let result = EntityHierarchy::builder(EntityId(0)).tier1_rivals([EntityId(1)]).tier3_rivals([EntityId(0)]).build();
+
+		let err = result.unwrap_err();
+		assert!(err.contains("primary") && err.contains("tier-3"), "unexpected error: {err}");
+
+		let hierarchy = EntityHierarchy::builder(EntityId(0))
+			.tier1_rivals([EntityId(1), EntityId(2)])
+			.tier2_rivals([EntityId(3)])
+			.tier3_rivals([EntityId(4)])
+			.build()
+			.unwrap();
+		assert_eq!(hierarchy.all_entities(), create_simple_hierarchy().all_entities());
+	}
+
+	// ========================================================================
+	// Portfolio Tests
+	// ========================================================================
+
+	#[test]
+	fn test_portfolio_utility_accounts_for_both_primaries() {
+		let hierarchy = EntityHierarchy::builder(EntityId(0)).tier1_rivals([EntityId(2)]).build().unwrap();
+		let weights = HierarchicalWeights::default();
+		let portfolio = PrimaryPortfolio::new([(EntityId(0), 1.0), (EntityId(1), 0.5)]).unwrap();
+		let engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy, weights.clone(), 17).unwrap().with_portfolio(portfolio).unwrap();
+		let state = State::<TeamRecord>::new();
+
+		// Only the second primary wins; the rival is still measured against it
+		let mut outcomes = PeriodOutcomes::new();
+		outcomes.set_outcome(EntityId(0), GameOutcome::Loss);
+		outcomes.set_outcome(EntityId(1), GameOutcome::Win);
+		outcomes.set_outcome(EntityId(2), GameOutcome::Loss);
+		let expected = weights.w_primary * 0.5 + weights.w_tiers[0];
+		assert!((engine.period_utility(&state, &outcomes) - expected).abs() < 1e-10);
+
+		// Both primaries winning is worth more than either alone
+		outcomes.set_outcome(EntityId(0), GameOutcome::Win);
+		let both = engine.period_utility(&state, &outcomes);
+		assert!((both - engine.max_period_utility()).abs() < 1e-10);
+		assert!(both > expected);
+	}
+
+	#[test]
+	fn test_single_primary_portfolio_matches_default() {
+		let hierarchy = create_simple_hierarchy();
+		let weights = HierarchicalWeights::default();
+		let default_engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights.clone(), 17).unwrap();
+		let portfolio_engine: TeamOptimalityEngine = GenericOptimalityEngine::new(hierarchy.clone(), weights.clone(), 17)
+			.unwrap()
+			.with_portfolio(PrimaryPortfolio::single(hierarchy.primary))
+			.unwrap();
+
+		let state = State::<TeamRecord>::new();
+		let mixed = create_mixed_week(&hierarchy);
+		assert_eq!(default_engine.period_utility(&state, &mixed), portfolio_engine.period_utility(&state, &mixed));
+		assert_eq!(default_engine.max_period_utility(), portfolio_engine.max_period_utility());
+
+		// Portfolio members cannot double as rivals
+		let overlapping = PrimaryPortfolio::new([(EntityId(0), 1.0), (EntityId(1), 1.0)]).unwrap();
+		assert!(GenericOptimalityEngine::<TeamRecord>::new(hierarchy, weights, 17)
+			.unwrap()
+			.with_portfolio(overlapping)
+			.is_err());
+		assert!(PrimaryPortfolio::new([]).is_err());
+	}
 }
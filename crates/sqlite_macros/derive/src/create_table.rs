@@ -0,0 +1,34 @@
+use crate::schema::{columns, primary_key, table_name, ColumnDef};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+/// Expand `#[derive(Schema)]`
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+	let Data::Struct(data) = &input.data else {
+		return Err(syn::Error::new_spanned(&input.ident, "Schema only supports structs"));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(syn::Error::new_spanned(&input.ident, "Schema requires named fields"));
+	};
+
+	let key = primary_key(input, fields).ok().and_then(|key| key.ident.as_ref()).map(ToString::to_string);
+	let mut columns = columns(input)?;
+	for column in &mut columns {
+		column.primary_key = key.as_deref() == Some(column.name.as_str());
+	}
+
+	let definitions = columns.iter().map(ColumnDef::to_sql).collect::<Vec<_>>().join(", ");
+	let sql = ["CREATE TABLE IF NOT EXISTS ", &table_name(input), " (", &definitions, ")"].concat();
+
+	let name = &input.ident;
+	Ok(quote! {
+		impl #name {
+			/// `CREATE TABLE IF NOT EXISTS` statement for this struct's table
+			#[must_use]
+			pub const fn create_table_sql() -> &'static str {
+				#sql
+			}
+		}
+	})
+}
@@ -0,0 +1,56 @@
+use crate::schema::{primary_key, table_name};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+/// Expand `#[derive(SqliteEntity)]`
+///
+/// The primary key is the `#[primary_key]` field, or `id` when none is marked.
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+	let Data::Struct(data) = &input.data else {
+		return Err(syn::Error::new_spanned(&input.ident, "SqliteEntity only supports structs"));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(syn::Error::new_spanned(&input.ident, "SqliteEntity requires named fields"));
+	};
+
+	let key = primary_key(input, fields)?;
+	let (key_name, key_type) = (key.ident.as_ref().map(ToString::to_string).unwrap_or_default(), &key.ty);
+
+	let table = table_name(input);
+	let count_sql = ["SELECT COUNT(*) FROM ", &table].concat();
+	let exists_sql = ["SELECT EXISTS(SELECT 1 FROM ", &table, " WHERE ", &key_name, " = ?)"].concat();
+
+	let name = &input.ident;
+	Ok(quote! {
+		impl #name {
+			/// Statement run by [`Self::count`]
+			#[must_use]
+			pub const fn count_all_sql() -> &'static str {
+				#count_sql
+			}
+
+			/// Statement run by [`Self::exists`], binding the primary key
+			#[must_use]
+			pub const fn exists_sql() -> &'static str {
+				#exists_sql
+			}
+
+			/// Number of rows in the table
+			pub async fn count<'e, E>(executor: E) -> Result<i64, sqlx::Error>
+			where
+				E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+			{
+				sqlx::query_scalar(Self::count_all_sql()).fetch_one(executor).await
+			}
+
+			/// Whether a row with this primary key exists
+			pub async fn exists<'e, E>(executor: E, id: &#key_type) -> Result<bool, sqlx::Error>
+			where
+				E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+			{
+				sqlx::query_scalar(Self::exists_sql()).bind(id).fetch_one(executor).await
+			}
+		}
+	})
+}
@@ -0,0 +1,70 @@
+use crate::schema::{columns, table_name};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+	parse::{Parse, ParseStream},
+	DeriveInput, Token,
+};
+
+mod kw {
+	syn::custom_keyword!(old);
+	syn::custom_keyword!(new);
+}
+
+/// `old: struct A { .. } new: struct A { .. }`
+pub struct SchemaChange {
+	old: DeriveInput,
+	new: DeriveInput,
+}
+
+impl Parse for SchemaChange {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		input.parse::<kw::old>()?;
+		input.parse::<Token![:]>()?;
+		let old = input.parse()?;
+		input.parse::<Option<Token![,]>>()?;
+		input.parse::<kw::new>()?;
+		input.parse::<Token![:]>()?;
+		let new = input.parse()?;
+		input.parse::<Option<Token![,]>>()?;
+		Ok(Self { old, new })
+	}
+}
+
+/// Expands to a `&[&str]` of `ALTER TABLE ... ADD COLUMN` statements, one per
+/// column present in `new` but not in `old`
+pub fn expand(change: &SchemaChange) -> syn::Result<TokenStream> {
+	let table = table_name(&change.new);
+	let existing = columns(&change.old)?;
+
+	let mut statements = Vec::new();
+	for column in columns(&change.new)? {
+		if existing.iter().any(|c| c.name == column.name) {
+			continue;
+		}
+		// SQLite rejects ADD COLUMN ... NOT NULL without a non-null default
+		if !column.nullable && column.default.is_none() {
+			return Err(syn::Error::new_spanned(
+				&change.new.ident,
+				[
+					"added column `",
+					column.name.as_str(),
+					"` is NOT NULL and needs a `#[sql_default = \"...\"]` (or make it an Option)",
+				]
+				.concat(),
+			));
+		}
+		// Nor can it add a UNIQUE column; that takes a new index or a table rebuild
+		if column.unique {
+			return Err(syn::Error::new_spanned(
+				&change.new.ident,
+				["added column `", column.name.as_str(), "` is UNIQUE, which ADD COLUMN cannot create"].concat(),
+			));
+		}
+		statements.push(["ALTER TABLE ", table.as_str(), " ADD COLUMN ", column.to_sql().as_str(), ";"].concat());
+	}
+
+	Ok(quote! {
+		&[#(#statements),*] as &[&str]
+	})
+}
@@ -0,0 +1,124 @@
+use syn::{Attribute, Data, DeriveInput, Field, Fields, FieldsNamed, GenericArgument, Lit, Meta, PathArguments, Type};
+
+/// Column definition derived from a struct field
+pub struct ColumnDef {
+	pub name: String,
+	pub sql_type: &'static str,
+	pub nullable: bool,
+	pub primary_key: bool,
+	/// Marked `#[unique]`
+	pub unique: bool,
+	pub default: Option<String>,
+}
+
+impl ColumnDef {
+	/// Column definition as it appears in DDL, e.g. `email TEXT NOT NULL UNIQUE DEFAULT ''`
+	pub fn to_sql(&self) -> String {
+		let mut sql = [self.name.as_str(), " ", self.sql_type].concat();
+		if !self.nullable {
+			sql.push_str(" NOT NULL");
+		}
+		if self.primary_key {
+			sql.push_str(" PRIMARY KEY");
+		}
+		if self.unique {
+			sql.push_str(" UNIQUE");
+		}
+		if let Some(default) = &self.default {
+			sql.push_str(" DEFAULT ");
+			sql.push_str(default);
+		}
+		sql
+	}
+}
+
+/// Table name from `#[table_name = "..."]`, falling back to the snake case struct name
+pub fn table_name(input: &DeriveInput) -> String {
+	string_attr(&input.attrs, "table_name").unwrap_or_else(|| to_snake_case(&input.ident.to_string()))
+}
+
+/// The `#[primary_key]` field, or the one named `id` when none is marked
+pub fn primary_key<'a>(input: &DeriveInput, fields: &'a FieldsNamed) -> syn::Result<&'a Field> {
+	fields
+		.named
+		.iter()
+		.find(|field| field.attrs.iter().any(|attr| attr.path.is_ident("primary_key")))
+		.or_else(|| fields.named.iter().find(|field| field.ident.as_ref().is_some_and(|ident| ident == "id")))
+		.ok_or_else(|| syn::Error::new_spanned(&input.ident, "mark the primary key with #[primary_key] or name it `id`"))
+}
+
+/// Column definitions for every named field of a struct
+pub fn columns(input: &DeriveInput) -> syn::Result<Vec<ColumnDef>> {
+	let Data::Struct(data) = &input.data else {
+		return Err(syn::Error::new_spanned(&input.ident, "only structs describe a table schema"));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(syn::Error::new_spanned(&input.ident, "table schema requires named fields"));
+	};
+
+	fields
+		.named
+		.iter()
+		.map(|field| {
+			let (sql_type, nullable) = sql_type(&field.ty).ok_or_else(|| syn::Error::new_spanned(&field.ty, "unsupported column type"))?;
+			Ok(ColumnDef {
+				name: field.ident.as_ref().map(ToString::to_string).unwrap_or_default(),
+				sql_type,
+				nullable,
+				primary_key: false,
+				unique: field.attrs.iter().any(|attr| attr.path.is_ident("unique")),
+				default: string_attr(&field.attrs, "sql_default"),
+			})
+		})
+		.collect()
+}
+
+/// `SQLite` storage class for a Rust type, and whether it is nullable (`Option<T>`)
+fn sql_type(ty: &Type) -> Option<(&'static str, bool)> {
+	let Type::Path(type_path) = ty else {
+		return None;
+	};
+	let segment = type_path.path.segments.last()?;
+
+	match segment.ident.to_string().as_str() {
+		"Option" => {
+			let PathArguments::AngleBracketed(args) = &segment.arguments else {
+				return None;
+			};
+			match args.args.first()? {
+				GenericArgument::Type(inner) => sql_type(inner).map(|(sql, _)| (sql, true)),
+				_ => None,
+			}
+		}
+		"i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "bool" => Some(("INTEGER", false)),
+		"f32" | "f64" => Some(("REAL", false)),
+		"Vec" => Some(("BLOB", false)),
+		// Strings and text-encoded newtypes (e.g. `SqliteType` derives)
+		_ => Some(("TEXT", false)),
+	}
+}
+
+fn string_attr(attrs: &[Attribute], name: &str) -> Option<String> {
+	attrs.iter().filter(|attr| attr.path.is_ident(name)).find_map(|attr| match attr.parse_meta() {
+		Ok(Meta::NameValue(nv)) => match nv.lit {
+			Lit::Str(lit) => Some(lit.value()),
+			_ => None,
+		},
+		_ => None,
+	})
+}
+
+fn to_snake_case(name: &str) -> String {
+	let mut snake = String::with_capacity(name.len() + 4);
+	for (i, ch) in name.chars().enumerate() {
+		if ch.is_uppercase() {
+			if i > 0 {
+				snake.push('_');
+			}
+			snake.extend(ch.to_lowercase());
+		} else {
+			snake.push(ch);
+		}
+	}
+	snake
+}
@@ -0,0 +1,115 @@
+use crate::schema::{primary_key, table_name};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields, Ident};
+
+/// Expand `#[derive(SqliteUpdate)]`
+///
+/// The primary key is the `#[primary_key]` field, or `id` when none is marked.
+/// A `#[version]` field opts the table into optimistic locking.
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+	let Data::Struct(data) = &input.data else {
+		return Err(syn::Error::new_spanned(&input.ident, "SqliteUpdate only supports structs"));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(syn::Error::new_spanned(&input.ident, "SqliteUpdate requires named fields"));
+	};
+
+	// Named fields always carry an ident
+	let field_name = |field: &Field| field.ident.clone();
+	let has_attr = |field: &Field, name: &str| field.attrs.iter().any(|attr| attr.path.is_ident(name));
+
+	let primary_key = field_name(primary_key(input, fields)?).ok_or_else(|| syn::Error::new_spanned(&input.ident, "primary key must be a named field"))?;
+
+	let mut versions = fields.named.iter().filter(|field| has_attr(field, "version")).filter_map(field_name);
+	let version = versions.next();
+	if let Some(extra) = versions.next() {
+		return Err(syn::Error::new_spanned(extra, "only one field can be #[version]"));
+	}
+
+	let assigned: Vec<Ident> = fields
+		.named
+		.iter()
+		.filter_map(field_name)
+		.filter(|name| *name != primary_key && Some(name) != version.as_ref())
+		.collect();
+
+	let mut set: Vec<String> = assigned.iter().map(|name| [&name.to_string(), " = ?"].concat()).collect();
+	let mut filter = [&primary_key.to_string(), " = ?"].concat();
+	if let Some(version) = &version {
+		let version = version.to_string();
+		set.push([&version, " = ", &version, " + 1"].concat());
+		filter.push_str(&[" AND ", &version, " = ?"].concat());
+	}
+	let sql = ["UPDATE ", &table_name(input), " SET ", &set.join(", "), " WHERE ", &filter].concat();
+
+	let name = &input.ident;
+	let error_type = Ident::new(&[&name.to_string(), "UpdateError"].concat(), name.span());
+	let (bind_version, no_rows, bump_version) = version.as_ref().map_or_else(
+		|| (quote! {}, quote! { #error_type::NotFound }, quote! {}),
+		|version| {
+			(
+				quote! { .bind(&self.#version) },
+				quote! { #error_type::ConcurrencyConflict },
+				quote! { self.#version += 1; },
+			)
+		},
+	);
+
+	Ok(quote! {
+		#[derive(Debug)]
+		pub enum #error_type {
+			/// No row with this primary key
+			NotFound,
+			/// The row's version moved on since it was read
+			ConcurrencyConflict,
+			Database(sqlx::Error),
+		}
+
+		impl std::fmt::Display for #error_type {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				match self {
+					Self::NotFound => write!(f, "{} not found", stringify!(#name)),
+					Self::ConcurrencyConflict => write!(f, "{} was modified by another writer", stringify!(#name)),
+					Self::Database(e) => write!(f, "Database error: {}", e),
+				}
+			}
+		}
+
+		impl std::error::Error for #error_type {}
+
+		impl From<sqlx::Error> for #error_type {
+			fn from(err: sqlx::Error) -> Self {
+				Self::Database(err)
+			}
+		}
+
+		impl #name {
+			/// Statement run by [`Self::update`]
+			pub const UPDATE_SQL: &'static str = #sql;
+
+			/// Write every column back to this row
+			///
+			/// With a `#[version]` field the write only lands if nobody else has
+			/// updated the row since it was read; the in-memory version is bumped
+			/// to match on success.
+			pub async fn update<'e, E>(&mut self, executor: E) -> Result<(), #error_type>
+			where
+				E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+			{
+				let result = sqlx::query(Self::UPDATE_SQL)
+					#(.bind(&self.#assigned))*
+					.bind(&self.#primary_key)
+					#bind_version
+					.execute(executor)
+					.await?;
+
+				if result.rows_affected() == 0 {
+					return Err(#no_rows);
+				}
+				#bump_version
+				Ok(())
+			}
+		}
+	})
+}
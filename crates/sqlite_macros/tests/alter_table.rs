@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+	use sqlite_macros::alter_table_migration;
+
+	#[test]
+	fn test_added_columns_generate_alter_statements() {
+		let statements = alter_table_migration! {
+			old: struct MoodEvent {
+				id: i64,
+				mood: String,
+			}
+			new: struct MoodEvent {
+				id: i64,
+				mood: String,
+				#[sql_default = "''"]
+				note: String,
+				intensity: Option<f64>,
+			}
+		};
+
+		assert_eq!(
+			statements,
+			[
+				"ALTER TABLE mood_event ADD COLUMN note TEXT NOT NULL DEFAULT '';",
+				"ALTER TABLE mood_event ADD COLUMN intensity REAL;",
+			]
+		);
+	}
+
+	#[test]
+	fn test_unchanged_schema_generates_nothing() {
+		let statements = alter_table_migration! {
+			old: #[table_name = "tabs"] struct Tab { id: i64, url: String }
+			new: #[table_name = "tabs"] struct Tab { id: i64, url: String }
+		};
+
+		assert!(statements.is_empty());
+	}
+}
@@ -0,0 +1,21 @@
+use sqlite_macros::SqliteType;
+use sqlx::SqlitePool;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, SqliteType)]
+#[sqlite_type(backing = "i64")]
+struct UserId(i64);
+
+#[tokio::test]
+async fn test_i64_backed_type_roundtrips() {
+	let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+	sqlx::query("CREATE TABLE users (id INTEGER PRIMARY KEY)").execute(&pool).await.unwrap();
+	sqlx::query("INSERT INTO users (id) VALUES (?)").bind(UserId(42)).execute(&pool).await.unwrap();
+
+	let stored: i64 = sqlx::query_scalar("SELECT id FROM users").fetch_one(&pool).await.unwrap();
+	assert_eq!(stored, 42);
+	let stored_type: String = sqlx::query_scalar("SELECT typeof(id) FROM users").fetch_one(&pool).await.unwrap();
+	assert_eq!(stored_type, "integer");
+
+	let id: UserId = sqlx::query_scalar("SELECT id FROM users WHERE id = ?").bind(UserId(42)).fetch_one(&pool).await.unwrap();
+	assert_eq!(id, UserId(42));
+}
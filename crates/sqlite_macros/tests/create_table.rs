@@ -0,0 +1,49 @@
+use sqlite_macros::Schema;
+use sqlx::SqlitePool;
+
+#[allow(dead_code)]
+#[derive(Schema)]
+#[table_name = "users"]
+struct User {
+	id: i64,
+	#[unique]
+	email: String,
+	#[sql_default = "'UTC'"]
+	timezone: String,
+	nickname: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Schema)]
+struct Tag {
+	#[primary_key]
+	slug: String,
+	#[unique]
+	#[sql_default = "0"]
+	position: i64,
+}
+
+#[test]
+fn test_create_table_sql() {
+	let sql = User::create_table_sql();
+	assert!(sql.contains("email TEXT NOT NULL UNIQUE"), "{sql}");
+	assert_eq!(
+		sql,
+		"CREATE TABLE IF NOT EXISTS users (id INTEGER NOT NULL PRIMARY KEY, email TEXT NOT NULL UNIQUE, timezone TEXT NOT NULL DEFAULT 'UTC', nickname TEXT)"
+	);
+	assert_eq!(
+		Tag::create_table_sql(),
+		"CREATE TABLE IF NOT EXISTS tag (slug TEXT NOT NULL PRIMARY KEY, position INTEGER NOT NULL UNIQUE DEFAULT 0)"
+	);
+}
+
+#[tokio::test]
+async fn test_unique_column_is_enforced() {
+	let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+	sqlx::query(User::create_table_sql()).execute(&pool).await.unwrap();
+
+	sqlx::query("INSERT INTO users (id, email) VALUES (1, 'a@example.com')").execute(&pool).await.unwrap();
+	let timezone: String = sqlx::query_scalar("SELECT timezone FROM users WHERE id = 1").fetch_one(&pool).await.unwrap();
+	assert_eq!(timezone, "UTC");
+	assert!(sqlx::query("INSERT INTO users (id, email) VALUES (2, 'a@example.com')").execute(&pool).await.is_err());
+}
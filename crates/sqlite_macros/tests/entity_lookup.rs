@@ -0,0 +1,48 @@
+use sqlite_macros::SqliteEntity;
+use sqlx::SqlitePool;
+
+#[derive(SqliteEntity)]
+#[table_name = "tabs"]
+struct Tab {
+	id: i64,
+	title: String,
+}
+
+#[derive(SqliteEntity)]
+struct Note {
+	#[primary_key]
+	slug: String,
+	body: String,
+}
+
+async fn pool() -> SqlitePool {
+	let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+	sqlx::query("CREATE TABLE tabs (id INTEGER PRIMARY KEY, title TEXT NOT NULL)").execute(&pool).await.unwrap();
+	sqlx::query("CREATE TABLE note (slug TEXT PRIMARY KEY, body TEXT NOT NULL)").execute(&pool).await.unwrap();
+	pool
+}
+
+#[test]
+fn test_lookup_sql() {
+	assert_eq!(Tab::count_all_sql(), "SELECT COUNT(*) FROM tabs");
+	assert_eq!(Tab::exists_sql(), "SELECT EXISTS(SELECT 1 FROM tabs WHERE id = ?)");
+	assert_eq!(Note::exists_sql(), "SELECT EXISTS(SELECT 1 FROM note WHERE slug = ?)");
+}
+
+#[tokio::test]
+async fn test_count_and_exists() {
+	let pool = pool().await;
+	assert_eq!(Tab::count(&pool).await.unwrap(), 0);
+	assert!(!Tab::exists(&pool, &1).await.unwrap());
+
+	sqlx::query("INSERT INTO tabs (id, title) VALUES (1, 'one'), (2, 'two')").execute(&pool).await.unwrap();
+	sqlx::query("INSERT INTO note (slug, body) VALUES ('hello', 'world')").execute(&pool).await.unwrap();
+
+	assert_eq!(Tab::count(&pool).await.unwrap(), 2);
+	assert!(Tab::exists(&pool, &2).await.unwrap());
+	assert!(!Tab::exists(&pool, &3).await.unwrap());
+
+	assert_eq!(Note::count(&pool).await.unwrap(), 1);
+	assert!(Note::exists(&pool, &"hello".to_string()).await.unwrap());
+	assert!(!Note::exists(&pool, &"missing".to_string()).await.unwrap());
+}
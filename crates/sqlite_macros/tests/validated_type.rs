@@ -0,0 +1,43 @@
+use sqlite_macros::SqliteType;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, SqliteType)]
+#[sqlite_type(validate = true, pattern = "^[a-z0-9-]+$", min_length = 3, max_length = 16)]
+struct Slug(String);
+
+impl fmt::Display for Slug {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl FromStr for Slug {
+	type Err = SlugError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::validate(s)?;
+		Ok(Self(s.to_string()))
+	}
+}
+
+#[test]
+fn test_value_breaking_the_pattern_is_invalid() {
+	let err = Slug::validate("Not A Slug").unwrap_err();
+	assert!(matches!(err, SlugError::Invalid(_)));
+	assert!(err.to_string().contains("does not match pattern ^[a-z0-9-]+$"), "{err}");
+}
+
+#[test]
+fn test_value_under_min_length_is_invalid() {
+	let err = Slug::validate("ab").unwrap_err();
+	assert!(matches!(err, SlugError::Invalid(_)));
+	assert!(err.to_string().contains("shorter than minimum length of 3"), "{err}");
+}
+
+#[test]
+fn test_value_meeting_every_constraint_is_valid() {
+	assert!(Slug::validate("abc").is_ok());
+	assert_eq!("weekly-recap-2".parse::<Slug>().unwrap(), Slug("weekly-recap-2".to_string()));
+	assert!(Slug::validate("much-too-long-for-a-slug").is_err());
+}
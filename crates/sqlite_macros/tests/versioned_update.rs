@@ -0,0 +1,74 @@
+use sqlite_macros::SqliteUpdate;
+use sqlx::SqlitePool;
+
+#[derive(Clone, Debug, SqliteUpdate, sqlx::FromRow)]
+#[table_name = "tabs"]
+struct Tab {
+	id: i64,
+	title: String,
+	#[version]
+	version: i64,
+}
+
+#[derive(Clone, Debug, SqliteUpdate, sqlx::FromRow)]
+struct Note {
+	#[primary_key]
+	slug: String,
+	body: String,
+}
+
+async fn pool() -> SqlitePool {
+	let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+	sqlx::query("CREATE TABLE tabs (id INTEGER PRIMARY KEY, title TEXT NOT NULL, version INTEGER NOT NULL DEFAULT 0)")
+		.execute(&pool)
+		.await
+		.unwrap();
+	sqlx::query("INSERT INTO tabs (id, title) VALUES (1, 'draft')").execute(&pool).await.unwrap();
+	sqlx::query("CREATE TABLE note (slug TEXT PRIMARY KEY, body TEXT NOT NULL)").execute(&pool).await.unwrap();
+	pool
+}
+
+async fn read_tab(pool: &SqlitePool) -> Tab {
+	sqlx::query_as("SELECT id, title, version FROM tabs WHERE id = 1").fetch_one(pool).await.unwrap()
+}
+
+#[test]
+fn test_update_sql() {
+	assert_eq!(Tab::UPDATE_SQL, "UPDATE tabs SET title = ?, version = version + 1 WHERE id = ? AND version = ?");
+	assert_eq!(Note::UPDATE_SQL, "UPDATE note SET body = ? WHERE slug = ?");
+}
+
+#[tokio::test]
+async fn test_second_concurrent_update_conflicts() {
+	let pool = pool().await;
+	let mut first = read_tab(&pool).await;
+	let mut second = read_tab(&pool).await;
+
+	first.title = "first".to_string();
+	first.update(&pool).await.unwrap();
+	assert_eq!(first.version, 1);
+
+	second.title = "second".to_string();
+	assert!(matches!(second.update(&pool).await, Err(TabUpdateError::ConcurrencyConflict)));
+	assert_eq!(second.version, 0);
+
+	let stored = read_tab(&pool).await;
+	assert_eq!((stored.title.as_str(), stored.version), ("first", 1));
+
+	// A fresh read picks up the new version and can write again
+	let mut retry = stored;
+	retry.title = "second".to_string();
+	retry.update(&pool).await.unwrap();
+	assert_eq!(read_tab(&pool).await.version, 2);
+}
+
+#[tokio::test]
+async fn test_unversioned_update_of_missing_row() {
+	let pool = pool().await;
+	let mut note = Note {
+		slug: "missing".to_string(),
+		body: "hello".to_string(),
+	};
+
+	assert!(matches!(note.update(&pool).await, Err(NoteUpdateError::NotFound)));
+}
@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use stream_stepper::{Context, LiveTimeline, Payload, TimelineEvent};
+
+/// A multi-hour stream: 1000 closed chapters back to back, then one still going
+fn long_stream() -> (LiveTimeline, u64) {
+	let mut timeline = LiveTimeline::new();
+	let start = timeline.current_state().stream_start;
+	let chapter = |i: u64| TimelineEvent::StartChapter {
+		uid: i.to_string(),
+		context: Context::new("Play"),
+		start_time: start + i * 1_000,
+		payload: Payload::empty(),
+	};
+
+	for i in 0..1_000 {
+		timeline.process_event(chapter(i)).unwrap();
+		timeline
+			.process_event(TimelineEvent::EndChapter {
+				uid: i.to_string(),
+				end_time: start + (i + 1) * 1_000,
+				final_payload: None,
+			})
+			.unwrap();
+	}
+	timeline.process_event(chapter(1_000)).unwrap();
+	(timeline, start)
+}
+
+fn benchmark_snapshot(c: &mut Criterion) {
+	let (mut timeline, start) = long_stream();
+	let now = start + 1_000_500;
+
+	c.bench_function("snapshot 1000 closed chapters", |b| {
+		b.iter(|| timeline.generate_timeline_snapshot(black_box(now)).unwrap());
+	});
+
+	timeline.compact(start + 1_000_000);
+	c.bench_function("snapshot after compact", |b| {
+		b.iter(|| timeline.generate_timeline_snapshot(black_box(now)).unwrap());
+	});
+}
+
+criterion_group!(benches, benchmark_snapshot);
+criterion_main!(benches);
@@ -0,0 +1,115 @@
+use crate::types::Timestamp;
+use crate::{TimelineSegment, TimelineSnapshot, ARCHIVED_SEGMENT_TITLE};
+use serde::{Deserialize, Serialize};
+
+/// Duration stats over a snapshot's chapter segments, for post-stream reporting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineAnalytics {
+	/// Number of segments covered by at least one chapter
+	pub total_chapters: usize,
+	/// Time covered by chapters, excluding gaps and archived history
+	pub total_active_ms: u64,
+	/// Longest segment, the earliest one on ties
+	pub longest: Option<SegmentSummary>,
+	/// Shortest segment, the earliest one on ties
+	pub shortest: Option<SegmentSummary>,
+	/// Mean segment duration, 0 when there are no segments
+	pub mean_ms: f64,
+}
+
+/// Identifies one segment in [`TimelineAnalytics`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentSummary {
+	pub start_time: Timestamp,
+	pub title: String,
+	pub duration: u64,
+}
+
+impl From<&TimelineSegment> for SegmentSummary {
+	fn from(segment: &TimelineSegment) -> Self {
+		Self {
+			start_time: segment.start_time,
+			title: segment.title.clone(),
+			duration: segment.duration,
+		}
+	}
+}
+
+impl TimelineSnapshot {
+	/// Duration stats over the chapter segments in this snapshot
+	///
+	/// Ongoing segments count with their duration up to `current_time`, so
+	/// stats taken mid-stream reflect the stream so far. The archived-history
+	/// placeholder is not a chapter and is left out.
+	#[must_use]
+	#[allow(clippy::cast_precision_loss)]
+	pub fn analytics(&self) -> TimelineAnalytics {
+		let chapters: Vec<&TimelineSegment> = self.segments.iter().filter(|s| !(s.chapters.is_empty() && s.title == ARCHIVED_SEGMENT_TITLE)).collect();
+		let total_active_ms: u64 = chapters.iter().map(|s| s.duration).sum();
+
+		// Reversed so `max_by_key`/`min_by_key`, which keep the last of equals, pick the earliest
+		let longest = chapters.iter().rev().max_by_key(|s| s.duration).map(|s| SegmentSummary::from(*s));
+		let shortest = chapters.iter().rev().min_by_key(|s| s.duration).map(|s| SegmentSummary::from(*s));
+		let mean_ms = if chapters.is_empty() { 0.0 } else { total_active_ms as f64 / chapters.len() as f64 };
+
+		TimelineAnalytics {
+			total_chapters: chapters.len(),
+			total_active_ms,
+			longest,
+			shortest,
+			mean_ms,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::types::{Context, Payload};
+	use crate::{LiveChapters, TimelineEvent};
+
+	#[test]
+	fn test_analytics_over_closed_and_ongoing_segments() {
+		let mut chapters = LiveChapters::new();
+		let start = chapters.current_state().stream_start;
+		let begin = |uid: &str, start_time| TimelineEvent::StartChapter {
+			uid: uid.to_string(),
+			context: Context::new(uid),
+			start_time,
+			payload: Payload::empty(),
+		};
+		let end = |uid: &str, end_time| TimelineEvent::EndChapter {
+			uid: uid.to_string(),
+			end_time,
+			final_payload: None,
+		};
+
+		let events = vec![
+			begin("intro", start),
+			end("intro", start + 2_000),
+			begin("main", start + 2_000),
+			end("main", start + 8_000),
+			// A 1s gap with no chapter, then a segment still running
+			begin("qa", start + 9_000),
+		];
+		let snapshot = chapters.process_events_at_time(events, start + 12_000).unwrap();
+		let analytics = snapshot.analytics();
+
+		assert_eq!(analytics.total_chapters, 3);
+		assert_eq!(analytics.total_active_ms, 11_000);
+		let longest = analytics.longest.unwrap();
+		assert_eq!((longest.title.as_str(), longest.duration), ("main", 6_000));
+		let shortest = analytics.shortest.unwrap();
+		assert_eq!((shortest.title.as_str(), shortest.duration), ("intro", 2_000));
+		assert!((analytics.mean_ms - 11_000.0 / 3.0).abs() < 1e-9);
+
+		// The ongoing segment counts up to the snapshot time
+		let mut later = snapshot.clone();
+		later.refresh_durations(start + 20_000);
+		let analytics = later.analytics();
+		assert_eq!(analytics.total_active_ms, 19_000);
+		assert_eq!(analytics.longest.unwrap().title, "qa");
+
+		let empty = LiveChapters::new().get_timeline_snapshot(start).unwrap().analytics();
+		assert_eq!((empty.total_chapters, empty.total_active_ms, empty.longest, empty.mean_ms), (0, 0, None, 0.0));
+	}
+}
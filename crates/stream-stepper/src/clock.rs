@@ -0,0 +1,20 @@
+use crate::types::Timestamp;
+use std::cell::Cell;
+
+thread_local! {
+	/// Wall-clock time pinned while a batch is processed, so a replay can reproduce it
+	static PINNED: Cell<Option<Timestamp>> = const { Cell::new(None) };
+}
+
+/// Current wall-clock time, or the time pinned by [`pinned_at`]
+pub fn now() -> Timestamp {
+	PINNED.with(Cell::get).unwrap_or_else(|| u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or_default())
+}
+
+/// Run `f` with [`now`] reporting `at`
+pub fn pinned_at<T>(at: Timestamp, f: impl FnOnce() -> T) -> T {
+	let previous = PINNED.with(|pinned| pinned.replace(Some(at)));
+	let result = f();
+	PINNED.with(|pinned| pinned.set(previous));
+	result
+}
@@ -0,0 +1,165 @@
+use crate::error::{ChapterError, Result};
+use crate::types::Timestamp;
+use crate::{PlannedChapter, TimelineSegment, TimelineSnapshot};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Incremental update between two timeline snapshots
+///
+/// Segments are identified by their `start_time`, which is unique within a
+/// snapshot. Clients apply deltas in order; `base_version` lets them detect a
+/// missed delta and fall back to requesting a full snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDelta {
+	/// Version of the snapshot this delta was computed against
+	pub base_version: u64,
+	/// Version of the snapshot after applying this delta
+	pub version: u64,
+	/// The current livestream time
+	pub current_time: Timestamp,
+	/// Total duration of the livestream so far
+	pub total_duration: u64,
+	/// Number of active (ongoing) chapters
+	pub active_count: usize,
+	/// Segments that did not exist in the previous snapshot
+	pub added: Vec<TimelineSegment>,
+	/// Segments whose content changed since the previous snapshot
+	pub changed: Vec<TimelineSegment>,
+	/// Start times of segments that no longer exist
+	pub removed: Vec<Timestamp>,
+	/// Planned chapters, sent whole when they changed
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub upcoming: Option<Vec<PlannedChapter>>,
+}
+
+impl SnapshotDelta {
+	/// Check if the delta carries no segment or plan changes
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty() && self.upcoming.is_none()
+	}
+}
+
+impl TimelineSnapshot {
+	/// Compute the delta that turns `prev` into `self`
+	#[must_use]
+	pub fn diff(&self, prev: &Self) -> SnapshotDelta {
+		let previous: HashMap<Timestamp, &TimelineSegment> = prev.segments.iter().map(|s| (s.start_time, s)).collect();
+		let mut added = Vec::new();
+		let mut changed = Vec::new();
+
+		for segment in &self.segments {
+			match previous.get(&segment.start_time) {
+				None => added.push(segment.clone()),
+				Some(old) if *old != segment => changed.push(segment.clone()),
+				Some(_) => {}
+			}
+		}
+
+		let removed = prev
+			.segments
+			.iter()
+			.map(|s| s.start_time)
+			.filter(|start| !self.segments.iter().any(|s| s.start_time == *start))
+			.collect();
+
+		SnapshotDelta {
+			base_version: prev.version,
+			version: self.version,
+			current_time: self.current_time,
+			total_duration: self.total_duration,
+			active_count: self.active_count,
+			added,
+			changed,
+			removed,
+			upcoming: (self.upcoming != prev.upcoming).then(|| self.upcoming.clone()),
+		}
+	}
+
+	/// Apply a delta produced by [`TimelineSnapshot::diff`] against this snapshot
+	///
+	/// # Errors
+	///
+	/// Returns `VersionMismatch` if the delta was not computed against this
+	/// snapshot's version, meaning an earlier delta was missed.
+	pub fn apply(&mut self, delta: SnapshotDelta) -> Result<()> {
+		if delta.base_version != self.version {
+			return Err(ChapterError::VersionMismatch {
+				expected: self.version,
+				found: delta.base_version,
+			});
+		}
+
+		self.segments.retain(|s| !delta.removed.contains(&s.start_time));
+		for segment in delta.changed {
+			if let Some(existing) = self.segments.iter_mut().find(|s| s.start_time == segment.start_time) {
+				*existing = segment;
+			}
+		}
+		self.segments.extend(delta.added);
+		self.segments.sort_by_key(|s| s.start_time);
+
+		self.current_time = delta.current_time;
+		self.total_duration = delta.total_duration;
+		self.active_count = delta.active_count;
+		if let Some(upcoming) = delta.upcoming {
+			self.upcoming = upcoming;
+		}
+		self.version = delta.version;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn segment(start_time: Timestamp, end_time: Option<Timestamp>, title: &str) -> TimelineSegment {
+		TimelineSegment {
+			start_time,
+			end_time,
+			duration: end_time.unwrap_or(300).saturating_sub(start_time),
+			title: title.to_string(),
+			is_active: end_time.is_none(),
+			chapters: Vec::new(),
+			percentage: 0.0,
+		}
+	}
+
+	fn snapshot(version: u64, segments: Vec<TimelineSegment>) -> TimelineSnapshot {
+		TimelineSnapshot {
+			current_time: 300,
+			total_duration: 300,
+			segments,
+			active_count: 1,
+			upcoming: Vec::new(),
+			version,
+		}
+	}
+
+	#[test]
+	fn test_diff_contains_only_changed_segment() {
+		let prev = snapshot(1, vec![segment(0, Some(100), "Intro"), segment(100, Some(200), "Setup"), segment(200, None, "Gameplay")]);
+		let next = snapshot(
+			2,
+			vec![segment(0, Some(100), "Intro"), segment(100, Some(200), "Setup (edited)"), segment(200, None, "Gameplay")],
+		);
+
+		let delta = next.diff(&prev);
+
+		assert_eq!(delta.base_version, 1);
+		assert_eq!(delta.version, 2);
+		assert!(delta.added.is_empty());
+		assert!(delta.removed.is_empty());
+		assert_eq!(delta.changed.len(), 1);
+		assert_eq!(delta.changed[0].title, "Setup (edited)");
+
+		let mut applied = prev;
+		applied.apply(delta.clone()).unwrap();
+		assert_eq!(applied.segments, next.segments);
+		assert_eq!(applied.version, 2);
+
+		// Replaying against a stale base is rejected so the client can resync
+		assert!(applied.apply(delta).is_err());
+	}
+}
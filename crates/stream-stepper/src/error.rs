@@ -24,4 +24,7 @@ pub enum ChapterError {
 
 	#[error("Timeline generation error: {0}")]
 	TimelineGeneration(String),
+
+	#[error("Snapshot version mismatch: expected base {expected}, found {found}")]
+	VersionMismatch { expected: u64, found: u64 },
 }
@@ -21,6 +21,9 @@ pub enum TimelineEvent {
 	/// Update context/title of an existing chapter
 	UpdateContext { uid: Uid, context: Context },
 
+	/// Rename an existing chapter, keeping its tags and revision
+	RenameChapter { uid: Uid, new_title: String },
+
 	/// Remove a chapter completely
 	RemoveChapter { uid: Uid },
 
@@ -42,6 +45,7 @@ impl TimelineEvent {
 			| TimelineEvent::EndChapter { uid, .. }
 			| TimelineEvent::UpdatePayload { uid, .. }
 			| TimelineEvent::UpdateContext { uid, .. }
+			| TimelineEvent::RenameChapter { uid, .. }
 			| TimelineEvent::RemoveChapter { uid, .. }
 			| TimelineEvent::ExtendChapter { uid, .. }
 			| TimelineEvent::CompleteChapter { uid, .. } => Some(uid),
@@ -75,6 +79,9 @@ impl TimelineEvent {
 
 	/// Check if this event is a retroactive update
 	pub fn is_retroactive_update(&self) -> bool {
-		matches!(self, TimelineEvent::UpdatePayload { .. } | TimelineEvent::UpdateContext { .. })
+		matches!(
+			self,
+			TimelineEvent::UpdatePayload { .. } | TimelineEvent::UpdateContext { .. } | TimelineEvent::RenameChapter { .. }
+		)
 	}
 }
@@ -0,0 +1,135 @@
+use crate::event::TimelineEvent;
+use crate::template::ChapterTemplate;
+use crate::types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Append-only record of the changes a [`LiveChapters`](crate::LiveChapters) applied
+///
+/// Persist it and hand it to [`LiveChapters::replay`](crate::LiveChapters::replay)
+/// to rebuild the same timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLog {
+	/// Stream start of the timeline the events were applied to
+	pub stream_start: Timestamp,
+	/// Applied changes, oldest first
+	pub entries: Vec<LogEntry>,
+}
+
+/// One change to the timeline, with the wall-clock time it was applied at
+///
+/// `recorded_at` is reused for chapter bookkeeping on replay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LogEntry {
+	/// One call to [`LiveChapters::process_events_at_time`](crate::LiveChapters::process_events_at_time)
+	Events {
+		/// Events in the order they were applied
+		events: Vec<TimelineEvent>,
+		/// Timeline time the batch advanced to
+		current_time: Timestamp,
+		recorded_at: Timestamp,
+	},
+	/// One call to [`LiveChapters::apply_template`](crate::LiveChapters::apply_template)
+	Template {
+		template: ChapterTemplate,
+		start_time: Timestamp,
+		recorded_at: Timestamp,
+	},
+}
+
+impl EventLog {
+	/// Create an empty log for a timeline starting at `stream_start`
+	#[must_use]
+	pub const fn new(stream_start: Timestamp) -> Self {
+		Self {
+			stream_start,
+			entries: Vec::new(),
+		}
+	}
+
+	/// Append an applied change
+	pub fn push(&mut self, entry: LogEntry) {
+		self.entries.push(entry);
+	}
+
+	/// Number of recorded changes
+	#[must_use]
+	pub const fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Check if nothing has been recorded yet
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::types::*;
+	use crate::{ChapterTemplate, EventLog, LiveChapters, TimelineEvent};
+
+	#[test]
+	fn test_replayed_log_reproduces_snapshots() {
+		let mut chapters = LiveChapters::new();
+		let start = chapters.current_state().stream_start;
+		let chapter = |uid: &str, start_time| TimelineEvent::StartChapter {
+			uid: uid.to_string(),
+			context: Context::new(uid).with_tag("phase", "live"),
+			start_time,
+			payload: Payload::empty(),
+		};
+
+		let template = ChapterTemplate::new("show").with_chapter("Q&A", 1_000).with_chapter("Outro", 500);
+		let planned = chapters.apply_template(&template, start + 2_500).unwrap();
+		// Applying it twice clashes on the planned UIDs, and is recorded and rejected again on replay
+		assert!(chapters.apply_template(&template, start + 2_500).is_err());
+
+		chapters.process_events_at_time(vec![chapter("intro", start), chapter("chat", start)], start + 500).unwrap();
+		chapters
+			.process_events_at_time(
+				vec![
+					TimelineEvent::EndChapter {
+						uid: "intro".to_string(),
+						end_time: start + 1_000,
+						final_payload: Some(Payload::new("done").unwrap()),
+					},
+					chapter("main", start + 1_000),
+				],
+				start + 2_000,
+			)
+			.unwrap();
+		// A rejected event is recorded too, and fails the same way on replay
+		assert!(chapters
+			.process_event_at_time(
+				TimelineEvent::RenameChapter {
+					uid: "missing".to_string(),
+					new_title: "Nope".to_string(),
+				},
+				start + 2_500,
+			)
+			.is_err());
+		chapters
+			.process_event_at_time(
+				TimelineEvent::RenameChapter {
+					uid: "main".to_string(),
+					new_title: "Main event".to_string(),
+				},
+				start + 3_000,
+			)
+			.unwrap();
+		assert_eq!(chapters.event_log().len(), 6);
+
+		let json = serde_json::to_string(chapters.event_log()).unwrap();
+		let replayed = LiveChapters::replay(serde_json::from_str::<EventLog>(&json).unwrap());
+
+		let now = start + 4_000;
+		assert_eq!(replayed.get_timeline_snapshot(now).unwrap(), chapters.get_timeline_snapshot(now).unwrap());
+		assert_eq!(replayed.current_state().version, chapters.current_state().version);
+		assert_eq!(replayed.current_state().last_updated, chapters.current_state().last_updated);
+		assert_eq!(replayed.event_log().len(), 6);
+		// The first planned chapter went live during replay, the second is still upcoming
+		assert!(replayed.current_state().has_chapter(&planned[0]));
+		assert_eq!(replayed.get_timeline_snapshot(now).unwrap().upcoming.len(), 1);
+	}
+}
@@ -1,28 +1,86 @@
+pub mod analytics;
+mod clock;
+pub mod delta;
 pub mod error;
 pub mod event;
+pub mod event_log;
 pub mod state;
+pub mod template;
 pub mod timeline;
 pub mod types;
 
+pub use analytics::{SegmentSummary, TimelineAnalytics};
+pub use delta::SnapshotDelta;
 pub use error::{ChapterError, Result};
 pub use event::TimelineEvent;
-pub use state::{Chapter, TimelineState};
-pub use timeline::LiveTimeline;
+pub use event_log::{EventLog, LogEntry};
+pub use state::{ArchivedSummary, Chapter, PlannedChapter, TimelineState};
+pub use template::{ChapterTemplate, TemplateChapter, TEMPLATE_TAG};
+pub use timeline::{LiveTimeline, ARCHIVED_SEGMENT_TITLE};
 pub use types::*;
 
 /// Main entry point for the Live Chapters system
 pub struct LiveChapters {
 	timeline: LiveTimeline,
+	log: EventLog,
 }
 
 impl LiveChapters {
 	/// Create a new LiveChapters instance
 	pub fn new() -> Self {
-		Self { timeline: LiveTimeline::new() }
+		let timeline = LiveTimeline::new();
+		let log = EventLog::new(timeline.current_state().stream_start);
+		Self { timeline, log }
+	}
+
+	/// Rebuild an instance by re-applying every entry in `log`, in order
+	///
+	/// Entries run with the wall-clock time they were recorded at, so the
+	/// result snapshots identically to the instance that wrote the log. An
+	/// entry that failed when recorded fails the same way again and replay
+	/// carries on with the next one.
+	#[must_use]
+	pub fn replay(log: EventLog) -> Self {
+		let mut replayed = Self {
+			timeline: clock::pinned_at(log.stream_start, LiveTimeline::new),
+			log: EventLog::new(log.stream_start),
+		};
+		for entry in log.entries {
+			match &entry {
+				LogEntry::Events {
+					events,
+					current_time,
+					recorded_at,
+				} => {
+					let _ = clock::pinned_at(*recorded_at, || replayed.apply_events(events.clone(), *current_time));
+				}
+				LogEntry::Template {
+					template,
+					start_time,
+					recorded_at,
+				} => {
+					let _ = clock::pinned_at(*recorded_at, || replayed.timeline.apply_template(template, *start_time));
+				}
+			}
+			replayed.log.push(entry);
+		}
+		replayed
 	}
 
 	/// Process multiple events at time t and return the updated timeline snapshot
+	///
+	/// The batch is recorded in the [`event_log`](Self::event_log) whether or not it succeeds.
 	pub fn process_events_at_time(&mut self, events: Vec<TimelineEvent>, current_time: Timestamp) -> Result<TimelineSnapshot> {
+		let recorded_at = clock::now();
+		self.log.push(LogEntry::Events {
+			events: events.clone(),
+			current_time,
+			recorded_at,
+		});
+		clock::pinned_at(recorded_at, || self.apply_events(events, current_time))
+	}
+
+	fn apply_events(&mut self, events: Vec<TimelineEvent>, current_time: Timestamp) -> Result<TimelineSnapshot> {
 		// Process all events for this timestamp
 		for event in events {
 			self.timeline.process_event(event)?;
@@ -40,6 +98,20 @@ impl LiveChapters {
 		self.process_events_at_time(vec![event], current_time)
 	}
 
+	/// Pre-create a template's chapters from `start_time`, returning their UIDs
+	///
+	/// See [`LiveTimeline::apply_template`]. Like events, the template is
+	/// recorded in the [`event_log`](Self::event_log) whether or not it applies.
+	pub fn apply_template(&mut self, template: &ChapterTemplate, start_time: Timestamp) -> Result<Vec<Uid>> {
+		let recorded_at = clock::now();
+		self.log.push(LogEntry::Template {
+			template: template.clone(),
+			start_time,
+			recorded_at,
+		});
+		clock::pinned_at(recorded_at, || self.timeline.apply_template(template, start_time))
+	}
+
 	/// Get current timeline snapshot without processing events
 	pub fn get_timeline_snapshot(&self, current_time: Timestamp) -> Result<TimelineSnapshot> {
 		self.timeline.generate_timeline_snapshot(current_time)
@@ -49,6 +121,12 @@ impl LiveChapters {
 	pub fn current_state(&self) -> &TimelineState {
 		self.timeline.current_state()
 	}
+
+	/// Every event batch and template applied so far
+	#[must_use]
+	pub const fn event_log(&self) -> &EventLog {
+		&self.log
+	}
 }
 
 impl Default for LiveChapters {
@@ -58,7 +136,7 @@ impl Default for LiveChapters {
 }
 
 /// Timeline snapshot for UI rendering - represents the complete timeline at time t
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct TimelineSnapshot {
 	/// The current livestream time
 	pub current_time: Timestamp,
@@ -68,12 +146,38 @@ pub struct TimelineSnapshot {
 	pub segments: Vec<TimelineSegment>,
 	/// Number of active (ongoing) chapters
 	pub active_count: usize,
+	/// Planned chapters that have not started yet
+	#[serde(default)]
+	pub upcoming: Vec<PlannedChapter>,
 	/// State version for change tracking
 	pub version: u64,
 }
 
+impl TimelineSnapshot {
+	/// Recompute ongoing segments against `now` without reprocessing events
+	///
+	/// Meant for cheap periodic UI ticks between real snapshots. Segments with
+	/// no `end_time` are stretched to `now`, closed segments keep their
+	/// duration, and every percentage is recomputed against the new total.
+	/// A `now` at or before the snapshot's `current_time` leaves it unchanged.
+	pub fn refresh_durations(&mut self, now: Timestamp) {
+		if now <= self.current_time {
+			return;
+		}
+
+		self.total_duration += now - self.current_time;
+		self.current_time = now;
+		for segment in &mut self.segments {
+			if segment.end_time.is_none() {
+				segment.duration = now.saturating_sub(segment.start_time);
+			}
+			segment.percentage = timeline::percentage_of(segment.duration, self.total_duration);
+		}
+	}
+}
+
 /// A segment in the timeline UI - represents a visual block in the stepper
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct TimelineSegment {
 	/// Start time of this segment
 	pub start_time: Timestamp,
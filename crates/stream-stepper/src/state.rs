@@ -7,6 +7,12 @@ use std::collections::HashMap;
 pub struct TimelineState {
 	/// All chapters indexed by UID
 	pub chapters: HashMap<Uid, Chapter>,
+	/// Closed chapters folded away by compaction
+	#[serde(default)]
+	pub archive: Option<ArchivedSummary>,
+	/// Template chapters waiting to be activated, ordered by planned start
+	#[serde(default)]
+	pub planned: Vec<PlannedChapter>,
 	/// Current timeline time
 	pub current_time: Timestamp,
 	/// Stream start time
@@ -20,9 +26,11 @@ pub struct TimelineState {
 impl TimelineState {
 	/// Create a new empty state
 	pub fn new() -> Self {
-		let now = chrono::Utc::now().timestamp_millis() as u64;
+		let now = crate::clock::now();
 		Self {
 			chapters: HashMap::new(),
+			archive: None,
+			planned: Vec::new(),
 			current_time: now,
 			stream_start: now,
 			last_updated: now,
@@ -77,10 +85,20 @@ impl TimelineState {
 		is_new
 	}
 
+	/// Rename a chapter, returning whether it existed
+	pub fn rename_chapter(&mut self, uid: &str, new_title: String) -> bool {
+		let Some(chapter) = self.chapters.get_mut(uid) else {
+			return false;
+		};
+		chapter.rename(new_title);
+		self.increment_version();
+		true
+	}
+
 	/// Remove a chapter
 	pub fn remove_chapter(&mut self, uid: &str) -> Option<Chapter> {
 		let removed = self.chapters.remove(uid);
-		if removed.is_some() {
+		if removed.is_some() || self.unplan_chapter(uid) {
 			self.increment_version();
 		}
 		removed
@@ -88,17 +106,88 @@ impl TimelineState {
 
 	/// Clear all chapters
 	pub fn clear_chapters(&mut self) {
-		if !self.chapters.is_empty() {
+		if !self.chapters.is_empty() || self.archive.is_some() || !self.planned.is_empty() {
 			self.chapters.clear();
+			self.archive = None;
+			self.planned.clear();
 			self.increment_version();
 		}
 	}
 
+	/// Check if a chapter is planned but not yet activated
+	pub fn is_planned(&self, uid: &str) -> bool {
+		self.planned.iter().any(|planned| planned.uid == uid)
+	}
+
+	/// Queue planned chapters for activation
+	pub fn plan_chapters(&mut self, planned: impl IntoIterator<Item = PlannedChapter>) {
+		self.planned.extend(planned);
+		self.planned.sort_by_key(|planned| planned.start);
+		self.increment_version();
+	}
+
+	/// Drop a planned chapter without activating it, returning whether it was planned
+	pub fn unplan_chapter(&mut self, uid: &str) -> bool {
+		let before = self.planned.len();
+		self.planned.retain(|planned| planned.uid != uid);
+		self.planned.len() != before
+	}
+
+	/// Turn planned chapters starting at or before `now` into live chapters,
+	/// returning how many were activated
+	///
+	/// Each activated chapter closes the template chapter before it if that one
+	/// is still running, so a template plays out back to back.
+	pub fn activate_planned(&mut self, now: Timestamp) -> usize {
+		let due = self.planned.partition_point(|planned| planned.start <= now);
+		let activated: Vec<PlannedChapter> = self.planned.drain(..due).collect();
+
+		for planned in &activated {
+			if let Some(previous) = planned.follows.as_deref().and_then(|uid| self.chapters.get_mut(uid)) {
+				if previous.is_active() && previous.time_range.start < planned.start {
+					previous.time_range.end = Some(planned.start);
+				}
+			}
+			let chapter = Chapter::new(planned.uid.clone(), planned.context.clone(), TimeRange::new(planned.start, None), Payload::empty());
+			self.chapters.insert(chapter.uid.clone(), chapter);
+		}
+
+		if !activated.is_empty() {
+			self.increment_version();
+		}
+		activated.len()
+	}
+
+	/// Fold closed chapters that ended at or before `before` into the archive,
+	/// returning how many were folded
+	pub fn compact_before(&mut self, before: Timestamp) -> usize {
+		let old: Vec<Uid> = self
+			.chapters
+			.values()
+			.filter(|chapter| chapter.time_range.end.is_some_and(|end| end <= before))
+			.map(|chapter| chapter.uid.clone())
+			.collect();
+
+		for uid in &old {
+			if let Some(chapter) = self.chapters.remove(uid) {
+				match &mut self.archive {
+					Some(archive) => archive.absorb(&chapter),
+					None => self.archive = Some(ArchivedSummary::from_chapter(&chapter)),
+				}
+			}
+		}
+
+		if !old.is_empty() {
+			self.increment_version();
+		}
+		old.len()
+	}
+
 	/// Update the current time
 	pub fn update_current_time(&mut self, timestamp: Timestamp) {
 		if timestamp > self.current_time {
 			self.current_time = timestamp;
-			self.last_updated = chrono::Utc::now().timestamp_millis() as u64;
+			self.last_updated = crate::clock::now();
 			self.increment_version();
 		}
 	}
@@ -119,8 +208,73 @@ impl Default for TimelineState {
 	}
 }
 
+/// What is left of chapters removed by compaction
+///
+/// Keeps the span they covered and their combined length so duration
+/// totals stay the same after the detailed chapters are dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedSummary {
+	/// Earliest start among the archived chapters
+	pub start: Timestamp,
+	/// Latest end among the archived chapters
+	pub end: Timestamp,
+	/// Number of chapters archived
+	pub chapter_count: usize,
+	/// Sum of the archived chapters' durations (overlaps counted once per chapter)
+	pub chapter_duration: u64,
+}
+
+impl ArchivedSummary {
+	fn from_chapter(chapter: &Chapter) -> Self {
+		let end = chapter.time_range.effective_end(chapter.time_range.start);
+		Self {
+			start: chapter.time_range.start,
+			end,
+			chapter_count: 1,
+			chapter_duration: end - chapter.time_range.start,
+		}
+	}
+
+	fn absorb(&mut self, chapter: &Chapter) {
+		let end = chapter.time_range.effective_end(chapter.time_range.start);
+		self.start = self.start.min(chapter.time_range.start);
+		self.end = self.end.max(end);
+		self.chapter_count += 1;
+		self.chapter_duration += end - chapter.time_range.start;
+	}
+
+	/// Time range covered by the archived chapters
+	#[must_use]
+	pub fn time_range(&self) -> TimeRange {
+		TimeRange::new(self.start, Some(self.end))
+	}
+}
+
+/// A chapter laid out ahead of time by a template, not yet running
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedChapter {
+	/// UID the chapter will have once activated
+	pub uid: Uid,
+	/// Context the chapter will start with
+	pub context: Context,
+	/// When the chapter is planned to start
+	pub start: Timestamp,
+	/// How long the chapter is planned to run
+	pub planned_duration: u64,
+	/// The template chapter this one takes over from
+	pub follows: Option<Uid>,
+}
+
+impl PlannedChapter {
+	/// When the chapter is planned to end
+	#[must_use]
+	pub const fn planned_end(&self) -> Timestamp {
+		self.start + self.planned_duration
+	}
+}
+
 /// A chapter in the timeline
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Chapter {
 	/// Unique identifier
 	pub uid: Uid,
@@ -140,7 +294,7 @@ pub struct Chapter {
 impl Chapter {
 	/// Create a new chapter
 	pub fn new(uid: Uid, context: Context, time_range: TimeRange, payload: Payload) -> Self {
-		let now = chrono::Utc::now().timestamp_millis() as u64;
+		let now = crate::clock::now();
 		Self {
 			uid,
 			context,
@@ -164,13 +318,19 @@ impl Chapter {
 	/// Update the payload and timestamp
 	pub fn update_payload(&mut self, payload: Payload) {
 		self.payload = payload;
-		self.updated_at = chrono::Utc::now().timestamp_millis() as u64;
+		self.updated_at = crate::clock::now();
 	}
 
 	/// Update the context and timestamp
 	pub fn update_context(&mut self, context: Context) {
 		self.context = context;
-		self.updated_at = chrono::Utc::now().timestamp_millis() as u64;
+		self.updated_at = crate::clock::now();
+	}
+
+	/// Replace the title and timestamp, leaving the rest of the context as is
+	pub fn rename(&mut self, new_title: String) {
+		self.context.title = new_title;
+		self.updated_at = crate::clock::now();
 	}
 
 	/// Close this chapter at a specific time
@@ -179,7 +339,7 @@ impl Chapter {
 			return Err(crate::ChapterError::InvalidTimestamp("End time must be after start time".to_string()));
 		}
 		self.time_range.end = Some(end_time);
-		self.updated_at = chrono::Utc::now().timestamp_millis() as u64;
+		self.updated_at = crate::clock::now();
 		Ok(())
 	}
 
@@ -192,7 +352,7 @@ impl Chapter {
 		if self.time_range.end.is_some() {
 			self.time_range.end = Some(extend_time);
 		}
-		self.updated_at = chrono::Utc::now().timestamp_millis() as u64;
+		self.updated_at = crate::clock::now();
 		Ok(())
 	}
 
@@ -0,0 +1,139 @@
+use crate::error::{ChapterError, Result};
+use crate::state::PlannedChapter;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+
+/// Tag set on chapters created from a template, holding the template's name
+pub const TEMPLATE_TAG: &str = "template";
+
+/// A reusable run of chapters, e.g. the usual structure of an episode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChapterTemplate {
+	/// Template name, used to derive the planned chapters' UIDs
+	pub name: String,
+	/// Chapters in the order they play out
+	pub chapters: Vec<TemplateChapter>,
+}
+
+/// One chapter of a [`ChapterTemplate`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateChapter {
+	/// Title the chapter starts with
+	pub title: String,
+	/// How long the chapter is planned to run
+	pub planned_duration: u64,
+}
+
+impl ChapterTemplate {
+	/// Create an empty template
+	pub fn new(name: impl Into<String>) -> Self {
+		Self {
+			name: name.into(),
+			chapters: Vec::new(),
+		}
+	}
+
+	/// Append a chapter to the template
+	pub fn with_chapter(mut self, title: impl Into<String>, planned_duration: u64) -> Self {
+		self.chapters.push(TemplateChapter {
+			title: title.into(),
+			planned_duration,
+		});
+		self
+	}
+
+	/// Sum of the planned chapter durations
+	pub fn total_duration(&self) -> u64 {
+		self.chapters.iter().map(|chapter| chapter.planned_duration).sum()
+	}
+
+	/// Lay the template out back to back from `start_time`
+	///
+	/// Chapter UIDs are `{name}-{start_time}-{index}`, so the same template can
+	/// be applied to several streams or several times in one stream.
+	pub fn plan(&self, start_time: Timestamp) -> Result<Vec<PlannedChapter>> {
+		if self.chapters.is_empty() {
+			return Err(ChapterError::Timeline(["Template '", &self.name, "' has no chapters"].concat()));
+		}
+
+		let start_label = start_time.to_string();
+		let mut start = start_time;
+		let mut follows = None;
+		let mut planned = Vec::with_capacity(self.chapters.len());
+		for (index, chapter) in self.chapters.iter().enumerate() {
+			if chapter.planned_duration == 0 {
+				return Err(ChapterError::Timeline(["Template chapter '", &chapter.title, "' has no planned duration"].concat()));
+			}
+
+			let uid = [self.name.as_str(), "-", &start_label, "-", &index.to_string()].concat();
+			planned.push(PlannedChapter {
+				uid: uid.clone(),
+				context: Context::new(chapter.title.clone()).with_tag(TEMPLATE_TAG, self.name.clone()),
+				start,
+				planned_duration: chapter.planned_duration,
+				follows: follows.replace(uid),
+			});
+			start += chapter.planned_duration;
+		}
+		Ok(planned)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{LiveChapters, TimelineEvent};
+
+	#[test]
+	fn test_applied_template_is_upcoming_until_activated() {
+		let mut chapters = LiveChapters::new();
+		let start = chapters.current_state().stream_start;
+		let template = ChapterTemplate::new("episode")
+			.with_chapter("Intro", 1_000)
+			.with_chapter("Main", 5_000)
+			.with_chapter("Outro", 1_000);
+
+		let uids = chapters.apply_template(&template, start + 1_000).unwrap();
+		assert_eq!(uids.len(), 3);
+
+		let snapshot = chapters.process_events_at_time(Vec::new(), start + 500).unwrap();
+		assert!(snapshot.segments.is_empty());
+		assert_eq!(snapshot.active_count, 0);
+		let upcoming: Vec<_> = snapshot.upcoming.iter().map(|p| (p.context.title.as_str(), p.start, p.planned_end())).collect();
+		assert_eq!(
+			upcoming,
+			vec![
+				("Intro", start + 1_000, start + 2_000),
+				("Main", start + 2_000, start + 7_000),
+				("Outro", start + 7_000, start + 8_000)
+			]
+		);
+
+		// Reaching Main's planned start activates both Intro and Main, closing Intro
+		let snapshot = chapters.process_events_at_time(Vec::new(), start + 3_000).unwrap();
+		let titles: Vec<_> = snapshot.segments.iter().map(|s| (s.title.as_str(), s.end_time)).collect();
+		assert_eq!(titles, vec![("Intro", Some(start + 2_000)), ("Main", None)]);
+		assert_eq!(snapshot.active_count, 1);
+		assert_eq!(snapshot.upcoming.len(), 1);
+		assert_eq!(snapshot.upcoming[0].uid, uids[2]);
+
+		// Starting the outro early takes it off the plan
+		let snapshot = chapters
+			.process_event_at_time(
+				TimelineEvent::StartChapter {
+					uid: uids[2].clone(),
+					context: Context::new("Outro"),
+					start_time: start + 4_000,
+					payload: Payload::empty(),
+				},
+				start + 4_500,
+			)
+			.unwrap();
+		assert!(snapshot.upcoming.is_empty());
+		assert_eq!(chapters.current_state().get_chapter(&uids[2]).unwrap().time_range.start, start + 4_000);
+
+		// The same template at the same start would collide
+		assert!(chapters.apply_template(&template, start + 1_000).is_err());
+		assert!(ChapterTemplate::new("empty").plan(start).is_err());
+	}
+}
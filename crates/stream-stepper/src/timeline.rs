@@ -1,10 +1,14 @@
 use crate::error::*;
 use crate::event::TimelineEvent;
-use crate::state::{Chapter, TimelineState};
+use crate::state::{ArchivedSummary, Chapter, TimelineState};
+use crate::template::ChapterTemplate;
 use crate::types::*;
 use crate::{TimelineSegment, TimelineSnapshot};
 use std::collections::BTreeMap;
 
+/// Title of the snapshot segment standing in for compacted chapters
+pub const ARCHIVED_SEGMENT_TITLE: &str = "Archived";
+
 /// The main timeline processor that handles FSM transitions
 pub struct LiveTimeline {
 	state: TimelineState,
@@ -40,6 +44,10 @@ impl LiveTimeline {
 				self.handle_update_context(uid, context)?;
 			}
 
+			TimelineEvent::RenameChapter { uid, new_title } => {
+				self.handle_rename_chapter(uid, new_title)?;
+			}
+
 			TimelineEvent::RemoveChapter { uid } => {
 				self.handle_remove_chapter(uid)?;
 			}
@@ -64,9 +72,36 @@ impl LiveTimeline {
 		Ok(())
 	}
 
-	/// Advance timeline to current time
+	/// Advance timeline to current time, activating planned chapters that are due
 	pub fn advance_to(&mut self, current_time: Timestamp) {
 		self.state.update_current_time(current_time);
+		self.state.activate_planned(current_time);
+	}
+
+	/// Plan a template's chapters back to back from `start_time`, returning their UIDs
+	///
+	/// The chapters show up as `upcoming` in snapshots and become live chapters
+	/// as the timeline advances past each planned start. Starting one early with
+	/// a `StartChapter` event for its UID takes it off the plan.
+	pub fn apply_template(&mut self, template: &ChapterTemplate, start_time: Timestamp) -> Result<Vec<Uid>> {
+		let planned = template.plan(start_time)?;
+		if let Some(taken) = planned.iter().find(|p| self.state.has_chapter(&p.uid) || self.state.is_planned(&p.uid)) {
+			return Err(ChapterError::Timeline(["Chapter '", &taken.uid, "' already exists"].concat()));
+		}
+
+		let uids = planned.iter().map(|p| p.uid.clone()).collect();
+		self.state.plan_chapters(planned);
+		self.state.activate_planned(self.state.current_time);
+		Ok(uids)
+	}
+
+	/// Collapse closed chapters that ended at or before `before` into one archived summary
+	///
+	/// Ongoing chapters and chapters closed after `before` are kept as they are.
+	/// Snapshots show the archived span as a single segment titled
+	/// [`ARCHIVED_SEGMENT_TITLE`]. Returns how many chapters were compacted.
+	pub fn compact(&mut self, before: Timestamp) -> usize {
+		self.state.compact_before(before)
 	}
 
 	/// Get the current state
@@ -88,6 +123,7 @@ impl LiveTimeline {
 			total_duration,
 			segments,
 			active_count,
+			upcoming: self.state.planned.clone(),
 			version: self.state.version,
 		})
 	}
@@ -105,6 +141,12 @@ impl LiveTimeline {
 			}
 		}
 
+		let archive = self.state.archive.as_ref().map(ArchivedSummary::time_range);
+		if let Some(archive) = &archive {
+			timeline_points.insert(archive.start, ());
+			timeline_points.insert(archive.effective_end(current_time), ());
+		}
+
 		// Add stream start and current time
 		timeline_points.insert(self.state.stream_start, ());
 		timeline_points.insert(current_time, ());
@@ -120,8 +162,8 @@ impl LiveTimeline {
 				continue;
 			}
 
-			// Find all chapters that overlap with this segment
-			let overlapping_chapters: Vec<Chapter> = self
+			// Find all chapters that overlap with this segment, in a stable order
+			let mut overlapping_chapters: Vec<Chapter> = self
 				.state
 				.chapters
 				.values()
@@ -131,6 +173,7 @@ impl LiveTimeline {
 				})
 				.cloned()
 				.collect();
+			overlapping_chapters.sort_by(|a, b| (a.time_range.start, &a.uid).cmp(&(b.time_range.start, &b.uid)));
 
 			if !overlapping_chapters.is_empty() {
 				// Use the title of the most recent chapter that starts in this segment
@@ -142,7 +185,7 @@ impl LiveTimeline {
 					.unwrap();
 
 				let duration = end - start;
-				let percentage = if total_duration > 0 { (duration as f64 / total_duration as f64) * 100.0 } else { 0.0 };
+				let percentage = percentage_of(duration, total_duration);
 
 				let is_active = overlapping_chapters.iter().any(|c| c.is_active());
 
@@ -155,6 +198,27 @@ impl LiveTimeline {
 					chapters: overlapping_chapters,
 					percentage,
 				});
+			} else if archive.as_ref().is_some_and(|archive| archive.start <= start && archive.effective_end(current_time) >= end) {
+				let duration = end - start;
+				let percentage = percentage_of(duration, total_duration);
+
+				// Live chapters can split the archived span; join the pieces back up
+				match segments.last_mut() {
+					Some(last) if last.title == ARCHIVED_SEGMENT_TITLE && last.chapters.is_empty() && last.end_time == Some(start) => {
+						last.end_time = Some(end);
+						last.duration += duration;
+						last.percentage += percentage;
+					}
+					_ => segments.push(TimelineSegment {
+						start_time: start,
+						end_time: Some(end),
+						duration,
+						title: ARCHIVED_SEGMENT_TITLE.to_string(),
+						is_active: false,
+						chapters: Vec::new(),
+						percentage,
+					}),
+				}
 			}
 		}
 
@@ -180,6 +244,7 @@ impl LiveTimeline {
 	// Event handlers
 
 	fn handle_start_chapter(&mut self, uid: Uid, context: Context, start_time: Timestamp, payload: Payload) -> Result<()> {
+		self.state.unplan_chapter(&uid);
 		let time_range = TimeRange::new(start_time, None);
 		let chapter = Chapter::new(uid, context, time_range, payload);
 		self.state.upsert_chapter(chapter);
@@ -210,6 +275,13 @@ impl LiveTimeline {
 		Ok(())
 	}
 
+	fn handle_rename_chapter(&mut self, uid: Uid, new_title: String) -> Result<()> {
+		if !self.state.rename_chapter(&uid, new_title) {
+			return Err(ChapterError::ChapterNotFound(uid));
+		}
+		Ok(())
+	}
+
 	fn handle_remove_chapter(&mut self, uid: Uid) -> Result<()> {
 		if self.state.remove_chapter(&uid).is_some() {}
 		Ok(())
@@ -235,8 +307,154 @@ impl LiveTimeline {
 	}
 }
 
+/// Share of `total_duration` taken up by `duration`, in percent
+pub(crate) fn percentage_of(duration: u64, total_duration: u64) -> f64 {
+	if total_duration > 0 {
+		(duration as f64 / total_duration as f64) * 100.0
+	} else {
+		0.0
+	}
+}
+
 impl Default for LiveTimeline {
 	fn default() -> Self {
 		Self::new()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_rename_chapter_updates_snapshot() {
+		let mut timeline = LiveTimeline::new();
+		let start = timeline.current_state().stream_start;
+		timeline
+			.process_event(TimelineEvent::StartChapter {
+				uid: "intro".to_string(),
+				context: Context::new("Intro").with_tag("segment", "opening"),
+				start_time: start,
+				payload: Payload::empty(),
+			})
+			.unwrap();
+		let before = timeline.generate_timeline_snapshot(start + 1_000).unwrap();
+		assert_eq!(before.segments[0].title, "Intro");
+
+		timeline
+			.process_event(TimelineEvent::RenameChapter {
+				uid: "intro".to_string(),
+				new_title: "Pre-show Q&A".to_string(),
+			})
+			.unwrap();
+		let after = timeline.generate_timeline_snapshot(start + 1_000).unwrap();
+
+		assert_eq!(after.segments[0].title, "Pre-show Q&A");
+		assert!(after.version > before.version);
+		// Only the title changes
+		let chapter = timeline.current_state().get_chapter("intro").unwrap();
+		assert_eq!(chapter.context.tags.get("segment").map(String::as_str), Some("opening"));
+	}
+
+	#[test]
+	fn test_compact_collapses_old_chapters_into_one_segment() {
+		let mut timeline = LiveTimeline::new();
+		let start = timeline.current_state().stream_start;
+		let chapter = |i: u64| TimelineEvent::StartChapter {
+			uid: i.to_string(),
+			context: Context::new("Play"),
+			start_time: start + i * 1_000,
+			payload: Payload::empty(),
+		};
+
+		// A multi-hour stream: 1000 closed chapters back to back, then one still going
+		for i in 0..1_000 {
+			timeline.process_event(chapter(i)).unwrap();
+			timeline
+				.process_event(TimelineEvent::EndChapter {
+					uid: i.to_string(),
+					end_time: start + (i + 1) * 1_000,
+					final_payload: None,
+				})
+				.unwrap();
+		}
+		timeline.process_event(chapter(1_000)).unwrap();
+		let now = start + 1_000_500;
+
+		let before = timeline.generate_timeline_snapshot(now).unwrap();
+		assert_eq!(before.segments.len(), 1_001);
+
+		assert_eq!(timeline.compact(start + 1_000_000), 1_000);
+		assert_eq!(timeline.current_state().chapters.len(), 1);
+
+		let after = timeline.generate_timeline_snapshot(now).unwrap();
+
+		assert_eq!(after.segments.len(), 2);
+		let archived = &after.segments[0];
+		assert_eq!(archived.title, ARCHIVED_SEGMENT_TITLE);
+		assert_eq!((archived.start_time, archived.end_time), (start, Some(start + 1_000_000)));
+		assert!(after.segments[1].is_active);
+
+		// Durations still add up to the same total
+		let covered = |snapshot: &TimelineSnapshot| snapshot.segments.iter().map(|s| s.duration).sum::<u64>();
+		assert_eq!(covered(&after), covered(&before));
+		assert_eq!(after.total_duration, before.total_duration);
+		assert_eq!(timeline.current_state().archive.as_ref().unwrap().chapter_duration, 1_000_000);
+	}
+
+	#[test]
+	fn test_refresh_durations_grows_only_ongoing_segments() {
+		let mut timeline = LiveTimeline::new();
+		let start = timeline.current_state().stream_start;
+		let chapter = |uid: &str, start_time| TimelineEvent::StartChapter {
+			uid: uid.to_string(),
+			context: Context::new(uid),
+			start_time,
+			payload: Payload::empty(),
+		};
+
+		timeline.process_event(chapter("intro", start)).unwrap();
+		timeline
+			.process_event(TimelineEvent::EndChapter {
+				uid: "intro".to_string(),
+				end_time: start + 1_000,
+				final_payload: None,
+			})
+			.unwrap();
+		timeline.process_event(chapter("main", start + 1_000)).unwrap();
+
+		let mut snapshot = timeline.generate_timeline_snapshot(start + 2_000).unwrap();
+		assert_eq!(snapshot.segments.len(), 2);
+		let (closed, ongoing) = (snapshot.segments[0].clone(), snapshot.segments[1].clone());
+		assert_eq!(ongoing.end_time, None);
+
+		snapshot.refresh_durations(start + 4_000);
+
+		assert_eq!((snapshot.current_time, snapshot.total_duration), (start + 4_000, 4_000));
+		assert_eq!(snapshot.segments[0].duration, closed.duration);
+		assert_eq!(snapshot.segments[1].duration, 3_000);
+		assert!(snapshot.segments[1].duration > ongoing.duration);
+		assert!((snapshot.segments[0].percentage - 25.0).abs() < 1e-9);
+		assert!((snapshot.segments[1].percentage - 75.0).abs() < 1e-9);
+
+		// Matches what a full snapshot at the same time would report
+		let regenerated = timeline.generate_timeline_snapshot(start + 4_000).unwrap();
+		assert_eq!(snapshot.segments, regenerated.segments);
+
+		// Moving backwards is ignored
+		snapshot.refresh_durations(start + 3_000);
+		assert_eq!(snapshot.current_time, start + 4_000);
+	}
+
+	#[test]
+	fn test_rename_unknown_chapter_errors() {
+		let mut timeline = LiveTimeline::new();
+		let result = timeline.process_event(TimelineEvent::RenameChapter {
+			uid: "missing".to_string(),
+			new_title: "Anything".to_string(),
+		});
+
+		assert!(matches!(result, Err(ChapterError::ChapterNotFound(uid)) if uid == "missing"));
+		assert_eq!(timeline.current_state().version, 0);
+	}
+}
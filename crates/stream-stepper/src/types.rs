@@ -52,7 +52,7 @@ impl Context {
 }
 
 /// Generic payload that can hold any satellite data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Payload {
 	/// The actual data as JSON value
 	pub data: serde_json::Value,
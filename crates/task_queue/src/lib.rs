@@ -10,9 +10,18 @@ pub mod trees;
 
 use axum::{extract::State, Json};
 use chrono::{DateTime, Utc};
+use prometheus::{IntGauge, Registry};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc, time::Duration};
-use tokio::sync::{broadcast, RwLock};
+use std::{
+	cmp::{Ordering, Reverse},
+	collections::{BinaryHeap, HashMap},
+	sync::{
+		atomic::{AtomicUsize, Ordering as AtomicOrdering},
+		Arc, OnceLock,
+	},
+	time::Duration,
+};
+use tokio::sync::{Mutex, Notify, RwLock, Semaphore};
 use tokio::time;
 use uuid::Uuid;
 
@@ -32,31 +41,221 @@ pub struct Task {
 	name: String,
 	schedule_time: DateTime<Utc>,
 	status: TaskStatus,
+	/// Higher runs first among tasks that are ready at the same time
+	#[serde(default)]
+	priority: u8,
 	payload: serde_json::Value,
 }
 
+impl Task {
+	#[must_use]
+	pub const fn id(&self) -> Uuid {
+		self.id
+	}
+
+	#[must_use]
+	pub const fn priority(&self) -> u8 {
+		self.priority
+	}
+}
+
 // Task request for API
 #[derive(Debug, Deserialize)]
 pub struct ScheduleTaskRequest {
 	name: String,
 	schedule_time: DateTime<Utc>,
+	#[serde(default)]
+	priority: u8,
 	payload: serde_json::Value,
 }
 
+// Dispatch key: highest priority first, then earliest schedule time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct QueuedTask {
+	priority: u8,
+	schedule_time: DateTime<Utc>,
+	id: Uuid,
+}
+
+impl Ord for QueuedTask {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self
+			.priority
+			.cmp(&other.priority)
+			.then_with(|| other.schedule_time.cmp(&self.schedule_time))
+			.then_with(|| other.id.cmp(&self.id))
+	}
+}
+
+impl PartialOrd for QueuedTask {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+// Tasks waiting for their schedule time, and tasks ready to run
+#[derive(Default)]
+struct DispatchQueue {
+	waiting: BinaryHeap<Reverse<(DateTime<Utc>, QueuedTask)>>,
+	ready: BinaryHeap<QueuedTask>,
+}
+
+impl DispatchQueue {
+	fn push(&mut self, task: QueuedTask) {
+		self.waiting.push(Reverse((task.schedule_time, task)));
+	}
+
+	// Promote every task due by `now`, then take the most important one
+	fn pop_ready(&mut self, now: DateTime<Utc>) -> Option<QueuedTask> {
+		while self.waiting.peek().is_some_and(|Reverse((at, _))| *at <= now) {
+			if let Some(Reverse((_, task))) = self.waiting.pop() {
+				self.ready.push(task);
+			}
+		}
+		self.ready.pop()
+	}
+
+	fn next_due(&self) -> Option<DateTime<Utc>> {
+		self.waiting.peek().map(|Reverse((at, _))| *at)
+	}
+
+	fn len(&self) -> usize {
+		self.waiting.len() + self.ready.len()
+	}
+}
+
+/// Tasks [`run_scheduler`] runs at once unless configured otherwise
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+// Prometheus mirrors of the worker counters, once registered
+struct WorkerGauges {
+	active_workers: IntGauge,
+	queue_depth: IntGauge,
+}
+
 // Scheduler state
 pub struct Scheduler {
 	pub tasks: RwLock<HashMap<Uuid, Task>>,
-	pub task_tx: broadcast::Sender<Task>,
+	queue: Mutex<DispatchQueue>,
+	wakeup: Notify,
+	/// One permit per worker; due tasks stay queued while none are free
+	workers: Arc<Semaphore>,
+	active_workers: AtomicUsize,
+	queue_depth: AtomicUsize,
+	gauges: OnceLock<WorkerGauges>,
 }
 
 impl Scheduler {
 	pub fn new() -> Self {
-		let (task_tx, _) = broadcast::channel(100);
+		Self::with_concurrency(DEFAULT_CONCURRENCY)
+	}
+
+	/// Scheduler whose [`run_scheduler`] loop runs at most `concurrency` tasks at once
+	#[must_use]
+	pub fn with_concurrency(concurrency: usize) -> Self {
 		Self {
 			tasks: RwLock::new(HashMap::new()),
-			task_tx,
+			queue: Mutex::new(DispatchQueue::default()),
+			wakeup: Notify::new(),
+			workers: Arc::new(Semaphore::new(concurrency.max(1))),
+			active_workers: AtomicUsize::new(0),
+			queue_depth: AtomicUsize::new(0),
+			gauges: OnceLock::new(),
+		}
+	}
+
+	/// Expose `scheduler_active_workers` and `scheduler_queue_depth` on `registry`
+	///
+	/// # Errors
+	///
+	/// Returns an error if metrics with the same names are already registered.
+	pub fn register_metrics(&self, registry: &Registry) -> Result<(), prometheus::Error> {
+		let gauges = WorkerGauges {
+			active_workers: IntGauge::new("scheduler_active_workers", "Tasks currently being processed")?,
+			queue_depth: IntGauge::new("scheduler_queue_depth", "Tasks scheduled but not yet handed to a worker")?,
+		};
+		registry.register(Box::new(gauges.active_workers.clone()))?;
+		registry.register(Box::new(gauges.queue_depth.clone()))?;
+		gauges.active_workers.set(to_gauge(self.active_workers()));
+		gauges.queue_depth.set(to_gauge(self.queue_depth()));
+		let _ = self.gauges.set(gauges);
+		Ok(())
+	}
+
+	/// Tasks currently being processed
+	#[must_use]
+	pub fn active_workers(&self) -> usize {
+		self.active_workers.load(AtomicOrdering::Relaxed)
+	}
+
+	/// Tasks scheduled but not yet handed to a worker, whether due or not
+	#[must_use]
+	pub fn queue_depth(&self) -> usize {
+		self.queue_depth.load(AtomicOrdering::Relaxed)
+	}
+
+	fn set_queue_depth(&self, depth: usize) {
+		self.queue_depth.store(depth, AtomicOrdering::Relaxed);
+		if let Some(gauges) = self.gauges.get() {
+			gauges.queue_depth.set(to_gauge(depth));
+		}
+	}
+
+	fn set_active_workers(&self, active: usize) {
+		if let Some(gauges) = self.gauges.get() {
+			gauges.active_workers.set(to_gauge(active));
+		}
+	}
+
+	/// Run the highest-priority task that is due, returning its id
+	///
+	/// Returns None when nothing is ready yet.
+	pub async fn run_next(&self) -> Option<Uuid> {
+		let next = self.pop_ready().await?;
+		self.execute(next.id).await;
+		Some(next.id)
+	}
+
+	async fn pop_ready(&self) -> Option<QueuedTask> {
+		let mut queue = self.queue.lock().await;
+		let next = queue.pop_ready(Utc::now());
+		self.set_queue_depth(queue.len());
+		next
+	}
+
+	// Process a task without holding the task map, so workers don't serialize on it
+	async fn execute(&self, id: Uuid) {
+		let Some(mut task) = self.tasks.write().await.get_mut(&id).map(|task| {
+			task.status = TaskStatus::Running;
+			task.clone()
+		}) else {
+			return;
+		};
+
+		self.set_active_workers(self.active_workers.fetch_add(1, AtomicOrdering::Relaxed) + 1);
+		process_task(&mut task).await;
+		self.set_active_workers(self.active_workers.fetch_sub(1, AtomicOrdering::Relaxed) - 1);
+
+		if let Some(stored) = self.tasks.write().await.get_mut(&id) {
+			stored.status = task.status;
 		}
 	}
+
+	async fn enqueue(&self, task: &Task) {
+		let mut queue = self.queue.lock().await;
+		queue.push(QueuedTask {
+			priority: task.priority,
+			schedule_time: task.schedule_time,
+			id: task.id,
+		});
+		self.set_queue_depth(queue.len());
+		drop(queue);
+		self.wakeup.notify_one();
+	}
+}
+
+fn to_gauge(count: usize) -> i64 {
+	i64::try_from(count).unwrap_or(i64::MAX)
 }
 
 // API handlers
@@ -66,6 +265,7 @@ pub async fn schedule_task(State(scheduler): State<Arc<Scheduler>>, Json(request
 		name: request.name,
 		schedule_time: request.schedule_time,
 		status: TaskStatus::Scheduled,
+		priority: request.priority,
 		payload: request.payload,
 	};
 
@@ -73,7 +273,7 @@ pub async fn schedule_task(State(scheduler): State<Arc<Scheduler>>, Json(request
 	scheduler.tasks.write().await.insert(task.id, task.clone());
 
 	// Notify scheduler
-	let _ = scheduler.task_tx.send(task.clone());
+	scheduler.enqueue(&task).await;
 
 	Json(task)
 }
@@ -86,39 +286,35 @@ async fn process_task(task: &mut Task) {
 	task.status = TaskStatus::Completed;
 }
 
-// Background scheduler
+// Background scheduler: hands due tasks to a bounded pool of workers, highest priority first
+//
+// Due tasks wait in the queue while every worker is busy, so a burst can't
+// spawn more than the scheduler's concurrency at once.
 pub async fn run_scheduler(scheduler: Arc<Scheduler>) {
-	let mut task_rx = scheduler.task_tx.subscribe();
-
 	loop {
-		tokio::select! {
-				Ok(task) = task_rx.recv() => {
-						let scheduler_clone = scheduler.clone();
-
-						// Spawn a new task for handling the scheduled job
-						tokio::spawn(async move {
-								let now = Utc::now();
-								if task.schedule_time <= now {
-										// Process immediately if schedule time has passed
-										let mut tasks = scheduler_clone.tasks.write().await;
-										if let Some(task) = tasks.get_mut(&task.id) {
-												task.status = TaskStatus::Running;
-												process_task(task).await;
-										}
-								} else {
-										// Schedule for future execution
-										let delay = task.schedule_time.timestamp() - now.timestamp();
-										if delay > 0 {
-												time::sleep(Duration::from_secs(delay as u64)).await;
-												let mut tasks = scheduler_clone.tasks.write().await;
-												if let Some(task) = tasks.get_mut(&task.id) {
-														task.status = TaskStatus::Running;
-														process_task(task).await;
-												}
-										}
-								}
-						});
+		let Ok(permit) = Arc::clone(&scheduler.workers).acquire_owned().await else {
+			return;
+		};
+		if let Some(next) = scheduler.pop_ready().await {
+			let scheduler = Arc::clone(&scheduler);
+			tokio::spawn(async move {
+				scheduler.execute(next.id).await;
+				drop(permit);
+			});
+			continue;
+		}
+		drop(permit);
+
+		let next_due = scheduler.queue.lock().await.next_due();
+		match next_due {
+			Some(at) => {
+				let delay = (at - Utc::now()).to_std().unwrap_or_default();
+				tokio::select! {
+					() = time::sleep(delay) => {}
+					() = scheduler.wakeup.notified() => {}
 				}
+			}
+			None => scheduler.wakeup.notified().await,
 		}
 	}
 }
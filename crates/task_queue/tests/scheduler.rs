@@ -0,0 +1,53 @@
+use axum::{extract::State, Json};
+use chrono::Utc;
+use std::sync::Arc;
+use task_queue::{schedule_task, ScheduleTaskRequest, Scheduler};
+
+fn request(name: &str, priority: u8) -> Json<ScheduleTaskRequest> {
+	Json(
+		serde_json::from_value(serde_json::json!({
+			"name": name,
+			"schedule_time": Utc::now(),
+			"priority": priority,
+			"payload": {},
+		}))
+		.unwrap(),
+	)
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_ready_tasks_run_highest_priority_first() {
+	let scheduler = Arc::new(Scheduler::new());
+
+	let Json(low) = schedule_task(State(scheduler.clone()), request("low", 1)).await;
+	let Json(high) = schedule_task(State(scheduler.clone()), request("high", 9)).await;
+
+	assert_eq!(scheduler.run_next().await, Some(high.id()));
+	assert_eq!(scheduler.run_next().await, Some(low.id()));
+	assert_eq!(scheduler.run_next().await, None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_worker_pool_caps_concurrent_tasks() {
+	let scheduler = Arc::new(Scheduler::with_concurrency(3));
+	for i in 0..10 {
+		let Json(_) = schedule_task(State(scheduler.clone()), request(&["task-", &i.to_string()].concat(), 0)).await;
+	}
+	assert_eq!(scheduler.queue_depth(), 10);
+
+	tokio::spawn(task_queue::run_scheduler(scheduler.clone()));
+
+	// Each task takes 2s, so sampling every 100ms sees every batch of workers
+	let mut peak = 0;
+	while scheduler.queue_depth() > 0 || scheduler.active_workers() > 0 {
+		tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+		let active = scheduler.active_workers();
+		assert!(active <= 3, "{active} tasks running with 3 workers");
+		peak = peak.max(active);
+	}
+
+	assert_eq!(peak, 3);
+	let tasks = scheduler.tasks.read().await;
+	assert_eq!(tasks.len(), 10);
+	assert!(tasks.values().all(|task| serde_json::to_value(task).unwrap()["status"] == "Completed"));
+}
@@ -0,0 +1,122 @@
+//! Per-client queue contention: the previous `DashMap<_, VecDeque>` layout,
+//! where every enqueue/dequeue takes the entry write guard, against the
+//! `SegQueue` + atomic counter layout that only needs a shared reference.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use crossbeam::queue::SegQueue;
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Instant;
+use tokio::sync::oneshot;
+use ws_conn_manager::MAX_QUEUE_PER_CLIENT;
+
+const OPS_PER_THREAD: usize = 2_000;
+const CLIENT: &str = "hot-client";
+
+struct LockedQueue {
+	clients: DashMap<String, VecDeque<oneshot::Sender<()>>>,
+}
+
+impl LockedQueue {
+	fn new() -> Self {
+		let clients = DashMap::new();
+		clients.insert(CLIENT.to_string(), VecDeque::new());
+		Self { clients }
+	}
+
+	fn enqueue(&self, tx: oneshot::Sender<()>) -> bool {
+		let mut queue = self.clients.get_mut(CLIENT).unwrap();
+		if queue.len() >= MAX_QUEUE_PER_CLIENT {
+			return false;
+		}
+		queue.push_back(tx);
+		true
+	}
+
+	fn dequeue(&self) -> Option<oneshot::Sender<()>> {
+		self.clients.get_mut(CLIENT).unwrap().pop_front()
+	}
+}
+
+struct LockFreeQueue {
+	clients: DashMap<String, (AtomicUsize, SegQueue<oneshot::Sender<()>>)>,
+}
+
+impl LockFreeQueue {
+	fn new() -> Self {
+		let clients = DashMap::new();
+		clients.insert(CLIENT.to_string(), (AtomicUsize::new(0), SegQueue::new()));
+		Self { clients }
+	}
+
+	fn enqueue(&self, tx: oneshot::Sender<()>) -> bool {
+		let state = self.clients.get(CLIENT).unwrap();
+		if state
+			.0
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |q| (q < MAX_QUEUE_PER_CLIENT).then_some(q + 1))
+			.is_err()
+		{
+			return false;
+		}
+		state.1.push(tx);
+		true
+	}
+
+	fn dequeue(&self) -> Option<oneshot::Sender<()>> {
+		let state = self.clients.get(CLIENT).unwrap();
+		let tx = state.1.pop()?;
+		state.0.fetch_sub(1, Ordering::SeqCst);
+		Some(tx)
+	}
+}
+
+/// Each thread alternates enqueue and dequeue against the same client
+fn churn(threads: usize, enqueue: impl Fn(oneshot::Sender<()>) -> bool + Sync, dequeue: impl Fn() -> Option<oneshot::Sender<()>> + Sync) {
+	thread::scope(|scope| {
+		for _ in 0..threads {
+			scope.spawn(|| {
+				for _ in 0..OPS_PER_THREAD {
+					let (tx, _rx) = oneshot::channel();
+					black_box(enqueue(tx));
+					black_box(dequeue());
+				}
+			});
+		}
+	});
+}
+
+fn bench_client_queue_contention(c: &mut Criterion) {
+	let mut group = c.benchmark_group("client_queue_contention");
+
+	for threads in [1, 2, 4, 8] {
+		group.throughput(Throughput::Elements((threads * OPS_PER_THREAD) as u64));
+
+		group.bench_with_input(BenchmarkId::new("dashmap_vecdeque", threads), &threads, |b, &threads| {
+			b.iter_custom(|iters| {
+				let queue = LockedQueue::new();
+				let start = Instant::now();
+				for _ in 0..iters {
+					churn(threads, |tx| queue.enqueue(tx), || queue.dequeue());
+				}
+				start.elapsed()
+			});
+		});
+
+		group.bench_with_input(BenchmarkId::new("segqueue_atomic", threads), &threads, |b, &threads| {
+			b.iter_custom(|iters| {
+				let queue = LockFreeQueue::new();
+				let start = Instant::now();
+				for _ in 0..iters {
+					churn(threads, |tx| queue.enqueue(tx), || queue.dequeue());
+				}
+				start.elapsed()
+			});
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, bench_client_queue_contention);
+criterion_main!(benches);
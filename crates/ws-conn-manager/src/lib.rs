@@ -29,32 +29,40 @@
 //! ## Current Known Limitations
 //!
 //! 1. **Fairness between clients**
-//!    - Queues are FIFO *per client*, but there is no global fairness
-//!      mechanism. A single client releasing frequently may monopolize
-//!      available slots while others remain queued.
-//!    - **Future improvement:** introduce a global fair scheduler
-//!      (e.g. weighted round-robin or rotating priority) to interleave
-//!      wakeups across clients.
+//!    - Queues are FIFO *per client*, and by default requests waiting on the
+//!      global limit are served first come, first served. A single client
+//!      releasing frequently may monopolize available slots while others
+//!      remain queued.
+//!    - `ConnectionGuardConfig { fair: true }` hands each freed global slot to
+//!      the next waiting client in round-robin order instead. Rotation is
+//!      unweighted and only applies to the global limit.
+//!    - **Future improvement:** weighted rotation or rotating priority.
+//!    - **Visibility:** `starving_clients()` and the background detector
+//!      spawned by `spawn_starvation_detector()` flag clients whose oldest
+//!      waiter has exceeded the configured starvation threshold.
 //!
 //! 2. **Global–queue coupling**
 //!    - Currently a global permit is acquired *before* inspecting per-client
 //!      state. Under heavy contention, clients whose queues are already
-//!      full may still block global capacity briefly.
+//!      full may still block global capacity briefly. Queued requests give
+//!      their permit back while they wait (see 8).
 //!    - **Future improvement:** perform a fast per-client pre-check before
 //!      acquiring the global semaphore, or add hierarchical admission
 //!      control (client-level pre-semaphores).
 //!
-//! 3. **No cross-client backpressure signaling**
-//!    - Clients are independently queued; there is no visibility into
-//!      global saturation for adaptive retry strategies.
-//!    - **Future improvement:** expose a shared metrics or notification API
-//!      that allows callers to detect near-saturation and apply jittered
-//!      backoff instead of immediate retry.
+//! 3. **Coarse cross-client backpressure signaling**
+//!    - Clients are independently queued. `subscribe_saturation()` reports
+//!      global usage as `Healthy`, `Warning` or `Critical` so callers can
+//!      apply jittered backoff near saturation, but there is no per-client
+//!      or queue-depth signal.
+//!    - **Future improvement:** expose a shared metrics API with finer
+//!      grained signals for adaptive retry strategies.
 //!
 //! 4. **Queue cleanup of canceled waiters**
-//!    - When a queued task is dropped before being woken, its oneshot
-//!      receiver is dropped but the sender remains in the queue until
-//!      the next wakeup attempt (which will fail silently).
+//!    - When a queued task times out or is dropped before being woken, it
+//!      gives its queue spot back straight away, but its sender stays in the
+//!      `SegQueue` (which can't remove from the middle) until the next
+//!      wakeup pops and skips it.
 //!    - **Future improvement:** periodically prune stale waiters or switch
 //!      to an `async_broadcast`/`Notify`-based structure that detects
 //!      cancellation earlier.
@@ -74,16 +82,24 @@
 //!      structured concurrency patterns that avoid spawning in Drop.
 //!
 //! 7. **Lock contention on per-client state**
-//!    - `DashMap` entries require mutable access for queue modifications.
-//!      Under heavy per-client load, this can create contention bottlenecks.
-//!    - **Future improvement:** replace `VecDeque` with lock-free concurrent
-//!      queue (e.g. `crossbeam::queue::SegQueue`) or per-client channels.
+//!    - The per-client queue is a `crossbeam::queue::SegQueue` with atomic
+//!      active/queued counters, so acquire and release only take a shared
+//!      `DashMap` reference. The entry write lock is still taken to insert
+//!      a client's first state and to remove it once idle.
+//!    - The queue can't be peeked, so `snapshot()` and `starving_clients()`
+//!      read waiter ages from a short side list of enqueue times. Only
+//!      joining the queue takes its lock; entries for waiters that were
+//!      woken or gave up are pruned lazily.
 //!
-//! 8. **Global semaphore held during queue await**
-//!    - If a request must queue, it holds the global semaphore permit while
-//!      waiting, which can reduce effective global capacity.
-//!    - **Future improvement:** release and re-acquire global permit around
-//!      queue waits, or implement two-phase admission control.
+//! 8. **Global permit re-acquired after a queue wait**
+//!    - A request that must queue releases its global permit while it waits
+//!      and takes a fresh one once it is handed a per-client slot, so queued
+//!      requests don't eat into global capacity.
+//!    - A woken request can therefore still wait on the global semaphore,
+//!      holding its per-client slot, if other clients filled global capacity
+//!      in the meantime.
+//!    - **Future improvement:** two-phase admission control that reserves the
+//!      global slot before waking a queued request.
 //!
 //! ## API Summary
 //!
@@ -100,26 +116,54 @@
 //!     }
 //! }
 //!
+//! // Bound the queue wait by the caller's own deadline
+//! let permit = guard.acquire_with_deadline(client_id, request_deadline).await?;
+//!
+//! // Or by a fixed timeout, leaving the queue if it elapses
+//! let permit = guard.acquire_timeout(client_id, Duration::from_secs(5)).await?;
+//!
+//! // Interleave global wakeups across clients instead of serving FIFO
+//! let guard = ConnectionGuard::with_config(ConnectionGuardConfig { fair: true, ..Default::default() });
+//!
+//! // Or configure limits fluently, rejecting inconsistent settings
+//! let guard = ConnectionGuard::builder().max_global(200).max_per_client(2).max_queue_per_client(4).fair(true).build()?;
+//!
 //! // Fast hint check before expensive operations
 //! if !guard.try_acquire_permit_hint() {
 //!     // Global capacity exhausted, reject early
 //! }
 //!
+//! // Back off while the server is close to its global limit
+//! let mut saturation = guard.subscribe_saturation();
+//! if *saturation.borrow() == SaturationLevel::Critical {
+//!     // Retry after a jittered delay
+//! }
+//!
 //! // Query current state
 //! let global_active = guard.active_global();
 //! let client_active = guard.active_per_client("client-123");
+//!
+//! // Per-client active/queued counts and how long each waiter has been queued
+//! for client in guard.snapshot() {
+//!     // client.waiter_ages is oldest first
+//! }
+//!
+//! // Flag clients whose oldest waiter exceeded the starvation threshold
+//! let detector = guard.spawn_starvation_detector(Duration::from_secs(5));
+//! for (client_id, waited) in guard.starving_clients() {
+//!     // Client has been queued for `waited`
+//! }
 //! ```
 //!
 //! ## Recommended Future Work
 //!
-//! - Integrate global fairness and client rotation.
+//! - Weight the fair rotation per client.
 //! - Provide a non-blocking `try_acquire()` API for early rejection.
 //! - Add tracing spans and metrics hooks for observability.
 //! - Implement an optional *hierarchical semaphore* model to separate
 //!   global from per-client resource pools.
 //! - Expose structured diagnostics for testing invariants under load.
 //! - Replace `tokio::spawn` in Drop with explicit async cleanup method.
-//! - Benchmark and optimize lock-free alternatives to VecDeque + DashMap.
 //!
 //! ---
 //! **Summary:**  
@@ -128,17 +172,24 @@
 //! fine-grained scheduling, and lock-free optimizations are deferred to
 //! future iterations.
 
-use dashmap::DashMap;
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
-use tracing::{debug, info};
+use crossbeam::queue::SegQueue;
+use dashmap::{mapref::one::Ref, DashMap};
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, watch, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
 
 /// Configuration constants
 pub const MAX_GLOBAL: usize = 1000;
 pub const MAX_PER_CLIENT: usize = 5;
 pub const MAX_QUEUE_PER_CLIENT: usize = 10;
+pub const DEFAULT_STARVATION_THRESHOLD: Duration = Duration::from_secs(30);
+pub const DEFAULT_WARNING_PERCENT: u8 = 75;
+pub const DEFAULT_CRITICAL_PERCENT: u8 = 90;
 
 /// Errors for acquire failures
 #[derive(Debug, thiserror::Error)]
@@ -147,6 +198,21 @@ pub enum AcquireErrorKind {
 	QueueFull,
 	#[error("global limit reached")]
 	GlobalLimit,
+	#[error("deadline passed before a slot was free")]
+	QueueTimeout,
+	#[error("timed out waiting for a slot")]
+	Timeout,
+}
+
+/// Settings a [`ConnectionGuardBuilder`] refuses to build a guard from
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ConfigError {
+	#[error("global limit must admit at least one connection")]
+	ZeroGlobal,
+	#[error("requests can't queue for a client that is never given a slot")]
+	QueueWithoutSlots,
+	#[error("warning threshold ({warning}%) is above the critical threshold ({critical}%)")]
+	ThresholdsInverted { warning: u8, critical: u8 },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -155,13 +221,105 @@ pub struct AcquireError {
 	pub kind: AcquireErrorKind,
 }
 
+/// Settings for a [`ConnectionGuard`]
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionGuardConfig {
+	/// Connections admitted across all clients
+	pub max_global: usize,
+	/// Active connections admitted per client
+	pub max_per_client: usize,
+	/// Requests a client may have queued once its active slots are full
+	pub max_queue_per_client: usize,
+	/// Drop a client's state as soon as it has nothing active or queued
+	pub cleanup_idle_clients: bool,
+	/// How long a client's oldest waiter may wait before it is reported as starving
+	pub starvation_threshold: Duration,
+	/// Hand freed global slots to waiting clients in round-robin order rather than first come, first served
+	pub fair: bool,
+	/// Percentage of `max_global` in use at which saturation is reported as `Warning`
+	pub warning_percent: u8,
+	/// Percentage of `max_global` in use at which saturation is reported as `Critical`
+	pub critical_percent: u8,
+}
+
+impl ConnectionGuardConfig {
+	/// Check that the settings describe a guard that can admit anything
+	///
+	/// # Errors
+	///
+	/// Returns the first inconsistency found.
+	pub const fn validate(&self) -> Result<(), ConfigError> {
+		if self.max_global == 0 {
+			return Err(ConfigError::ZeroGlobal);
+		}
+		if self.max_queue_per_client > 0 && self.max_per_client == 0 {
+			return Err(ConfigError::QueueWithoutSlots);
+		}
+		if self.warning_percent > self.critical_percent {
+			return Err(ConfigError::ThresholdsInverted {
+				warning: self.warning_percent,
+				critical: self.critical_percent,
+			});
+		}
+		Ok(())
+	}
+}
+
+impl Default for ConnectionGuardConfig {
+	fn default() -> Self {
+		Self {
+			max_global: MAX_GLOBAL,
+			max_per_client: MAX_PER_CLIENT,
+			max_queue_per_client: MAX_QUEUE_PER_CLIENT,
+			cleanup_idle_clients: true,
+			starvation_threshold: DEFAULT_STARVATION_THRESHOLD,
+			fair: false,
+			warning_percent: DEFAULT_WARNING_PERCENT,
+			critical_percent: DEFAULT_CRITICAL_PERCENT,
+		}
+	}
+}
+
+/// How close the guard is to its global limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum SaturationLevel {
+	/// Below the warning threshold
+	#[default]
+	Healthy,
+	/// At or above the warning threshold
+	Warning,
+	/// At or above the critical threshold; callers should back off
+	Critical,
+}
+
+impl SaturationLevel {
+	fn classify(in_use: usize, max_global: usize, warning_percent: u8, critical_percent: u8) -> Self {
+		let reached = |percent: u8| in_use.saturating_mul(100) >= max_global.saturating_mul(usize::from(percent));
+		if reached(critical_percent) {
+			Self::Critical
+		} else if reached(warning_percent) {
+			Self::Warning
+		} else {
+			Self::Healthy
+		}
+	}
+}
+
 /// RAII permit holding both global and per-client resources
 pub struct ConnectionPermit {
-	_global: OwnedSemaphorePermit,
+	global: Option<OwnedSemaphorePermit>,
 	client_id: String,
 	guard: Arc<ConnectionGuardInner>,
 }
 
+impl Drop for ConnectionPermit {
+	fn drop(&mut self) {
+		if let Some(global) = self.global.take() {
+			self.guard.release_global(global);
+		}
+	}
+}
+
 impl ConnectionPermit {
 	/// Explicit async cleanup (instead of spawning in Drop)
 	pub fn release(self) {
@@ -170,37 +328,339 @@ impl ConnectionPermit {
 	}
 
 	fn cleanup(&self) {
-		if let Some(mut client_state) = self.guard.clients.get_mut(&self.client_id) {
-			// decrement active count
-			let active = client_state.active.fetch_sub(1, Ordering::SeqCst);
-			tracing::info!("ConnectionPermit released for client {} (active={})", self.client_id, active - 1);
-
-			// wake next queued connection if any
-			if let Some(waiter) = client_state.queue.pop_front() {
-				let _ = waiter.send(());
-				debug!("Client {} dequeued into active slot", self.client_id);
+		self.guard.release_slot(&self.client_id);
+	}
+}
+
+/// Queued request waiting for a per-client slot
+pub struct Waiter {
+	pub tx: oneshot::Sender<()>,
+	pub enqueued_at: Instant,
+	/// Set by whichever of `dispatch` and the waiter itself takes it out of
+	/// the `queued` count first, so a withdrawn waiter isn't counted twice
+	claimed: Arc<AtomicBool>,
+}
+
+/// Per-client state
+///
+/// Only `active` and `queued` are ever updated in place, so every operation
+/// works through a shared `DashMap` reference; the write guard is needed
+/// only to insert or remove the entry itself.
+pub struct ClientState {
+	pub active: AtomicUsize,
+	/// Waiters in `queue`, plus any that have reserved a spot and are about to
+	/// push. This is what the per-client queue limit is enforced against.
+	pub queued: AtomicUsize,
+	pub queue: SegQueue<Waiter>,
+	/// Enqueue time of each waiter, keyed by its `claimed` flag; entries whose
+	/// flag is set have left the queue and are dropped on the next visit
+	waiting_since: Mutex<Vec<(Arc<AtomicBool>, Instant)>>,
+}
+
+impl ClientState {
+	const fn new() -> Self {
+		Self {
+			active: AtomicUsize::new(0),
+			queued: AtomicUsize::new(0),
+			queue: SegQueue::new(),
+			waiting_since: Mutex::new(Vec::new()),
+		}
+	}
+
+	fn is_idle(&self) -> bool {
+		self.active.load(Ordering::SeqCst) == 0 && self.queued.load(Ordering::SeqCst) == 0
+	}
+
+	/// Take a per-client slot if one of `max` is free, returning the previous active count
+	fn try_claim_slot(&self, max: usize) -> Option<usize> {
+		self.active.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |a| (a < max).then_some(a + 1)).ok()
+	}
+
+	/// Remember when the waiter owning `claimed` joined the queue
+	fn record_waiter(&self, claimed: &Arc<AtomicBool>, enqueued_at: Instant) {
+		let mut waiting = self.waiting_since.lock().unwrap_or_else(PoisonError::into_inner);
+		waiting.retain(|(claimed, _)| !claimed.load(Ordering::SeqCst));
+		waiting.push((Arc::clone(claimed), enqueued_at));
+	}
+
+	/// How long each waiter still in the queue has waited, oldest first
+	fn waiter_ages(&self) -> Vec<Duration> {
+		let mut waiting = self.waiting_since.lock().unwrap_or_else(PoisonError::into_inner);
+		waiting.retain(|(claimed, _)| !claimed.load(Ordering::SeqCst));
+		let mut ages: Vec<Duration> = waiting.iter().map(|(_, enqueued_at)| enqueued_at.elapsed()).collect();
+		drop(waiting);
+		ages.sort_unstable_by(|a, b| b.cmp(a));
+		ages
+	}
+
+	/// How long the longest-waiting request still in the queue has waited
+	///
+	/// Cancelled waiters still count until the next release drains them.
+	fn oldest_waiter_age(&self) -> Option<Duration> {
+		let mut waiting = self.waiting_since.lock().unwrap_or_else(PoisonError::into_inner);
+		waiting.retain(|(claimed, _)| !claimed.load(Ordering::SeqCst));
+		waiting.iter().map(|(_, enqueued_at)| *enqueued_at).min().map(|enqueued_at| enqueued_at.elapsed())
+	}
+}
+
+/// Point-in-time view of one client's slots and queue, from [`ConnectionGuard::snapshot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientSnapshot {
+	pub client_id: String,
+	pub active: usize,
+	pub queued: usize,
+	/// How long each queued waiter has been waiting, oldest first
+	///
+	/// Can briefly be shorter than `queued` while a waiter that has reserved
+	/// its spot is still joining the queue.
+	pub waiter_ages: Vec<Duration>,
+}
+
+/// Requests waiting on the global limit in fair mode
+#[derive(Default)]
+struct FairQueue {
+	/// Clients with requests waiting, in the order they'll be handed a slot
+	rotation: VecDeque<String>,
+	/// Each client's waiters, oldest first; non-empty exactly for clients in `rotation`
+	waiters: HashMap<String, VecDeque<oneshot::Sender<OwnedSemaphorePermit>>>,
+}
+
+impl FairQueue {
+	fn push(&mut self, client_id: &str, tx: oneshot::Sender<OwnedSemaphorePermit>) {
+		let waiters = self.waiters.entry(client_id.to_string()).or_default();
+		if waiters.is_empty() {
+			self.rotation.push_back(client_id.to_string());
+		}
+		waiters.push_back(tx);
+	}
+
+	/// Hand `permit` to the oldest waiter of the next client in rotation,
+	/// returning it if every waiter has gone away
+	fn hand_off(&mut self, mut permit: OwnedSemaphorePermit) -> Option<OwnedSemaphorePermit> {
+		while let Some(client_id) = self.rotation.pop_front() {
+			let Some(waiters) = self.waiters.get_mut(&client_id) else {
+				continue;
+			};
+			let tx = waiters.pop_front();
+			if waiters.is_empty() {
+				self.waiters.remove(&client_id);
+			} else {
+				self.rotation.push_back(client_id);
 			}
 
-			// cleanup if empty
-			if client_state.active.load(Ordering::SeqCst) == 0 && client_state.queue.is_empty() {
-				drop(client_state); // Release before remove
-				self.guard.clients.remove(&self.client_id);
-				debug!("Client state cleaned up for {}", self.client_id);
+			// A cancelled waiter hands the permit straight back
+			if let Some(tx) = tx {
+				match tx.send(permit) {
+					Ok(()) => return None,
+					Err(returned) => permit = returned,
+				}
 			}
 		}
+		Some(permit)
 	}
 }
 
-/// Per-client state
-pub struct ClientState {
-	pub active: AtomicUsize,
-	pub queue: VecDeque<oneshot::Sender<()>>,
+/// A fair-mode wait for a global slot
+///
+/// If the wait is dropped after a slot was handed to it, the slot goes to the
+/// next client in rotation rather than back to the semaphore behind its back.
+struct PendingGlobal<'a> {
+	inner: &'a ConnectionGuardInner,
+	rx: oneshot::Receiver<OwnedSemaphorePermit>,
+}
+
+impl Drop for PendingGlobal<'_> {
+	fn drop(&mut self) {
+		self.rx.close();
+		if let Ok(permit) = self.rx.try_recv() {
+			self.inner.release_global(permit);
+		}
+	}
 }
 
 /// Inner shared state
 pub struct ConnectionGuardInner {
 	pub global: Arc<Semaphore>,
+	/// Permits `global` was created with
+	pub max_global: usize,
+	/// Active connections admitted per client
+	pub max_per_client: usize,
+	/// Requests a client may have queued
+	pub max_queue_per_client: usize,
+	/// Whether idle client state is removed once released
+	cleanup_idle_clients: bool,
 	pub clients: DashMap<String, ClientState>,
+	pub starvation_threshold: Duration,
+	pub starvation_events: AtomicU64,
+	/// Round-robin wait list for global slots, if the guard is fair
+	fair: Option<Mutex<FairQueue>>,
+	/// Latest global saturation, updated on every acquire and release
+	saturation: watch::Sender<SaturationLevel>,
+	warning_percent: u8,
+	critical_percent: u8,
+}
+
+impl ConnectionGuardInner {
+	/// Shared reference to a client's state, inserting it if this is the client's first request
+	fn client_state(&self, client_id: &str) -> Ref<'_, String, ClientState> {
+		if let Some(state) = self.clients.get(client_id) {
+			return state;
+		}
+		self.clients.entry(client_id.to_string()).or_insert_with(ClientState::new).downgrade()
+	}
+
+	/// Take a global slot, publishing the new saturation level
+	///
+	/// Returns `None` only if the semaphore was closed.
+	async fn acquire_global(&self, client_id: &str) -> Option<OwnedSemaphorePermit> {
+		let permit = self.wait_for_global(client_id).await;
+		self.update_saturation();
+		permit
+	}
+
+	/// Wait for a global slot, taking turns with other clients if the guard is fair
+	async fn wait_for_global(&self, client_id: &str) -> Option<OwnedSemaphorePermit> {
+		let Some(fair) = &self.fair else {
+			return self.global.clone().acquire_owned().await.ok();
+		};
+
+		let rx = {
+			let mut fair = fair.lock().unwrap_or_else(PoisonError::into_inner);
+			if fair.rotation.is_empty() {
+				if let Ok(permit) = self.global.clone().try_acquire_owned() {
+					return Some(permit);
+				}
+			}
+
+			let (tx, rx) = oneshot::channel();
+			fair.push(client_id, tx);
+			// Nothing returns slots to the semaphore while the lock is held, but
+			// pass on any that are already there rather than leave waiters asleep
+			while let Ok(permit) = self.global.clone().try_acquire_owned() {
+				if fair.hand_off(permit).is_some() {
+					break;
+				}
+			}
+			drop(fair);
+			rx
+		};
+
+		let mut pending = PendingGlobal { inner: self, rx };
+		(&mut pending.rx).await.ok()
+	}
+
+	/// Give back a global slot, handing it to the next client in rotation if the guard is fair
+	fn release_global(&self, permit: OwnedSemaphorePermit) {
+		if let Some(fair) = &self.fair {
+			let mut fair = fair.lock().unwrap_or_else(PoisonError::into_inner);
+			// An unclaimed permit goes back to the semaphore under the lock, so a
+			// waiter joining concurrently either sees it there or is handed it
+			drop(fair.hand_off(permit));
+			drop(fair);
+		} else {
+			drop(permit);
+		}
+		self.update_saturation();
+	}
+
+	fn update_saturation(&self) {
+		let in_use = self.max_global - self.global.available_permits();
+		let level = SaturationLevel::classify(in_use, self.max_global, self.warning_percent, self.critical_percent);
+		self.saturation.send_if_modified(|current| std::mem::replace(current, level) != level);
+	}
+
+	/// Hand free per-client slots to queued waiters, oldest first
+	///
+	/// Called both after a slot is released and after a waiter joins the
+	/// queue, so a release racing with an enqueue can't leave a waiter asleep
+	/// next to a free slot.
+	fn dispatch(&self, state: &ClientState, client_id: &str) {
+		while state.queued.load(Ordering::SeqCst) > 0 {
+			if state.try_claim_slot(self.max_per_client).is_none() {
+				return;
+			}
+
+			let Some(waiter) = state.queue.pop() else {
+				// A waiter has reserved its spot but not pushed yet; retry until it lands
+				state.active.fetch_sub(1, Ordering::SeqCst);
+				std::hint::spin_loop();
+				continue;
+			};
+			if waiter.claimed.swap(true, Ordering::SeqCst) {
+				// The waiter already withdrew and left the count; this entry is stale
+				state.active.fetch_sub(1, Ordering::SeqCst);
+				continue;
+			}
+			state.queued.fetch_sub(1, Ordering::SeqCst);
+
+			if waiter.tx.send(()).is_ok() {
+				debug!("Client {} dequeued into active slot after {:?}", client_id, waiter.enqueued_at.elapsed());
+			} else {
+				// The waiter was cancelled; give the slot back and try the next one
+				state.active.fetch_sub(1, Ordering::SeqCst);
+			}
+		}
+	}
+
+	/// Return a per-client slot, passing it to the next waiter if there is one
+	fn release_slot(&self, client_id: &str) {
+		let Some(state) = self.clients.get(client_id) else {
+			return;
+		};
+
+		let active = state.active.fetch_sub(1, Ordering::SeqCst);
+		tracing::info!("ConnectionPermit released for client {} (active={})", client_id, active - 1);
+		self.dispatch(&state, client_id);
+		self.remove_if_idle(state, client_id);
+	}
+
+	/// Give back a queue spot whose waiter left before being handed a slot
+	fn withdraw_waiter(&self, client_id: &str) {
+		let Some(state) = self.clients.get(client_id) else {
+			return;
+		};
+
+		let queued = state.queued.fetch_sub(1, Ordering::SeqCst);
+		debug!("Client {} left the queue without a slot (queue={})", client_id, queued - 1);
+		self.remove_if_idle(state, client_id);
+	}
+
+	fn remove_if_idle(&self, state: Ref<'_, String, ClientState>, client_id: &str) {
+		let idle = self.cleanup_idle_clients && state.is_idle();
+		drop(state); // Release before remove
+		if idle && self.clients.remove_if(client_id, |_, state| state.is_idle()).is_some() {
+			debug!("Client state cleaned up for {}", client_id);
+		}
+	}
+}
+
+/// A queued `acquire` waiting to be handed a slot
+///
+/// If the acquire times out or is dropped while still queued, it gives its
+/// queue spot back. If a slot was already handed to it but it never resumed,
+/// the slot is passed on rather than leaked.
+struct PendingSlot<'a> {
+	inner: &'a ConnectionGuardInner,
+	client_id: &'a str,
+	rx: oneshot::Receiver<()>,
+	claimed: Arc<AtomicBool>,
+	granted: bool,
+}
+
+impl Drop for PendingSlot<'_> {
+	fn drop(&mut self) {
+		if self.granted {
+			return;
+		}
+		if !self.claimed.swap(true, Ordering::SeqCst) {
+			self.inner.withdraw_waiter(self.client_id);
+			return;
+		}
+		self.rx.close();
+		if self.rx.try_recv().is_ok() {
+			self.inner.release_slot(self.client_id);
+		}
+	}
 }
 
 /// Public ConnectionGuard
@@ -211,87 +671,285 @@ pub struct ConnectionGuard {
 
 impl ConnectionGuard {
 	pub fn new() -> Self {
+		Self::with_config(ConnectionGuardConfig::default())
+	}
+
+	/// Create a guard that reports clients as starving once their oldest
+	/// queued waiter has waited longer than `threshold`
+	#[must_use]
+	pub fn with_starvation_threshold(threshold: Duration) -> Self {
+		Self::with_config(ConnectionGuardConfig {
+			starvation_threshold: threshold,
+			..ConnectionGuardConfig::default()
+		})
+	}
+
+	/// Create a guard admitting at most `max_global` connections across all clients
+	#[must_use]
+	pub fn with_max_global(max_global: usize) -> Self {
+		Self::with_config(ConnectionGuardConfig {
+			max_global,
+			..ConnectionGuardConfig::default()
+		})
+	}
+
+	/// Start configuring a guard from the default settings
+	#[must_use]
+	pub fn builder() -> ConnectionGuardBuilder {
+		ConnectionGuardBuilder::default()
+	}
+
+	/// Create a guard from a full set of settings
+	#[must_use]
+	pub fn with_config(config: ConnectionGuardConfig) -> Self {
 		Self {
 			inner: Arc::new(ConnectionGuardInner {
-				global: Arc::new(Semaphore::new(MAX_GLOBAL)),
+				global: Arc::new(Semaphore::new(config.max_global)),
+				max_global: config.max_global,
+				max_per_client: config.max_per_client,
+				max_queue_per_client: config.max_queue_per_client,
+				cleanup_idle_clients: config.cleanup_idle_clients,
 				clients: DashMap::new(),
+				starvation_threshold: config.starvation_threshold,
+				starvation_events: AtomicU64::new(0),
+				fair: config.fair.then(Mutex::default),
+				saturation: watch::Sender::new(SaturationLevel::Healthy),
+				warning_percent: config.warning_percent,
+				critical_percent: config.critical_percent,
 			}),
 		}
 	}
 
 	pub async fn acquire(&self, client_id: String) -> Result<ConnectionPermit, AcquireError> {
+		self.acquire_within(client_id, None).await
+	}
+
+	/// Like [`Self::acquire`], but gives up with `QueueTimeout` once `deadline` passes
+	///
+	/// Lets a caller bound the queue wait by its own overall request deadline.
+	/// A deadline that has already passed fails straight away without queueing.
+	///
+	/// # Errors
+	///
+	/// `QueueTimeout` if no slot was free before `deadline`, otherwise as [`Self::acquire`].
+	pub async fn acquire_with_deadline(&self, client_id: String, deadline: Instant) -> Result<ConnectionPermit, AcquireError> {
+		let remaining = deadline.saturating_duration_since(Instant::now());
+		if remaining.is_zero() {
+			info!("Client {} connection rejected: deadline already passed", client_id);
+			return Err(AcquireError {
+				kind: AcquireErrorKind::QueueTimeout,
+			});
+		}
+		self.acquire_within(client_id, Some(remaining)).await
+	}
+
+	/// Like [`Self::acquire`], but gives up with `Timeout` if no slot is free within `timeout`
+	///
+	/// A request that times out while queued leaves the queue, so it no longer
+	/// counts against the client's queue limit and never takes an active slot.
+	///
+	/// # Errors
+	///
+	/// `Timeout` if no slot was free in time, otherwise as [`Self::acquire`].
+	pub async fn acquire_timeout(&self, client_id: String, timeout: Duration) -> Result<ConnectionPermit, AcquireError> {
+		self.acquire_within(client_id, Some(timeout)).await.map_err(|e| match e.kind {
+			AcquireErrorKind::QueueTimeout => AcquireError { kind: AcquireErrorKind::Timeout },
+			_ => e,
+		})
+	}
+
+	/// Acquire, waiting at most `timeout` (if any) for the global and per-client slots
+	async fn acquire_within(&self, client_id: String, timeout: Option<Duration>) -> Result<ConnectionPermit, AcquireError> {
 		info!("Client {} attempting to acquire connection permit", client_id);
+		let deadline = timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+		let timed_out = || {
+			info!("Client {} connection rejected: timed out waiting for a slot", client_id);
+			AcquireError {
+				kind: AcquireErrorKind::QueueTimeout,
+			}
+		};
+
+		let acquire_global = || async {
+			let global = self.inner.acquire_global(&client_id);
+			match deadline {
+				Some(deadline) => tokio::time::timeout_at(deadline, global).await.map_err(|_| timed_out())?,
+				None => global.await,
+			}
+			.ok_or(AcquireError {
+				kind: AcquireErrorKind::GlobalLimit,
+			})
+		};
 
 		// fast global check
-		let global_permit = self.inner.global.clone().acquire_owned().await.map_err(|_| AcquireError {
-			kind: AcquireErrorKind::GlobalLimit,
-		})?;
+		let global_permit = acquire_global().await?;
 
-		let mut client_state = self.inner.clients.entry(client_id.clone()).or_insert_with(|| ClientState {
-			active: AtomicUsize::new(0),
-			queue: VecDeque::new(),
-		});
-
-		let active_count = client_state.active.load(Ordering::SeqCst);
-
-		if active_count < MAX_PER_CLIENT {
-			client_state.active.fetch_add(1, Ordering::SeqCst);
-			info!("Client {} acquired active slot ({}/{})", client_id, active_count + 1, MAX_PER_CLIENT);
-			return Ok(ConnectionPermit {
-				_global: global_permit,
-				client_id,
-				guard: self.inner.clone(),
-			});
-		}
+		let (rx, claimed) = {
+			let client_state = self.inner.client_state(&client_id);
 
-		if client_state.queue.len() < MAX_QUEUE_PER_CLIENT {
+			if let Some(active_count) = client_state.try_claim_slot(self.inner.max_per_client) {
+				info!("Client {} acquired active slot ({}/{})", client_id, active_count + 1, self.inner.max_per_client);
+				return Ok(ConnectionPermit {
+					global: Some(global_permit),
+					client_id,
+					guard: self.inner.clone(),
+				});
+			}
+
+			let Ok(queued) = client_state
+				.queued
+				.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |q| (q < self.inner.max_queue_per_client).then_some(q + 1))
+			else {
+				drop(client_state);
+				self.inner.release_global(global_permit);
+				info!("Client {} connection rejected: queue full", client_id);
+				return Err(AcquireError {
+					kind: AcquireErrorKind::QueueFull,
+				});
+			};
 			let (tx, rx) = oneshot::channel();
-			client_state.queue.push_back(tx);
-			info!(
-				"Client {} queued for connection slot (queue={}/{})",
-				client_id,
-				client_state.queue.len(),
-				MAX_QUEUE_PER_CLIENT
-			);
-			drop(client_state); // Release lock before awaiting
-
-			let _ = rx.await;
-
-			// Re-acquire lock to increment active count
-			let client_state = self.inner.clients.get(&client_id).expect("client state should exist");
-			client_state.active.fetch_add(1, Ordering::SeqCst);
-
-			info!(
-				"Client {} dequeued into active slot ({}/{})",
-				client_id,
-				client_state.active.load(Ordering::SeqCst),
-				MAX_PER_CLIENT
-			);
-			return Ok(ConnectionPermit {
-				_global: global_permit,
-				client_id,
-				guard: self.inner.clone(),
+			let claimed = Arc::new(AtomicBool::new(false));
+			let enqueued_at = Instant::now();
+			client_state.record_waiter(&claimed, enqueued_at);
+			client_state.queue.push(Waiter {
+				tx,
+				enqueued_at,
+				claimed: Arc::clone(&claimed),
 			});
+			info!("Client {} queued for connection slot (queue={}/{})", client_id, queued + 1, self.inner.max_queue_per_client);
+
+			// A slot may have been released while we were joining the queue
+			self.inner.dispatch(&client_state, &client_id);
+			(rx, claimed)
+		}; // Release the map reference before awaiting
+
+		// Don't hold global capacity while queued; a fresh permit is taken once woken
+		self.inner.release_global(global_permit);
+
+		// The slot is handed over already claimed, so there is nothing to increment here
+		let mut pending = PendingSlot {
+			inner: &self.inner,
+			client_id: &client_id,
+			rx,
+			claimed,
+			granted: false,
+		};
+		match deadline {
+			// On timeout `pending` drops still ungranted, leaving the queue or passing on any slot that raced in
+			Some(deadline) => {
+				let _ = tokio::time::timeout_at(deadline, &mut pending.rx).await.map_err(|_| timed_out())?;
+			}
+			None => {
+				let _ = (&mut pending.rx).await;
+			}
 		}
+		pending.granted = true;
+		drop(pending);
+
+		// The per-client slot is ours now, so hand it back if no global slot can be had
+		let global_permit = match acquire_global().await {
+			Ok(permit) => permit,
+			Err(e) => {
+				self.inner.release_slot(&client_id);
+				return Err(e);
+			}
+		};
 
-		drop(global_permit);
-		info!("Client {} connection rejected: queue full", client_id);
-		Err(AcquireError {
-			kind: AcquireErrorKind::QueueFull,
+		info!(
+			"Client {} dequeued into active slot ({}/{})",
+			client_id,
+			self.active_per_client(&client_id),
+			self.inner.max_per_client
+		);
+		Ok(ConnectionPermit {
+			global: Some(global_permit),
+			client_id,
+			guard: self.inner.clone(),
 		})
 	}
 
+	/// Watch global saturation, e.g. to apply jittered retry while it is `Critical`
+	///
+	/// The level is recomputed on every acquire and release, and receivers
+	/// are only notified when it changes.
+	#[must_use]
+	pub fn subscribe_saturation(&self) -> watch::Receiver<SaturationLevel> {
+		self.inner.saturation.subscribe()
+	}
+
 	pub fn try_acquire_permit_hint(&self) -> bool {
 		self.inner.global.available_permits() > 0
 	}
 
 	pub fn active_global(&self) -> usize {
-		MAX_GLOBAL - self.inner.global.available_permits()
+		self.inner.max_global - self.inner.global.available_permits()
 	}
 
 	pub fn active_per_client(&self, client_id: &str) -> usize {
 		self.inner.clients.get(client_id).map(|c| c.active.load(Ordering::SeqCst)).unwrap_or(0)
 	}
+
+	/// Every client's active and queued counts with the ages of its queued waiters, by client id
+	#[must_use]
+	pub fn snapshot(&self) -> Vec<ClientSnapshot> {
+		let mut clients: Vec<ClientSnapshot> = self
+			.inner
+			.clients
+			.iter()
+			.map(|entry| ClientSnapshot {
+				client_id: entry.key().clone(),
+				active: entry.active.load(Ordering::SeqCst),
+				queued: entry.queued.load(Ordering::SeqCst),
+				waiter_ages: entry.waiter_ages(),
+			})
+			.collect();
+		clients.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+		clients
+	}
+
+	/// Clients whose oldest queued waiter has exceeded the starvation threshold,
+	/// paired with how long that waiter has been queued (longest first)
+	#[must_use]
+	pub fn starving_clients(&self) -> Vec<(String, Duration)> {
+		let threshold = self.inner.starvation_threshold;
+		let mut starving: Vec<(String, Duration)> = self
+			.inner
+			.clients
+			.iter()
+			.filter_map(|entry| {
+				let waited = entry.value().oldest_waiter_age()?;
+				(waited > threshold).then(|| (entry.key().clone(), waited))
+			})
+			.collect();
+		starving.sort_by_key(|(_, waited)| Reverse(*waited));
+		starving
+	}
+
+	/// Total number of starvation observations made by the background detector
+	#[must_use]
+	pub fn starvation_events_total(&self) -> u64 {
+		self.inner.starvation_events.load(Ordering::Relaxed)
+	}
+
+	/// Spawn a background task that checks for starving clients every `interval`,
+	/// logging a warning and bumping the starvation counter for each one found.
+	/// Abort the returned handle to stop the detector.
+	#[must_use]
+	pub fn spawn_starvation_detector(&self, interval: Duration) -> JoinHandle<()> {
+		let guard = self.clone();
+		tokio::spawn(async move {
+			let mut ticker = tokio::time::interval(interval);
+			loop {
+				ticker.tick().await;
+				for (client_id, waited) in guard.starving_clients() {
+					guard.inner.starvation_events.fetch_add(1, Ordering::Relaxed);
+					warn!(
+						"Client {} starving: oldest waiter queued for {:?} (threshold {:?})",
+						client_id, waited, guard.inner.starvation_threshold
+					);
+				}
+			}
+		})
+	}
 }
 
 impl Default for ConnectionGuard {
@@ -299,3 +957,71 @@ impl Default for ConnectionGuard {
 		Self::new()
 	}
 }
+
+/// Fluent alternative to filling in a [`ConnectionGuardConfig`] by hand
+///
+/// Unlike [`ConnectionGuard::with_config`], [`Self::build`] rejects settings
+/// that can't admit a connection.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConnectionGuardBuilder {
+	config: ConnectionGuardConfig,
+}
+
+impl ConnectionGuardBuilder {
+	#[must_use]
+	pub const fn max_global(mut self, max_global: usize) -> Self {
+		self.config.max_global = max_global;
+		self
+	}
+
+	#[must_use]
+	pub const fn max_per_client(mut self, max_per_client: usize) -> Self {
+		self.config.max_per_client = max_per_client;
+		self
+	}
+
+	#[must_use]
+	pub const fn max_queue_per_client(mut self, max_queue_per_client: usize) -> Self {
+		self.config.max_queue_per_client = max_queue_per_client;
+		self
+	}
+
+	/// Hand freed global slots to waiting clients in round-robin order
+	#[must_use]
+	pub const fn fair(mut self, fair: bool) -> Self {
+		self.config.fair = fair;
+		self
+	}
+
+	/// Whether a client's state is dropped once it has nothing active or queued
+	///
+	/// On by default. Turning it off keeps entries around for clients that
+	/// reconnect constantly, at the cost of never freeing them.
+	#[must_use]
+	pub const fn with_cleanup(mut self, cleanup_idle_clients: bool) -> Self {
+		self.config.cleanup_idle_clients = cleanup_idle_clients;
+		self
+	}
+
+	#[must_use]
+	pub const fn starvation_threshold(mut self, threshold: Duration) -> Self {
+		self.config.starvation_threshold = threshold;
+		self
+	}
+
+	/// Percentages of `max_global` in use at which saturation turns `Warning` and `Critical`
+	#[must_use]
+	pub const fn saturation_thresholds(mut self, warning_percent: u8, critical_percent: u8) -> Self {
+		self.config.warning_percent = warning_percent;
+		self.config.critical_percent = critical_percent;
+		self
+	}
+
+	/// # Errors
+	///
+	/// Returns a [`ConfigError`] if the settings are inconsistent, see [`ConnectionGuardConfig::validate`].
+	pub fn build(self) -> Result<ConnectionGuard, ConfigError> {
+		self.config.validate()?;
+		Ok(ConnectionGuard::with_config(self.config))
+	}
+}
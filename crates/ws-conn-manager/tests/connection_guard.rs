@@ -0,0 +1,448 @@
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::Ordering;
+	use std::sync::{Arc, Mutex};
+	use std::time::{Duration, Instant};
+	use ws_conn_manager::{AcquireErrorKind, ConfigError, ConnectionGuard, ConnectionGuardConfig, SaturationLevel, MAX_PER_CLIENT, MAX_QUEUE_PER_CLIENT};
+
+	#[tokio::test]
+	async fn test_starving_client_reported_past_threshold() {
+		let guard = ConnectionGuard::with_starvation_threshold(Duration::from_millis(50));
+
+		// Fill every active slot for the client so the next request queues
+		let mut permits = Vec::new();
+		for _ in 0..MAX_PER_CLIENT {
+			permits.push(guard.acquire("hungry".to_string()).await.expect("should acquire"));
+		}
+		permits.push(guard.acquire("fed".to_string()).await.expect("should acquire"));
+
+		let queued = {
+			let guard = guard.clone();
+			tokio::spawn(async move { guard.acquire("hungry".to_string()).await })
+		};
+
+		tokio::time::sleep(Duration::from_millis(10)).await;
+		assert!(guard.starving_clients().is_empty(), "waiter should not be starving yet");
+
+		let detector = guard.spawn_starvation_detector(Duration::from_millis(10));
+		tokio::time::sleep(Duration::from_millis(100)).await;
+
+		let starving = guard.starving_clients();
+		assert_eq!(starving.len(), 1);
+		assert_eq!(starving[0].0, "hungry");
+		assert!(starving[0].1 > Duration::from_millis(50));
+		assert!(guard.starvation_events_total() > 0);
+
+		detector.abort();
+		queued.abort();
+	}
+
+	#[tokio::test]
+	async fn test_starvation_measured_from_oldest_queued_waiter() {
+		let guard = ConnectionGuard::with_starvation_threshold(Duration::from_millis(50));
+		let mut permits = Vec::new();
+		for _ in 0..MAX_PER_CLIENT {
+			permits.push(guard.acquire("hungry".to_string()).await.unwrap());
+		}
+
+		let mut waiters = Vec::new();
+		for _ in 0..2 {
+			let guard = guard.clone();
+			waiters.push(tokio::spawn(async move { guard.acquire("hungry".to_string()).await }));
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+		tokio::time::sleep(Duration::from_millis(80)).await;
+
+		// The queue moves, but the waiter left behind has still waited past the threshold
+		permits.pop().unwrap().release();
+		permits.push(waiters.remove(0).await.unwrap().unwrap());
+		let starving = guard.starving_clients();
+		assert_eq!(starving.len(), 1);
+		assert_eq!(starving[0].0, "hungry");
+		assert!(starving[0].1 > Duration::from_millis(50), "{:?}", starving[0].1);
+
+		waiters[0].abort();
+	}
+
+	#[tokio::test]
+	async fn test_snapshot_reports_queued_waiter_ages_oldest_first() {
+		let guard = ConnectionGuard::new();
+		let mut permits = Vec::new();
+		for _ in 0..MAX_PER_CLIENT {
+			permits.push(guard.acquire("busy".to_string()).await.unwrap());
+		}
+
+		let mut waiters = Vec::new();
+		for _ in 0..2 {
+			let guard = guard.clone();
+			waiters.push(tokio::spawn(async move { guard.acquire("busy".to_string()).await }));
+			tokio::time::sleep(Duration::from_millis(20)).await;
+		}
+
+		let snapshot = guard.snapshot();
+		assert_eq!(snapshot.len(), 1);
+		let client = &snapshot[0];
+		assert_eq!((client.client_id.as_str(), client.active, client.queued), ("busy", MAX_PER_CLIENT, 2));
+		assert_eq!(client.waiter_ages.len(), 2);
+		assert!(client.waiter_ages[0] > client.waiter_ages[1], "{:?}", client.waiter_ages);
+		assert!(client.waiter_ages[1] > Duration::ZERO);
+
+		// The oldest waiter takes the freed slot and drops out of the report
+		permits.pop().unwrap().release();
+		permits.push(waiters.remove(0).await.unwrap().unwrap());
+		assert_eq!(guard.snapshot()[0].waiter_ages.len(), 1);
+
+		waiters[0].abort();
+	}
+
+	#[tokio::test]
+	async fn test_queued_waiters_are_woken_in_fifo_order() {
+		let guard = ConnectionGuard::new();
+		let mut permits = Vec::new();
+		for _ in 0..MAX_PER_CLIENT {
+			permits.push(guard.acquire("busy".to_string()).await.unwrap());
+		}
+
+		let order = Arc::new(Mutex::new(Vec::new()));
+		let mut waiters = Vec::new();
+		for i in 0..3 {
+			let (guard, order) = (guard.clone(), Arc::clone(&order));
+			waiters.push(tokio::spawn(async move {
+				let permit = guard.acquire("busy".to_string()).await.unwrap();
+				order.lock().unwrap().push(i);
+				permit
+			}));
+			// Let each waiter reach the queue before spawning the next
+			tokio::time::sleep(Duration::from_millis(5)).await;
+		}
+
+		for permit in permits.drain(..3) {
+			permit.release();
+			tokio::time::sleep(Duration::from_millis(5)).await;
+		}
+
+		for waiter in waiters {
+			permits.push(waiter.await.unwrap());
+		}
+		assert_eq!(*order.lock().unwrap(), vec![0, 1, 2]);
+		assert_eq!(guard.active_per_client("busy"), MAX_PER_CLIENT);
+
+		for permit in permits {
+			permit.release();
+		}
+		assert_eq!(guard.active_per_client("busy"), 0);
+		assert!(guard.inner.clients.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_queue_full_rejects_without_holding_a_slot() {
+		let guard = ConnectionGuard::new();
+		let mut permits = Vec::new();
+		for _ in 0..MAX_PER_CLIENT {
+			permits.push(guard.acquire("full".to_string()).await.unwrap());
+		}
+
+		let mut waiters = Vec::new();
+		for _ in 0..MAX_QUEUE_PER_CLIENT {
+			let guard = guard.clone();
+			waiters.push(tokio::spawn(async move { guard.acquire("full".to_string()).await }));
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+
+		let rejected = guard.acquire("full".to_string()).await;
+		assert!(matches!(rejected, Err(e) if matches!(e.kind, AcquireErrorKind::QueueFull)));
+		// Queued waiters don't hold global slots
+		assert_eq!(guard.active_global(), MAX_PER_CLIENT);
+
+		for waiter in waiters {
+			waiter.abort();
+		}
+	}
+
+	#[tokio::test]
+	async fn test_elapsed_deadline_fails_without_queueing() {
+		let guard = ConnectionGuard::new();
+		let mut permits = Vec::new();
+		for _ in 0..MAX_PER_CLIENT {
+			permits.push(guard.acquire("late".to_string()).await.unwrap());
+		}
+
+		let deadline = Instant::now().checked_sub(Duration::from_millis(1)).unwrap();
+		let started = Instant::now();
+		let rejected = guard.acquire_with_deadline("late".to_string(), deadline).await;
+		assert!(matches!(rejected, Err(e) if matches!(e.kind, AcquireErrorKind::QueueTimeout)));
+		assert!(started.elapsed() < Duration::from_millis(50));
+		assert_eq!(guard.inner.clients.get("late").unwrap().queued.load(Ordering::SeqCst), 0);
+		assert_eq!(guard.active_global(), MAX_PER_CLIENT);
+
+		// A deadline in the future waits in the queue, then gives up
+		let timed_out = guard.acquire_with_deadline("late".to_string(), Instant::now() + Duration::from_millis(20)).await;
+		assert!(matches!(timed_out, Err(e) if matches!(e.kind, AcquireErrorKind::QueueTimeout)));
+		assert_eq!(guard.active_global(), MAX_PER_CLIENT);
+	}
+
+	#[tokio::test]
+	async fn test_acquire_timeout_leaves_the_queue() {
+		let guard = ConnectionGuard::new();
+		let mut permits = Vec::new();
+		for _ in 0..MAX_PER_CLIENT {
+			permits.push(guard.acquire("slow".to_string()).await.unwrap());
+		}
+
+		let timed_out = guard.acquire_timeout("slow".to_string(), Duration::from_millis(20)).await;
+		assert!(matches!(timed_out, Err(e) if matches!(e.kind, AcquireErrorKind::Timeout)));
+		assert_eq!(guard.inner.clients.get("slow").unwrap().queued.load(Ordering::SeqCst), 0);
+		assert_eq!(guard.active_per_client("slow"), MAX_PER_CLIENT);
+
+		// The whole queue is available again, and the stale entry doesn't swallow a wakeup
+		let waiters: Vec<_> = (0..MAX_QUEUE_PER_CLIENT)
+			.map(|_| {
+				let guard = guard.clone();
+				tokio::spawn(async move { guard.acquire("slow".to_string()).await })
+			})
+			.collect();
+		tokio::time::sleep(Duration::from_millis(10)).await;
+		assert_eq!(guard.inner.clients.get("slow").unwrap().queued.load(Ordering::SeqCst), MAX_QUEUE_PER_CLIENT);
+
+		permits.pop().unwrap().release();
+		let mut waiters = waiters.into_iter();
+		let woken = tokio::time::timeout(Duration::from_secs(1), waiters.next().unwrap())
+			.await
+			.expect("oldest live waiter should be woken")
+			.unwrap()
+			.unwrap();
+		assert_eq!(guard.active_per_client("slow"), MAX_PER_CLIENT);
+
+		for waiter in waiters {
+			waiter.abort();
+		}
+		drop(woken);
+	}
+
+	#[tokio::test]
+	async fn test_queued_requests_free_global_slots_for_other_clients() {
+		let max_global = MAX_PER_CLIENT + 1;
+		let guard = ConnectionGuard::with_max_global(max_global);
+		let mut permits = Vec::new();
+		for _ in 0..MAX_PER_CLIENT {
+			permits.push(guard.acquire("busy".to_string()).await.unwrap());
+		}
+
+		let waiters: Vec<_> = (0..MAX_QUEUE_PER_CLIENT)
+			.map(|_| {
+				let guard = guard.clone();
+				tokio::spawn(async move { guard.acquire("busy".to_string()).await })
+			})
+			.collect();
+		tokio::time::sleep(Duration::from_millis(10)).await;
+		assert_eq!(guard.inner.clients.get("busy").unwrap().queued.load(Ordering::SeqCst), MAX_QUEUE_PER_CLIENT);
+		assert_eq!(guard.active_global(), MAX_PER_CLIENT);
+
+		// The one spare global slot keeps serving other clients while "busy" waits
+		for client in ["a", "b", "c"] {
+			let permit = tokio::time::timeout(Duration::from_millis(100), guard.acquire(client.to_string()))
+				.await
+				.expect("global slot should not be held by queued requests")
+				.unwrap();
+			assert_eq!(guard.active_global(), max_global);
+			permit.release();
+		}
+
+		// Woken waiters take a fresh global slot, oldest first
+		for permit in permits {
+			permit.release();
+		}
+		let mut waiters = waiters.into_iter();
+		let woken: Vec<_> = tokio::time::timeout(Duration::from_secs(1), futures::future::join_all(waiters.by_ref().take(MAX_PER_CLIENT)))
+			.await
+			.expect("queued requests should be woken")
+			.into_iter()
+			.map(|waiter| waiter.unwrap().unwrap())
+			.collect();
+		assert_eq!(guard.active_global(), MAX_PER_CLIENT);
+		assert_eq!(guard.active_per_client("busy"), MAX_PER_CLIENT);
+
+		for waiter in waiters {
+			waiter.abort();
+		}
+		drop(woken);
+	}
+
+	#[tokio::test]
+	async fn test_fair_guard_interleaves_global_wakeups_across_clients() {
+		let guard = ConnectionGuard::with_config(ConnectionGuardConfig {
+			max_global: 1,
+			fair: true,
+			..ConnectionGuardConfig::default()
+		});
+		let held = guard.acquire("holder".to_string()).await.unwrap();
+
+		// Each client queues all its requests before the next client shows up,
+		// so first come, first served would wake them in blocks
+		let order = Arc::new(Mutex::new(Vec::new()));
+		let mut waiters = Vec::new();
+		for client in ["a", "b", "c"] {
+			for _ in 0..4 {
+				let (guard, order) = (guard.clone(), Arc::clone(&order));
+				waiters.push(tokio::spawn(async move {
+					let permit = guard.acquire(client.to_string()).await.unwrap();
+					order.lock().unwrap().push(client);
+					permit.release();
+				}));
+				tokio::time::sleep(Duration::from_millis(2)).await;
+			}
+		}
+
+		held.release();
+		tokio::time::timeout(Duration::from_secs(1), futures::future::join_all(waiters))
+			.await
+			.expect("every waiter should get a turn");
+
+		let order = order.lock().unwrap();
+		assert_eq!(order.len(), 12);
+		for round in order.chunks(3) {
+			let mut round = round.to_vec();
+			round.sort_unstable();
+			assert_eq!(round, ["a", "b", "c"], "wakeups should rotate between clients: {order:?}");
+		}
+		assert_eq!(guard.active_global(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_saturation_turns_critical_past_ninety_percent() {
+		let guard = ConnectionGuard::with_max_global(20);
+		let mut saturation = guard.subscribe_saturation();
+		assert_eq!(*saturation.borrow_and_update(), SaturationLevel::Healthy);
+
+		let mut permits = Vec::new();
+		for i in 0..15 {
+			permits.push(guard.acquire(format!("client-{}", i % 4)).await.unwrap());
+		}
+		assert!(saturation.has_changed().unwrap());
+		assert_eq!(*saturation.borrow_and_update(), SaturationLevel::Warning);
+
+		// 19 of 20 slots in use is past the 90% critical threshold
+		for i in 15..19 {
+			permits.push(guard.acquire(format!("client-{}", i % 4)).await.unwrap());
+		}
+		tokio::time::timeout(Duration::from_secs(1), saturation.changed()).await.unwrap().unwrap();
+		assert_eq!(*saturation.borrow_and_update(), SaturationLevel::Critical);
+
+		for permit in permits.drain(..10) {
+			permit.release();
+		}
+		assert_eq!(*saturation.borrow_and_update(), SaturationLevel::Healthy);
+	}
+
+	/// Many tasks churning one client's slots; a lost wakeup would hang the test
+	#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+	async fn test_contended_client_never_loses_a_wakeup() {
+		let guard = ConnectionGuard::new();
+		let tasks: Vec<_> = (0..MAX_PER_CLIENT + MAX_QUEUE_PER_CLIENT)
+			.map(|_| {
+				let guard = guard.clone();
+				tokio::spawn(async move {
+					for _ in 0..500 {
+						let permit = guard.acquire("contended".to_string()).await.unwrap();
+						tokio::task::yield_now().await;
+						permit.release();
+					}
+				})
+			})
+			.collect();
+
+		tokio::time::timeout(Duration::from_secs(30), futures::future::join_all(tasks))
+			.await
+			.expect("every acquire should eventually be woken");
+
+		assert_eq!(guard.active_per_client("contended"), 0);
+		assert!(guard.inner.clients.is_empty());
+	}
+
+	/// Permits released while other tasks are mid-dequeue, with idle client entries
+	/// removed and recreated throughout; a woken waiter must never find its entry gone
+	#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+	async fn test_dequeue_survives_concurrent_client_cleanup() {
+		let guard = ConnectionGuard::new();
+		let tasks: Vec<_> = (0..4 * (MAX_PER_CLIENT + MAX_QUEUE_PER_CLIENT))
+			.map(|task| {
+				let guard = guard.clone();
+				let client_id = format!("client-{}", task % 4);
+				tokio::spawn(async move {
+					for i in 0..1_000 {
+						// Some waiters give up mid-dequeue, racing the slot handed to them
+						let acquired = if i % 3 == 0 {
+							guard.acquire_timeout(client_id.clone(), Duration::from_micros(50)).await.ok()
+						} else {
+							guard.acquire(client_id.clone()).await.ok()
+						};
+						if let Some(permit) = acquired {
+							if i % 2 == 0 {
+								tokio::task::yield_now().await;
+							}
+							permit.release();
+						}
+					}
+				})
+			})
+			.collect();
+
+		let results = tokio::time::timeout(Duration::from_secs(60), futures::future::join_all(tasks))
+			.await
+			.expect("every acquire should eventually be woken");
+		assert!(results.iter().all(Result::is_ok), "no task should panic");
+
+		assert_eq!(guard.active_global(), 0);
+		assert!(guard.inner.clients.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_builder_limits_are_enforced() {
+		let guard = ConnectionGuard::builder()
+			.max_global(3)
+			.max_per_client(2)
+			.max_queue_per_client(1)
+			.with_cleanup(false)
+			.build()
+			.unwrap();
+
+		let first = guard.acquire("a".to_string()).await.unwrap();
+		let second = guard.acquire("a".to_string()).await.unwrap();
+		assert_eq!(guard.active_per_client("a"), 2);
+
+		// One request fits in the queue, the next is turned away
+		let queued = {
+			let guard = guard.clone();
+			tokio::spawn(async move { guard.acquire("a".to_string()).await })
+		};
+		tokio::time::sleep(Duration::from_millis(10)).await;
+		let rejected = guard.acquire("a".to_string()).await;
+		assert!(matches!(rejected, Err(e) if matches!(e.kind, AcquireErrorKind::QueueFull)));
+
+		// The last global slot goes to another client, then the guard is full
+		let other = guard.acquire("b".to_string()).await.unwrap();
+		assert_eq!(guard.active_global(), 3);
+		assert!(!guard.try_acquire_permit_hint());
+
+		first.release();
+		drop(other);
+		let woken = tokio::time::timeout(Duration::from_secs(1), queued).await.unwrap().unwrap().unwrap();
+		drop((second, woken));
+
+		// Cleanup was turned off, so idle clients keep their state
+		assert_eq!(guard.active_global(), 0);
+		assert!(guard.inner.clients.contains_key("a"));
+	}
+
+	#[test]
+	fn test_builder_rejects_inconsistent_settings() {
+		let queue_without_slots = ConnectionGuard::builder().max_per_client(0).build();
+		assert_eq!(queue_without_slots.err(), Some(ConfigError::QueueWithoutSlots));
+		assert!(ConnectionGuard::builder().max_per_client(0).max_queue_per_client(0).build().is_ok());
+		assert_eq!(ConnectionGuard::builder().max_global(0).build().err(), Some(ConfigError::ZeroGlobal));
+		assert_eq!(
+			ConnectionGuard::builder().saturation_thresholds(95, 80).build().err(),
+			Some(ConfigError::ThresholdsInverted { warning: 95, critical: 80 })
+		);
+	}
+}
@@ -34,6 +34,13 @@ impl<K: EventKey> ConnectionActor<K> {
 		}
 	}
 
+	/// Cap the number of event keys this connection may subscribe to
+	#[must_use]
+	pub fn with_subscription_quota(mut self, max_subscriptions: usize) -> Self {
+		self.subscriptions = self.subscriptions.with_quota(max_subscriptions);
+		self
+	}
+
 	/// Run the actor event loop
 	pub async fn run(mut self) {
 		while let Some(cmd) = self.commands.recv().await {
@@ -42,11 +49,14 @@ impl<K: EventKey> ConnectionActor<K> {
 					self.state.record_activity();
 				}
 
-				ConnectionCommand::Subscribe { event_types } => {
-					let change = self.subscriptions.subscribe(event_types);
-					if change.added > 0 {
-						tracing::debug!("Connection {} subscribed to {} events", self.id, change.added);
+				ConnectionCommand::Subscribe { event_types, reply } => {
+					let result = self.subscriptions.try_subscribe(event_types);
+					match &result {
+						Ok(change) if change.added > 0 => tracing::debug!("Connection {} subscribed to {} events", self.id, change.added),
+						Ok(_) => {}
+						Err(e) => tracing::warn!("Connection {} subscribe rejected: {}", self.id, e),
 					}
+					let _ = reply.send(result);
 				}
 
 				ConnectionCommand::Unsubscribe { event_types } => {
@@ -66,6 +76,10 @@ impl<K: EventKey> ConnectionActor<K> {
 					let _ = reply.send(subs);
 				}
 
+				ConnectionCommand::GetSubscriptionCount { reply } => {
+					let _ = reply.send(self.subscriptions.count());
+				}
+
 				ConnectionCommand::CheckStale { timeout } => {
 					if self.state.should_be_stale(timeout) {
 						self.state.mark_stale("timeout".to_string());
@@ -2,28 +2,50 @@ use std::{collections::HashSet, time::Duration};
 use tokio::sync::oneshot;
 
 use super::state::ConnectionState;
-use crate::core::subscription::EventKey;
+use crate::core::subscription::{EventKey, QuotaExceeded, SubscriptionChange};
 
 /// Messages that can be sent to a connection actor
 #[derive(Debug)]
 pub enum ConnectionCommand<K: EventKey> {
 	RecordActivity,
 
-	Subscribe { event_types: Vec<K> },
+	Subscribe {
+		event_types: Vec<K>,
+		reply: oneshot::Sender<Result<SubscriptionChange, QuotaExceeded>>,
+	},
 
-	Unsubscribe { event_types: Vec<K> },
+	Unsubscribe {
+		event_types: Vec<K>,
+	},
 
-	IsSubscribedTo { event_type: K, reply: oneshot::Sender<bool> },
+	IsSubscribedTo {
+		event_type: K,
+		reply: oneshot::Sender<bool>,
+	},
 
-	GetSubscriptions { reply: oneshot::Sender<HashSet<K>> },
+	GetSubscriptions {
+		reply: oneshot::Sender<HashSet<K>>,
+	},
 
-	CheckStale { timeout: Duration },
+	GetSubscriptionCount {
+		reply: oneshot::Sender<usize>,
+	},
 
-	MarkStale { reason: String },
+	CheckStale {
+		timeout: Duration,
+	},
 
-	Disconnect { reason: String },
+	MarkStale {
+		reason: String,
+	},
 
-	GetState { reply: oneshot::Sender<ConnectionState> },
+	Disconnect {
+		reason: String,
+	},
+
+	GetState {
+		reply: oneshot::Sender<ConnectionState>,
+	},
 
 	Shutdown,
 }
@@ -1,3 +1,4 @@
+use crate::core::subscription::QuotaExceeded;
 use thiserror::Error;
 use tokio::sync::oneshot;
 
@@ -15,6 +16,10 @@ pub enum ConnectionError {
 	#[error("Failed to get state from connection actor: {0}")]
 	StateRetrievalFailed(#[from] oneshot::error::RecvError),
 
+	/// The connection already holds as many subscriptions as it is allowed
+	#[error(transparent)]
+	SubscriptionQuotaExceeded(#[from] QuotaExceeded),
+
 	/// Multiple Arc references detected
 	#[error("Multiple Arc references to connection detected during {operation}")]
 	MultipleArcReferences { operation: String },
@@ -7,7 +7,7 @@ use super::error::{ConnectionError, Result};
 use super::state::ConnectionState;
 use super::ConnectionActor;
 use crate::core::conn::Connection;
-use crate::core::subscription::EventKey;
+use crate::core::subscription::{EventKey, SubscriptionChange};
 
 /// Handle for communicating with a connection actor
 #[derive(Clone, Debug)]
@@ -37,6 +37,15 @@ impl<K: EventKey> ConnectionHandle<K> {
 		(handle, actor, token)
 	}
 
+	/// Token cancelled once the actor is shut down or its parent is cancelled
+	///
+	/// Transport tasks can derive child tokens from it so they stop when the
+	/// connection is removed from its store.
+	#[must_use]
+	pub fn cancel_token(&self) -> CancellationToken {
+		self.cancel_token.clone()
+	}
+
 	/// Record recent activity
 	pub async fn record_activity(&self) -> Result<()> {
 		self
@@ -47,12 +56,18 @@ impl<K: EventKey> ConnectionHandle<K> {
 	}
 
 	/// Subscribe to event types
-	pub async fn subscribe(&self, event_types: Vec<K>) -> Result<()> {
+	///
+	/// Fails with `ConnectionError::SubscriptionQuotaExceeded`, subscribing to
+	/// none of them, if the connection would go past its subscription quota.
+	pub async fn subscribe(&self, event_types: Vec<K>) -> Result<SubscriptionChange> {
+		let (tx, rx) = oneshot::channel();
 		self
 			.sender
-			.send(ConnectionCommand::Subscribe { event_types })
+			.send(ConnectionCommand::Subscribe { event_types, reply: tx })
 			.await
-			.map_err(|e| ConnectionError::ActorUnavailable(Box::new(e)))
+			.map_err(|e| ConnectionError::ActorUnavailable(Box::new(e)))?;
+
+		Ok(rx.await.map_err(|e| ConnectionError::ActorUnavailable(Box::new(e)))??)
 	}
 
 	/// Unsubscribe from event types
@@ -88,6 +103,22 @@ impl<K: EventKey> ConnectionHandle<K> {
 		rx.await.map_err(|e| ConnectionError::ActorUnavailable(Box::new(e)))
 	}
 
+	/// Number of event types currently subscribed to
+	///
+	/// # Errors
+	///
+	/// Returns `ConnectionError::ActorUnavailable` if the actor has shut down.
+	pub async fn subscription_count(&self) -> Result<usize> {
+		let (tx, rx) = oneshot::channel();
+		self
+			.sender
+			.send(ConnectionCommand::GetSubscriptionCount { reply: tx })
+			.await
+			.map_err(|e| ConnectionError::ActorUnavailable(Box::new(e)))?;
+
+		rx.await.map_err(|e| ConnectionError::ActorUnavailable(Box::new(e)))
+	}
+
 	/// Check if connection should be marked stale
 	pub async fn check_stale(&self, timeout: Duration) -> Result<()> {
 		self
@@ -1,5 +1,6 @@
 use crate::types::{ClientId, ConnectionId};
 use std::{
+	collections::HashMap,
 	net::SocketAddr,
 	time::{Duration, Instant},
 };
@@ -11,6 +12,8 @@ pub struct Connection {
 	pub client_id: ClientId,
 	pub established_at: Instant,
 	pub source_addr: SocketAddr,
+	/// Free-form tags (user agent, tier, region, ...) used to route and filter
+	pub metadata: HashMap<String, String>,
 }
 
 impl Connection {
@@ -21,9 +24,23 @@ impl Connection {
 			client_id,
 			established_at: Instant::now(),
 			source_addr,
+			metadata: HashMap::new(),
 		}
 	}
 
+	/// Attach a metadata tag at connect time
+	#[must_use]
+	pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+		self.metadata.insert(key.into(), value.into());
+		self
+	}
+
+	/// Whether this connection carries `key=value`
+	#[must_use]
+	pub fn has_tag(&self, key: &str, value: &str) -> bool {
+		self.metadata.get(key).is_some_and(|v| v == value)
+	}
+
 	/// Get connection duration
 	pub fn get_duration(&self) -> Duration {
 		self.established_at.elapsed()
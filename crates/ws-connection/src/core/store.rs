@@ -3,23 +3,41 @@ use crate::core::conn::Connection;
 use crate::core::subscription::EventKey;
 use dashmap::DashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 
+/// How long [`ConnectionStore::broadcast`] waits for an actor to say whether it's subscribed
+const SUBSCRIPTION_QUERY_TIMEOUT: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Clone)]
 pub struct ConnectionStore<K: EventKey = String> {
 	handles: Arc<DashMap<String, ConnectionHandle<K>>>,
+	/// Per-connection subscription cap applied to every inserted connection
+	subscription_quota: Option<usize>,
 }
 
 impl<K: EventKey> ConnectionStore<K> {
 	pub fn new() -> Self {
 		Self {
 			handles: Arc::new(DashMap::new()),
+			subscription_quota: None,
 		}
 	}
 
+	/// Limit how many event keys each connection may subscribe to
+	#[must_use]
+	pub const fn with_subscription_quota(mut self, max_subscriptions: usize) -> Self {
+		self.subscription_quota = Some(max_subscriptions);
+		self
+	}
+
 	/// Insert connection handle and spawn its actor
 	pub fn insert(self: &Arc<Self>, key: String, connection: Connection, parent_token: &CancellationToken) -> ConnectionHandle<K> {
 		let (handle, actor, token) = ConnectionHandle::new(connection, 100, parent_token);
+		let actor = match self.subscription_quota {
+			Some(max_subscriptions) => actor.with_subscription_quota(max_subscriptions),
+			None => actor,
+		};
 		let store = self.clone();
 
 		tokio::spawn({
@@ -56,6 +74,17 @@ impl<K: EventKey> ConnectionStore<K> {
 		}
 	}
 
+	/// Handles of all connections tagged `key=value` at connect time
+	#[must_use]
+	pub fn find_by_tag(&self, key: &str, value: &str) -> Vec<ConnectionHandle<K>> {
+		self
+			.handles
+			.iter()
+			.filter(|entry| entry.value().connection.has_tag(key, value))
+			.map(|entry| entry.value().clone())
+			.collect()
+	}
+
 	pub fn len(&self) -> usize {
 		self.handles.len()
 	}
@@ -102,6 +131,51 @@ impl<K: EventKey> ConnectionStore<K> {
 		false
 	}
 
+	/// Deliver `message` to every connection subscribed to `event_key`
+	///
+	/// Outbound channels belong to the transport layer, so `deliver` does the
+	/// actual send and returns whether the message was accepted. A connection
+	/// that applies backpressure (for example a full bounded channel hit with
+	/// `try_send`) should return `false`; it is skipped rather than awaited, so
+	/// one slow client cannot hold up the rest of the group.
+	///
+	/// Actors are asked about their subscriptions concurrently. One that
+	/// doesn't answer within 500ms is skipped, the same as an unsubscribed
+	/// connection.
+	///
+	/// Returns the number of connections the message was delivered to.
+	pub async fn broadcast<M, F>(&self, event_key: &K, message: M, mut deliver: F) -> usize
+	where
+		M: Clone,
+		F: FnMut(&ConnectionHandle<K>, M) -> bool,
+	{
+		use tokio::task::JoinSet;
+
+		let mut join_set = JoinSet::new();
+
+		// Spawn concurrent subscription queries
+		for entry in self.handles.iter() {
+			let handle = entry.value().clone();
+			let event_key = event_key.clone();
+			join_set.spawn(async move {
+				let subscribed = tokio::time::timeout(SUBSCRIPTION_QUERY_TIMEOUT, handle.is_subscribed_to(event_key)).await;
+				(handle, matches!(subscribed, Ok(Ok(true))))
+			});
+		}
+
+		// Deliver as the answers come in
+		let mut delivered = 0;
+		while let Some(result) = join_set.join_next().await {
+			if let Ok((handle, true)) = result {
+				if deliver(&handle, message.clone()) {
+					delivered += 1;
+				}
+			}
+		}
+
+		delivered
+	}
+
 	/// Get stats by querying all actors (with concurrent queries for speed)
 	pub async fn stats(&self) -> ConnectionStoreStats {
 		use tokio::task::JoinSet;
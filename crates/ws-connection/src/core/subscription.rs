@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use std::hash::Hash;
+use thiserror::Error;
 
 /// Trait for event keys that can be subscribed to.
 pub trait EventKey: Clone + Eq + Hash + std::fmt::Debug + Send + Sync + 'static {}
@@ -11,6 +12,21 @@ impl<T> EventKey for T where T: Clone + Eq + Hash + std::fmt::Debug + Send + Syn
 #[derive(Debug, Clone)]
 pub struct SubscriptionManager<K: EventKey = String> {
 	subscriptions: HashSet<K>,
+	/// Most keys this connection may hold at once; `None` is unlimited
+	quota: Option<usize>,
+}
+
+/// A subscribe request that would take a connection past its quota.
+///
+/// The request is rejected as a whole, so existing subscriptions are untouched.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("subscription quota exceeded: {current} held + {requested} new > {limit}")]
+pub struct QuotaExceeded {
+	pub limit: usize,
+	/// Subscriptions held before the request
+	pub current: usize,
+	/// Keys in the request that weren't already subscribed
+	pub requested: usize,
 }
 
 /// Describes changes in subscription state.
@@ -25,7 +41,10 @@ impl<K: EventKey> SubscriptionManager<K> {
 	/// Create an empty subscription manager.
 	#[must_use]
 	pub fn new() -> Self {
-		Self { subscriptions: HashSet::new() }
+		Self {
+			subscriptions: HashSet::new(),
+			quota: None,
+		}
 	}
 
 	/// Create a subscription manager with initial subscriptions.
@@ -36,10 +55,42 @@ impl<K: EventKey> SubscriptionManager<K> {
 	{
 		Self {
 			subscriptions: event_keys.into_iter().collect(),
+			quota: None,
+		}
+	}
+
+	/// Cap how many keys this manager will hold at once.
+	///
+	/// Subscriptions already held above the cap are kept; only further
+	/// subscribes are rejected.
+	#[must_use]
+	pub const fn with_quota(mut self, max_subscriptions: usize) -> Self {
+		self.quota = Some(max_subscriptions);
+		self
+	}
+
+	/// The subscription cap, if any.
+	#[must_use]
+	pub const fn quota(&self) -> Option<usize> {
+		self.quota
+	}
+
+	/// Reject `new_keys` if adding them would go past the quota.
+	fn check_quota(&self, new_keys: usize) -> Result<(), QuotaExceeded> {
+		let current = self.subscriptions.len();
+		match self.quota {
+			Some(limit) if new_keys > 0 && current + new_keys > limit => Err(QuotaExceeded {
+				limit,
+				current,
+				requested: new_keys,
+			}),
+			_ => Ok(()),
 		}
 	}
 
 	/// Subscribe to event keys.
+	///
+	/// Not subject to the quota; use [`Self::try_subscribe`] to enforce it.
 	#[must_use]
 	pub fn subscribe<I>(&mut self, event_keys: I) -> SubscriptionChange
 	where
@@ -59,6 +110,21 @@ impl<K: EventKey> SubscriptionManager<K> {
 		}
 	}
 
+	/// Subscribe to event keys, within the quota.
+	///
+	/// # Errors
+	///
+	/// Returns [`QuotaExceeded`] without subscribing to any of the keys if
+	/// the ones not already held would take the connection past its quota.
+	pub fn try_subscribe<I>(&mut self, event_keys: I) -> Result<SubscriptionChange, QuotaExceeded>
+	where
+		I: IntoIterator<Item = K>,
+	{
+		let new_keys: HashSet<K> = event_keys.into_iter().filter(|key| !self.subscriptions.contains(key)).collect();
+		self.check_quota(new_keys.len())?;
+		Ok(self.subscribe(new_keys))
+	}
+
 	/// Unsubscribe from event keys.
 	#[must_use]
 	pub fn unsubscribe<I>(&mut self, event_keys: I) -> SubscriptionChange
@@ -117,6 +183,8 @@ impl<K: EventKey> SubscriptionManager<K> {
 	}
 
 	/// Replace current subscriptions with a new set.
+	///
+	/// Not subject to the quota.
 	#[must_use]
 	pub fn set_subscriptions<I>(&mut self, event_keys: I) -> SubscriptionChange
 	where
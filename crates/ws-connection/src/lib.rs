@@ -6,5 +6,5 @@ pub mod types;
 pub use actor::{ConnectionHandle, ConnectionState};
 pub use core::conn::Connection;
 pub use core::store::ConnectionStore;
-pub use core::subscription::{EventKey, SubscriptionManager};
+pub use core::subscription::{EventKey, QuotaExceeded, SubscriptionManager};
 pub use types::{ClientId, ConnectionId};
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use ws_connection::actor::ConnectionError;
+use ws_connection::{ClientId, Connection, ConnectionHandle, ConnectionStore, QuotaExceeded};
+
+fn addr() -> SocketAddr {
+	"127.0.0.1:8080".parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_find_by_tag_returns_matching_subset() {
+	let store: Arc<ConnectionStore<String>> = Arc::new(ConnectionStore::new());
+	let token = CancellationToken::new();
+
+	let tagged = [("eu-1", "eu", "pro"), ("eu-2", "eu", "free"), ("us-1", "us", "pro")];
+	for (key, region, tier) in tagged {
+		let conn = Connection::new(ClientId::new(key), addr()).with_metadata("region", region).with_metadata("tier", tier);
+		store.insert(key.to_string(), conn, &token);
+	}
+	store.insert("untagged".to_string(), Connection::new(ClientId::new("untagged"), addr()), &token);
+
+	let mut eu: Vec<_> = store.find_by_tag("region", "eu").into_iter().map(|h| h.connection.client_id.to_string()).collect();
+	eu.sort();
+	assert_eq!(eu, vec!["eu-1", "eu-2"]);
+
+	let pro = store.find_by_tag("tier", "pro");
+	assert_eq!(pro.len(), 2);
+	assert!(pro.iter().all(|h| h.connection.metadata["tier"] == "pro"));
+
+	assert!(store.find_by_tag("region", "apac").is_empty());
+	assert!(store.find_by_tag("missing", "eu").is_empty());
+
+	token.cancel();
+}
+
+#[tokio::test]
+async fn test_broadcast_reaches_only_subscribers() {
+	let store: Arc<ConnectionStore<String>> = Arc::new(ConnectionStore::new());
+	let token = CancellationToken::new();
+	let mut outbound = HashMap::new();
+	let mut receivers = HashMap::new();
+
+	for key in ["sub-1", "sub-2", "sub-3", "bystander"] {
+		let handle = store.insert(key.to_string(), Connection::new(ClientId::new(key), addr()), &token);
+		if key != "bystander" {
+			handle.subscribe(vec!["scores".to_string()]).await.unwrap();
+		}
+		let (tx, rx) = mpsc::channel::<String>(1);
+		outbound.insert(handle.connection.id, tx);
+		receivers.insert(key, rx);
+	}
+
+	let deliver = |handle: &ConnectionHandle<String>, message: String| outbound[&handle.connection.id].try_send(message).is_ok();
+	assert_eq!(store.broadcast(&"scores".to_string(), "1-0".to_string(), deliver).await, 3);
+
+	// Each outbound buffer holds one message, so a second broadcast is dropped instead of waiting
+	assert_eq!(store.broadcast(&"scores".to_string(), "2-0".to_string(), deliver).await, 0);
+
+	for key in ["sub-1", "sub-2", "sub-3"] {
+		assert_eq!(receivers.get_mut(key).unwrap().try_recv().unwrap(), "1-0");
+	}
+	assert!(receivers.get_mut("bystander").unwrap().try_recv().is_err());
+
+	token.cancel();
+}
+
+#[tokio::test]
+async fn test_subscriptions_past_quota_are_rejected() {
+	let store: Arc<ConnectionStore<String>> = Arc::new(ConnectionStore::new().with_subscription_quota(3));
+	let token = CancellationToken::new();
+	let handle = store.insert("greedy".to_string(), Connection::new(ClientId::new("greedy"), addr()), &token);
+	let keys = |names: &[&str]| names.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+	let change = handle.subscribe(keys(&["scores", "clock"])).await.unwrap();
+	assert_eq!((change.added, change.total), (2, 2));
+
+	// Two new keys on top of two held is one too many, so neither is added
+	let err = handle.subscribe(keys(&["scores", "odds", "injuries"])).await.unwrap_err();
+	assert!(matches!(
+		err,
+		ConnectionError::SubscriptionQuotaExceeded(QuotaExceeded {
+			limit: 3,
+			current: 2,
+			requested: 2
+		})
+	));
+	assert_eq!(handle.subscription_count().await.unwrap(), 2);
+	assert!(handle.is_subscribed_to("scores".to_string()).await.unwrap());
+	assert!(!handle.is_subscribed_to("odds".to_string()).await.unwrap());
+
+	// Filling the quota exactly is fine, and re-subscribing to held keys costs nothing
+	handle.subscribe(keys(&["odds"])).await.unwrap();
+	handle.subscribe(keys(&["clock", "odds"])).await.unwrap();
+	assert_eq!(handle.subscription_count().await.unwrap(), 3);
+
+	token.cancel();
+}
@@ -59,6 +59,21 @@ pub enum Event {
 		stream_id: String,
 		state: OrchestratorState,
 	},
+	/// Request for the current state of `stream_id`, answered with an
+	/// `OrchestratorState`, or an `OrchestratorError` if there is no such stream.
+	/// An empty `stream_id` asks for every stream, answered with `OrchestratorStates`
+	OrchestratorStateQuery {
+		stream_id: String,
+	},
+	/// Current state of every live stream, by stream id
+	OrchestratorStates {
+		states: Vec<(String, OrchestratorState)>,
+	},
+	/// A command for `stream_id` was rejected before reaching its orchestrator
+	OrchestratorError {
+		stream_id: String,
+		reason: String,
+	},
 	AudioChunk {
 		sample_rate: u32,
 		channels: u32,
@@ -85,7 +100,9 @@ impl Event {
 			Self::TabMetaData { .. } => Some(EventType::TabMetaData),
 			Self::Utterance { .. } => Some(EventType::Utterance),
 			Self::OrchestratorCommandData { .. } => Some(EventType::OrchestratorCommandData),
-			Self::OrchestratorState { .. } => Some(EventType::OrchestratorState),
+			Self::OrchestratorState { .. } | Self::OrchestratorStates { .. } => Some(EventType::OrchestratorState),
+			Self::OrchestratorStateQuery { .. } => Some(EventType::OrchestratorStateQuery),
+			Self::OrchestratorError { .. } => Some(EventType::OrchestratorError),
 			Self::AudioChunk { .. } => Some(EventType::AudioChunk),
 			Self::Subtitle { .. } => Some(EventType::Subtitle),
 			// System events don't have EventTypes
@@ -1,21 +1,40 @@
+use enum_name_derive::Subject;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Subject)]
 #[serde(rename_all = "camelCase")]
 pub enum EventType {
+	#[subject = "obs.status"]
 	ObsStatus,
+	#[subject = "obs.command"]
 	ObsCommand,
+	#[subject = "system.client_count"]
 	ClientCount,
+	// Ping and Pong don't have real subjects as they're not transported
+	#[subject = "system.ping"]
 	Ping,
+	#[subject = "system.pong"]
 	Pong,
+	#[subject = "system.error"]
 	Error,
+	#[subject = "tab.metadata"]
 	TabMetaData,
+	#[subject = "utterance"]
 	Utterance,
+	#[subject = "orchestrator.command"]
 	OrchestratorCommandData,
+	#[subject = "orchestrator.state"]
 	OrchestratorState,
+	#[subject = "orchestrator.state.query"]
+	OrchestratorStateQuery,
+	#[subject = "orchestrator.error"]
+	OrchestratorError,
+	#[subject = "system"]
 	SystemEvent,
+	#[subject = "audio.chunk"]
 	AudioChunk,
+	#[subject = "audio.subtitle"]
 	Subtitle,
 }
 
@@ -32,25 +51,6 @@ impl fmt::Display for EventType {
 }
 
 impl EventType {
-	/// Get the NATS subject prefix for this event type
-	pub fn subject(&self) -> &'static str {
-		match self {
-			EventType::ObsStatus => "obs.status",
-			EventType::ObsCommand => "obs.command",
-			EventType::TabMetaData => "tab.metadata",
-			EventType::ClientCount => "system.client_count",
-			EventType::Error => "system.error",
-			EventType::Utterance => "utterance",
-			EventType::OrchestratorCommandData => "orchestrator.command",
-			EventType::OrchestratorState => "orchestrator.state",
-			EventType::SystemEvent => "system",
-			EventType::AudioChunk => "audio.chunk",
-			EventType::Subtitle => "audio.subtitle",
-			// These don't have subjects as they're not transported
-			EventType::Ping | EventType::Pong => "system.ping",
-		}
-	}
-
 	/// Get the connection-specific subject for this event type
 	pub fn connection_subject(&self, connection_id: &str) -> String {
 		format!("{}.{}", self.subject(), connection_id)
@@ -66,14 +66,11 @@ impl std::str::FromStr for EventType {
 	type Err = String;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if let Some(event_type) = Self::from_subject(s) {
+			return Ok(event_type);
+		}
+
 		match s {
-			"obs.status" => Ok(EventType::ObsStatus),
-			"obs.command" => Ok(EventType::ObsCommand),
-			"tab.metadata" => Ok(EventType::TabMetaData),
-			"system.client_count" => Ok(EventType::ClientCount),
-			"system.error" => Ok(EventType::Error),
-			"utterance" => Ok(EventType::Utterance),
-			"system.ping" => Ok(EventType::Ping),
 			"obsStatus" => Ok(EventType::ObsStatus),
 			"obsCommand" => Ok(EventType::ObsCommand),
 			"clientCount" => Ok(EventType::ClientCount),
@@ -81,8 +78,6 @@ impl std::str::FromStr for EventType {
 			"pong" => Ok(EventType::Pong),
 			"error" => Ok(EventType::Error),
 			"tabMetaData" => Ok(EventType::TabMetaData),
-			"orchestrator.command" => Ok(EventType::OrchestratorCommandData),
-			"orchestrator.state" => Ok(EventType::OrchestratorState),
 			"systemEvent" => Ok(EventType::SystemEvent),
 			_ => Err(format!("Unknown event type: {}", s)),
 		}
@@ -51,6 +51,11 @@ impl OrchestratorMode {
 		matches!(self, OrchestratorMode::Finished | OrchestratorMode::Stopped | OrchestratorMode::Error)
 	}
 
+	/// Returns true if this terminal mode was reached by failing rather than by finishing or being stopped
+	pub fn is_failure(&self) -> bool {
+		matches!(self, OrchestratorMode::Error)
+	}
+
 	/// Returns true if this mode allows playback operations
 	pub fn is_active(&self) -> bool {
 		matches!(self, OrchestratorMode::Idle | OrchestratorMode::Running | OrchestratorMode::Paused)
@@ -97,6 +102,11 @@ impl OrchestratorState {
 		self.mode.is_terminal()
 	}
 
+	/// Returns true if the orchestrator terminated because of an error
+	pub fn is_failure(&self) -> bool {
+		self.mode.is_failure()
+	}
+
 	/// Returns true if playback has completed (time reached end)
 	pub fn is_complete(&self) -> bool {
 		self.current_time >= self.total_duration
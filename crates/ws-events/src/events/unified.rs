@@ -1,4 +1,5 @@
 use super::common::{Event, EventType, SystemEvent};
+use enum_name_derive::SchemaFingerprint;
 use prost::Message;
 
 mod audio;
@@ -11,22 +12,22 @@ mod utterance;
 pub use audio::{AudioChunkMessage, SubtitleMessage};
 use now_playing::TabMetaDataMessage;
 pub use obs::{ObsCommandMessage, ObsStatusMessage};
-use orchestrator::{OrchestratorStateMessage, TickCommandMessage};
+use orchestrator::{OrchestratorErrorMessage, OrchestratorStateMessage, OrchestratorStateQueryMessage, OrchestratorStatesMessage, TickCommandMessage};
 use system::{ClientCountMessage, ErrorMessage, SystemEventMessage};
 use utterance::UtteranceMessage;
 
 /// Unified event type for NATS transport (Prost-compatible)
 /// Contains only events that should be transported via NATS
-#[derive(Clone, Message)]
+#[derive(Clone, Message, SchemaFingerprint)]
 pub struct UnifiedEvent {
-	#[prost(oneof = "unified_event::Event", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11")]
+	#[prost(oneof = "unified_event::Event", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14")]
 	pub event: Option<unified_event::Event>,
 }
 
 pub mod unified_event {
 	use super::*;
 
-	#[derive(Clone, PartialEq, prost::Oneof)]
+	#[derive(Clone, PartialEq, prost::Oneof, SchemaFingerprint)]
 	pub enum Event {
 		#[prost(message, tag = "1")]
 		ObsStatus(ObsStatusMessage),
@@ -50,6 +51,12 @@ pub mod unified_event {
 		AudioChunk(AudioChunkMessage),
 		#[prost(message, tag = "11")]
 		Subtitle(SubtitleMessage),
+		#[prost(message, tag = "12")]
+		OrchestratorError(OrchestratorErrorMessage),
+		#[prost(message, tag = "13")]
+		OrchestratorStateQuery(OrchestratorStateQueryMessage),
+		#[prost(message, tag = "14")]
+		OrchestratorStates(OrchestratorStatesMessage),
 	}
 }
 
@@ -98,6 +105,15 @@ impl From<Event> for Option<UnifiedEvent> {
 			Event::OrchestratorState { stream_id, state } => OrchestratorStateMessage::from_orchestrator_state(stream_id, &state).ok().map(|msg| UnifiedEvent {
 				event: Some(unified_event::Event::OrchestratorState(msg)),
 			}),
+			Event::OrchestratorStateQuery { stream_id } => Some(UnifiedEvent {
+				event: Some(unified_event::Event::OrchestratorStateQuery(OrchestratorStateQueryMessage { stream_id })),
+			}),
+			Event::OrchestratorStates { states } => OrchestratorStatesMessage::from_states(&states).ok().map(|msg| UnifiedEvent {
+				event: Some(unified_event::Event::OrchestratorStates(msg)),
+			}),
+			Event::OrchestratorError { stream_id, reason } => Some(UnifiedEvent {
+				event: Some(unified_event::Event::OrchestratorError(OrchestratorErrorMessage { stream_id, reason })),
+			}),
 			Event::AudioChunk { sample_rate, channels, samples } => Some(UnifiedEvent {
 				event: Some(unified_event::Event::AudioChunk(AudioChunkMessage::new(sample_rate, channels, samples))),
 			}),
@@ -144,6 +160,12 @@ impl From<UnifiedEvent> for Result<Event, String> {
 				.map_err(|e| format!("Failed to deserialize SystemEvent: {}", e)),
 			Some(unified_event::Event::OrchestratorCommandData(msg)) => msg.to_tick_command().map(|(stream_id, command)| Event::OrchestratorCommandData { stream_id, command }),
 			Some(unified_event::Event::OrchestratorState(msg)) => msg.to_orchestrator_state().map(|(stream_id, state)| Event::OrchestratorState { stream_id, state }),
+			Some(unified_event::Event::OrchestratorStateQuery(msg)) => Ok(Event::OrchestratorStateQuery { stream_id: msg.stream_id }),
+			Some(unified_event::Event::OrchestratorStates(msg)) => msg.to_states().map(|states| Event::OrchestratorStates { states }),
+			Some(unified_event::Event::OrchestratorError(msg)) => Ok(Event::OrchestratorError {
+				stream_id: msg.stream_id,
+				reason: msg.reason,
+			}),
 			Some(unified_event::Event::AudioChunk(msg)) => {
 				// Decode bytes back to f32 samples
 				let samples = msg.decode_samples().map_err(|e| format!("Failed to decode audio samples: {}", e))?;
@@ -177,7 +199,9 @@ impl UnifiedEvent {
 			Some(unified_event::Event::Utterance(_)) => Some(EventType::Utterance),
 			Some(unified_event::Event::SystemEvent(_)) => Some(EventType::SystemEvent),
 			Some(unified_event::Event::OrchestratorCommandData(_)) => Some(EventType::OrchestratorCommandData),
-			Some(unified_event::Event::OrchestratorState(_)) => Some(EventType::OrchestratorState),
+			Some(unified_event::Event::OrchestratorState(_) | unified_event::Event::OrchestratorStates(_)) => Some(EventType::OrchestratorState),
+			Some(unified_event::Event::OrchestratorStateQuery(_)) => Some(EventType::OrchestratorStateQuery),
+			Some(unified_event::Event::OrchestratorError(_)) => Some(EventType::OrchestratorError),
 			Some(unified_event::Event::AudioChunk(_)) => Some(EventType::AudioChunk),
 			Some(unified_event::Event::Subtitle(_)) => Some(EventType::Subtitle),
 			None => None,
@@ -1,6 +1,7 @@
+use enum_name_derive::SchemaFingerprint;
 use prost::Message;
 
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct AudioChunkMessage {
 	/// Raw audio samples as bytes (float32 little-endian encoded)
 	#[prost(bytes = "vec", tag = "1")]
@@ -74,7 +75,7 @@ impl AudioChunkMessage {
 	}
 }
 
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct SubtitleMessage {
 	#[prost(string, tag = "1")]
 	pub text: String,
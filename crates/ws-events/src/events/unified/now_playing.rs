@@ -1,7 +1,8 @@
 use crate::events::NowPlaying;
+use enum_name_derive::SchemaFingerprint;
 use prost::Message;
 
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct TabMetaDataMessage {
 	#[prost(string, tag = "1")]
 	pub title: String,
@@ -1,8 +1,9 @@
+use enum_name_derive::SchemaFingerprint;
 use obs_websocket::{ObsCommand, ObsEvent};
 use prost::Message;
 use serde_json::Value;
 
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct ObsStatusMessage {
 	/// Timestamp when the event occurred
 	#[prost(int64, tag = "1")]
@@ -15,7 +16,7 @@ pub struct ObsStatusMessage {
 	pub metadata: Option<Vec<u8>>,
 }
 
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct ObsCommandMessage {
 	/// Unique request ID for tracking responses
 	#[prost(string, tag = "1")]
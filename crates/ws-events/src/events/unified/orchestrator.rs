@@ -1,8 +1,9 @@
 use crate::events::{OrchestratorCommandData, OrchestratorConfigData, OrchestratorMode, OrchestratorState};
+use enum_name_derive::SchemaFingerprint;
 use prost::Message;
 
 /// Prost-compatible OrchestratorCommandData message
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct TickCommandMessage {
 	#[prost(string, tag = "1")]
 	pub stream_id: String,
@@ -13,7 +14,7 @@ pub struct TickCommandMessage {
 pub mod tick_command_message {
 	use super::*;
 
-	#[derive(Clone, PartialEq, prost::Oneof)]
+	#[derive(Clone, PartialEq, prost::Oneof, SchemaFingerprint)]
 	pub enum Command {
 		#[prost(message, tag = "2")]
 		Start(StartCommand),
@@ -35,31 +36,31 @@ pub mod tick_command_message {
 		Configure(ConfigureCommand),
 	}
 
-	#[derive(Clone, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 	pub struct StartCommand {}
 
-	#[derive(Clone, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 	pub struct StopCommand {}
 
-	#[derive(Clone, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 	pub struct PauseCommand {}
 
-	#[derive(Clone, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 	pub struct ResumeCommand {}
 
-	#[derive(Clone, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 	pub struct ResetCommand {}
 
-	#[derive(Clone, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 	pub struct ForceSceneCommand {
 		#[prost(string, tag = "1")]
 		pub scene_name: String,
 	}
 
-	#[derive(Clone, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 	pub struct SkipCurrentSceneCommand {}
 
-	#[derive(Clone, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 	pub struct UpdateStreamStatusCommand {
 		#[prost(bool, tag = "1")]
 		pub is_streaming: bool,
@@ -69,7 +70,7 @@ pub mod tick_command_message {
 		pub timecode: String,
 	}
 
-	#[derive(Clone, PartialEq, Message)]
+	#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 	pub struct ConfigureCommand {
 		/// JSON-encoded OrchestratorConfigData
 		#[prost(bytes, tag = "1")]
@@ -134,8 +135,24 @@ impl TickCommandMessage {
 	}
 }
 
+/// Prost-compatible OrchestratorStateQuery message
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
+pub struct OrchestratorStateQueryMessage {
+	#[prost(string, tag = "1")]
+	pub stream_id: String,
+}
+
+/// Prost-compatible OrchestratorError message
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
+pub struct OrchestratorErrorMessage {
+	#[prost(string, tag = "1")]
+	pub stream_id: String,
+	#[prost(string, tag = "2")]
+	pub reason: String,
+}
+
 /// Prost-compatible OrchestratorState message
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct OrchestratorStateMessage {
 	#[prost(string, tag = "1")]
 	pub stream_id: String,
@@ -157,8 +174,15 @@ pub struct OrchestratorStateMessage {
 	pub stream_status_json: Vec<u8>,
 }
 
+/// Prost-compatible `OrchestratorStates` message
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
+pub struct OrchestratorStatesMessage {
+	#[prost(message, repeated, tag = "1")]
+	pub states: Vec<OrchestratorStateMessage>,
+}
+
 /// Protobuf enum for OrchestratorMode
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration, SchemaFingerprint)]
 #[repr(i32)]
 pub enum OrchestratorModeProto {
 	Unconfigured = 0,
@@ -238,3 +262,17 @@ impl OrchestratorStateMessage {
 		Ok((self.stream_id.clone(), state))
 	}
 }
+
+impl OrchestratorStatesMessage {
+	pub fn from_states(states: &[(String, OrchestratorState)]) -> Result<Self, String> {
+		let states = states
+			.iter()
+			.map(|(stream_id, state)| OrchestratorStateMessage::from_orchestrator_state(stream_id.clone(), state))
+			.collect::<Result<_, _>>()?;
+		Ok(Self { states })
+	}
+
+	pub fn to_states(&self) -> Result<Vec<(String, OrchestratorState)>, String> {
+		self.states.iter().map(OrchestratorStateMessage::to_orchestrator_state).collect()
+	}
+}
@@ -1,18 +1,19 @@
+use enum_name_derive::SchemaFingerprint;
 use prost::Message;
 
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct ClientCountMessage {
 	#[prost(uint64, tag = "1")]
 	pub count: u64,
 }
 
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct ErrorMessage {
 	#[prost(string, tag = "1")]
 	pub message: String,
 }
 
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct SystemEventMessage {
 	#[prost(string, tag = "1")]
 	pub event_type: String,
@@ -1,7 +1,8 @@
 use crate::events::UtteranceMetadata;
+use enum_name_derive::SchemaFingerprint;
 use prost::Message;
 
-#[derive(Clone, PartialEq, Message)]
+#[derive(Clone, PartialEq, Message, SchemaFingerprint)]
 pub struct UtteranceMessage {
 	#[prost(string, tag = "1")]
 	pub text: String,
@@ -0,0 +1,14 @@
+use std::collections::HashSet;
+use ws_events::events::EventType;
+
+#[test]
+fn test_every_event_type_round_trips_through_its_subject() {
+	for event_type in EventType::VARIANTS {
+		assert_eq!(EventType::from_subject(event_type.subject()).as_ref(), Some(event_type));
+		assert_eq!(event_type.subject().parse::<EventType>().as_ref(), Ok(event_type));
+	}
+
+	let subjects: HashSet<_> = EventType::SUBJECTS.iter().collect();
+	assert_eq!(subjects.len(), EventType::VARIANTS.len(), "subjects must be unique");
+	assert_eq!(EventType::from_subject("obs.*"), None);
+}